@@ -0,0 +1,17 @@
+CREATE TABLE IF NOT EXISTS gi_weapon_charts (
+    uid integer NOT NULL,
+    banner integer NOT NULL,
+    weapon integer NOT NULL
+);
+
+ALTER TABLE ONLY gi_weapon_charts
+    ADD CONSTRAINT gi_weapon_charts_pkey PRIMARY KEY (uid, banner);
+
+ALTER TABLE ONLY gi_weapon_charts
+    ADD CONSTRAINT gi_weapon_charts_uid_fkey FOREIGN KEY (uid) REFERENCES gi_profiles (uid) ON UPDATE CASCADE ON DELETE CASCADE;
+
+ALTER TABLE ONLY gi_weapon_charts
+    ADD CONSTRAINT gi_weapon_charts_banner_fkey FOREIGN KEY (banner) REFERENCES gi_banners (id) ON DELETE CASCADE;
+
+ALTER TABLE ONLY gi_weapon_charts
+    ADD CONSTRAINT gi_weapon_charts_weapon_fkey FOREIGN KEY (weapon) REFERENCES gi_weapons (id) ON UPDATE CASCADE ON DELETE CASCADE;
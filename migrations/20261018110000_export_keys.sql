@@ -0,0 +1,9 @@
+CREATE TABLE IF NOT EXISTS export_keys (
+    id text NOT NULL,
+    public_key bytea NOT NULL,
+    created timestamp with time zone NOT NULL,
+    retired timestamp with time zone
+);
+
+ALTER TABLE ONLY export_keys
+    ADD CONSTRAINT export_keys_pkey PRIMARY KEY (id);
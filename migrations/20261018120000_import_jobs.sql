@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS import_jobs (
+    id uuid NOT NULL,
+    game text NOT NULL,
+    uid integer NOT NULL,
+    url text,
+    ignore_timestamps boolean NOT NULL,
+    status text NOT NULL,
+    gacha_type text,
+    counts jsonb NOT NULL DEFAULT '{}',
+    error text,
+    attempts integer NOT NULL DEFAULT 0,
+    run_after timestamp with time zone NOT NULL,
+    created timestamp with time zone NOT NULL,
+    updated timestamp with time zone NOT NULL
+);
+
+ALTER TABLE ONLY import_jobs
+    ADD CONSTRAINT import_jobs_pkey PRIMARY KEY (id);
+
+CREATE UNIQUE INDEX import_jobs_game_uid_active_idx ON import_jobs (game, uid)
+WHERE
+    status IN ('pending', 'running', 'calculating');
+
+CREATE INDEX import_jobs_status_run_after_idx ON import_jobs (status, run_after);
@@ -0,0 +1,27 @@
+ALTER TABLE users
+    ADD COLUMN IF NOT EXISTS email_verified boolean NOT NULL DEFAULT FALSE;
+
+-- Existing emails were already trusted for emergency logins
+UPDATE
+    users
+SET
+    email_verified = TRUE
+WHERE
+    email IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS users_tokens (
+    hash bytea NOT NULL,
+    username text NOT NULL,
+    purpose text NOT NULL,
+    email text,
+    expires timestamp with time zone NOT NULL,
+    created timestamp with time zone NOT NULL
+);
+
+ALTER TABLE ONLY users_tokens
+    ADD CONSTRAINT users_tokens_pkey PRIMARY KEY (hash);
+
+ALTER TABLE ONLY users_tokens
+    ADD CONSTRAINT users_tokens_username_fkey FOREIGN KEY (username) REFERENCES users (username) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX users_tokens_username_purpose_idx ON users_tokens (username, purpose);
@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS users_totp (
+    username text NOT NULL,
+    secret bytea NOT NULL,
+    enabled boolean NOT NULL DEFAULT FALSE,
+    last_step bigint NOT NULL DEFAULT 0,
+    created timestamp with time zone NOT NULL DEFAULT now()
+);
+
+ALTER TABLE ONLY users_totp
+    ADD CONSTRAINT users_totp_pkey PRIMARY KEY (username);
+
+ALTER TABLE ONLY users_totp
+    ADD CONSTRAINT users_totp_username_fkey FOREIGN KEY (username) REFERENCES users (username) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE TABLE IF NOT EXISTS users_recovery_codes (
+    username text NOT NULL,
+    hash bytea NOT NULL
+);
+
+ALTER TABLE ONLY users_recovery_codes
+    ADD CONSTRAINT users_recovery_codes_pkey PRIMARY KEY (username, hash);
+
+ALTER TABLE ONLY users_recovery_codes
+    ADD CONSTRAINT users_recovery_codes_username_fkey FOREIGN KEY (username) REFERENCES users (username) ON UPDATE CASCADE ON DELETE CASCADE;
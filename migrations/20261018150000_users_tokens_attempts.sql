@@ -0,0 +1,2 @@
+ALTER TABLE users_tokens
+    ADD COLUMN IF NOT EXISTS attempts integer NOT NULL DEFAULT 0;
@@ -0,0 +1,10 @@
+ALTER TABLE sessions
+    ADD COLUMN IF NOT EXISTS id uuid NOT NULL DEFAULT gen_random_uuid(),
+    ADD COLUMN IF NOT EXISTS created timestamp with time zone NOT NULL DEFAULT now(),
+    ADD COLUMN IF NOT EXISTS last_seen timestamp with time zone NOT NULL DEFAULT now(),
+    ADD COLUMN IF NOT EXISTS user_agent text,
+    ADD COLUMN IF NOT EXISTS ip_hash bytea;
+
+CREATE UNIQUE INDEX IF NOT EXISTS sessions_id_idx ON sessions (id);
+
+CREATE INDEX IF NOT EXISTS sessions_username_idx ON sessions (username);
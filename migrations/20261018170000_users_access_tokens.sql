@@ -0,0 +1,21 @@
+CREATE TABLE IF NOT EXISTS users_access_tokens (
+    id uuid NOT NULL DEFAULT gen_random_uuid(),
+    username text NOT NULL,
+    name text NOT NULL,
+    hash bytea NOT NULL,
+    scopes text[] NOT NULL,
+    expires timestamp with time zone,
+    last_used timestamp with time zone,
+    created timestamp with time zone NOT NULL DEFAULT now()
+);
+
+ALTER TABLE ONLY users_access_tokens
+    ADD CONSTRAINT users_access_tokens_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY users_access_tokens
+    ADD CONSTRAINT users_access_tokens_hash_key UNIQUE (hash);
+
+ALTER TABLE ONLY users_access_tokens
+    ADD CONSTRAINT users_access_tokens_username_fkey FOREIGN KEY (username) REFERENCES users (username) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS users_access_tokens_username_idx ON users_access_tokens (username);
@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS service_keys (
+    id uuid NOT NULL DEFAULT gen_random_uuid(),
+    name text NOT NULL,
+    owner text NOT NULL,
+    hash bytea NOT NULL,
+    route_groups text[] NOT NULL,
+    quota_per_minute integer,
+    requests bigint NOT NULL DEFAULT 0,
+    last_used timestamp with time zone,
+    created timestamp with time zone NOT NULL DEFAULT now()
+);
+
+ALTER TABLE ONLY service_keys
+    ADD CONSTRAINT service_keys_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY service_keys
+    ADD CONSTRAINT service_keys_name_key UNIQUE (name);
+
+ALTER TABLE ONLY service_keys
+    ADD CONSTRAINT service_keys_hash_key UNIQUE (hash);
@@ -0,0 +1,22 @@
+CREATE TABLE IF NOT EXISTS users_deletions (
+    username text NOT NULL,
+    delete_pulls boolean NOT NULL,
+    export_hash bytea NOT NULL,
+    scheduled timestamp with time zone NOT NULL,
+    created timestamp with time zone NOT NULL DEFAULT now()
+);
+
+ALTER TABLE ONLY users_deletions
+    ADD CONSTRAINT users_deletions_pkey PRIMARY KEY (username);
+
+ALTER TABLE ONLY users_deletions
+    ADD CONSTRAINT users_deletions_username_fkey FOREIGN KEY (username) REFERENCES users (username) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE TABLE IF NOT EXISTS deleted_users_exports (
+    hash bytea NOT NULL,
+    export text NOT NULL,
+    expires timestamp with time zone NOT NULL
+);
+
+ALTER TABLE ONLY deleted_users_exports
+    ADD CONSTRAINT deleted_users_exports_pkey PRIMARY KEY (hash);
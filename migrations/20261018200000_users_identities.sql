@@ -0,0 +1,32 @@
+CREATE TABLE IF NOT EXISTS users_identities (
+    provider text NOT NULL,
+    subject text NOT NULL,
+    username text NOT NULL,
+    email text,
+    created timestamp with time zone NOT NULL DEFAULT now()
+);
+
+ALTER TABLE ONLY users_identities
+    ADD CONSTRAINT users_identities_pkey PRIMARY KEY (provider, subject);
+
+ALTER TABLE ONLY users_identities
+    ADD CONSTRAINT users_identities_username_provider_key UNIQUE (username, provider);
+
+ALTER TABLE ONLY users_identities
+    ADD CONSTRAINT users_identities_username_fkey FOREIGN KEY (username) REFERENCES users (username) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE TABLE IF NOT EXISTS users_oidc_states (
+    hash bytea NOT NULL,
+    provider text NOT NULL,
+    code_verifier text NOT NULL,
+    nonce text NOT NULL,
+    username text,
+    redirect text NOT NULL,
+    expires timestamp with time zone NOT NULL
+);
+
+ALTER TABLE ONLY users_oidc_states
+    ADD CONSTRAINT users_oidc_states_pkey PRIMARY KEY (hash);
+
+ALTER TABLE ONLY users_oidc_states
+    ADD CONSTRAINT users_oidc_states_username_fkey FOREIGN KEY (username) REFERENCES users (username) ON UPDATE CASCADE ON DELETE CASCADE;
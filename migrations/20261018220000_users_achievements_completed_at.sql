@@ -0,0 +1,23 @@
+ALTER TABLE users_achievements_completed
+    ADD COLUMN IF NOT EXISTS completed_at timestamp with time zone,
+    ADD COLUMN IF NOT EXISTS imported boolean NOT NULL DEFAULT FALSE;
+
+-- Only new completions get a time, older ones stay unknown
+ALTER TABLE users_achievements_completed
+    ALTER COLUMN completed_at SET DEFAULT now();
+
+ALTER TABLE gi_users_achievements_completed
+    ADD COLUMN IF NOT EXISTS completed_at timestamp with time zone,
+    ADD COLUMN IF NOT EXISTS imported boolean NOT NULL DEFAULT FALSE;
+
+-- Only new completions get a time, older ones stay unknown
+ALTER TABLE gi_users_achievements_completed
+    ALTER COLUMN completed_at SET DEFAULT now();
+
+ALTER TABLE zzz_users_achievements_completed
+    ADD COLUMN IF NOT EXISTS completed_at timestamp with time zone,
+    ADD COLUMN IF NOT EXISTS imported boolean NOT NULL DEFAULT FALSE;
+
+-- Only new completions get a time, older ones stay unknown
+ALTER TABLE zzz_users_achievements_completed
+    ALTER COLUMN completed_at SET DEFAULT now();
@@ -0,0 +1,3 @@
+-- The progress UIAF reports with a completion, so it survives an export
+ALTER TABLE gi_users_achievements_completed
+    ADD COLUMN IF NOT EXISTS current integer;
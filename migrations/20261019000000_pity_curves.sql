@@ -0,0 +1,41 @@
+CREATE TABLE IF NOT EXISTS pity_curves (
+    banner text NOT NULL,
+    rarity integer NOT NULL,
+    pity integer NOT NULL,
+    pulls bigint NOT NULL,
+    hits bigint NOT NULL,
+    rate double precision NOT NULL,
+    lower double precision NOT NULL,
+    upper double precision NOT NULL
+);
+
+ALTER TABLE ONLY pity_curves
+    ADD CONSTRAINT pity_curves_pkey PRIMARY KEY (banner, rarity, pity);
+
+CREATE TABLE IF NOT EXISTS gi_pity_curves (
+    banner text NOT NULL,
+    rarity integer NOT NULL,
+    pity integer NOT NULL,
+    pulls bigint NOT NULL,
+    hits bigint NOT NULL,
+    rate double precision NOT NULL,
+    lower double precision NOT NULL,
+    upper double precision NOT NULL
+);
+
+ALTER TABLE ONLY gi_pity_curves
+    ADD CONSTRAINT gi_pity_curves_pkey PRIMARY KEY (banner, rarity, pity);
+
+CREATE TABLE IF NOT EXISTS zzz_pity_curves (
+    banner text NOT NULL,
+    rarity integer NOT NULL,
+    pity integer NOT NULL,
+    pulls bigint NOT NULL,
+    hits bigint NOT NULL,
+    rate double precision NOT NULL,
+    lower double precision NOT NULL,
+    upper double precision NOT NULL
+);
+
+ALTER TABLE ONLY zzz_pity_curves
+    ADD CONSTRAINT zzz_pity_curves_pkey PRIMARY KEY (banner, rarity, pity);
@@ -0,0 +1,56 @@
+CREATE TABLE IF NOT EXISTS banners_stats (
+    id integer NOT NULL,
+    pulls bigint NOT NULL,
+    uids bigint NOT NULL,
+    copies bigint[] NOT NULL,
+    wins bigint NOT NULL,
+    losses bigint NOT NULL,
+    guarantees bigint NOT NULL,
+    featured_pity double precision,
+    loss_items integer[] NOT NULL,
+    loss_counts bigint[] NOT NULL
+);
+
+ALTER TABLE ONLY banners_stats
+    ADD CONSTRAINT banners_stats_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY banners_stats
+    ADD CONSTRAINT banners_stats_id_fkey FOREIGN KEY (id) REFERENCES banners (id) ON DELETE CASCADE;
+
+CREATE TABLE IF NOT EXISTS gi_banners_stats (
+    id integer NOT NULL,
+    pulls bigint NOT NULL,
+    uids bigint NOT NULL,
+    copies bigint[] NOT NULL,
+    wins bigint NOT NULL,
+    losses bigint NOT NULL,
+    guarantees bigint NOT NULL,
+    featured_pity double precision,
+    loss_items integer[] NOT NULL,
+    loss_counts bigint[] NOT NULL
+);
+
+ALTER TABLE ONLY gi_banners_stats
+    ADD CONSTRAINT gi_banners_stats_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY gi_banners_stats
+    ADD CONSTRAINT gi_banners_stats_id_fkey FOREIGN KEY (id) REFERENCES gi_banners (id) ON DELETE CASCADE;
+
+CREATE TABLE IF NOT EXISTS zzz_banners_stats (
+    id integer NOT NULL,
+    pulls bigint NOT NULL,
+    uids bigint NOT NULL,
+    copies bigint[] NOT NULL,
+    wins bigint NOT NULL,
+    losses bigint NOT NULL,
+    guarantees bigint NOT NULL,
+    featured_pity double precision,
+    loss_items integer[] NOT NULL,
+    loss_counts bigint[] NOT NULL
+);
+
+ALTER TABLE ONLY zzz_banners_stats
+    ADD CONSTRAINT zzz_banners_stats_pkey PRIMARY KEY (id);
+
+ALTER TABLE ONLY zzz_banners_stats
+    ADD CONSTRAINT zzz_banners_stats_id_fkey FOREIGN KEY (id) REFERENCES zzz_banners (id) ON DELETE CASCADE;
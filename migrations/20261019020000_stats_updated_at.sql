@@ -0,0 +1,72 @@
+ALTER TABLE ONLY warps_stats_standard
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS warps_stats_standard_updated_at_index ON warps_stats_standard (updated_at);
+
+ALTER TABLE ONLY warps_stats_special
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS warps_stats_special_updated_at_index ON warps_stats_special (updated_at);
+
+ALTER TABLE ONLY warps_stats_lc
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS warps_stats_lc_updated_at_index ON warps_stats_lc (updated_at);
+
+ALTER TABLE ONLY warps_stats_collab
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS warps_stats_collab_updated_at_index ON warps_stats_collab (updated_at);
+
+ALTER TABLE ONLY warps_stats_collab_lc
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS warps_stats_collab_lc_updated_at_index ON warps_stats_collab_lc (updated_at);
+
+ALTER TABLE ONLY gi_wishes_stats_standard
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS gi_wishes_stats_standard_updated_at_index ON gi_wishes_stats_standard (updated_at);
+
+ALTER TABLE ONLY gi_wishes_stats_character
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS gi_wishes_stats_character_updated_at_index ON gi_wishes_stats_character (updated_at);
+
+ALTER TABLE ONLY gi_wishes_stats_weapon
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS gi_wishes_stats_weapon_updated_at_index ON gi_wishes_stats_weapon (updated_at);
+
+ALTER TABLE ONLY gi_wishes_stats_chronicled
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS gi_wishes_stats_chronicled_updated_at_index ON gi_wishes_stats_chronicled (updated_at);
+
+ALTER TABLE ONLY zzz_signals_stats_standard
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS zzz_signals_stats_standard_updated_at_index ON zzz_signals_stats_standard (updated_at);
+
+ALTER TABLE ONLY zzz_signals_stats_special
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS zzz_signals_stats_special_updated_at_index ON zzz_signals_stats_special (updated_at);
+
+ALTER TABLE ONLY zzz_signals_stats_w_engine
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS zzz_signals_stats_w_engine_updated_at_index ON zzz_signals_stats_w_engine (updated_at);
+
+ALTER TABLE ONLY zzz_signals_stats_bangboo
+    ADD COLUMN IF NOT EXISTS updated_at timestamp with time zone NOT NULL DEFAULT now();
+
+CREATE INDEX IF NOT EXISTS zzz_signals_stats_bangboo_updated_at_index ON zzz_signals_stats_bangboo (updated_at);
+
+CREATE TABLE IF NOT EXISTS stats_global_updates (
+    name text NOT NULL,
+    updated_at timestamp with time zone NOT NULL
+);
+
+ALTER TABLE ONLY stats_global_updates
+    ADD CONSTRAINT stats_global_updates_pkey PRIMARY KEY (name);
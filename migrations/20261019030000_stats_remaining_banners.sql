@@ -0,0 +1,117 @@
+CREATE TABLE IF NOT EXISTS warps_stats_departure (
+    uid integer NOT NULL,
+    luck_4 double precision NOT NULL,
+    luck_5 double precision NOT NULL,
+    updated_at timestamp with time zone NOT NULL DEFAULT now()
+);
+
+ALTER TABLE ONLY warps_stats_departure
+    ADD CONSTRAINT warps_stats_departure_pkey PRIMARY KEY (uid);
+
+ALTER TABLE ONLY warps_stats_departure
+    ADD CONSTRAINT warps_stats_departure_uid_fkey FOREIGN KEY (uid) REFERENCES mihomo (uid) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS warps_stats_departure_updated_at_index ON warps_stats_departure (updated_at);
+
+CREATE TABLE IF NOT EXISTS warps_stats_global_departure (
+    uid integer NOT NULL,
+    count_percentile double precision NOT NULL,
+    luck_4_percentile double precision NOT NULL,
+    luck_5_percentile double precision NOT NULL
+);
+
+ALTER TABLE ONLY warps_stats_global_departure
+    ADD CONSTRAINT warps_stats_global_departure_pkey PRIMARY KEY (uid);
+
+ALTER TABLE ONLY warps_stats_global_departure
+    ADD CONSTRAINT warps_stats_global_departure_uid_fkey FOREIGN KEY (uid) REFERENCES mihomo (uid) ON DELETE CASCADE;
+
+CREATE TABLE IF NOT EXISTS gi_wishes_stats_beginner (
+    uid integer NOT NULL,
+    luck_4 double precision NOT NULL,
+    luck_5 double precision NOT NULL,
+    updated_at timestamp with time zone NOT NULL DEFAULT now()
+);
+
+ALTER TABLE ONLY gi_wishes_stats_beginner
+    ADD CONSTRAINT gi_wishes_stats_beginner_pkey PRIMARY KEY (uid);
+
+ALTER TABLE ONLY gi_wishes_stats_beginner
+    ADD CONSTRAINT gi_wishes_stats_beginner_uid_fkey FOREIGN KEY (uid) REFERENCES gi_profiles (uid) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS gi_wishes_stats_beginner_updated_at_index ON gi_wishes_stats_beginner (updated_at);
+
+CREATE TABLE IF NOT EXISTS gi_wishes_stats_global_beginner (
+    uid integer NOT NULL,
+    count_percentile double precision NOT NULL,
+    luck_4_percentile double precision NOT NULL,
+    luck_5_percentile double precision NOT NULL
+);
+
+ALTER TABLE ONLY gi_wishes_stats_global_beginner
+    ADD CONSTRAINT gi_wishes_stats_global_beginner_pkey PRIMARY KEY (uid);
+
+ALTER TABLE ONLY gi_wishes_stats_global_beginner
+    ADD CONSTRAINT gi_wishes_stats_global_beginner_uid_fkey FOREIGN KEY (uid) REFERENCES gi_profiles (uid) ON UPDATE CASCADE ON DELETE CASCADE;
+
+CREATE TABLE IF NOT EXISTS zzz_signals_stats_exclusive_rescreening (
+    uid integer NOT NULL,
+    luck_a double precision NOT NULL,
+    luck_s double precision NOT NULL,
+    win_rate double precision NOT NULL,
+    win_streak integer NOT NULL,
+    loss_streak integer NOT NULL,
+    updated_at timestamp with time zone NOT NULL DEFAULT now()
+);
+
+ALTER TABLE ONLY zzz_signals_stats_exclusive_rescreening
+    ADD CONSTRAINT zzz_signals_stats_exclusive_rescreening_pkey PRIMARY KEY (uid);
+
+ALTER TABLE ONLY zzz_signals_stats_exclusive_rescreening
+    ADD CONSTRAINT zzz_signals_stats_exclusive_rescreening_uid_fkey FOREIGN KEY (uid) REFERENCES zzz_uids (uid) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS zzz_signals_stats_exclusive_rescreening_updated_at_index ON zzz_signals_stats_exclusive_rescreening (updated_at);
+
+CREATE TABLE IF NOT EXISTS zzz_signals_stats_global_exclusive_rescreening (
+    uid integer NOT NULL,
+    count_percentile double precision NOT NULL,
+    luck_a_percentile double precision NOT NULL,
+    luck_s_percentile double precision NOT NULL
+);
+
+ALTER TABLE ONLY zzz_signals_stats_global_exclusive_rescreening
+    ADD CONSTRAINT zzz_signals_stats_global_exclusive_rescreening_pkey PRIMARY KEY (uid);
+
+ALTER TABLE ONLY zzz_signals_stats_global_exclusive_rescreening
+    ADD CONSTRAINT zzz_signals_stats_global_exclusive_rescreening_uid_fkey FOREIGN KEY (uid) REFERENCES zzz_uids (uid) ON DELETE CASCADE;
+
+CREATE TABLE IF NOT EXISTS zzz_signals_stats_w_engine_reverberation (
+    uid integer NOT NULL,
+    luck_a double precision NOT NULL,
+    luck_s double precision NOT NULL,
+    win_rate double precision NOT NULL,
+    win_streak integer NOT NULL,
+    loss_streak integer NOT NULL,
+    updated_at timestamp with time zone NOT NULL DEFAULT now()
+);
+
+ALTER TABLE ONLY zzz_signals_stats_w_engine_reverberation
+    ADD CONSTRAINT zzz_signals_stats_w_engine_reverberation_pkey PRIMARY KEY (uid);
+
+ALTER TABLE ONLY zzz_signals_stats_w_engine_reverberation
+    ADD CONSTRAINT zzz_signals_stats_w_engine_reverberation_uid_fkey FOREIGN KEY (uid) REFERENCES zzz_uids (uid) ON DELETE CASCADE;
+
+CREATE INDEX IF NOT EXISTS zzz_signals_stats_w_engine_reverberation_updated_at_index ON zzz_signals_stats_w_engine_reverberation (updated_at);
+
+CREATE TABLE IF NOT EXISTS zzz_signals_stats_global_w_engine_reverberation (
+    uid integer NOT NULL,
+    count_percentile double precision NOT NULL,
+    luck_a_percentile double precision NOT NULL,
+    luck_s_percentile double precision NOT NULL
+);
+
+ALTER TABLE ONLY zzz_signals_stats_global_w_engine_reverberation
+    ADD CONSTRAINT zzz_signals_stats_global_w_engine_reverberation_pkey PRIMARY KEY (uid);
+
+ALTER TABLE ONLY zzz_signals_stats_global_w_engine_reverberation
+    ADD CONSTRAINT zzz_signals_stats_global_w_engine_reverberation_uid_fkey FOREIGN KEY (uid) REFERENCES zzz_uids (uid) ON DELETE CASCADE;
@@ -0,0 +1,4 @@
+ALTER TABLE users_totp
+    ADD COLUMN IF NOT EXISTS failures integer NOT NULL DEFAULT 0,
+    ADD COLUMN IF NOT EXISTS failed_at timestamp with time zone;
+
@@ -0,0 +1,17 @@
+-- UIGF imports carry the uploaded file instead of a url and can cover many uids, so they aren't
+-- limited to one active job per uid
+ALTER TABLE import_jobs
+    ADD COLUMN IF NOT EXISTS username text,
+    ADD COLUMN IF NOT EXISTS data text,
+    ADD COLUMN IF NOT EXISTS admin boolean NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS dry_run boolean NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS report jsonb;
+
+ALTER TABLE ONLY import_jobs
+    ADD CONSTRAINT import_jobs_username_fkey FOREIGN KEY (username) REFERENCES users (username) ON UPDATE CASCADE ON DELETE CASCADE;
+
+DROP INDEX IF EXISTS import_jobs_game_uid_active_idx;
+
+CREATE UNIQUE INDEX import_jobs_game_uid_active_idx ON import_jobs (game, uid)
+WHERE
+    status IN ('pending', 'running', 'calculating') AND game <> 'uigf';
@@ -0,0 +1,5 @@
+-- Every instance signs with the active key, so its private half is kept here instead of in a local
+-- file. Revoked keys don't verify anymore.
+ALTER TABLE export_keys
+    ADD COLUMN IF NOT EXISTS private_key bytea,
+    ADD COLUMN IF NOT EXISTS revoked timestamp with time zone;
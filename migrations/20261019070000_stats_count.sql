@@ -0,0 +1,305 @@
+ALTER TABLE ONLY gi_wishes_stats_beginner
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    gi_wishes_stats_beginner
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        gi_wishes_beginner
+    GROUP BY
+        uid) counts
+WHERE
+    gi_wishes_stats_beginner.uid = counts.uid;
+
+ALTER TABLE ONLY gi_wishes_stats_character
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    gi_wishes_stats_character
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        gi_wishes_character
+    GROUP BY
+        uid) counts
+WHERE
+    gi_wishes_stats_character.uid = counts.uid;
+
+ALTER TABLE ONLY gi_wishes_stats_chronicled
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    gi_wishes_stats_chronicled
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        gi_wishes_chronicled
+    GROUP BY
+        uid) counts
+WHERE
+    gi_wishes_stats_chronicled.uid = counts.uid;
+
+ALTER TABLE ONLY gi_wishes_stats_standard
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    gi_wishes_stats_standard
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        gi_wishes_standard
+    GROUP BY
+        uid) counts
+WHERE
+    gi_wishes_stats_standard.uid = counts.uid;
+
+ALTER TABLE ONLY gi_wishes_stats_weapon
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    gi_wishes_stats_weapon
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        gi_wishes_weapon
+    GROUP BY
+        uid) counts
+WHERE
+    gi_wishes_stats_weapon.uid = counts.uid;
+
+ALTER TABLE ONLY warps_stats_collab
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    warps_stats_collab
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        warps_collab
+    GROUP BY
+        uid) counts
+WHERE
+    warps_stats_collab.uid = counts.uid;
+
+ALTER TABLE ONLY warps_stats_collab_lc
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    warps_stats_collab_lc
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        warps_collab_lc
+    GROUP BY
+        uid) counts
+WHERE
+    warps_stats_collab_lc.uid = counts.uid;
+
+ALTER TABLE ONLY warps_stats_departure
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    warps_stats_departure
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        warps_departure
+    GROUP BY
+        uid) counts
+WHERE
+    warps_stats_departure.uid = counts.uid;
+
+ALTER TABLE ONLY warps_stats_lc
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    warps_stats_lc
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        warps_lc
+    GROUP BY
+        uid) counts
+WHERE
+    warps_stats_lc.uid = counts.uid;
+
+ALTER TABLE ONLY warps_stats_special
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    warps_stats_special
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        warps_special
+    GROUP BY
+        uid) counts
+WHERE
+    warps_stats_special.uid = counts.uid;
+
+ALTER TABLE ONLY warps_stats_standard
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    warps_stats_standard
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        warps_standard
+    GROUP BY
+        uid) counts
+WHERE
+    warps_stats_standard.uid = counts.uid;
+
+ALTER TABLE ONLY zzz_signals_stats_bangboo
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    zzz_signals_stats_bangboo
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        zzz_signals_bangboo
+    GROUP BY
+        uid) counts
+WHERE
+    zzz_signals_stats_bangboo.uid = counts.uid;
+
+ALTER TABLE ONLY zzz_signals_stats_exclusive_rescreening
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    zzz_signals_stats_exclusive_rescreening
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        zzz_signals_exclusive_rescreening
+    GROUP BY
+        uid) counts
+WHERE
+    zzz_signals_stats_exclusive_rescreening.uid = counts.uid;
+
+ALTER TABLE ONLY zzz_signals_stats_special
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    zzz_signals_stats_special
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        zzz_signals_special
+    GROUP BY
+        uid) counts
+WHERE
+    zzz_signals_stats_special.uid = counts.uid;
+
+ALTER TABLE ONLY zzz_signals_stats_standard
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    zzz_signals_stats_standard
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        zzz_signals_standard
+    GROUP BY
+        uid) counts
+WHERE
+    zzz_signals_stats_standard.uid = counts.uid;
+
+ALTER TABLE ONLY zzz_signals_stats_w_engine
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    zzz_signals_stats_w_engine
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        zzz_signals_w_engine
+    GROUP BY
+        uid) counts
+WHERE
+    zzz_signals_stats_w_engine.uid = counts.uid;
+
+ALTER TABLE ONLY zzz_signals_stats_w_engine_reverberation
+    ADD COLUMN IF NOT EXISTS count integer NOT NULL DEFAULT 0;
+
+UPDATE
+    zzz_signals_stats_w_engine_reverberation
+SET
+    count = counts.count
+FROM (
+    SELECT
+        uid,
+        count(*)
+    FROM
+        zzz_signals_w_engine_reverberation
+    GROUP BY
+        uid) counts
+WHERE
+    zzz_signals_stats_w_engine_reverberation.uid = counts.uid;
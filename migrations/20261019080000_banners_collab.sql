@@ -0,0 +1,2 @@
+ALTER TABLE ONLY banners
+    ADD COLUMN IF NOT EXISTS collab boolean NOT NULL DEFAULT FALSE;
@@ -0,0 +1,2 @@
+DELETE FROM banners_stats;
+
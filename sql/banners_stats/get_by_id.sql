@@ -0,0 +1,7 @@
+SELECT
+    *
+FROM
+    banners_stats
+WHERE
+    id = $1;
+
@@ -0,0 +1,59 @@
+-- Concurrent banners of the same kind share their pulls. Collab warps are their own kind
+WITH pulls AS (
+    SELECT
+        'character' AS kind,
+        uid,
+        timestamp
+    FROM
+        warps_special
+    WHERE
+        official
+    UNION ALL
+    SELECT
+        'collab_character' AS kind,
+        uid,
+        timestamp
+    FROM
+        warps_collab
+    WHERE
+        official
+    UNION ALL
+    SELECT
+        'light_cone' AS kind,
+        uid,
+        timestamp
+    FROM
+        warps_lc
+    WHERE
+        official
+    UNION ALL
+    SELECT
+        'collab_light_cone' AS kind,
+        uid,
+        timestamp
+    FROM
+        warps_collab_lc
+    WHERE
+        official
+)
+SELECT
+    banners.id,
+    COUNT(*) AS "pulls!",
+    COUNT(DISTINCT pulls.uid) AS "uids!"
+FROM
+    banners
+    INNER JOIN pulls ON pulls.kind = CASE WHEN banners.collab
+        AND banners.character IS NOT NULL THEN
+        'collab_character'
+    WHEN banners.collab THEN
+        'collab_light_cone'
+    WHEN banners.character IS NOT NULL THEN
+        'character'
+    ELSE
+        'light_cone'
+    END
+    AND pulls.timestamp >= banners.start
+    AND pulls.timestamp < banners."end"
+GROUP BY
+    banners.id;
+
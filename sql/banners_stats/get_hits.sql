@@ -0,0 +1,83 @@
+-- Hits are pulls of the top rarity. `pity` counts the pulls since the previous hit of the
+-- same source and is NULL for the first one, because the pity it started with is unknown.
+WITH pulls AS (
+    SELECT
+        'special' AS source,
+        'character' AS kind,
+        warps_special.uid,
+        warps_special.id,
+        warps_special.timestamp,
+        warps_special.official,
+        COALESCE(warps_special.character, warps_special.light_cone) AS item,
+        COALESCE(characters.rarity, light_cones.rarity) AS rarity
+    FROM
+        warps_special
+        LEFT JOIN characters ON characters.id = warps_special.character
+        LEFT JOIN light_cones ON light_cones.id = warps_special.light_cone
+    UNION ALL
+    SELECT
+        'collab' AS source,
+        'collab_character' AS kind,
+        warps_collab.uid,
+        warps_collab.id,
+        warps_collab.timestamp,
+        warps_collab.official,
+        COALESCE(warps_collab.character, warps_collab.light_cone) AS item,
+        COALESCE(characters.rarity, light_cones.rarity) AS rarity
+    FROM
+        warps_collab
+        LEFT JOIN characters ON characters.id = warps_collab.character
+        LEFT JOIN light_cones ON light_cones.id = warps_collab.light_cone
+    UNION ALL
+    SELECT
+        'lc' AS source,
+        'light_cone' AS kind,
+        warps_lc.uid,
+        warps_lc.id,
+        warps_lc.timestamp,
+        warps_lc.official,
+        COALESCE(warps_lc.character, warps_lc.light_cone) AS item,
+        COALESCE(characters.rarity, light_cones.rarity) AS rarity
+    FROM
+        warps_lc
+        LEFT JOIN characters ON characters.id = warps_lc.character
+        LEFT JOIN light_cones ON light_cones.id = warps_lc.light_cone
+    UNION ALL
+    SELECT
+        'collab_lc' AS source,
+        'collab_light_cone' AS kind,
+        warps_collab_lc.uid,
+        warps_collab_lc.id,
+        warps_collab_lc.timestamp,
+        warps_collab_lc.official,
+        COALESCE(warps_collab_lc.character, warps_collab_lc.light_cone) AS item,
+        COALESCE(characters.rarity, light_cones.rarity) AS rarity
+    FROM
+        warps_collab_lc
+        LEFT JOIN characters ON characters.id = warps_collab_lc.character
+        LEFT JOIN light_cones ON light_cones.id = warps_collab_lc.light_cone
+),
+numbered AS (
+    SELECT
+        *,
+        ROW_NUMBER() OVER (PARTITION BY source, uid ORDER BY id) AS pull
+    FROM
+        pulls
+)
+SELECT
+    source AS "source!",
+    kind AS "kind!",
+    uid AS "uid!",
+    timestamp AS "timestamp!",
+    official AS "official!",
+    item AS "item!",
+    (pull - LAG(pull) OVER (PARTITION BY source, uid ORDER BY id))::integer AS pity
+FROM
+    numbered
+WHERE
+    rarity = 5
+ORDER BY
+    source,
+    uid,
+    id;
+
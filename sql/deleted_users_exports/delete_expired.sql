@@ -0,0 +1,3 @@
+DELETE FROM deleted_users_exports
+WHERE expires <= now();
+
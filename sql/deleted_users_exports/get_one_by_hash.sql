@@ -0,0 +1,8 @@
+SELECT
+    export
+FROM
+    deleted_users_exports
+WHERE
+    hash = $1
+    AND expires > now();
+
@@ -0,0 +1,5 @@
+INSERT INTO deleted_users_exports (hash, export, expires)
+    VALUES ($1, $2, $3)
+ON CONFLICT (hash)
+    DO NOTHING;
+
@@ -0,0 +1,17 @@
+-- One statement, so racing instances can't retire each other's keys and leave none active. Only
+-- the active key signs, so the retired ones lose their private half.
+WITH retired AS (
+    UPDATE
+        export_keys
+    SET
+        retired = now(),
+        private_key = NULL
+    WHERE
+        id != $1
+        AND retired IS NULL)
+INSERT INTO export_keys (id, public_key, private_key, created)
+    VALUES ($1, $2, $3, now())
+ON CONFLICT (id)
+    DO UPDATE SET
+        private_key = EXCLUDED.private_key,
+        retired = NULL;
@@ -0,0 +1,12 @@
+SELECT
+    id,
+    private_key AS "private_key!"
+FROM
+    export_keys
+WHERE
+    retired IS NULL
+    AND revoked IS NULL
+    AND private_key IS NOT NULL
+ORDER BY
+    created DESC
+LIMIT 1;
@@ -0,0 +1,11 @@
+SELECT
+    id,
+    public_key,
+    created,
+    retired,
+    revoked
+FROM
+    export_keys
+WHERE
+    id = $1;
+
@@ -0,0 +1,8 @@
+UPDATE
+    export_keys
+SET
+    retired = COALESCE(retired, now()),
+    revoked = COALESCE(revoked, now()),
+    private_key = NULL
+WHERE
+    id = $1;
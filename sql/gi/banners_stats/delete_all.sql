@@ -0,0 +1,2 @@
+DELETE FROM gi_banners_stats;
+
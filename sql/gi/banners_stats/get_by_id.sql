@@ -0,0 +1,7 @@
+SELECT
+    *
+FROM
+    gi_banners_stats
+WHERE
+    id = $1;
+
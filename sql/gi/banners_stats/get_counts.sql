@@ -0,0 +1,36 @@
+-- Concurrent banners of the same kind share their pulls
+WITH pulls AS (
+    SELECT
+        'character' AS kind,
+        uid,
+        timestamp
+    FROM
+        gi_wishes_character
+    WHERE
+        official
+    UNION ALL
+    SELECT
+        'weapon' AS kind,
+        uid,
+        timestamp
+    FROM
+        gi_wishes_weapon
+    WHERE
+        official
+)
+SELECT
+    gi_banners.id,
+    COUNT(*) AS "pulls!",
+    COUNT(DISTINCT pulls.uid) AS "uids!"
+FROM
+    gi_banners
+    INNER JOIN pulls ON pulls.kind = CASE WHEN gi_banners.character IS NOT NULL THEN
+        'character'
+    ELSE
+        'weapon'
+    END
+    AND pulls.timestamp >= gi_banners.start
+    AND pulls.timestamp < gi_banners."end"
+GROUP BY
+    gi_banners.id;
+
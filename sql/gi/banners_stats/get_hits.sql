@@ -0,0 +1,55 @@
+-- Hits are pulls of the top rarity. `pity` counts the pulls since the previous hit of the
+-- same source and is NULL for the first one, because the pity it started with is unknown.
+WITH pulls AS (
+    SELECT
+        'character' AS source,
+        'character' AS kind,
+        gi_wishes_character.uid,
+        gi_wishes_character.id,
+        gi_wishes_character.timestamp,
+        gi_wishes_character.official,
+        COALESCE(gi_wishes_character.character, gi_wishes_character.weapon) AS item,
+        COALESCE(gi_characters.rarity, gi_weapons.rarity) AS rarity
+    FROM
+        gi_wishes_character
+        LEFT JOIN gi_characters ON gi_characters.id = gi_wishes_character.character
+        LEFT JOIN gi_weapons ON gi_weapons.id = gi_wishes_character.weapon
+    UNION ALL
+    SELECT
+        'weapon' AS source,
+        'weapon' AS kind,
+        gi_wishes_weapon.uid,
+        gi_wishes_weapon.id,
+        gi_wishes_weapon.timestamp,
+        gi_wishes_weapon.official,
+        COALESCE(gi_wishes_weapon.character, gi_wishes_weapon.weapon) AS item,
+        COALESCE(gi_characters.rarity, gi_weapons.rarity) AS rarity
+    FROM
+        gi_wishes_weapon
+        LEFT JOIN gi_characters ON gi_characters.id = gi_wishes_weapon.character
+        LEFT JOIN gi_weapons ON gi_weapons.id = gi_wishes_weapon.weapon
+),
+numbered AS (
+    SELECT
+        *,
+        ROW_NUMBER() OVER (PARTITION BY source, uid ORDER BY id) AS pull
+    FROM
+        pulls
+)
+SELECT
+    source AS "source!",
+    kind AS "kind!",
+    uid AS "uid!",
+    timestamp AS "timestamp!",
+    official AS "official!",
+    item AS "item!",
+    (pull - LAG(pull) OVER (PARTITION BY source, uid ORDER BY id))::integer AS pity
+FROM
+    numbered
+WHERE
+    rarity = 5
+ORDER BY
+    source,
+    uid,
+    id;
+
@@ -0,0 +1,3 @@
+INSERT INTO gi_banners_stats (id, pulls, uids, copies, wins, losses, guarantees, featured_pity, loss_items, loss_counts)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10);
+
@@ -0,0 +1,2 @@
+DELETE FROM gi_pity_curves;
+
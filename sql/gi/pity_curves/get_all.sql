@@ -0,0 +1,9 @@
+SELECT
+    *
+FROM
+    gi_pity_curves
+ORDER BY
+    banner,
+    rarity,
+    pity;
+
@@ -0,0 +1,117 @@
+-- Pity counts all pulls, but only official ones are counted. Pulls before the first hit of a uid
+-- are left out, because the pity they started with is unknown.
+WITH pulls AS (
+    SELECT
+        'beginner' AS banner,
+        gi_wishes_beginner.uid,
+        gi_wishes_beginner.id,
+        gi_wishes_beginner.official,
+        COALESCE(gi_characters.rarity, gi_weapons.rarity) AS rarity
+    FROM
+        gi_wishes_beginner
+        LEFT JOIN gi_characters ON gi_characters.id = gi_wishes_beginner.character
+        LEFT JOIN gi_weapons ON gi_weapons.id = gi_wishes_beginner.weapon
+    UNION ALL
+    SELECT
+        'standard' AS banner,
+        gi_wishes_standard.uid,
+        gi_wishes_standard.id,
+        gi_wishes_standard.official,
+        COALESCE(gi_characters.rarity, gi_weapons.rarity) AS rarity
+    FROM
+        gi_wishes_standard
+        LEFT JOIN gi_characters ON gi_characters.id = gi_wishes_standard.character
+        LEFT JOIN gi_weapons ON gi_weapons.id = gi_wishes_standard.weapon
+    UNION ALL
+    SELECT
+        'character' AS banner,
+        gi_wishes_character.uid,
+        gi_wishes_character.id,
+        gi_wishes_character.official,
+        COALESCE(gi_characters.rarity, gi_weapons.rarity) AS rarity
+    FROM
+        gi_wishes_character
+        LEFT JOIN gi_characters ON gi_characters.id = gi_wishes_character.character
+        LEFT JOIN gi_weapons ON gi_weapons.id = gi_wishes_character.weapon
+    UNION ALL
+    SELECT
+        'weapon' AS banner,
+        gi_wishes_weapon.uid,
+        gi_wishes_weapon.id,
+        gi_wishes_weapon.official,
+        COALESCE(gi_characters.rarity, gi_weapons.rarity) AS rarity
+    FROM
+        gi_wishes_weapon
+        LEFT JOIN gi_characters ON gi_characters.id = gi_wishes_weapon.character
+        LEFT JOIN gi_weapons ON gi_weapons.id = gi_wishes_weapon.weapon
+    UNION ALL
+    SELECT
+        'chronicled' AS banner,
+        gi_wishes_chronicled.uid,
+        gi_wishes_chronicled.id,
+        gi_wishes_chronicled.official,
+        COALESCE(gi_characters.rarity, gi_weapons.rarity) AS rarity
+    FROM
+        gi_wishes_chronicled
+        LEFT JOIN gi_characters ON gi_characters.id = gi_wishes_chronicled.character
+        LEFT JOIN gi_weapons ON gi_weapons.id = gi_wishes_chronicled.weapon
+),
+segments AS (
+    SELECT
+        banner,
+        uid,
+        id,
+        official,
+        rarity,
+        COUNT(*) FILTER (WHERE rarity = 5) OVER previous AS segment_5,
+        COUNT(*) FILTER (WHERE rarity = 4) OVER previous AS segment_4
+    FROM
+        pulls
+    WINDOW previous AS (PARTITION BY banner, uid ORDER BY id ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING)
+),
+pities AS (
+    SELECT
+        banner,
+        official,
+        rarity,
+        segment_5,
+        segment_4,
+        ROW_NUMBER() OVER (PARTITION BY banner, uid, segment_5 ORDER BY id) AS pity_5,
+        ROW_NUMBER() OVER (PARTITION BY banner, uid, segment_4 ORDER BY id) AS pity_4
+    FROM
+        segments
+)
+SELECT
+    banner AS "banner!",
+    5 AS "rarity!",
+    pity_5::integer AS "pity!",
+    COUNT(*) AS "pulls!",
+    COUNT(*) FILTER (WHERE rarity = 5) AS "hits!"
+FROM
+    pities
+WHERE
+    official
+    AND segment_5 > 0
+GROUP BY
+    banner,
+    pity_5
+UNION ALL
+SELECT
+    banner,
+    4,
+    pity_4::integer,
+    COUNT(*),
+    COUNT(*) FILTER (WHERE rarity = 4)
+FROM
+    pities
+WHERE
+    official
+    AND segment_4 > 0
+GROUP BY
+    banner,
+    pity_4
+ORDER BY
+    1,
+    2,
+    3;
+
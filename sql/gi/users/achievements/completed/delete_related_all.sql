@@ -0,0 +1,9 @@
+-- Only one achievement of a set can be completed, the imported ones win
+DELETE FROM gi_users_achievements_completed
+USING gi_achievements, gi_achievements imported
+WHERE gi_users_achievements_completed.username = $1
+    AND gi_achievements.id = gi_users_achievements_completed.id
+    AND imported.id = ANY ($2)
+    AND imported.set = gi_achievements.set
+    AND NOT gi_achievements.id = ANY ($2);
+
@@ -1,5 +1,6 @@
 SELECT
-    *
+    username,
+    id
 FROM
     gi_users_achievements_completed
 WHERE
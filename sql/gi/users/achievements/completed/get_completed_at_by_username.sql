@@ -0,0 +1,9 @@
+SELECT
+    id,
+    completed_at,
+    imported
+FROM
+    gi_users_achievements_completed
+WHERE
+    username = $1;
+
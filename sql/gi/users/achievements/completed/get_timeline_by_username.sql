@@ -0,0 +1,13 @@
+SELECT
+    gi_users_achievements_completed.completed_at,
+    gi_users_achievements_completed.imported,
+    gi_achievements.version,
+    gi_achievements.primogems AS rewards
+FROM
+    gi_users_achievements_completed
+    INNER JOIN gi_achievements ON gi_achievements.id = gi_users_achievements_completed.id
+WHERE
+    gi_users_achievements_completed.username = $1
+ORDER BY
+    gi_users_achievements_completed.completed_at;
+
@@ -0,0 +1,11 @@
+SELECT
+    id,
+    completed_at,
+    current
+FROM
+    gi_users_achievements_completed
+WHERE
+    username = $1
+ORDER BY
+    id;
+
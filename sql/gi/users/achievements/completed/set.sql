@@ -1,5 +1,5 @@
-INSERT INTO gi_users_achievements_completed (username, id)
-    VALUES ($1, $2)
+INSERT INTO gi_users_achievements_completed (username, id, imported)
+    VALUES ($1, $2, $3)
 ON CONFLICT (username, id)
     DO NOTHING;
 
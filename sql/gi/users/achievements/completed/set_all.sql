@@ -0,0 +1,16 @@
+-- Impossible and unknown achievements are skipped
+INSERT INTO gi_users_achievements_completed (username, id, completed_at, current, imported)
+SELECT
+    $1,
+    uiaf.id,
+    COALESCE(uiaf.completed_at, now()),
+    uiaf.current,
+    TRUE
+FROM
+    UNNEST($2::integer[], $3::timestamptz[], $4::integer[]) AS uiaf (id, completed_at, current)
+    INNER JOIN gi_achievements ON gi_achievements.id = uiaf.id
+WHERE
+    NOT gi_achievements.impossible
+ON CONFLICT (username, id)
+    DO NOTHING;
+
@@ -0,0 +1,9 @@
+UPDATE
+    gi_users_achievements_completed
+SET
+    completed_at = $3,
+    imported = $4
+WHERE
+    username = $1
+    AND id = $2;
+
@@ -0,0 +1,12 @@
+-- Known times replace unknown or later ones
+UPDATE
+    gi_users_achievements_completed
+SET
+    completed_at = LEAST(gi_users_achievements_completed.completed_at, uiaf.completed_at),
+    current = COALESCE(uiaf.current, gi_users_achievements_completed.current)
+FROM
+    UNNEST($2::integer[], $3::timestamptz[], $4::integer[]) AS uiaf (id, completed_at, current)
+WHERE
+    gi_users_achievements_completed.username = $1
+    AND gi_users_achievements_completed.id = uiaf.id;
+
@@ -0,0 +1,2 @@
+DELETE FROM gi_users_achievements_favorites
+WHERE username = $1;
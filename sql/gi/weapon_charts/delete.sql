@@ -0,0 +1,4 @@
+DELETE FROM gi_weapon_charts
+WHERE uid = $1
+    AND banner = $2;
+
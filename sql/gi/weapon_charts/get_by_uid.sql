@@ -0,0 +1,7 @@
+SELECT
+    *
+FROM
+    gi_weapon_charts
+WHERE
+    uid = $1;
+
@@ -0,0 +1,6 @@
+INSERT INTO gi_weapon_charts (uid, banner, weapon)
+    VALUES ($1, $2, $3)
+ON CONFLICT (uid, banner)
+    DO UPDATE SET
+        weapon = EXCLUDED.weapon;
+
@@ -1,7 +0,0 @@
-SELECT
-    count(*)
-FROM
-    gi_wishes_character
-WHERE
-    uid = $1;
-
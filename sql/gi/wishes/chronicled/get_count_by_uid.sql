@@ -1,7 +0,0 @@
-SELECT
-    count(*)
-FROM
-    gi_wishes_chronicled
-WHERE
-    uid = $1;
-
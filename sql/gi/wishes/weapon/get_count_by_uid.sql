@@ -1,7 +0,0 @@
-SELECT
-    count(*)
-FROM
-    gi_wishes_weapon
-WHERE
-    uid = $1;
-
@@ -0,0 +1,9 @@
+SELECT
+    uid,
+    luck_4,
+    luck_5
+FROM
+    gi_wishes_stats_beginner
+WHERE
+    uid = $1;
+
@@ -0,0 +1,12 @@
+INSERT INTO gi_wishes_stats_beginner (uid, luck_4, luck_5, count)
+    VALUES ($1, $2, $3, (
+            SELECT
+                count(*)
+            FROM
+                gi_wishes_beginner
+            WHERE
+                uid = $1))
+ON CONFLICT (uid)
+    DO UPDATE SET
+        luck_4 = EXCLUDED.luck_4, luck_5 = EXCLUDED.luck_5, count = EXCLUDED.count, updated_at = now();
+
@@ -1,5 +1,10 @@
 SELECT
-    *
+    uid,
+    luck_4,
+    luck_5,
+    win_rate,
+    win_streak,
+    loss_streak
 FROM
     gi_wishes_stats_character
 WHERE
@@ -1,5 +1,7 @@
 SELECT
-    *
+    uid,
+    luck_4,
+    luck_5
 FROM
     gi_wishes_stats_chronicled
 WHERE
@@ -1,5 +1,5 @@
 SELECT
-    *
+    max(updated_at)
 FROM
     gi_wishes_stats_chronicled;
 
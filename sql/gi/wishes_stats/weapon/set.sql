@@ -1,6 +1,12 @@
-INSERT INTO gi_wishes_stats_weapon (uid, luck_4, luck_5, win_rate, win_streak, loss_streak)
-    VALUES ($1, $2, $3, $4, $5, $6)
+INSERT INTO gi_wishes_stats_weapon (uid, luck_4, luck_5, win_rate, win_streak, loss_streak, count)
+    VALUES ($1, $2, $3, $4, $5, $6, (
+            SELECT
+                count(*)
+            FROM
+                gi_wishes_weapon
+            WHERE
+                uid = $1))
 ON CONFLICT (uid)
     DO UPDATE SET
-        luck_4 = EXCLUDED.luck_4, luck_5 = EXCLUDED.luck_5, win_rate = EXCLUDED.win_rate, win_streak = EXCLUDED.win_streak, loss_streak = EXCLUDED.loss_streak;
+        luck_4 = EXCLUDED.luck_4, luck_5 = EXCLUDED.luck_5, win_rate = EXCLUDED.win_rate, win_streak = EXCLUDED.win_streak, loss_streak = EXCLUDED.loss_streak, count = EXCLUDED.count, updated_at = now();
 
@@ -0,0 +1,9 @@
+SELECT
+    count_percentile,
+    luck_4_percentile,
+    luck_5_percentile
+FROM
+    gi_wishes_stats_global_beginner
+WHERE
+    uid = $1;
+
@@ -1,5 +1,7 @@
 SELECT
-    *
+    count_percentile,
+    luck_4_percentile,
+    luck_5_percentile
 FROM
     gi_wishes_stats_global_weapon
 WHERE
@@ -0,0 +1,35 @@
+-- Exact percentiles need the rank among all uids, so a single changed uid re-ranks every one. This
+-- only reads the stats tables, which keep the pull count from the last import.
+WITH percentiles AS (
+    SELECT
+        gi_wishes_stats_weapon.uid,
+        round((rank() OVER (ORDER BY gi_wishes_stats_weapon.count DESC) - 1)::numeric / count(*) OVER (), 4)::double precision AS count_percentile,
+        round((rank() OVER (ORDER BY gi_wishes_stats_weapon.luck_4) - 1)::numeric / count(*) OVER (), 4)::double precision AS luck_4_percentile,
+        round((rank() OVER (ORDER BY gi_wishes_stats_weapon.luck_5) - 1)::numeric / count(*) OVER (), 4)::double precision AS luck_5_percentile
+    FROM
+        gi_wishes_stats_weapon
+    WHERE
+        gi_wishes_stats_weapon.count >= 100
+),
+deleted AS (
+    DELETE FROM gi_wishes_stats_global_weapon
+    WHERE NOT EXISTS (
+            SELECT
+                *
+            FROM
+                percentiles
+            WHERE
+                percentiles.uid = gi_wishes_stats_global_weapon.uid))
+INSERT INTO gi_wishes_stats_global_weapon (uid, count_percentile, luck_4_percentile, luck_5_percentile)
+SELECT
+    uid,
+    count_percentile,
+    luck_4_percentile,
+    luck_5_percentile
+FROM
+    percentiles
+ON CONFLICT (uid)
+    DO UPDATE SET
+        count_percentile = EXCLUDED.count_percentile, luck_4_percentile = EXCLUDED.luck_4_percentile, luck_5_percentile = EXCLUDED.luck_5_percentile
+    WHERE (gi_wishes_stats_global_weapon.count_percentile, gi_wishes_stats_global_weapon.luck_4_percentile, gi_wishes_stats_global_weapon.luck_5_percentile) IS DISTINCT FROM (EXCLUDED.count_percentile, EXCLUDED.luck_4_percentile, EXCLUDED.luck_5_percentile);
+
@@ -0,0 +1,30 @@
+-- A job reclaimed while calculating stays calculating, its pulls are already fetched
+UPDATE
+    import_jobs
+SET
+    status = CASE WHEN status = 'calculating' THEN
+        'calculating'
+    ELSE
+        'running'
+    END,
+    attempts = attempts + 1,
+    updated = now()
+WHERE
+    id = (
+        SELECT
+            id
+        FROM
+            import_jobs
+        WHERE (status = 'pending'
+            AND run_after <= now())
+            OR (status IN ('running', 'calculating')
+                AND updated < now() - interval '5 minutes'
+                AND attempts < $1)
+        ORDER BY
+            run_after
+        LIMIT 1
+        FOR UPDATE
+            SKIP LOCKED)
+RETURNING
+    *;
+
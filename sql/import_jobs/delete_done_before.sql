@@ -0,0 +1,4 @@
+DELETE FROM import_jobs
+WHERE status IN ('finished', 'error')
+    AND updated < $1;
+
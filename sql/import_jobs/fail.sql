@@ -0,0 +1,11 @@
+UPDATE
+    import_jobs
+SET
+    status = 'error',
+    url = NULL,
+    data = NULL,
+    error = $2,
+    updated = now()
+WHERE
+    id = $1;
+
@@ -0,0 +1,13 @@
+UPDATE
+    import_jobs
+SET
+    status = 'error',
+    url = NULL,
+    data = NULL,
+    error = 'Import stopped responding',
+    updated = now()
+WHERE
+    status IN ('running', 'calculating')
+    AND updated < now() - interval '5 minutes'
+    AND attempts >= $1;
+
@@ -0,0 +1,11 @@
+UPDATE
+    import_jobs
+SET
+    status = 'finished',
+    url = NULL,
+    data = NULL,
+    error = NULL,
+    updated = now()
+WHERE
+    id = $1;
+
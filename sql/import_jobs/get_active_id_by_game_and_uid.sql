@@ -0,0 +1,9 @@
+SELECT
+    id
+FROM
+    import_jobs
+WHERE
+    game = $1
+    AND uid = $2
+    AND status IN ('pending', 'running', 'calculating');
+
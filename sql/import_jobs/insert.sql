@@ -0,0 +1,9 @@
+INSERT INTO import_jobs (id, game, uid, url, ignore_timestamps, status, error, run_after, created, updated)
+    VALUES (gen_random_uuid(), $1, $2, $3, $4, $5, $6, now(), now(), now())
+ON CONFLICT (game, uid)
+WHERE
+    status IN ('pending', 'running', 'calculating')
+    AND game <> 'uigf'
+        DO NOTHING
+    RETURNING
+        id;
@@ -0,0 +1,4 @@
+INSERT INTO import_jobs (id, game, uid, username, data, admin, dry_run, ignore_timestamps, status, run_after, created, updated)
+    VALUES (gen_random_uuid(), 'uigf', 0, $1, $2, $3, $4, FALSE, 'pending', now(), now(), now())
+RETURNING
+    id;
@@ -0,0 +1,12 @@
+-- The url is kept, the next attempt needs it to resume fetching. It is dropped once fetching
+-- is done or the job fails.
+UPDATE
+    import_jobs
+SET
+    status = 'pending',
+    error = $2,
+    run_after = now() + make_interval(secs => $3),
+    updated = now()
+WHERE
+    id = $1;
+
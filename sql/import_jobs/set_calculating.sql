@@ -0,0 +1,10 @@
+-- All pulls are fetched, so the url with the authkey isn't needed anymore
+UPDATE
+    import_jobs
+SET
+    status = 'calculating',
+    url = NULL,
+    updated = now()
+WHERE
+    id = $1;
+
@@ -0,0 +1,9 @@
+UPDATE
+    import_jobs
+SET
+    gacha_type = $2,
+    counts = jsonb_set(counts, ARRAY[$2], to_jsonb($3::integer)),
+    updated = now()
+WHERE
+    id = $1;
+
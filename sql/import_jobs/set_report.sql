@@ -0,0 +1,7 @@
+UPDATE
+    import_jobs
+SET
+    report = $2,
+    updated = now()
+WHERE
+    id = $1;
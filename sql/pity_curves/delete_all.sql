@@ -0,0 +1,2 @@
+DELETE FROM pity_curves;
+
@@ -0,0 +1,9 @@
+SELECT
+    *
+FROM
+    pity_curves
+ORDER BY
+    banner,
+    rarity,
+    pity;
+
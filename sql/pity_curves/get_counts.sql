@@ -0,0 +1,128 @@
+-- Pity counts all pulls, but only official ones are counted. Pulls before the first hit of a uid
+-- are left out, because the pity they started with is unknown.
+WITH pulls AS (
+    SELECT
+        'departure' AS banner,
+        warps_departure.uid,
+        warps_departure.id,
+        warps_departure.official,
+        COALESCE(characters.rarity, light_cones.rarity) AS rarity
+    FROM
+        warps_departure
+        LEFT JOIN characters ON characters.id = warps_departure.character
+        LEFT JOIN light_cones ON light_cones.id = warps_departure.light_cone
+    UNION ALL
+    SELECT
+        'standard' AS banner,
+        warps_standard.uid,
+        warps_standard.id,
+        warps_standard.official,
+        COALESCE(characters.rarity, light_cones.rarity) AS rarity
+    FROM
+        warps_standard
+        LEFT JOIN characters ON characters.id = warps_standard.character
+        LEFT JOIN light_cones ON light_cones.id = warps_standard.light_cone
+    UNION ALL
+    SELECT
+        'special' AS banner,
+        warps_special.uid,
+        warps_special.id,
+        warps_special.official,
+        COALESCE(characters.rarity, light_cones.rarity) AS rarity
+    FROM
+        warps_special
+        LEFT JOIN characters ON characters.id = warps_special.character
+        LEFT JOIN light_cones ON light_cones.id = warps_special.light_cone
+    UNION ALL
+    SELECT
+        'lc' AS banner,
+        warps_lc.uid,
+        warps_lc.id,
+        warps_lc.official,
+        COALESCE(characters.rarity, light_cones.rarity) AS rarity
+    FROM
+        warps_lc
+        LEFT JOIN characters ON characters.id = warps_lc.character
+        LEFT JOIN light_cones ON light_cones.id = warps_lc.light_cone
+    UNION ALL
+    SELECT
+        'collab' AS banner,
+        warps_collab.uid,
+        warps_collab.id,
+        warps_collab.official,
+        COALESCE(characters.rarity, light_cones.rarity) AS rarity
+    FROM
+        warps_collab
+        LEFT JOIN characters ON characters.id = warps_collab.character
+        LEFT JOIN light_cones ON light_cones.id = warps_collab.light_cone
+    UNION ALL
+    SELECT
+        'collab_lc' AS banner,
+        warps_collab_lc.uid,
+        warps_collab_lc.id,
+        warps_collab_lc.official,
+        COALESCE(characters.rarity, light_cones.rarity) AS rarity
+    FROM
+        warps_collab_lc
+        LEFT JOIN characters ON characters.id = warps_collab_lc.character
+        LEFT JOIN light_cones ON light_cones.id = warps_collab_lc.light_cone
+),
+segments AS (
+    SELECT
+        banner,
+        uid,
+        id,
+        official,
+        rarity,
+        COUNT(*) FILTER (WHERE rarity = 5) OVER previous AS segment_5,
+        COUNT(*) FILTER (WHERE rarity = 4) OVER previous AS segment_4
+    FROM
+        pulls
+    WINDOW previous AS (PARTITION BY banner, uid ORDER BY id ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING)
+),
+pities AS (
+    SELECT
+        banner,
+        official,
+        rarity,
+        segment_5,
+        segment_4,
+        ROW_NUMBER() OVER (PARTITION BY banner, uid, segment_5 ORDER BY id) AS pity_5,
+        ROW_NUMBER() OVER (PARTITION BY banner, uid, segment_4 ORDER BY id) AS pity_4
+    FROM
+        segments
+)
+SELECT
+    banner AS "banner!",
+    5 AS "rarity!",
+    pity_5::integer AS "pity!",
+    COUNT(*) AS "pulls!",
+    COUNT(*) FILTER (WHERE rarity = 5) AS "hits!"
+FROM
+    pities
+WHERE
+    official
+    AND segment_5 > 0
+GROUP BY
+    banner,
+    pity_5
+UNION ALL
+SELECT
+    banner,
+    4,
+    pity_4::integer,
+    COUNT(*),
+    COUNT(*) FILTER (WHERE rarity = 4)
+FROM
+    pities
+WHERE
+    official
+    AND segment_4 > 0
+GROUP BY
+    banner,
+    pity_4
+ORDER BY
+    1,
+    2,
+    3;
+
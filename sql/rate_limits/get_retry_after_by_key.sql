@@ -0,0 +1,7 @@
+SELECT
+    (1 - LEAST($2::float8, tokens + EXTRACT(EPOCH FROM now() - updated)::float8 * $3::float8)) / $3::float8 AS "retry_after!"
+FROM
+    rate_limits
+WHERE
+    key = $1;
+
@@ -0,0 +1,12 @@
+-- Refills by the time since the last update, then takes a token if there is one
+INSERT INTO rate_limits (key, tokens, updated)
+    VALUES ($1, $2::float8 - 1, now())
+ON CONFLICT (key)
+    DO UPDATE SET
+        tokens = LEAST($2::float8, rate_limits.tokens + EXTRACT(EPOCH FROM now() - rate_limits.updated)::float8 * $3::float8) - 1,
+        updated = now()
+    WHERE
+        LEAST($2::float8, rate_limits.tokens + EXTRACT(EPOCH FROM now() - rate_limits.updated)::float8 * $3::float8) >= 1
+    RETURNING
+        tokens;
+
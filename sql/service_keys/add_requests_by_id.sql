@@ -0,0 +1,8 @@
+UPDATE
+    service_keys
+SET
+    requests = requests + $2,
+    last_used = $3
+WHERE
+    id = $1;
+
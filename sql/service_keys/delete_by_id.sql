@@ -0,0 +1,3 @@
+DELETE FROM service_keys
+WHERE id = $1;
+
@@ -0,0 +1,7 @@
+SELECT
+    *
+FROM
+    service_keys
+ORDER BY
+    name;
+
@@ -0,0 +1,7 @@
+INSERT INTO service_keys (name, owner, hash, route_groups, quota_per_minute)
+    VALUES ($1, $2, $3, $4, $5)
+ON CONFLICT (name)
+    DO NOTHING
+RETURNING
+    id;
+
@@ -0,0 +1,7 @@
+UPDATE
+    service_keys
+SET
+    hash = $2
+WHERE
+    id = $1;
+
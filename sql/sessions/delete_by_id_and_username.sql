@@ -0,0 +1,4 @@
+DELETE FROM sessions
+WHERE id = $1
+    AND username = $2;
+
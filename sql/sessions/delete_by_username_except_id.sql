@@ -0,0 +1,4 @@
+DELETE FROM sessions
+WHERE username = $1
+    AND id IS DISTINCT FROM $2;
+
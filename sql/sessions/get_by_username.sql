@@ -0,0 +1,10 @@
+SELECT
+    *
+FROM
+    sessions
+WHERE
+    username = $1
+    AND expiry > NOW()
+ORDER BY
+    last_seen DESC;
+
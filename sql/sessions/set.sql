@@ -1,6 +1,6 @@
-INSERT INTO sessions (uuid, username, expiry)
-    VALUES ($1, $2, $3)
+INSERT INTO sessions (uuid, username, expiry, id, created, last_seen, user_agent, ip_hash)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
 ON CONFLICT (uuid)
     DO UPDATE SET
-        username = EXCLUDED.username, expiry = EXCLUDED.expiry;
+        username = EXCLUDED.username, expiry = EXCLUDED.expiry, last_seen = EXCLUDED.last_seen, user_agent = EXCLUDED.user_agent, ip_hash = EXCLUDED.ip_hash;
 
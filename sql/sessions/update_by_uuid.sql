@@ -0,0 +1,10 @@
+UPDATE
+    sessions
+SET
+    username = $2,
+    expiry = $3,
+    user_agent = COALESCE($4, user_agent),
+    ip_hash = COALESCE($5, ip_hash)
+WHERE
+    uuid = $1;
+
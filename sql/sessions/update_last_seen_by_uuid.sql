@@ -0,0 +1,7 @@
+UPDATE
+    sessions
+SET
+    last_seen = now()
+WHERE
+    uuid = $1;
+
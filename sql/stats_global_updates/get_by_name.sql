@@ -0,0 +1,7 @@
+SELECT
+    updated_at
+FROM
+    stats_global_updates
+WHERE
+    name = $1;
+
@@ -0,0 +1,6 @@
+INSERT INTO stats_global_updates (name, updated_at)
+    VALUES ($1, $2)
+ON CONFLICT (name)
+    DO UPDATE SET
+        updated_at = EXCLUDED.updated_at;
+
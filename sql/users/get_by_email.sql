@@ -1,8 +1,10 @@
 SELECT
     username,
     password,
-    email
+    email,
+    email_verified
 FROM
     users
 WHERE
-    email = $1;
+    email = $1
+    AND email_verified;
@@ -1,7 +1,8 @@
 SELECT
     username,
     password,
-    email
+    email,
+    email_verified
 FROM
     users
 WHERE
@@ -1,3 +1,3 @@
-INSERT INTO users (username, PASSWORD, email)
-    VALUES ($1, $2, $3);
+INSERT INTO users (username, PASSWORD, email, email_verified)
+    VALUES ($1, $2, $3, $4);
 
@@ -1,7 +1,9 @@
 UPDATE
     users
 SET
-    email = $2
+    email = $2,
+    email_verified = email_verified
+    AND email IS NOT DISTINCT FROM $2
 WHERE
     username = $1;
 
@@ -0,0 +1,8 @@
+UPDATE
+    users
+SET
+    email_verified = TRUE
+WHERE
+    username = $1
+    AND email = $2;
+
@@ -0,0 +1,15 @@
+SELECT
+    id,
+    username,
+    name,
+    scopes,
+    expires,
+    last_used,
+    created
+FROM
+    users_access_tokens
+WHERE
+    username = $1
+ORDER BY
+    created DESC;
+
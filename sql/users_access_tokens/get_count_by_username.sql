@@ -0,0 +1,7 @@
+SELECT
+    COUNT(*)
+FROM
+    users_access_tokens
+WHERE
+    username = $1;
+
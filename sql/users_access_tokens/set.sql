@@ -0,0 +1,5 @@
+INSERT INTO users_access_tokens (username, name, hash, scopes, expires)
+    VALUES ($1, $2, $3, $4, $5)
+RETURNING
+    id;
+
@@ -0,0 +1,17 @@
+UPDATE
+    users_access_tokens
+SET
+    last_used = now()
+WHERE
+    hash = $1
+    AND (expires IS NULL
+        OR expires > now())
+RETURNING
+    id,
+    username,
+    name,
+    scopes,
+    expires,
+    last_used,
+    created;
+
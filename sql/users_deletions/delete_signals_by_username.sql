@@ -0,0 +1,150 @@
+-- Uids nobody else is verified on
+WITH uids AS (
+    SELECT
+        uid
+    FROM
+        zzz_connections
+    WHERE
+        username = $1
+        AND verified
+        AND NOT EXISTS (
+            SELECT
+                *
+            FROM
+                zzz_connections other
+            WHERE
+                other.uid = zzz_connections.uid
+                AND other.username != $1
+                AND other.verified)
+),
+deleted_zzz_signals_standard AS (
+    DELETE FROM zzz_signals_standard
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_special AS (
+    DELETE FROM zzz_signals_special
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_w_engine AS (
+    DELETE FROM zzz_signals_w_engine
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_bangboo AS (
+    DELETE FROM zzz_signals_bangboo
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_exclusive_rescreening AS (
+    DELETE FROM zzz_signals_exclusive_rescreening
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_w_engine_reverberation AS (
+    DELETE FROM zzz_signals_w_engine_reverberation
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_stats_standard AS (
+    DELETE FROM zzz_signals_stats_standard
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_stats_special AS (
+    DELETE FROM zzz_signals_stats_special
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_stats_w_engine AS (
+    DELETE FROM zzz_signals_stats_w_engine
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_stats_bangboo AS (
+    DELETE FROM zzz_signals_stats_bangboo
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_stats_exclusive_rescreening AS (
+    DELETE FROM zzz_signals_stats_exclusive_rescreening
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_stats_w_engine_reverberation AS (
+    DELETE FROM zzz_signals_stats_w_engine_reverberation
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_stats_global_standard AS (
+    DELETE FROM zzz_signals_stats_global_standard
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_stats_global_special AS (
+    DELETE FROM zzz_signals_stats_global_special
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_stats_global_w_engine AS (
+    DELETE FROM zzz_signals_stats_global_w_engine
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_stats_global_bangboo AS (
+    DELETE FROM zzz_signals_stats_global_bangboo
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_stats_global_exclusive_rescreening AS (
+    DELETE FROM zzz_signals_stats_global_exclusive_rescreening
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_zzz_signals_stats_global_w_engine_reverberation AS (
+    DELETE FROM zzz_signals_stats_global_w_engine_reverberation
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids))
+SELECT
+    uid AS "uid!"
+FROM
+    uids;
+
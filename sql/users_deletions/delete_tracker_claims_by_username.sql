@@ -0,0 +1,10 @@
+DELETE FROM ntehelper_tracker_uid_claim
+WHERE owner_user_id = (
+        SELECT
+            id
+        FROM
+            users
+        WHERE
+            username = $1)
+    AND claim_source = 'self';
+
@@ -0,0 +1,150 @@
+-- Uids nobody else is verified on
+WITH uids AS (
+    SELECT
+        uid
+    FROM
+        connections
+    WHERE
+        username = $1
+        AND verified
+        AND NOT EXISTS (
+            SELECT
+                *
+            FROM
+                connections other
+            WHERE
+                other.uid = connections.uid
+                AND other.username != $1
+                AND other.verified)
+),
+deleted_warps_departure AS (
+    DELETE FROM warps_departure
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_standard AS (
+    DELETE FROM warps_standard
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_special AS (
+    DELETE FROM warps_special
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_lc AS (
+    DELETE FROM warps_lc
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_collab AS (
+    DELETE FROM warps_collab
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_collab_lc AS (
+    DELETE FROM warps_collab_lc
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_stats_departure AS (
+    DELETE FROM warps_stats_departure
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_stats_standard AS (
+    DELETE FROM warps_stats_standard
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_stats_special AS (
+    DELETE FROM warps_stats_special
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_stats_lc AS (
+    DELETE FROM warps_stats_lc
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_stats_collab AS (
+    DELETE FROM warps_stats_collab
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_stats_collab_lc AS (
+    DELETE FROM warps_stats_collab_lc
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_stats_global_departure AS (
+    DELETE FROM warps_stats_global_departure
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_stats_global_standard AS (
+    DELETE FROM warps_stats_global_standard
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_stats_global_special AS (
+    DELETE FROM warps_stats_global_special
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_stats_global_lc AS (
+    DELETE FROM warps_stats_global_lc
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_stats_global_collab AS (
+    DELETE FROM warps_stats_global_collab
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_warps_stats_global_collab_lc AS (
+    DELETE FROM warps_stats_global_collab_lc
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids))
+SELECT
+    uid AS "uid!"
+FROM
+    uids;
+
@@ -0,0 +1,129 @@
+-- Uids nobody else is verified on
+WITH uids AS (
+    SELECT
+        uid
+    FROM
+        gi_connections
+    WHERE
+        username = $1
+        AND verified
+        AND NOT EXISTS (
+            SELECT
+                *
+            FROM
+                gi_connections other
+            WHERE
+                other.uid = gi_connections.uid
+                AND other.username != $1
+                AND other.verified)
+),
+deleted_gi_wishes_beginner AS (
+    DELETE FROM gi_wishes_beginner
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_standard AS (
+    DELETE FROM gi_wishes_standard
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_character AS (
+    DELETE FROM gi_wishes_character
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_weapon AS (
+    DELETE FROM gi_wishes_weapon
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_chronicled AS (
+    DELETE FROM gi_wishes_chronicled
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_stats_beginner AS (
+    DELETE FROM gi_wishes_stats_beginner
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_stats_standard AS (
+    DELETE FROM gi_wishes_stats_standard
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_stats_character AS (
+    DELETE FROM gi_wishes_stats_character
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_stats_weapon AS (
+    DELETE FROM gi_wishes_stats_weapon
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_stats_chronicled AS (
+    DELETE FROM gi_wishes_stats_chronicled
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_stats_global_beginner AS (
+    DELETE FROM gi_wishes_stats_global_beginner
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_stats_global_standard AS (
+    DELETE FROM gi_wishes_stats_global_standard
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_stats_global_character AS (
+    DELETE FROM gi_wishes_stats_global_character
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_stats_global_weapon AS (
+    DELETE FROM gi_wishes_stats_global_weapon
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids)),
+deleted_gi_wishes_stats_global_chronicled AS (
+    DELETE FROM gi_wishes_stats_global_chronicled
+    WHERE uid IN (
+            SELECT
+                uid
+            FROM
+                uids))
+SELECT
+    uid AS "uid!"
+FROM
+    uids;
+
@@ -0,0 +1,9 @@
+SELECT
+    username,
+    delete_pulls,
+    export_hash
+FROM
+    users_deletions
+WHERE
+    scheduled <= now();
+
@@ -0,0 +1,7 @@
+SELECT
+    scheduled
+FROM
+    users_deletions
+WHERE
+    username = $1;
+
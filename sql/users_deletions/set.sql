@@ -0,0 +1,9 @@
+INSERT INTO users_deletions (username, delete_pulls, export_hash, scheduled)
+    VALUES ($1, $2, $3, $4)
+ON CONFLICT (username)
+    DO UPDATE SET
+        delete_pulls = EXCLUDED.delete_pulls,
+        export_hash = EXCLUDED.export_hash,
+        scheduled = EXCLUDED.scheduled,
+        created = now();
+
@@ -0,0 +1,4 @@
+DELETE FROM users_identities
+WHERE provider = $1
+    AND username = $2;
+
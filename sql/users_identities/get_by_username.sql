@@ -0,0 +1,11 @@
+SELECT
+    provider,
+    email,
+    created
+FROM
+    users_identities
+WHERE
+    username = $1
+ORDER BY
+    provider;
+
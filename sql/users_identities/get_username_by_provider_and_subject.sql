@@ -0,0 +1,8 @@
+SELECT
+    username
+FROM
+    users_identities
+WHERE
+    provider = $1
+    AND subject = $2;
+
@@ -0,0 +1,5 @@
+INSERT INTO users_identities (provider, subject, username, email)
+    VALUES ($1, $2, $3, $4)
+ON CONFLICT
+    DO NOTHING;
+
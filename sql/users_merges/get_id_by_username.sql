@@ -0,0 +1,9 @@
+-- Locks the user, so nothing is added to it while it's merged
+SELECT
+    id
+FROM
+    users
+WHERE
+    username = $1
+FOR UPDATE;
+
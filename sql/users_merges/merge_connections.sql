@@ -0,0 +1,59 @@
+-- Uids both are connected to keep the strongest verification and the surviving privacy setting
+WITH source AS (
+    SELECT
+        uid,
+        verified,
+        private
+    FROM
+        connections
+    WHERE
+        username = $1
+),
+merged AS (
+    UPDATE
+        connections
+    SET
+        verified = connections.verified
+        OR source.verified
+    FROM
+        source
+    WHERE
+        connections.username = $2
+        AND connections.uid = source.uid
+    RETURNING
+        connections.uid
+),
+moved AS (
+INSERT INTO connections (uid, username, verified, private)
+    SELECT
+        uid,
+        $2,
+        verified,
+        private
+    FROM
+        source
+    WHERE
+        NOT EXISTS (
+            SELECT
+                *
+            FROM
+                connections
+            WHERE
+                username = $2
+                AND uid = source.uid)
+    ON CONFLICT
+        DO NOTHING
+    RETURNING
+        uid
+)
+SELECT
+    (
+        SELECT
+            count(*)
+        FROM moved) AS "moved!",
+    (
+        SELECT
+            count(*)
+        FROM merged) AS "merged!",
+    0::bigint AS "dropped!";
+
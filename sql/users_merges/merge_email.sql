@@ -0,0 +1,16 @@
+-- Only fills in a missing email, the surviving one is never replaced
+UPDATE
+    users
+SET
+    email = other.email,
+    email_verified = other.email_verified
+FROM
+    users other
+WHERE
+    users.username = $2
+    AND other.username = $1
+    AND users.email IS NULL
+    AND other.email IS NOT NULL
+RETURNING
+    users.username;
+
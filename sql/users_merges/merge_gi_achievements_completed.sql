@@ -0,0 +1,44 @@
+-- Achievements both completed keep the earliest completion and the known progress
+WITH source AS (
+    SELECT
+        id,
+        completed_at,
+        imported,
+        current
+    FROM
+        gi_users_achievements_completed
+    WHERE
+        username = $1
+),
+upserted AS (
+INSERT INTO gi_users_achievements_completed (username, id, completed_at, imported, current)
+    SELECT
+        $2,
+        id,
+        completed_at,
+        imported,
+        current
+    FROM
+        source
+    ON CONFLICT (username,
+        id)
+        DO UPDATE SET
+            completed_at = LEAST (gi_users_achievements_completed.completed_at, EXCLUDED.completed_at),
+            current = COALESCE(gi_users_achievements_completed.current, EXCLUDED.current)
+        RETURNING
+            xmax = 0 AS inserted
+)
+SELECT
+    (
+        SELECT
+            count(*)
+        FROM upserted
+        WHERE
+            inserted) AS "moved!",
+    (
+        SELECT
+            count(*)
+        FROM upserted
+        WHERE
+            NOT inserted) AS "merged!",
+    0::bigint AS "dropped!";
@@ -0,0 +1,34 @@
+WITH source AS (
+    SELECT
+        id
+    FROM
+        gi_users_achievements_favorites
+    WHERE
+        username = $1
+),
+moved AS (
+INSERT INTO gi_users_achievements_favorites (username, id)
+    SELECT
+        $2,
+        id
+    FROM
+        source
+    ON CONFLICT
+        DO NOTHING
+    RETURNING
+        id
+)
+SELECT
+    (
+        SELECT
+            count(*)
+        FROM moved) AS "moved!",
+    (
+        SELECT
+            count(*)
+        FROM source) - (
+        SELECT
+            count(*)
+        FROM moved) AS "merged!",
+    0::bigint AS "dropped!";
+
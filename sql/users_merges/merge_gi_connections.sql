@@ -0,0 +1,59 @@
+-- Uids both are connected to keep the strongest verification and the surviving privacy setting
+WITH source AS (
+    SELECT
+        uid,
+        verified,
+        private
+    FROM
+        gi_connections
+    WHERE
+        username = $1
+),
+merged AS (
+    UPDATE
+        gi_connections
+    SET
+        verified = gi_connections.verified
+        OR source.verified
+    FROM
+        source
+    WHERE
+        gi_connections.username = $2
+        AND gi_connections.uid = source.uid
+    RETURNING
+        gi_connections.uid
+),
+moved AS (
+INSERT INTO gi_connections (uid, username, verified, private)
+    SELECT
+        uid,
+        $2,
+        verified,
+        private
+    FROM
+        source
+    WHERE
+        NOT EXISTS (
+            SELECT
+                *
+            FROM
+                gi_connections
+            WHERE
+                username = $2
+                AND uid = source.uid)
+    ON CONFLICT
+        DO NOTHING
+    RETURNING
+        uid
+)
+SELECT
+    (
+        SELECT
+            count(*)
+        FROM moved) AS "moved!",
+    (
+        SELECT
+            count(*)
+        FROM merged) AS "merged!",
+    0::bigint AS "dropped!";
+
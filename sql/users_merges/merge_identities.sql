@@ -0,0 +1,35 @@
+-- An account can only have one identity per provider, so the surviving one wins
+WITH moved AS (
+    UPDATE
+        users_identities
+    SET
+        username = $2
+    WHERE
+        username = $1
+        AND provider NOT IN (
+            SELECT
+                provider
+            FROM
+                users_identities
+            WHERE
+                username = $2)
+    RETURNING
+        provider
+)
+SELECT
+    (
+        SELECT
+            count(*)
+        FROM moved) AS "moved!",
+    0::bigint AS "merged!",
+    (
+        SELECT
+            count(*)
+        FROM
+            users_identities
+        WHERE
+            username = $1) - (
+        SELECT
+            count(*)
+        FROM moved) AS "dropped!";
+
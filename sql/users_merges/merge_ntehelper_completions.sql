@@ -0,0 +1,38 @@
+WITH source AS (
+    SELECT
+        kind,
+        id,
+        completed_at
+    FROM
+        ntehelper_user_completion
+    WHERE
+        user_id = $1
+),
+moved AS (
+INSERT INTO ntehelper_user_completion (user_id, kind, id, completed_at)
+    SELECT
+        $2,
+        kind,
+        id,
+        completed_at
+    FROM
+        source
+    ON CONFLICT
+        DO NOTHING
+    RETURNING
+        id
+)
+SELECT
+    (
+        SELECT
+            count(*)
+        FROM moved) AS "moved!",
+    (
+        SELECT
+            count(*)
+        FROM source) - (
+        SELECT
+            count(*)
+        FROM moved) AS "merged!",
+    0::bigint AS "dropped!";
+
@@ -0,0 +1,41 @@
+-- Comments both voted on keep the surviving vote
+WITH source AS (
+    SELECT
+        comment_id,
+        value,
+        created_at,
+        updated_at
+    FROM
+        ntehelper_marker_comment_vote
+    WHERE
+        user_id = $1
+),
+moved AS (
+INSERT INTO ntehelper_marker_comment_vote (comment_id, user_id, value, created_at, updated_at)
+    SELECT
+        comment_id,
+        $2,
+        value,
+        created_at,
+        updated_at
+    FROM
+        source
+    ON CONFLICT
+        DO NOTHING
+    RETURNING
+        comment_id
+)
+SELECT
+    (
+        SELECT
+            count(*)
+        FROM moved) AS "moved!",
+    0::bigint AS "merged!",
+    (
+        SELECT
+            count(*)
+        FROM source) - (
+        SELECT
+            count(*)
+        FROM moved) AS "dropped!";
+
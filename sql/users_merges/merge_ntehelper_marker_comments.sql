@@ -0,0 +1,17 @@
+WITH moved AS (
+    UPDATE
+        ntehelper_marker_comment
+    SET
+        user_id = $2
+    WHERE
+        user_id = $1
+    RETURNING
+        id
+)
+SELECT
+    count(*) AS "moved!",
+    0::bigint AS "merged!",
+    0::bigint AS "dropped!"
+FROM
+    moved;
+
@@ -0,0 +1,39 @@
+-- Namespaces both have keep the surviving settings
+WITH source AS (
+    SELECT
+        namespace,
+        data,
+        updated_at
+    FROM
+        ntehelper_user_settings
+    WHERE
+        user_id = $1
+),
+moved AS (
+INSERT INTO ntehelper_user_settings (user_id, namespace, data, updated_at)
+    SELECT
+        $2,
+        namespace,
+        data,
+        updated_at
+    FROM
+        source
+    ON CONFLICT
+        DO NOTHING
+    RETURNING
+        namespace
+)
+SELECT
+    (
+        SELECT
+            count(*)
+        FROM moved) AS "moved!",
+    0::bigint AS "merged!",
+    (
+        SELECT
+            count(*)
+        FROM source) - (
+        SELECT
+            count(*)
+        FROM moved) AS "dropped!";
+
@@ -0,0 +1,18 @@
+WITH moved AS (
+    UPDATE
+        ntehelper_tracker_uid_claim
+    SET
+        owner_user_id = $2,
+        updated_at = now()
+    WHERE
+        owner_user_id = $1
+    RETURNING
+        uid
+)
+SELECT
+    count(*) AS "moved!",
+    0::bigint AS "merged!",
+    0::bigint AS "dropped!"
+FROM
+    moved;
+
@@ -0,0 +1,41 @@
+-- Achievements both completed keep the earliest completion
+WITH source AS (
+    SELECT
+        id,
+        completed_at,
+        imported
+    FROM
+        zzz_users_achievements_completed
+    WHERE
+        username = $1
+),
+upserted AS (
+INSERT INTO zzz_users_achievements_completed (username, id, completed_at, imported)
+    SELECT
+        $2,
+        id,
+        completed_at,
+        imported
+    FROM
+        source
+    ON CONFLICT (username,
+        id)
+        DO UPDATE SET
+            completed_at = LEAST (zzz_users_achievements_completed.completed_at, EXCLUDED.completed_at)
+        RETURNING
+            xmax = 0 AS inserted
+)
+SELECT
+    (
+        SELECT
+            count(*)
+        FROM upserted
+        WHERE
+            inserted) AS "moved!",
+    (
+        SELECT
+            count(*)
+        FROM upserted
+        WHERE
+            NOT inserted) AS "merged!",
+    0::bigint AS "dropped!";
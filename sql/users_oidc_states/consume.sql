@@ -0,0 +1,10 @@
+DELETE FROM users_oidc_states
+WHERE hash = $1
+    AND provider = $2
+    AND expires > now()
+RETURNING
+    code_verifier,
+    nonce,
+    username,
+    redirect;
+
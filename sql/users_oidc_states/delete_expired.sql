@@ -0,0 +1,3 @@
+DELETE FROM users_oidc_states
+WHERE expires <= now();
+
@@ -0,0 +1,3 @@
+INSERT INTO users_oidc_states (hash, provider, code_verifier, nonce, username, redirect, expires)
+    VALUES ($1, $2, $3, $4, $5, $6, $7);
+
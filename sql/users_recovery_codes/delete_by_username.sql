@@ -0,0 +1,3 @@
+DELETE FROM users_recovery_codes
+WHERE username = $1;
+
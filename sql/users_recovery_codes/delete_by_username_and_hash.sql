@@ -0,0 +1,4 @@
+DELETE FROM users_recovery_codes
+WHERE username = $1
+    AND hash = $2;
+
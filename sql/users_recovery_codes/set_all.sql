@@ -0,0 +1,5 @@
+INSERT INTO users_recovery_codes (username, hash)
+SELECT
+    $1,
+    unnest($2::bytea[]);
+
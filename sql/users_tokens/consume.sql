@@ -0,0 +1,11 @@
+DELETE FROM users_tokens
+WHERE hash = $1
+    AND purpose = $2
+    AND expires > now()
+RETURNING
+    hash,
+    username,
+    purpose,
+    email,
+    expires;
+
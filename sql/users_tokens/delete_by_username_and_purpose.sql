@@ -0,0 +1,4 @@
+DELETE FROM users_tokens
+WHERE username = $1
+    AND purpose = $2;
+
@@ -0,0 +1,3 @@
+DELETE FROM users_tokens
+WHERE expires <= now();
+
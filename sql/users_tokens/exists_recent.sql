@@ -0,0 +1,11 @@
+SELECT
+    EXISTS (
+        SELECT
+            *
+        FROM
+            users_tokens
+        WHERE
+            username = $1
+            AND purpose = $2
+            AND created > $3);
+
@@ -0,0 +1,14 @@
+SELECT
+    hash,
+    username,
+    purpose,
+    email,
+    expires
+FROM
+    users_tokens
+WHERE
+    hash = $1
+    AND purpose = $2
+    AND expires > now()
+    AND attempts < $3;
+
@@ -0,0 +1,7 @@
+UPDATE
+    users_tokens
+SET
+    attempts = attempts + 1
+WHERE
+    hash = $1;
+
@@ -0,0 +1,3 @@
+INSERT INTO users_tokens (hash, username, purpose, email, expires, created)
+    VALUES ($1, $2, $3, $4, $5, now());
+
@@ -0,0 +1,3 @@
+DELETE FROM users_totp
+WHERE username = $1;
+
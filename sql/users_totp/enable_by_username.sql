@@ -0,0 +1,7 @@
+UPDATE
+    users_totp
+SET
+    enabled = TRUE
+WHERE
+    username = $1;
+
@@ -0,0 +1,10 @@
+SELECT
+    secret,
+    enabled,
+    failures,
+    failed_at
+FROM
+    users_totp
+WHERE
+    username = $1;
+
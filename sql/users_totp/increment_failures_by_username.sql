@@ -0,0 +1,12 @@
+UPDATE
+    users_totp
+SET
+    failures = CASE WHEN failed_at > $2 THEN
+        failures + 1
+    ELSE
+        1
+    END,
+    failed_at = now()
+WHERE
+    username = $1;
+
@@ -0,0 +1,8 @@
+UPDATE
+    users_totp
+SET
+    failures = 0,
+    failed_at = NULL
+WHERE
+    username = $1;
+
@@ -0,0 +1,8 @@
+INSERT INTO users_totp (username, secret)
+    VALUES ($1, $2)
+ON CONFLICT (username)
+    DO UPDATE SET
+        secret = EXCLUDED.secret, last_step = 0, created = now()
+    WHERE
+        NOT users_totp.enabled;
+
@@ -0,0 +1,8 @@
+UPDATE
+    users_totp
+SET
+    last_step = $2
+WHERE
+    username = $1
+    AND last_step < $2;
+
@@ -0,0 +1,14 @@
+SELECT
+    warps_departure.character,
+    warps_departure.light_cone,
+    COALESCE(characters.rarity, light_cones.rarity) AS rarity,
+    warps_departure.timestamp
+FROM
+    warps_departure
+    LEFT JOIN characters ON characters.id = character
+    LEFT JOIN light_cones ON light_cones.id = light_cone
+WHERE
+    uid = $1
+ORDER BY
+    warps_departure.id;
+
@@ -0,0 +1,9 @@
+SELECT
+    uid,
+    luck_4,
+    luck_5
+FROM
+    warps_stats_departure
+WHERE
+    uid = $1;
+
@@ -0,0 +1,5 @@
+SELECT
+    max(updated_at)
+FROM
+    warps_stats_departure;
+
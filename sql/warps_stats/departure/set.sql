@@ -0,0 +1,12 @@
+INSERT INTO warps_stats_departure (uid, luck_4, luck_5, count)
+    VALUES ($1, $2, $3, (
+            SELECT
+                count(*)
+            FROM
+                warps_departure
+            WHERE
+                uid = $1))
+ON CONFLICT (uid)
+    DO UPDATE SET
+        luck_4 = EXCLUDED.luck_4, luck_5 = EXCLUDED.luck_5, count = EXCLUDED.count, updated_at = now();
+
@@ -1,5 +1,7 @@
 SELECT
-    *
+    uid,
+    luck_4,
+    luck_5
 FROM
     warps_stats_standard
 WHERE
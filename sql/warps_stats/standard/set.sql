@@ -1,6 +1,12 @@
-INSERT INTO warps_stats_standard (uid, luck_4, luck_5)
-    VALUES ($1, $2, $3)
+INSERT INTO warps_stats_standard (uid, luck_4, luck_5, count)
+    VALUES ($1, $2, $3, (
+            SELECT
+                count(*)
+            FROM
+                warps_standard
+            WHERE
+                uid = $1))
 ON CONFLICT (uid)
     DO UPDATE SET
-        luck_4 = EXCLUDED.luck_4, luck_5 = EXCLUDED.luck_5;
+        luck_4 = EXCLUDED.luck_4, luck_5 = EXCLUDED.luck_5, count = EXCLUDED.count, updated_at = now();
 
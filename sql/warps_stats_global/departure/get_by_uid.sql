@@ -0,0 +1,9 @@
+SELECT
+    count_percentile,
+    luck_4_percentile,
+    luck_5_percentile
+FROM
+    warps_stats_global_departure
+WHERE
+    uid = $1;
+
@@ -0,0 +1,3 @@
+DELETE FROM zzz_banners
+WHERE id = $1;
+
@@ -0,0 +1,5 @@
+SELECT
+    *
+FROM
+    zzz_banners;
+
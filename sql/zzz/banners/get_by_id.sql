@@ -0,0 +1,7 @@
+SELECT
+    *
+FROM
+    zzz_banners
+WHERE
+    id = $1;
+
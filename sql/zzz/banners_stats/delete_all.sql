@@ -0,0 +1,2 @@
+DELETE FROM zzz_banners_stats;
+
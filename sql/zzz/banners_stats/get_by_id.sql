@@ -0,0 +1,7 @@
+SELECT
+    *
+FROM
+    zzz_banners_stats
+WHERE
+    id = $1;
+
@@ -0,0 +1,54 @@
+-- Concurrent banners of the same kind share their pulls
+WITH pulls AS (
+    SELECT
+        'character' AS kind,
+        uid,
+        timestamp
+    FROM
+        zzz_signals_special
+    WHERE
+        official
+    UNION ALL
+    SELECT
+        'character' AS kind,
+        uid,
+        timestamp
+    FROM
+        zzz_signals_exclusive_rescreening
+    WHERE
+        official
+    UNION ALL
+    SELECT
+        'w_engine' AS kind,
+        uid,
+        timestamp
+    FROM
+        zzz_signals_w_engine
+    WHERE
+        official
+    UNION ALL
+    SELECT
+        'w_engine' AS kind,
+        uid,
+        timestamp
+    FROM
+        zzz_signals_w_engine_reverberation
+    WHERE
+        official
+)
+SELECT
+    zzz_banners.id,
+    COUNT(*) AS "pulls!",
+    COUNT(DISTINCT pulls.uid) AS "uids!"
+FROM
+    zzz_banners
+    INNER JOIN pulls ON pulls.kind = CASE WHEN zzz_banners.character IS NOT NULL THEN
+        'character'
+    ELSE
+        'w_engine'
+    END
+    AND pulls.timestamp >= zzz_banners.start
+    AND pulls.timestamp < zzz_banners."end"
+GROUP BY
+    zzz_banners.id;
+
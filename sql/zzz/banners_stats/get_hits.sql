@@ -0,0 +1,83 @@
+-- Hits are pulls of the top rarity. S-Rank is rarity 4. `pity` counts the pulls since the previous hit of the
+-- same source and is NULL for the first one, because the pity it started with is unknown.
+WITH pulls AS (
+    SELECT
+        'special' AS source,
+        'character' AS kind,
+        zzz_signals_special.uid,
+        zzz_signals_special.id,
+        zzz_signals_special.timestamp,
+        zzz_signals_special.official,
+        COALESCE(zzz_signals_special.character, zzz_signals_special.w_engine) AS item,
+        COALESCE(zzz_characters.rarity, zzz_w_engines.rarity) AS rarity
+    FROM
+        zzz_signals_special
+        LEFT JOIN zzz_characters ON zzz_characters.id = zzz_signals_special.character
+        LEFT JOIN zzz_w_engines ON zzz_w_engines.id = zzz_signals_special.w_engine
+    UNION ALL
+    SELECT
+        'exclusive_rescreening' AS source,
+        'character' AS kind,
+        zzz_signals_exclusive_rescreening.uid,
+        zzz_signals_exclusive_rescreening.id,
+        zzz_signals_exclusive_rescreening.timestamp,
+        zzz_signals_exclusive_rescreening.official,
+        COALESCE(zzz_signals_exclusive_rescreening.character, zzz_signals_exclusive_rescreening.w_engine) AS item,
+        COALESCE(zzz_characters.rarity, zzz_w_engines.rarity) AS rarity
+    FROM
+        zzz_signals_exclusive_rescreening
+        LEFT JOIN zzz_characters ON zzz_characters.id = zzz_signals_exclusive_rescreening.character
+        LEFT JOIN zzz_w_engines ON zzz_w_engines.id = zzz_signals_exclusive_rescreening.w_engine
+    UNION ALL
+    SELECT
+        'w_engine' AS source,
+        'w_engine' AS kind,
+        zzz_signals_w_engine.uid,
+        zzz_signals_w_engine.id,
+        zzz_signals_w_engine.timestamp,
+        zzz_signals_w_engine.official,
+        COALESCE(zzz_signals_w_engine.character, zzz_signals_w_engine.w_engine) AS item,
+        COALESCE(zzz_characters.rarity, zzz_w_engines.rarity) AS rarity
+    FROM
+        zzz_signals_w_engine
+        LEFT JOIN zzz_characters ON zzz_characters.id = zzz_signals_w_engine.character
+        LEFT JOIN zzz_w_engines ON zzz_w_engines.id = zzz_signals_w_engine.w_engine
+    UNION ALL
+    SELECT
+        'w_engine_reverberation' AS source,
+        'w_engine' AS kind,
+        zzz_signals_w_engine_reverberation.uid,
+        zzz_signals_w_engine_reverberation.id,
+        zzz_signals_w_engine_reverberation.timestamp,
+        zzz_signals_w_engine_reverberation.official,
+        COALESCE(zzz_signals_w_engine_reverberation.character, zzz_signals_w_engine_reverberation.w_engine) AS item,
+        COALESCE(zzz_characters.rarity, zzz_w_engines.rarity) AS rarity
+    FROM
+        zzz_signals_w_engine_reverberation
+        LEFT JOIN zzz_characters ON zzz_characters.id = zzz_signals_w_engine_reverberation.character
+        LEFT JOIN zzz_w_engines ON zzz_w_engines.id = zzz_signals_w_engine_reverberation.w_engine
+),
+numbered AS (
+    SELECT
+        *,
+        ROW_NUMBER() OVER (PARTITION BY source, uid ORDER BY id) AS pull
+    FROM
+        pulls
+)
+SELECT
+    source AS "source!",
+    kind AS "kind!",
+    uid AS "uid!",
+    timestamp AS "timestamp!",
+    official AS "official!",
+    item AS "item!",
+    (pull - LAG(pull) OVER (PARTITION BY source, uid ORDER BY id))::integer AS pity
+FROM
+    numbered
+WHERE
+    rarity = 4
+ORDER BY
+    source,
+    uid,
+    id;
+
@@ -0,0 +1,9 @@
+SELECT
+    *
+FROM
+    zzz_pity_curves
+ORDER BY
+    banner,
+    rarity,
+    pity;
+
@@ -0,0 +1,129 @@
+-- Pity counts all pulls, but only official ones are counted. Pulls before the first hit of a uid
+-- are left out, because the pity they started with is unknown.
+-- S-Rank and A-Rank are rarity 4 and 3, they are stored as 5 and 4 like in the other games
+WITH pulls AS (
+    SELECT
+        'standard' AS banner,
+        zzz_signals_standard.uid,
+        zzz_signals_standard.id,
+        zzz_signals_standard.official,
+        COALESCE(zzz_characters.rarity, zzz_w_engines.rarity) AS rarity
+    FROM
+        zzz_signals_standard
+        LEFT JOIN zzz_characters ON zzz_characters.id = zzz_signals_standard.character
+        LEFT JOIN zzz_w_engines ON zzz_w_engines.id = zzz_signals_standard.w_engine
+    UNION ALL
+    SELECT
+        'special' AS banner,
+        zzz_signals_special.uid,
+        zzz_signals_special.id,
+        zzz_signals_special.official,
+        COALESCE(zzz_characters.rarity, zzz_w_engines.rarity) AS rarity
+    FROM
+        zzz_signals_special
+        LEFT JOIN zzz_characters ON zzz_characters.id = zzz_signals_special.character
+        LEFT JOIN zzz_w_engines ON zzz_w_engines.id = zzz_signals_special.w_engine
+    UNION ALL
+    SELECT
+        'w_engine' AS banner,
+        zzz_signals_w_engine.uid,
+        zzz_signals_w_engine.id,
+        zzz_signals_w_engine.official,
+        COALESCE(zzz_characters.rarity, zzz_w_engines.rarity) AS rarity
+    FROM
+        zzz_signals_w_engine
+        LEFT JOIN zzz_characters ON zzz_characters.id = zzz_signals_w_engine.character
+        LEFT JOIN zzz_w_engines ON zzz_w_engines.id = zzz_signals_w_engine.w_engine
+    UNION ALL
+    SELECT
+        'bangboo' AS banner,
+        zzz_signals_bangboo.uid,
+        zzz_signals_bangboo.id,
+        zzz_signals_bangboo.official,
+        COALESCE(zzz_bangboos.rarity, zzz_w_engines.rarity) AS rarity
+    FROM
+        zzz_signals_bangboo
+        LEFT JOIN zzz_bangboos ON zzz_bangboos.id = zzz_signals_bangboo.bangboo
+        LEFT JOIN zzz_w_engines ON zzz_w_engines.id = zzz_signals_bangboo.w_engine
+    UNION ALL
+    SELECT
+        'exclusive_rescreening' AS banner,
+        zzz_signals_exclusive_rescreening.uid,
+        zzz_signals_exclusive_rescreening.id,
+        zzz_signals_exclusive_rescreening.official,
+        COALESCE(zzz_characters.rarity, zzz_w_engines.rarity) AS rarity
+    FROM
+        zzz_signals_exclusive_rescreening
+        LEFT JOIN zzz_characters ON zzz_characters.id = zzz_signals_exclusive_rescreening.character
+        LEFT JOIN zzz_w_engines ON zzz_w_engines.id = zzz_signals_exclusive_rescreening.w_engine
+    UNION ALL
+    SELECT
+        'w_engine_reverberation' AS banner,
+        zzz_signals_w_engine_reverberation.uid,
+        zzz_signals_w_engine_reverberation.id,
+        zzz_signals_w_engine_reverberation.official,
+        COALESCE(zzz_characters.rarity, zzz_w_engines.rarity) AS rarity
+    FROM
+        zzz_signals_w_engine_reverberation
+        LEFT JOIN zzz_characters ON zzz_characters.id = zzz_signals_w_engine_reverberation.character
+        LEFT JOIN zzz_w_engines ON zzz_w_engines.id = zzz_signals_w_engine_reverberation.w_engine
+),
+segments AS (
+    SELECT
+        banner,
+        uid,
+        id,
+        official,
+        rarity,
+        COUNT(*) FILTER (WHERE rarity = 4) OVER previous AS segment_5,
+        COUNT(*) FILTER (WHERE rarity = 3) OVER previous AS segment_4
+    FROM
+        pulls
+    WINDOW previous AS (PARTITION BY banner, uid ORDER BY id ROWS BETWEEN UNBOUNDED PRECEDING AND 1 PRECEDING)
+),
+pities AS (
+    SELECT
+        banner,
+        official,
+        rarity,
+        segment_5,
+        segment_4,
+        ROW_NUMBER() OVER (PARTITION BY banner, uid, segment_5 ORDER BY id) AS pity_5,
+        ROW_NUMBER() OVER (PARTITION BY banner, uid, segment_4 ORDER BY id) AS pity_4
+    FROM
+        segments
+)
+SELECT
+    banner AS "banner!",
+    5 AS "rarity!",
+    pity_5::integer AS "pity!",
+    COUNT(*) AS "pulls!",
+    COUNT(*) FILTER (WHERE rarity = 4) AS "hits!"
+FROM
+    pities
+WHERE
+    official
+    AND segment_5 > 0
+GROUP BY
+    banner,
+    pity_5
+UNION ALL
+SELECT
+    banner,
+    4,
+    pity_4::integer,
+    COUNT(*),
+    COUNT(*) FILTER (WHERE rarity = 3)
+FROM
+    pities
+WHERE
+    official
+    AND segment_4 > 0
+GROUP BY
+    banner,
+    pity_4
+ORDER BY
+    1,
+    2,
+    3;
+
@@ -0,0 +1,6 @@
+INSERT INTO zzz_pity_curves (banner, rarity, pity, pulls, hits, rate, lower, upper)
+SELECT
+    *
+FROM
+    UNNEST($1::text[], $2::integer[], $3::integer[], $4::bigint[], $5::bigint[], $6::double precision[], $7::double precision[], $8::double precision[]);
+
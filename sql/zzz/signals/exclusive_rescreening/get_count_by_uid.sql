@@ -1,7 +0,0 @@
-SELECT
-    count(*)
-FROM
-    zzz_signals_exclusive_rescreening
-WHERE
-    uid = $1;
-
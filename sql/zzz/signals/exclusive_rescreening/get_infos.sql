@@ -1,7 +1,8 @@
 SELECT
     zzz_signals_exclusive_rescreening.character,
     zzz_signals_exclusive_rescreening.w_engine,
-    COALESCE(zzz_characters.rarity, zzz_w_engines.rarity) AS rarity
+    COALESCE(zzz_characters.rarity, zzz_w_engines.rarity) AS rarity,
+    zzz_signals_exclusive_rescreening.timestamp
 FROM
     zzz_signals_exclusive_rescreening
     LEFT JOIN zzz_characters ON zzz_characters.id = character
@@ -1,7 +0,0 @@
-SELECT
-    count(*)
-FROM
-    zzz_signals_special
-WHERE
-    uid = $1;
-
@@ -1,7 +0,0 @@
-SELECT
-    count(*)
-FROM
-    zzz_signals_standard
-WHERE
-    uid = $1;
-
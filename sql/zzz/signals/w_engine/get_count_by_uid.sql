@@ -1,7 +0,0 @@
-SELECT
-    count(*)
-FROM
-    zzz_signals_w_engine
-WHERE
-    uid = $1;
-
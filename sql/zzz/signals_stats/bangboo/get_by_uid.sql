@@ -1,5 +1,7 @@
 SELECT
-    *
+    uid,
+    luck_a,
+    luck_s
 FROM
     zzz_signals_stats_bangboo
 WHERE
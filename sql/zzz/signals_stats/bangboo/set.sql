@@ -1,6 +1,12 @@
-INSERT INTO zzz_signals_stats_bangboo (uid, luck_a, luck_s)
-    VALUES ($1, $2, $3)
+INSERT INTO zzz_signals_stats_bangboo (uid, luck_a, luck_s, count)
+    VALUES ($1, $2, $3, (
+            SELECT
+                count(*)
+            FROM
+                zzz_signals_bangboo
+            WHERE
+                uid = $1))
 ON CONFLICT (uid)
     DO UPDATE SET
-        luck_a = EXCLUDED.luck_a, luck_s = EXCLUDED.luck_s;
+        luck_a = EXCLUDED.luck_a, luck_s = EXCLUDED.luck_s, count = EXCLUDED.count, updated_at = now();
 
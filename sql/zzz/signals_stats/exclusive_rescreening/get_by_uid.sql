@@ -0,0 +1,12 @@
+SELECT
+    uid,
+    luck_a,
+    luck_s,
+    win_rate,
+    win_streak,
+    loss_streak
+FROM
+    zzz_signals_stats_exclusive_rescreening
+WHERE
+    uid = $1;
+
@@ -1,5 +1,10 @@
 SELECT
-    *
+    uid,
+    luck_a,
+    luck_s,
+    win_rate,
+    win_streak,
+    loss_streak
 FROM
     zzz_signals_stats_special
 WHERE
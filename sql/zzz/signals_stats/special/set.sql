@@ -1,6 +1,12 @@
-INSERT INTO zzz_signals_stats_special (uid, luck_a, luck_s, win_rate, win_streak, loss_streak)
-    VALUES ($1, $2, $3, $4, $5, $6)
+INSERT INTO zzz_signals_stats_special (uid, luck_a, luck_s, win_rate, win_streak, loss_streak, count)
+    VALUES ($1, $2, $3, $4, $5, $6, (
+            SELECT
+                count(*)
+            FROM
+                zzz_signals_special
+            WHERE
+                uid = $1))
 ON CONFLICT (uid)
     DO UPDATE SET
-        luck_a = EXCLUDED.luck_a, luck_s = EXCLUDED.luck_s, win_rate = EXCLUDED.win_rate, win_streak = EXCLUDED.win_streak, loss_streak = EXCLUDED.loss_streak;
+        luck_a = EXCLUDED.luck_a, luck_s = EXCLUDED.luck_s, win_rate = EXCLUDED.win_rate, win_streak = EXCLUDED.win_streak, loss_streak = EXCLUDED.loss_streak, count = EXCLUDED.count, updated_at = now();
 
@@ -1,5 +1,5 @@
 SELECT
-    *
+    max(updated_at)
 FROM
     zzz_signals_stats_w_engine;
 
@@ -0,0 +1,12 @@
+SELECT
+    uid,
+    luck_a,
+    luck_s,
+    win_rate,
+    win_streak,
+    loss_streak
+FROM
+    zzz_signals_stats_w_engine_reverberation
+WHERE
+    uid = $1;
+
@@ -1,3 +0,0 @@
-DELETE FROM zzz_signals_stats_global_bangboo
-WHERE uid = $1;
-
@@ -1,5 +1,7 @@
 SELECT
-    *
+    count_percentile,
+    luck_a_percentile,
+    luck_s_percentile
 FROM
     zzz_signals_stats_global_bangboo
 WHERE
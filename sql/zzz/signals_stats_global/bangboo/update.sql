@@ -0,0 +1,36 @@
+-- Exact percentiles need the rank among all uids, so a single changed uid re-ranks every one. This
+-- only reads the stats tables, which keep the pull count from the last import.
+WITH percentiles AS (
+    SELECT
+        zzz_signals_stats_bangboo.uid,
+        round((rank() OVER (ORDER BY zzz_signals_stats_bangboo.count DESC) - 1)::numeric / count(*) OVER (), 4)::double precision AS count_percentile,
+        round((rank() OVER (ORDER BY zzz_signals_stats_bangboo.luck_a) - 1)::numeric / count(*) OVER (), 4)::double precision AS luck_a_percentile,
+        round((rank() OVER (ORDER BY zzz_signals_stats_bangboo.luck_s) - 1)::numeric / count(*) OVER (), 4)::double precision AS luck_s_percentile
+    FROM
+        zzz_signals_stats_bangboo
+    WHERE
+        zzz_signals_stats_bangboo.count >= 50
+        AND zzz_signals_stats_bangboo.luck_s != 0
+),
+deleted AS (
+    DELETE FROM zzz_signals_stats_global_bangboo
+    WHERE NOT EXISTS (
+            SELECT
+                *
+            FROM
+                percentiles
+            WHERE
+                percentiles.uid = zzz_signals_stats_global_bangboo.uid))
+INSERT INTO zzz_signals_stats_global_bangboo (uid, count_percentile, luck_a_percentile, luck_s_percentile)
+SELECT
+    uid,
+    count_percentile,
+    luck_a_percentile,
+    luck_s_percentile
+FROM
+    percentiles
+ON CONFLICT (uid)
+    DO UPDATE SET
+        count_percentile = EXCLUDED.count_percentile, luck_a_percentile = EXCLUDED.luck_a_percentile, luck_s_percentile = EXCLUDED.luck_s_percentile
+    WHERE (zzz_signals_stats_global_bangboo.count_percentile, zzz_signals_stats_global_bangboo.luck_a_percentile, zzz_signals_stats_global_bangboo.luck_s_percentile) IS DISTINCT FROM (EXCLUDED.count_percentile, EXCLUDED.luck_a_percentile, EXCLUDED.luck_s_percentile);
+
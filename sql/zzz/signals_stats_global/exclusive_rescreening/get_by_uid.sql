@@ -0,0 +1,9 @@
+SELECT
+    count_percentile,
+    luck_a_percentile,
+    luck_s_percentile
+FROM
+    zzz_signals_stats_global_exclusive_rescreening
+WHERE
+    uid = $1;
+
@@ -1,3 +0,0 @@
-DELETE FROM zzz_signals_stats_global_special
-WHERE uid = $1;
-
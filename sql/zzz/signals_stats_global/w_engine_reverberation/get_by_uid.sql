@@ -0,0 +1,9 @@
+SELECT
+    count_percentile,
+    luck_a_percentile,
+    luck_s_percentile
+FROM
+    zzz_signals_stats_global_w_engine_reverberation
+WHERE
+    uid = $1;
+
@@ -0,0 +1,197 @@
+//! Achievement completions bucketed by day and by the version the achievements came with. Shared by
+//! the timeline endpoints of every game.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use utoipa::ToSchema;
+
+pub struct Completion {
+    pub completed_at: Option<DateTime<Utc>>,
+    pub imported: bool,
+    pub version: Option<String>,
+    /// Jades, primogems or polychromes
+    pub rewards: i32,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Timeline {
+    /// By day in UTC, oldest first. Imports count on the day of the import
+    days: Vec<TimelineDay>,
+    /// By the version the achievements came with, oldest first
+    versions: Vec<TimelineVersion>,
+    /// Completed before completion times were recorded
+    untimed: i64,
+    untimed_rewards: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TimelineDay {
+    date: NaiveDate,
+    completed: i64,
+    /// How many of them came from imports
+    imported: i64,
+    rewards: i64,
+    /// Up to and including this day. Starts with the untimed completions, which are older
+    total_completed: i64,
+    total_rewards: i64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TimelineVersion {
+    /// `None` for achievements without a known version, which come last
+    version: Option<String>,
+    completed: i64,
+    rewards: i64,
+    /// Up to and including this version
+    total_completed: i64,
+    total_rewards: i64,
+}
+
+#[derive(Default)]
+struct Bucket {
+    completed: i64,
+    imported: i64,
+    rewards: i64,
+}
+
+impl Bucket {
+    fn add(&mut self, completion: &Completion) {
+        self.completed += 1;
+        self.rewards += completion.rewards as i64;
+
+        if completion.imported {
+            self.imported += 1;
+        }
+    }
+}
+
+pub fn timeline(completions: impl IntoIterator<Item = Completion>) -> Timeline {
+    let mut untimed = Bucket::default();
+    let mut days: BTreeMap<NaiveDate, Bucket> = BTreeMap::new();
+    let mut versions: BTreeMap<(bool, Vec<u32>, Option<String>), Bucket> = BTreeMap::new();
+
+    for completion in completions {
+        match completion.completed_at {
+            Some(completed_at) => days
+                .entry(completed_at.date_naive())
+                .or_default()
+                .add(&completion),
+            None => untimed.add(&completion),
+        }
+
+        versions
+            .entry(version_key(completion.version.clone()))
+            .or_default()
+            .add(&completion);
+    }
+
+    let (mut total_completed, mut total_rewards) = (untimed.completed, untimed.rewards);
+    let days = days
+        .into_iter()
+        .map(|(date, bucket)| {
+            total_completed += bucket.completed;
+            total_rewards += bucket.rewards;
+
+            TimelineDay {
+                date,
+                completed: bucket.completed,
+                imported: bucket.imported,
+                rewards: bucket.rewards,
+                total_completed,
+                total_rewards,
+            }
+        })
+        .collect();
+
+    let (mut total_completed, mut total_rewards) = (0, 0);
+    let versions = versions
+        .into_iter()
+        .map(|((_, _, version), bucket)| {
+            total_completed += bucket.completed;
+            total_rewards += bucket.rewards;
+
+            TimelineVersion {
+                version,
+                completed: bucket.completed,
+                rewards: bucket.rewards,
+                total_completed,
+                total_rewards,
+            }
+        })
+        .collect();
+
+    Timeline {
+        days,
+        versions,
+        untimed: untimed.completed,
+        untimed_rewards: untimed.rewards,
+    }
+}
+
+/// Sorts `1.10` after `1.9` and unknown versions last
+fn version_key(version: Option<String>) -> (bool, Vec<u32>, Option<String>) {
+    let numbers = version
+        .iter()
+        .flat_map(|version| version.split('.'))
+        .map_while(|part| part.trim().parse().ok())
+        .collect();
+
+    (version.is_none(), numbers, version)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn completion(day: Option<u32>, imported: bool, version: Option<&str>) -> Completion {
+        Completion {
+            completed_at: day.map(|day| Utc.with_ymd_and_hms(2026, 10, day, 12, 0, 0).unwrap()),
+            imported,
+            version: version.map(ToString::to_string),
+            rewards: 5,
+        }
+    }
+
+    #[test]
+    fn completions_are_bucketed_and_summed_up() {
+        let timeline = timeline([
+            completion(None, false, Some("1.10")),
+            completion(Some(2), true, Some("1.9")),
+            completion(Some(2), false, None),
+            completion(Some(1), false, Some("1.9")),
+        ]);
+
+        assert_eq!((timeline.untimed, timeline.untimed_rewards), (1, 5));
+
+        let days: Vec<_> = timeline
+            .days
+            .iter()
+            .map(|d| (d.date.to_string(), d.completed, d.imported, d.total_rewards))
+            .collect();
+        assert_eq!(
+            days,
+            [
+                ("2026-10-01".to_string(), 1, 0, 10),
+                ("2026-10-02".to_string(), 2, 1, 20),
+            ]
+        );
+
+        let versions: Vec<_> = timeline
+            .versions
+            .iter()
+            .map(|v| (v.version.clone(), v.completed, v.total_completed))
+            .collect();
+        assert_eq!(
+            versions,
+            [
+                (Some("1.9".to_string()), 2, 2),
+                (Some("1.10".to_string()), 1, 3),
+                (None, 1, 4),
+            ]
+        );
+    }
+}
@@ -48,7 +48,7 @@ async fn get_achievement(
 
     if let Some(set) = achievement.set {
         achievement.related =
-            Some(database::achievements::get_all_related_ids(achievement.id, set, &pool).await?);
+            Some(database::achievements::get_all_related_ids(achievement.id, set, &**pool).await?);
     }
 
     Ok(HttpResponse::Ok().json(achievement))
@@ -124,7 +124,7 @@ async fn get_achievements(
     for achievement in &mut achievements {
         if let Some(set) = achievement.set {
             achievement.related = Some(
-                database::achievements::get_all_related_ids(achievement.id, set, &pool).await?,
+                database::achievements::get_all_related_ids(achievement.id, set, &**pool).await?,
             );
         }
     }
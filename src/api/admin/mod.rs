@@ -4,6 +4,9 @@ mod delete_all_wishes;
 mod delete_unofficial_signals;
 mod delete_unofficial_warps;
 mod delete_unofficial_wishes;
+mod revoke_export_key;
+mod rotate_export_key;
+mod service_keys;
 
 use actix_web::web;
 
@@ -14,6 +17,9 @@ pub fn openapi() -> utoipa::openapi::OpenApi {
     openapi.merge(delete_unofficial_signals::openapi());
     openapi.merge(delete_unofficial_warps::openapi());
     openapi.merge(delete_unofficial_wishes::openapi());
+    openapi.merge(revoke_export_key::openapi());
+    openapi.merge(rotate_export_key::openapi());
+    openapi.merge(service_keys::openapi());
     openapi
 }
 
@@ -23,5 +29,8 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
         .configure(delete_all_wishes::configure)
         .configure(delete_unofficial_signals::configure)
         .configure(delete_unofficial_warps::configure)
-        .configure(delete_unofficial_wishes::configure);
+        .configure(delete_unofficial_wishes::configure)
+        .configure(revoke_export_key::configure)
+        .configure(rotate_export_key::configure)
+        .configure(service_keys::configure);
 }
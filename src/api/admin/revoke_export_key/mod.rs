@@ -0,0 +1,101 @@
+use actix_session::Session;
+use actix_web::{post, web, HttpResponse, Responder};
+use ed25519_dalek::SigningKey;
+use futures::lock::Mutex;
+use sqlx::PgPool;
+use utoipa::OpenApi;
+
+use crate::{api::ApiResult, database, signing};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "admin/revoke-export-key/{id}")),
+    paths(post_revoke_export_key),
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_revoke_export_key);
+}
+
+#[utoipa::path(
+    tag = "admin/revoke-export-key/{id}",
+    post,
+    path = "/api/admin/revoke-export-key/{id}",
+    responses(
+        (status = 200, description = "Revoked. If it was the current key, it was rotated first"),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "Unknown key id"),
+    ),
+    security(("admin" = []))
+)]
+#[post("/api/admin/revoke-export-key/{id}")]
+async fn post_revoke_export_key(
+    session: Session,
+    id: web::Path<String>,
+    signing_key: web::Data<Mutex<SigningKey>>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let admin = database::admins::exists(&username, &pool).await?;
+
+    if !admin {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    if !signing::revoke(&id, &signing_key, &pool).await? {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::ed25519::signature::SignerMut;
+
+    use super::*;
+    use crate::test_util::test_pool;
+
+    #[actix_web::test]
+    async fn rotations_reach_every_instance_and_revoked_keys_stop_verifying() {
+        let pool = test_pool().await;
+        let signing_key = Mutex::new(signing::load(&pool).await.unwrap());
+        let other_instance = Mutex::new(signing::load(&pool).await.unwrap());
+
+        let id = signing::rotate(&signing_key, &pool).await.unwrap();
+
+        let signature = {
+            let mut other_signing_key = signing::active(&other_instance, &pool).await.unwrap();
+            assert_eq!(signing::key_id(&other_signing_key.verifying_key()), id);
+
+            other_signing_key.sign(b"export").to_string()
+        };
+        assert_eq!(
+            signing::verify(b"export", &signature, Some(&id), &pool)
+                .await
+                .unwrap(),
+            Some(id.clone())
+        );
+
+        assert!(signing::revoke(&id, &signing_key, &pool).await.unwrap());
+        assert!(signing::verify(b"export", &signature, None, &pool)
+            .await
+            .unwrap()
+            .is_none());
+
+        let active = signing::active(&other_instance, &pool).await.unwrap();
+        assert_ne!(signing::key_id(&active.verifying_key()), id);
+
+        assert!(!signing::revoke("unknown", &signing_key, &pool)
+            .await
+            .unwrap());
+    }
+}
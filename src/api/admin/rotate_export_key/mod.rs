@@ -0,0 +1,54 @@
+use actix_session::Session;
+use actix_web::{post, web, HttpResponse, Responder};
+use ed25519_dalek::SigningKey;
+use futures::lock::Mutex;
+use sqlx::PgPool;
+use utoipa::OpenApi;
+
+use crate::{api::ApiResult, database, signing};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "admin/rotate-export-key")),
+    paths(post_rotate_export_key),
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_rotate_export_key);
+}
+
+#[utoipa::path(
+    tag = "admin/rotate-export-key",
+    post,
+    path = "/api/admin/rotate-export-key",
+    responses(
+        (status = 200, description = "Id of the new export key", body = String),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("admin" = []))
+)]
+#[post("/api/admin/rotate-export-key")]
+async fn post_rotate_export_key(
+    session: Session,
+    signing_key: web::Data<Mutex<SigningKey>>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let admin = database::admins::exists(&username, &pool).await?;
+
+    if !admin {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    let id = signing::rotate(&signing_key, &pool).await?;
+
+    Ok(HttpResponse::Ok().json(id))
+}
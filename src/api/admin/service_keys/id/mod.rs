@@ -0,0 +1,66 @@
+mod rotate;
+
+use actix_session::Session;
+use actix_web::{delete, web, HttpResponse, Responder};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::{
+    api::{service_keys, ApiResult},
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "admin/service-keys/{id}")),
+    paths(delete_service_key)
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(rotate::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(delete_service_key).configure(rotate::configure);
+}
+
+#[utoipa::path(
+    tag = "admin/service-keys/{id}",
+    delete,
+    path = "/api/admin/service-keys/{id}",
+    params(("id" = String, Path, description = "Key id")),
+    responses(
+        (status = 200, description = "Deleted the key"),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such key"),
+    ),
+    security(("admin" = []))
+)]
+#[delete("/api/admin/service-keys/{id}")]
+async fn delete_service_key(
+    session: Session,
+    id: web::Path<Uuid>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let admin = database::admins::exists(&username, &pool).await?;
+
+    if !admin {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    if !database::service_keys::delete_by_id(*id, &pool).await? {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    service_keys::reload(&pool).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
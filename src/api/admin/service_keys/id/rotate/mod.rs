@@ -0,0 +1,67 @@
+use actix_session::Session;
+use actix_web::{post, web, HttpResponse, Responder};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        admin::service_keys::{generate, CreatedServiceKey},
+        service_keys, ApiResult,
+    },
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "admin/service-keys/{id}/rotate")),
+    paths(post_rotate_service_key)
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_rotate_service_key);
+}
+
+#[utoipa::path(
+    tag = "admin/service-keys/{id}/rotate",
+    post,
+    path = "/api/admin/service-keys/{id}/rotate",
+    params(("id" = String, Path, description = "Key id")),
+    responses(
+        (status = 200, description = "New key. The old one stops working right away", body = CreatedServiceKey),
+        (status = 403, description = "Not an admin"),
+        (status = 404, description = "No such key"),
+    ),
+    security(("admin" = []))
+)]
+#[post("/api/admin/service-keys/{id}/rotate")]
+async fn post_rotate_service_key(
+    session: Session,
+    id: web::Path<Uuid>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let admin = database::admins::exists(&username, &pool).await?;
+
+    if !admin {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    let key = generate();
+
+    if !database::service_keys::update_hash_by_id(*id, &service_keys::hash(&key), &pool).await? {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    service_keys::reload(&pool).await?;
+
+    Ok(HttpResponse::Ok().json(CreatedServiceKey { id: *id, key }))
+}
@@ -0,0 +1,189 @@
+mod id;
+
+use actix_session::Session;
+use actix_web::{get, post, web, HttpResponse, Responder};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    api::{service_keys, ApiResult},
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "admin/service-keys")),
+    paths(get_service_keys, post_service_key),
+    components(schemas(ServiceKey, ServiceKeyCreate, CreatedServiceKey))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(id::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_service_keys)
+        .service(post_service_key)
+        .configure(id::configure);
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct ServiceKey {
+    #[schema(value_type = String)]
+    id: Uuid,
+    name: String,
+    owner: String,
+    route_groups: Vec<String>,
+    quota_per_minute: Option<i32>,
+    /// All accepted requests, as of the last flush of this instance
+    requests: i64,
+    /// Accepted requests in the current minute on this instance
+    requests_this_minute: i64,
+    /// Requests over quota or outside the route groups since this instance started
+    rejected: i64,
+    last_used: Option<DateTime<Utc>>,
+    created: DateTime<Utc>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct ServiceKeyCreate {
+    name: String,
+    owner: String,
+    /// First path segment after `/api/`, e.g. `pages`
+    route_groups: Vec<String>,
+    /// Unlimited if missing. Counted per instance, not across all of them
+    quota_per_minute: Option<i32>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CreatedServiceKey {
+    #[schema(value_type = String)]
+    pub id: Uuid,
+    /// Only shown once. Send it as `x-api-key`
+    pub key: String,
+}
+
+pub fn generate() -> String {
+    format!(
+        "{}{}",
+        service_keys::KEY_PREFIX,
+        BASE64_URL_SAFE_NO_PAD.encode(rand::rng().random::<[u8; 32]>())
+    )
+}
+
+#[utoipa::path(
+    tag = "admin/service-keys",
+    get,
+    path = "/api/admin/service-keys",
+    responses(
+        (status = 200, description = "Service keys with their usage", body = Vec<ServiceKey>),
+        (status = 403, description = "Not an admin"),
+    ),
+    security(("admin" = []))
+)]
+#[get("/api/admin/service-keys")]
+async fn get_service_keys(session: Session, pool: web::Data<PgPool>) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let admin = database::admins::exists(&username, &pool).await?;
+
+    if !admin {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    let service_keys: Vec<_> = database::service_keys::get_all(&pool)
+        .await?
+        .into_iter()
+        .map(|k| {
+            let usage = service_keys::usage(k.id);
+
+            ServiceKey {
+                id: k.id,
+                name: k.name,
+                owner: k.owner,
+                route_groups: k.route_groups,
+                quota_per_minute: k.quota_per_minute,
+                requests: k.requests,
+                requests_this_minute: usage.count,
+                rejected: usage.rejected,
+                last_used: k.last_used,
+                created: k.created,
+            }
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(service_keys))
+}
+
+#[utoipa::path(
+    tag = "admin/service-keys",
+    post,
+    path = "/api/admin/service-keys",
+    request_body(content = ServiceKeyCreate,
+        examples(
+            ("Partner" = (value = json!({"name": "partner", "owner": "partner.gg", "route_groups": ["pages"], "quota_per_minute": 600}))),
+        )
+    ),
+    responses(
+        (status = 200, description = "Created key", body = CreatedServiceKey),
+        (status = 400, description = "Invalid name, route groups or quota"),
+        (status = 403, description = "Not an admin"),
+        (status = 409, description = "Name taken"),
+    ),
+    security(("admin" = []))
+)]
+#[post("/api/admin/service-keys")]
+async fn post_service_key(
+    session: Session,
+    service_key_create: web::Json<ServiceKeyCreate>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let admin = database::admins::exists(&username, &pool).await?;
+
+    if !admin {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    let name = service_key_create.name.trim();
+
+    if name.is_empty()
+        || name.len() > 64
+        || service_key_create.route_groups.is_empty()
+        || service_key_create.quota_per_minute.is_some_and(|q| q < 1)
+    {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    let key = generate();
+
+    let Some(id) = database::service_keys::set(
+        name,
+        service_key_create.owner.trim(),
+        &service_keys::hash(&key),
+        &service_key_create.route_groups,
+        service_key_create.quota_per_minute,
+        &pool,
+    )
+    .await?
+    else {
+        return Ok(HttpResponse::Conflict().finish());
+    };
+
+    service_keys::reload(&pool).await?;
+
+    Ok(HttpResponse::Ok().json(CreatedServiceKey { id, key }))
+}
@@ -0,0 +1,191 @@
+use actix_session::SessionExt;
+use actix_web::{
+    dev::Payload, error::ErrorInternalServerError, http::header, web, FromRequest, HttpRequest,
+};
+use futures::future::LocalBoxFuture;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use utoipa::ToSchema;
+
+use crate::database;
+
+/// What a personal access token may do. Sessions may do everything.
+#[derive(
+    Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema, strum::Display, strum::EnumString,
+)]
+pub enum Scope {
+    /// Read private uids, the profile and exports
+    #[serde(rename = "read:private")]
+    #[strum(serialize = "read:private")]
+    ReadPrivate,
+    /// Complete and favorite achievements for every game
+    #[serde(rename = "achievements:write")]
+    #[strum(serialize = "achievements:write")]
+    AchievementsWrite,
+    /// Import warps, wishes and signals
+    #[serde(rename = "warps:import")]
+    #[strum(serialize = "warps:import")]
+    WarpsImport,
+    /// Replace everything with a signed export. Achievements, favorites and connections missing
+    /// from it are deleted
+    #[serde(rename = "users:restore")]
+    #[strum(serialize = "users:restore")]
+    Restore,
+}
+
+/// Who made the request. Either a logged in session or a personal access token sent as
+/// `Authorization: Bearer <token>`. A request with a token never falls back to the session.
+pub enum Auth {
+    Session(String),
+    Token {
+        username: String,
+        scopes: Vec<Scope>,
+    },
+    None,
+}
+
+impl Auth {
+    /// The user, if the request may act on their behalf with `scope`
+    pub fn username(&self, scope: Scope) -> Option<String> {
+        match self {
+            Auth::Session(username) => Some(username.clone()),
+            Auth::Token { username, scopes } if scopes.contains(&scope) => Some(username.clone()),
+            _ => None,
+        }
+    }
+
+    /// The user regardless of scopes. Only for bookkeeping like rate limits, never for access
+    pub fn owner(&self) -> Option<&str> {
+        match self {
+            Auth::Session(username) | Auth::Token { username, .. } => Some(username),
+            Auth::None => None,
+        }
+    }
+
+    /// Tokens never get admin rights, even if their user is an admin
+    pub async fn is_admin(&self, pool: &PgPool) -> anyhow::Result<bool> {
+        match self {
+            Auth::Session(username) => database::admins::exists(username, pool).await,
+            _ => Ok(false),
+        }
+    }
+}
+
+pub const TOKEN_PREFIX: &str = "sdb_";
+
+pub fn hash(token: &str) -> Vec<u8> {
+    Sha256::digest(token.as_bytes()).to_vec()
+}
+
+impl FromRequest for Auth {
+    type Error = actix_web::Error;
+    type Future = LocalBoxFuture<'static, Result<Self, Self::Error>>;
+
+    fn from_request(req: &HttpRequest, _: &mut Payload) -> Self::Future {
+        let token = req
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|authorization| authorization.to_str().ok())
+            .and_then(|authorization| authorization.strip_prefix("Bearer "))
+            .map(|token| token.trim().to_string());
+        let session = req.get_session();
+        let pool = req.app_data::<web::Data<PgPool>>().cloned();
+
+        Box::pin(async move {
+            let Some(token) = token else {
+                return Ok(match session.get::<String>("username") {
+                    Ok(Some(username)) => Auth::Session(username),
+                    _ => Auth::None,
+                });
+            };
+
+            let (Some(pool), true) = (pool, token.starts_with(TOKEN_PREFIX)) else {
+                return Ok(Auth::None);
+            };
+
+            let Some(access_token) =
+                database::users_access_tokens::use_by_hash(&hash(&token), &pool)
+                    .await
+                    .map_err(ErrorInternalServerError)?
+            else {
+                return Ok(Auth::None);
+            };
+
+            let scopes = access_token
+                .scopes
+                .iter()
+                .filter_map(|scope| scope.parse().ok())
+                .collect();
+
+            Ok(Auth::Token {
+                username: access_token.username,
+                scopes,
+            })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::test::TestRequest;
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::test_util::{create_test_user, delete_test_user, test_pool};
+
+    async fn extract(token: &str, pool: &PgPool) -> Auth {
+        let req = TestRequest::default()
+            .insert_header((header::AUTHORIZATION, format!("Bearer {token}")))
+            .app_data(web::Data::new(pool.clone()))
+            .to_http_request();
+
+        Auth::extract(&req).await.unwrap()
+    }
+
+    #[actix_web::test]
+    async fn tokens_only_act_within_their_scopes() {
+        let pool = test_pool().await;
+
+        let username = create_test_user("access_tokens_test", &pool).await;
+
+        let token = format!("{TOKEN_PREFIX}{}", Uuid::new_v4().simple());
+        let scopes = vec![Scope::AchievementsWrite.to_string()];
+        database::users_access_tokens::set(&username, "bot", &hash(&token), &scopes, None, &pool)
+            .await
+            .unwrap();
+
+        let auth = extract(&token, &pool).await;
+        assert_eq!(
+            auth.username(Scope::AchievementsWrite).as_deref(),
+            Some(username.as_str())
+        );
+        assert!(auth.username(Scope::ReadPrivate).is_none());
+        assert!(auth.username(Scope::Restore).is_none());
+        assert!(!auth.is_admin(&pool).await.unwrap());
+
+        let access_token = database::users_access_tokens::get_by_username(&username, &pool)
+            .await
+            .unwrap()
+            .remove(0);
+        assert!(access_token.last_used.is_some());
+
+        let expired = format!("{TOKEN_PREFIX}{}", Uuid::new_v4().simple());
+        database::users_access_tokens::set(
+            &username,
+            "expired",
+            &hash(&expired),
+            &scopes,
+            Some(Utc::now() - Duration::days(1)),
+            &pool,
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(extract(&expired, &pool).await, Auth::None));
+        assert!(matches!(extract("sdb_unknown", &pool).await, Auth::None));
+
+        delete_test_user(&username, &pool).await;
+    }
+}
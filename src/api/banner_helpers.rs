@@ -1,6 +1,9 @@
 use std::{collections::HashMap, ops::Range};
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, TimeZone, Utc};
+use sqlx::PgPool;
+
+use crate::database;
 
 // Standard-pool 5★ HSR characters and light cones. A pull outside every configured banner window
 // is a Loss only when the item is in this list; otherwise it is a Win.
@@ -15,6 +18,37 @@ pub const GI_STANDARD: &[i32] = &[
     15502, 11501, 14502, 13505, 14501, 15501, 12501, 13502, 12502, // weapons
 ];
 
+// Standard-pool S-rank ZZZ agents and W-Engines.
+pub const ZZZ_STANDARD: &[i32] = &[
+    1021, 1041, 1101, 1141, 1181, 1211, // agents
+    14102, 14104, 14110, 14114, 14118, 14121, // w-engines
+];
+
+// Banner windows of every featured ZZZ agent and W-Engine, for `is_win_fn`.
+pub async fn zzz_banner_ranges(
+    pool: &PgPool,
+) -> anyhow::Result<HashMap<i32, Vec<Range<DateTime<Utc>>>>> {
+    let mut banners: HashMap<_, Vec<_>> = HashMap::new();
+
+    for banner in database::zzz::banners::get_all(pool).await? {
+        if let Some(character) = banner.character {
+            banners
+                .entry(character)
+                .or_default()
+                .push(banner.start..banner.end);
+        }
+
+        if let Some(w_engine) = banner.w_engine {
+            banners
+                .entry(w_engine)
+                .or_default()
+                .push(banner.start..banner.end);
+        }
+    }
+
+    Ok(banners)
+}
+
 pub fn is_win_fn<'a>(
     banners: &'a HashMap<i32, Vec<Range<DateTime<Utc>>>>,
     standard: &'a [i32],
@@ -36,3 +70,217 @@ pub fn is_win_fn<'a>(
         is_win
     }
 }
+
+// Id of the HSR banner a warp of `gacha_type` was pulled on: the banner of the matching kind whose
+// window contains `timestamp`, preferring the one featuring `item_id`. `None` when no configured
+// banner matches, e.g. for the standard and departure warps.
+pub fn hsr_banner_id(
+    banners: &[database::banners::DbBanner],
+    gacha_type: &str,
+    item_id: i32,
+    timestamp: DateTime<Utc>,
+) -> Option<i32> {
+    let (light_cone, collab) = match gacha_type {
+        "11" => (false, false),
+        "12" => (true, false),
+        "21" => (false, true),
+        "22" => (true, true),
+        _ => return None,
+    };
+
+    let featured = |banner: &database::banners::DbBanner| {
+        if light_cone {
+            banner.light_cone
+        } else {
+            banner.character
+        }
+    };
+
+    banners
+        .iter()
+        .filter(|banner| banner.collab == collab && featured(banner).is_some())
+        .filter(|banner| (banner.start..banner.end).contains(&timestamp))
+        .min_by_key(|banner| (featured(banner) != Some(item_id), banner.id))
+        .map(|banner| banner.id)
+}
+
+// Genshin 5.0 added "Capturing Radiance" to the character event wish: after `GI_RADIANCE_LOSSES`
+// lost 50/50s in a row the next 50/50 is always won.
+pub const GI_RADIANCE_LOSSES: i32 = 3;
+
+// Release of Genshin 5.0. Character wishes before it are plain 50/50s.
+pub fn gi_radiance_start() -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2024, 8, 28, 0, 0, 0).unwrap()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WinOutcome {
+    Win,
+    Loss,
+    Guarantee,
+    Radiance,
+}
+
+// Guarantee and consecutive-loss state of a Genshin character event wish history. Feed it every
+// 5★ in order.
+#[derive(Default)]
+pub struct RadianceState {
+    pub guarantee: bool,
+    pub losses: i32,
+}
+
+impl RadianceState {
+    pub fn pull(&mut self, win: bool, timestamp: DateTime<Utc>) -> WinOutcome {
+        if self.guarantee {
+            self.guarantee = false;
+            return WinOutcome::Guarantee;
+        }
+
+        if !win {
+            if timestamp >= gi_radiance_start() {
+                self.losses += 1;
+            }
+
+            self.guarantee = true;
+            return WinOutcome::Loss;
+        }
+
+        let outcome = if timestamp >= gi_radiance_start() && self.losses >= GI_RADIANCE_LOSSES {
+            WinOutcome::Radiance
+        } else {
+            WinOutcome::Win
+        };
+
+        self.losses = 0;
+
+        outcome
+    }
+}
+
+// Fate points needed to force the charted weapon. The same 5.0 update lowered it from 2 to 1.
+pub fn gi_max_fate_points(timestamp: DateTime<Utc>) -> i32 {
+    if timestamp >= gi_radiance_start() {
+        1
+    } else {
+        2
+    }
+}
+
+// A Genshin weapon event wish. Both featured weapons are separate `gi_banners` rows sharing the same
+// window, the lowest id identifies the banner.
+pub struct GiWeaponBanner {
+    pub id: i32,
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+    pub weapons: Vec<i32>,
+}
+
+pub fn gi_weapon_banners(banners: &[database::gi::banners::DbBanner]) -> Vec<GiWeaponBanner> {
+    let mut weapon_banners: Vec<GiWeaponBanner> = Vec::new();
+
+    for banner in banners {
+        let Some(weapon) = banner.weapon else {
+            continue;
+        };
+
+        if let Some(weapon_banner) = weapon_banners
+            .iter_mut()
+            .find(|b| b.start == banner.start && b.end == banner.end)
+        {
+            weapon_banner.id = weapon_banner.id.min(banner.id);
+            weapon_banner.weapons.push(weapon);
+        } else {
+            weapon_banners.push(GiWeaponBanner {
+                id: banner.id,
+                start: banner.start,
+                end: banner.end,
+                weapons: vec![weapon],
+            });
+        }
+    }
+
+    weapon_banners.sort_by_key(|b| b.start);
+
+    weapon_banners
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn after_radiance() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2025, 1, 1, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn radiance_after_three_consecutive_losses() {
+        let mut state = RadianceState::default();
+        let t = after_radiance();
+
+        for _ in 0..GI_RADIANCE_LOSSES {
+            assert_eq!(state.pull(false, t), WinOutcome::Loss);
+            assert_eq!(state.pull(true, t), WinOutcome::Guarantee);
+        }
+
+        assert_eq!(state.losses, GI_RADIANCE_LOSSES);
+        assert_eq!(state.pull(true, t), WinOutcome::Radiance);
+        assert_eq!(state.losses, 0);
+    }
+
+    #[test]
+    fn win_resets_loss_counter() {
+        let mut state = RadianceState::default();
+        let t = after_radiance();
+
+        state.pull(false, t);
+        state.pull(true, t);
+        state.pull(false, t);
+        state.pull(true, t);
+        assert_eq!(state.pull(true, t), WinOutcome::Win);
+        assert_eq!(state.losses, 0);
+    }
+
+    #[test]
+    fn losses_before_radiance_do_not_count() {
+        let mut state = RadianceState::default();
+        let t = gi_radiance_start() - chrono::Duration::days(1);
+
+        for _ in 0..GI_RADIANCE_LOSSES {
+            state.pull(false, t);
+            state.pull(true, t);
+        }
+
+        assert_eq!(state.losses, 0);
+        assert_eq!(state.pull(true, after_radiance()), WinOutcome::Win);
+    }
+
+    #[test]
+    fn hsr_banner_id_prefers_the_featured_banner() {
+        let start = after_radiance();
+        let end = start + chrono::Duration::days(21);
+        let banner = |id, character, light_cone, collab| database::banners::DbBanner {
+            id,
+            name: String::new(),
+            start,
+            end,
+            character,
+            light_cone,
+            collab,
+        };
+        let banners = [
+            banner(2001, Some(1), None, false),
+            banner(2002, Some(2), None, false),
+            banner(3001, None, Some(20), false),
+            banner(5001, Some(3), None, true),
+        ];
+        let t = start + chrono::Duration::days(1);
+
+        assert_eq!(hsr_banner_id(&banners, "11", 2, t), Some(2002));
+        assert_eq!(hsr_banner_id(&banners, "11", 1004, t), Some(2001));
+        assert_eq!(hsr_banner_id(&banners, "12", 20, t), Some(3001));
+        assert_eq!(hsr_banner_id(&banners, "21", 3, t), Some(5001));
+        assert_eq!(hsr_banner_id(&banners, "22", 20, t), None);
+        assert_eq!(hsr_banner_id(&banners, "1", 1004, t), None);
+        assert_eq!(hsr_banner_id(&banners, "11", 2, end), None);
+    }
+}
@@ -0,0 +1,62 @@
+//! What happened on a limited banner, calculated by `update::banners_stats`. Shared by the banner
+//! stats endpoints of every game.
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::database::banners_stats::DbBannerStats;
+
+#[derive(Serialize, ToSchema)]
+pub struct BannerStats {
+    /// Official pulls on banners of this kind while it was running. Concurrent banners share them
+    pulls: i64,
+    uids: i64,
+    /// Uids by copies of the featured item, starting with 0
+    copies: Vec<i64>,
+    /// Of the 50/50s or 75/25s. Guarantees don't count
+    win_rate: Option<f64>,
+    wins: i64,
+    losses: i64,
+    guarantees: i64,
+    /// Average pulls per copy of the featured item, including the lost 50/50s before it
+    featured_pity: Option<f64>,
+    /// Most common first
+    losses_items: Vec<BannerLoss>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct BannerLoss {
+    item: i32,
+    count: i64,
+}
+
+impl From<DbBannerStats> for BannerStats {
+    fn from(stats: DbBannerStats) -> Self {
+        let without = stats.uids - stats.copies.iter().sum::<i64>();
+        let copies = std::iter::once(without.max(0))
+            .chain(stats.copies)
+            .collect();
+
+        let win_rate = (stats.wins + stats.losses > 0)
+            .then(|| stats.wins as f64 / (stats.wins + stats.losses) as f64);
+
+        let losses_items = stats
+            .loss_items
+            .into_iter()
+            .zip(stats.loss_counts)
+            .map(|(item, count)| BannerLoss { item, count })
+            .collect();
+
+        Self {
+            pulls: stats.pulls,
+            uids: stats.uids,
+            copies,
+            win_rate,
+            wins: stats.wins,
+            losses: stats.losses,
+            guarantees: stats.guarantees,
+            featured_pity: stats.featured_pity,
+            losses_items,
+        }
+    }
+}
@@ -1,3 +1,5 @@
+mod stats;
+
 use actix_session::Session;
 use actix_web::{delete, get, put, web, HttpResponse, Responder};
 use chrono::{DateTime, Utc};
@@ -18,13 +20,16 @@ use crate::{
 struct ApiDoc;
 
 pub fn openapi() -> utoipa::openapi::OpenApi {
-    ApiDoc::openapi()
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(stats::openapi());
+    openapi
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_banner)
         .service(put_banner)
-        .service(delete_banner);
+        .service(delete_banner)
+        .configure(stats::configure);
 }
 
 #[utoipa::path(
@@ -49,6 +54,9 @@ struct PutBanner {
     end: DateTime<Utc>,
     character: Option<i32>,
     light_cone: Option<i32>,
+    /// Pulled on the collab warps instead of the regular event warps
+    #[serde(default)]
+    collab: bool,
 }
 
 #[utoipa::path(
@@ -84,6 +92,7 @@ async fn put_banner(
         end: banner.end,
         character: banner.character,
         light_cone: banner.light_cone,
+        collab: banner.collab,
     };
 
     database::banners::set(&db_banner, &pool).await?;
@@ -26,6 +26,8 @@ struct Banner {
     end: DateTime<Utc>,
     character: Option<i32>,
     light_cone: Option<i32>,
+    /// Pulled on the collab warps instead of the regular event warps
+    collab: bool,
 }
 
 impl From<database::banners::DbBanner> for Banner {
@@ -37,6 +39,7 @@ impl From<database::banners::DbBanner> for Banner {
             end: banner.end,
             character: banner.character,
             light_cone: banner.light_cone,
+            collab: banner.collab,
         }
     }
 }
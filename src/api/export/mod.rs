@@ -0,0 +1,304 @@
+mod verify;
+
+use actix_web::web;
+use chrono::{DateTime, Utc};
+use utoipa::OpenApi;
+
+use crate::database;
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "export")),
+    components(schemas(
+        Export,
+        UserExport,
+        Completion,
+        Hsr,
+        Zzz,
+        Gi,
+        HsrUid,
+        ZzzUid,
+        GiUid,
+        Warps,
+        Warp,
+        WarpType,
+        Signals,
+        Signal,
+        SignalType,
+        Wishes,
+        Wish,
+        WishType,
+    ))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(verify::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.configure(verify::configure);
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Export {
+    pub user: UserExport,
+    pub signature: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_id: Option<String>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct UserExport {
+    pub username: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issued_at: Option<DateTime<Utc>>,
+    pub hsr: Hsr,
+    pub zzz: Zzz,
+    pub gi: Gi,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Completion {
+    pub id: i32,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub imported: bool,
+}
+
+// Fields added after the first export format are optional, so that documents
+// signed before they existed still serialize to the exact signed bytes.
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Hsr {
+    pub achievements: Vec<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favorites: Option<Vec<i32>>,
+    pub uids: Vec<HsrUid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completions: Option<Vec<Completion>>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct HsrUid {
+    pub uid: i32,
+    pub verified: bool,
+    pub private: bool,
+    pub warps: Warps,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Warps {
+    pub departure: Vec<Warp>,
+    pub standard: Vec<Warp>,
+    pub character: Vec<Warp>,
+    pub light_cone: Vec<Warp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collab: Option<Vec<Warp>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collab_lc: Option<Vec<Warp>>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Warp {
+    pub id: String,
+    pub item_id: i32,
+    pub r#type: WarpType,
+    pub timestamp: DateTime<Utc>,
+    pub official: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WarpType {
+    Character,
+    LightCone,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Zzz {
+    pub achievements: Vec<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favorites: Option<Vec<i32>>,
+    pub uids: Vec<ZzzUid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completions: Option<Vec<Completion>>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct ZzzUid {
+    pub uid: i32,
+    pub verified: bool,
+    pub private: bool,
+    pub signals: Signals,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Signals {
+    pub standard: Vec<Signal>,
+    pub character: Vec<Signal>,
+    pub w_engine: Vec<Signal>,
+    pub bangboo: Vec<Signal>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclusive_rescreening: Option<Vec<Signal>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub w_engine_reverberation: Option<Vec<Signal>>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Signal {
+    pub id: String,
+    pub item_id: i32,
+    pub r#type: SignalType,
+    pub timestamp: DateTime<Utc>,
+    pub official: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SignalType {
+    Character,
+    WEngine,
+    Bangboo,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Gi {
+    pub achievements: Vec<i32>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub favorites: Option<Vec<i32>>,
+    pub uids: Vec<GiUid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completions: Option<Vec<Completion>>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct GiUid {
+    pub uid: i32,
+    pub verified: bool,
+    pub private: bool,
+    pub wishes: Wishes,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Wishes {
+    pub beginner: Vec<Wish>,
+    pub standard: Vec<Wish>,
+    pub character: Vec<Wish>,
+    pub weapon: Vec<Wish>,
+    pub chronicled: Vec<Wish>,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+pub struct Wish {
+    pub id: String,
+    pub item_id: i32,
+    pub r#type: WishType,
+    pub timestamp: DateTime<Utc>,
+    pub official: bool,
+}
+
+#[derive(serde::Serialize, serde::Deserialize, utoipa::ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum WishType {
+    Character,
+    Weapon,
+}
+
+impl From<database::users_achievements_completed::DbUserAchievementCompletedAt> for Completion {
+    fn from(
+        completion: database::users_achievements_completed::DbUserAchievementCompletedAt,
+    ) -> Self {
+        Self {
+            id: completion.id,
+            completed_at: completion.completed_at,
+            imported: completion.imported,
+        }
+    }
+}
+
+impl From<database::zzz::users_achievements_completed::DbUserAchievementCompletedAt>
+    for Completion
+{
+    fn from(
+        completion: database::zzz::users_achievements_completed::DbUserAchievementCompletedAt,
+    ) -> Self {
+        Self {
+            id: completion.id,
+            completed_at: completion.completed_at,
+            imported: completion.imported,
+        }
+    }
+}
+
+impl From<database::gi::users_achievements_completed::DbUserAchievementCompletedAt> for Completion {
+    fn from(
+        completion: database::gi::users_achievements_completed::DbUserAchievementCompletedAt,
+    ) -> Self {
+        Self {
+            id: completion.id,
+            completed_at: completion.completed_at,
+            imported: completion.imported,
+        }
+    }
+}
+
+impl From<database::warps::DbWarp> for Warp {
+    fn from(warp: database::warps::DbWarp) -> Self {
+        let r#type = if warp.character.is_some() {
+            WarpType::Character
+        } else {
+            WarpType::LightCone
+        };
+
+        Self {
+            r#type,
+            id: warp.id.to_string(),
+            item_id: warp.character.or(warp.light_cone).unwrap(),
+            timestamp: warp.timestamp,
+            official: warp.official,
+        }
+    }
+}
+
+impl From<database::zzz::signals::DbSignal> for Signal {
+    fn from(signal: database::zzz::signals::DbSignal) -> Self {
+        let r#type = if signal.character.is_some() {
+            SignalType::Character
+        } else if signal.w_engine.is_some() {
+            SignalType::WEngine
+        } else {
+            SignalType::Bangboo
+        };
+
+        Self {
+            r#type,
+            id: signal.id.to_string(),
+            item_id: signal
+                .character
+                .or(signal.w_engine)
+                .or(signal.bangboo)
+                .unwrap(),
+            timestamp: signal.timestamp,
+            official: signal.official,
+        }
+    }
+}
+
+impl From<database::gi::wishes::DbWish> for Wish {
+    fn from(wish: database::gi::wishes::DbWish) -> Self {
+        let r#type = if wish.character.is_some() {
+            WishType::Character
+        } else {
+            WishType::Weapon
+        };
+
+        Self {
+            r#type,
+            id: wish.id.to_string(),
+            item_id: wish.character.or(wish.weapon).unwrap(),
+            timestamp: wish.timestamp,
+            official: wish.official,
+        }
+    }
+}
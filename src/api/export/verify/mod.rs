@@ -0,0 +1,65 @@
+use actix_web::{post, web, HttpResponse, Responder};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    api::{export::Export, ApiResult},
+    signing,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "export/verify")),
+    paths(post_export_verify),
+    components(schemas(
+        Verification
+    ))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_export_verify);
+}
+
+#[derive(Serialize, ToSchema)]
+struct Verification {
+    valid: bool,
+    key_id: Option<String>,
+}
+
+#[utoipa::path(
+    tag = "export/verify",
+    post,
+    path = "/api/export/verify",
+    request_body = Export,
+    responses(
+        (status = 200, description = "Verification", body = Verification),
+    )
+)]
+#[post("/api/export/verify")]
+async fn post_export_verify(
+    export: web::Json<Export>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let user_bytes = serde_json::to_vec(&export.user)?;
+
+    let key_id = signing::verify(
+        &user_bytes,
+        &export.signature,
+        export.key_id.as_deref(),
+        &pool,
+    )
+    .await?;
+
+    let verification = Verification {
+        valid: key_id.is_some(),
+        key_id,
+    };
+
+    Ok(HttpResponse::Ok().json(verification))
+}
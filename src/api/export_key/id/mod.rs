@@ -0,0 +1,43 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+
+use crate::{
+    api::{export_key::ExportKey, ApiResult},
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(tags((name = "export-key/{id}")), paths(get_export_key_by_id))]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_export_key_by_id);
+}
+
+#[utoipa::path(
+    tag = "export-key/{id}",
+    get,
+    path = "/api/export-key/{id}",
+    responses(
+        (status = 200, description = "Current, retired or revoked export key", body = ExportKey),
+        (status = 404, description = "Unknown key id"),
+    )
+)]
+#[get("/api/export-key/{id}")]
+async fn get_export_key_by_id(
+    id: web::Path<String>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(export_key) = database::export_keys::get_by_id(&id, &pool).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    let export_key = ExportKey::try_from(export_key)?;
+
+    Ok(HttpResponse::Ok().json(export_key))
+}
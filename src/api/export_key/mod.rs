@@ -0,0 +1,109 @@
+mod id;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use ed25519_dalek::{
+    pkcs8::{spki::der::pem::LineEnding, EncodePublicKey},
+    SigningKey, VerifyingKey,
+};
+use futures::lock::Mutex;
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{api::ApiResult, database, signing};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "export-key")),
+    paths(get_export_key),
+    components(schemas(
+        ExportKey,
+        Jwk,
+    ))
+)]
+struct ApiDoc;
+
+#[derive(Serialize, ToSchema)]
+struct ExportKey {
+    id: String,
+    pem: String,
+    jwk: Jwk,
+    created: DateTime<Utc>,
+    /// Exports are signed with a newer key, but signatures of this one still verify
+    retired: Option<DateTime<Utc>>,
+    /// Signatures of this key aren't trusted anymore
+    revoked: Option<DateTime<Utc>>,
+}
+
+#[derive(Serialize, ToSchema)]
+struct Jwk {
+    kty: String,
+    crv: String,
+    alg: String,
+    #[serde(rename = "use")]
+    r#use: String,
+    kid: String,
+    x: String,
+}
+
+impl TryFrom<database::export_keys::DbExportKey> for ExportKey {
+    type Error = anyhow::Error;
+
+    fn try_from(export_key: database::export_keys::DbExportKey) -> Result<Self, Self::Error> {
+        let verifying_key = VerifyingKey::from_bytes(export_key.public_key.as_slice().try_into()?)?;
+
+        let jwk = Jwk {
+            kty: "OKP".to_string(),
+            crv: "Ed25519".to_string(),
+            alg: "EdDSA".to_string(),
+            r#use: "sig".to_string(),
+            kid: export_key.id.clone(),
+            x: BASE64_URL_SAFE_NO_PAD.encode(verifying_key.as_bytes()),
+        };
+
+        Ok(Self {
+            id: export_key.id,
+            pem: verifying_key.to_public_key_pem(LineEnding::LF)?,
+            jwk,
+            created: export_key.created,
+            retired: export_key.retired,
+            revoked: export_key.revoked,
+        })
+    }
+}
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(id::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_export_key).configure(id::configure);
+}
+
+#[utoipa::path(
+    tag = "export-key",
+    get,
+    path = "/api/export-key",
+    responses(
+        (status = 200, description = "Key currently used to sign exports", body = ExportKey),
+    )
+)]
+#[get("/api/export-key")]
+async fn get_export_key(
+    signing_key: web::Data<Mutex<SigningKey>>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let id = signing::key_id(&signing::active(&signing_key, &pool).await?.verifying_key());
+
+    let Some(export_key) = database::export_keys::get_by_id(&id, &pool).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    let export_key = ExportKey::try_from(export_key)?;
+
+    Ok(HttpResponse::Ok().json(export_key))
+}
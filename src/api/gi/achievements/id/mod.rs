@@ -57,7 +57,7 @@ async fn get_gi_achievement(
 
     if let Some(set) = achievement.set {
         achievement.related = Some(
-            database::gi::achievements::get_all_related_ids(achievement.id, set, &pool).await?,
+            database::gi::achievements::get_all_related_ids(achievement.id, set, &**pool).await?,
         );
     }
 
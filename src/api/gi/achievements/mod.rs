@@ -131,7 +131,8 @@ async fn get_gi_achievements(
     for achievement in &mut achievements {
         if let Some(set) = achievement.set {
             achievement.related = Some(
-                database::gi::achievements::get_all_related_ids(achievement.id, set, &pool).await?,
+                database::gi::achievements::get_all_related_ids(achievement.id, set, &**pool)
+                    .await?,
             );
         }
     }
@@ -1,3 +1,5 @@
+mod stats;
+
 use actix_session::Session;
 use actix_web::{delete, get, put, web, HttpResponse, Responder};
 use chrono::{DateTime, Utc};
@@ -18,13 +20,16 @@ use crate::{
 struct ApiDoc;
 
 pub fn openapi() -> utoipa::openapi::OpenApi {
-    ApiDoc::openapi()
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(stats::openapi());
+    openapi
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_gi_banner)
         .service(put_gi_banner)
-        .service(delete_gi_banner);
+        .service(delete_gi_banner)
+        .configure(stats::configure);
 }
 
 #[utoipa::path(
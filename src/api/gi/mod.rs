@@ -1,8 +1,10 @@
 mod achievements;
 mod banners;
 mod paimon_wishes_import;
+mod simulate;
+mod stats;
 mod wishes;
-mod wishes_import;
+pub(super) mod wishes_import;
 
 use actix_web::web;
 use utoipa::OpenApi;
@@ -16,6 +18,8 @@ pub fn openapi() -> utoipa::openapi::OpenApi {
     openapi.merge(achievements::openapi());
     openapi.merge(banners::openapi());
     openapi.merge(paimon_wishes_import::openapi());
+    openapi.merge(simulate::openapi());
+    openapi.merge(stats::openapi());
     openapi.merge(wishes::openapi());
     openapi.merge(wishes_import::openapi());
     openapi
@@ -25,6 +29,8 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.configure(achievements::configure)
         .configure(banners::configure)
         .configure(paimon_wishes_import::configure)
+        .configure(simulate::configure)
+        .configure(stats::configure)
         .configure(wishes::configure)
         .configure(wishes_import::configure);
 }
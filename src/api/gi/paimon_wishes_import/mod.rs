@@ -1,11 +1,16 @@
-use actix_session::Session;
 use actix_web::{post, web, HttpResponse, Responder};
 use chrono::NaiveDateTime;
 use rand::seq::IndexedRandom as _;
 use sqlx::PgPool;
 use utoipa::OpenApi;
 
-use crate::{api::ApiResult, database, GiGachaType};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        ApiResult,
+    },
+    database, GiGachaType,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -57,11 +62,11 @@ struct Pull {
 )]
 #[post("/api/gi/paimon-wishes-import")]
 async fn post_paimon_warps_import(
-    session: Session,
+    auth: Auth,
     params: web::Json<PaimonWishesImportParams>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::WarpsImport) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
@@ -75,10 +80,10 @@ async fn post_paimon_warps_import(
         wish_uid.as_str().unwrap().parse()?
     };
 
-    let admin = database::admins::exists(&username, &pool).await?;
+    let admin = auth.is_admin(&pool).await?;
 
     if !admin
-        && database::gi::profiles::get_by_uid(uid, &pool)
+        && database::gi::profiles::get_by_uid(uid, &**pool)
             .await?
             .is_none()
     {
@@ -263,11 +268,11 @@ async fn post_paimon_warps_import(
         }
     }
 
-    database::gi::wishes::beginner::set_all(&set_all_beginner, &pool).await?;
-    database::gi::wishes::standard::set_all(&set_all_standard, &pool).await?;
-    database::gi::wishes::character::set_all(&set_all_character, &pool).await?;
-    database::gi::wishes::weapon::set_all(&set_all_weapon, &pool).await?;
-    database::gi::wishes::chronicled::set_all(&set_all_chronicled, &pool).await?;
+    database::gi::wishes::beginner::set_all(&set_all_beginner, &**pool).await?;
+    database::gi::wishes::standard::set_all(&set_all_standard, &**pool).await?;
+    database::gi::wishes::character::set_all(&set_all_character, &**pool).await?;
+    database::gi::wishes::weapon::set_all(&set_all_weapon, &**pool).await?;
+    database::gi::wishes::chronicled::set_all(&set_all_chronicled, &**pool).await?;
 
     Ok(HttpResponse::Ok().finish())
 }
@@ -0,0 +1,231 @@
+use std::collections::HashMap;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use chrono::Utc;
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        banner_helpers::{self, RadianceState, GI_STANDARD},
+        private,
+        pull_simulator::{self, Pity, Rules, SimulatedCopies, SimulatedSpend, Simulation, State},
+        ApiResult,
+    },
+    database, Language,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "gi/simulate")),
+    paths(get_gi_simulate),
+    components(schemas(SimulateBanner, Simulation, SimulatedCopies, SimulatedSpend, State))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_gi_simulate);
+}
+
+#[derive(Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulateBanner {
+    Character,
+    /// With the charted weapon as the target
+    Weapon,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SimulateParams {
+    banner: SimulateBanner,
+    /// Start from the pity, guarantee, Capturing Radiance and fate points of this uid's wishes
+    uid: Option<i32>,
+    /// Overrides the pity of the uid
+    pull: Option<usize>,
+    /// Overrides the guarantee of the uid
+    guarantee: Option<bool>,
+    /// Overrides the consecutive lost 50/50s of the uid. Character banner only
+    losses: Option<i32>,
+    /// Overrides the fate points of the uid. Weapon banner only
+    fate_points: Option<i32>,
+    /// Primogems
+    #[serde(default)]
+    currency: i32,
+    /// Intertwined Fates
+    #[serde(default)]
+    passes: i32,
+    /// Defaults to C6 or R5
+    copies: Option<usize>,
+}
+
+#[utoipa::path(
+    tag = "gi/simulate",
+    get,
+    path = "/api/gi/simulate",
+    params(SimulateParams),
+    responses(
+        (status = 200, description = "Simulated copies of the featured character or the charted weapon", body = Simulation),
+        (status = 403, description = "The uid is private"),
+    )
+)]
+#[get("/api/gi/simulate", guard = "private")]
+async fn get_gi_simulate(
+    auth: Auth,
+    params: web::Query<SimulateParams>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let mut state = State::default();
+
+    if let Some(uid) = params.uid {
+        let mut forbidden = database::gi::connections::get_by_uid(uid, &pool)
+            .await?
+            .iter()
+            .any(|c| c.private);
+
+        if forbidden {
+            if let Some(username) = auth.username(Scope::ReadPrivate) {
+                if let Ok(connection) =
+                    database::gi::connections::get_by_uid_and_username(uid, &username, &pool).await
+                {
+                    forbidden = !connection.verified;
+                }
+            }
+        }
+
+        if forbidden {
+            return Ok(HttpResponse::Forbidden().finish());
+        }
+
+        let gi_banners = database::gi::banners::get_all(&pool).await?;
+
+        let mut banners: HashMap<_, Vec<_>> = HashMap::new();
+
+        for banner in &gi_banners {
+            if let Some(character) = banner.character {
+                banners
+                    .entry(character)
+                    .or_default()
+                    .push(banner.start..banner.end);
+            }
+
+            if let Some(weapon) = banner.weapon {
+                banners
+                    .entry(weapon)
+                    .or_default()
+                    .push(banner.start..banner.end);
+            }
+        }
+
+        let is_win = banner_helpers::is_win_fn(&banners, GI_STANDARD);
+
+        match params.banner {
+            SimulateBanner::Character => {
+                let mut radiance = RadianceState::default();
+
+                for wish in
+                    database::gi::wishes::character::get_by_uid(uid, Language::En, &pool).await?
+                {
+                    state.pull += 1;
+
+                    if wish.rarity != Some(5) {
+                        continue;
+                    }
+
+                    state.pull = 0;
+
+                    radiance.pull(
+                        is_win(wish.character.unwrap(), wish.timestamp),
+                        wish.timestamp,
+                    );
+                }
+
+                state.guarantee = radiance.guarantee;
+                state.losses = radiance.losses;
+            }
+            SimulateBanner::Weapon => {
+                let wishes =
+                    database::gi::wishes::weapon::get_by_uid(uid, Language::En, &pool).await?;
+
+                for wish in &wishes {
+                    state.pull += 1;
+
+                    if wish.rarity != Some(5) {
+                        continue;
+                    }
+
+                    state.pull = 0;
+                    state.guarantee =
+                        !state.guarantee && !is_win(wish.weapon.unwrap(), wish.timestamp);
+                }
+
+                // Fate points expire with the banner, so only the running one counts
+                let now = Utc::now();
+                if let Some(weapon_banner) = banner_helpers::gi_weapon_banners(&gi_banners)
+                    .into_iter()
+                    .find(|b| (b.start..b.end).contains(&now))
+                {
+                    let chart = database::gi::weapon_charts::get_by_uid(uid, &pool)
+                        .await?
+                        .into_iter()
+                        .find(|c| c.banner == weapon_banner.id)
+                        .map(|c| c.weapon);
+
+                    if let Some(chart) = chart {
+                        for wish in wishes.iter().filter(|w| {
+                            w.rarity == Some(5)
+                                && (weapon_banner.start..weapon_banner.end).contains(&w.timestamp)
+                        }) {
+                            state.fate_points = if wish.weapon == Some(chart) {
+                                0
+                            } else {
+                                (state.fate_points + 1).min(banner_helpers::gi_max_fate_points(now))
+                            };
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    state.pull = params.pull.unwrap_or(state.pull);
+    state.guarantee = params.guarantee.unwrap_or(state.guarantee);
+    state.losses = params.losses.unwrap_or(state.losses);
+    state.fate_points = params.fate_points.unwrap_or(state.fate_points);
+
+    let (rules, max_copies) = match params.banner {
+        SimulateBanner::Character => (
+            Rules {
+                pity: Pity::GI,
+                win_rate: 0.5,
+                radiance: true,
+                epitomized: false,
+            },
+            7,
+        ),
+        SimulateBanner::Weapon => (
+            Rules {
+                pity: Pity::GI_WEAPON,
+                win_rate: 0.75,
+                radiance: false,
+                epitomized: true,
+            },
+            5,
+        ),
+    };
+
+    let copies = params.copies.unwrap_or(max_copies).clamp(1, max_copies);
+    let (currency, passes) = (params.currency.max(0), params.passes.max(0));
+
+    let simulation = web::block(move || {
+        pull_simulator::simulate(&rules, state, currency, passes, copies, &mut rand::rng())
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(simulation))
+}
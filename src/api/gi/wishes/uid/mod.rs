@@ -1,11 +1,13 @@
-use actix_session::Session;
 use actix_web::{get, web, HttpResponse, Responder};
 use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 use utoipa::OpenApi;
 
 use crate::{
-    api::{ApiResult, LanguageParams},
+    api::{
+        auth::{Auth, Scope},
+        ApiResult, LanguageParams,
+    },
     database,
 };
 
@@ -81,7 +83,7 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
 )]
 #[get("/api/gi/wishes/{uid}")]
 async fn get_gi_wishes(
-    session: Session,
+    auth: Auth,
     uid: web::Path<i32>,
     language_params: web::Query<LanguageParams>,
     pool: web::Data<PgPool>,
@@ -94,7 +96,7 @@ async fn get_gi_wishes(
         .any(|c| c.private);
 
     if forbidden {
-        if let Ok(Some(username)) = session.get::<String>("username") {
+        if let Some(username) = auth.username(Scope::ReadPrivate) {
             if let Ok(connection) =
                 database::gi::connections::get_by_uid_and_username(uid, &username, &pool).await
             {
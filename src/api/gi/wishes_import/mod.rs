@@ -1,21 +1,19 @@
-mod uid;
+use std::collections::HashMap;
 
-use std::{collections::HashMap, sync::Arc, time::Duration};
-
-use actix_session::Session;
-use actix_web::{post, rt, web, HttpResponse, Responder};
+use actix_web::{post, web, HttpResponse, Responder};
 use chrono::{FixedOffset, NaiveDateTime};
-use futures::lock::Mutex;
 use reqwest::header;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use strum::IntoEnumIterator;
 use url::Url;
 use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
 
 use crate::{
     api::{
-        banner_helpers::{self, GI_STANDARD},
+        auth::{Auth, Scope},
+        banner_helpers::{self, RadianceState, WinOutcome, GI_STANDARD},
+        import_jobs::{self, GachaLogFetcher, Game, Upstream},
         validate_import_url, ApiResult,
     },
     database, GiGachaType,
@@ -25,24 +23,16 @@ use crate::{
 #[openapi(
     tags((name = "gi/wishes-import")),
     paths(post_gi_wishes_import),
-    components(schemas(WishesImportParams, WishesImport, WishesImportInfo, Status))
+    components(schemas(WishesImportParams, WishesImport))
 )]
 struct ApiDoc;
 
 pub fn openapi() -> utoipa::openapi::OpenApi {
-    let mut openapi = ApiDoc::openapi();
-    openapi.merge(uid::openapi());
-    openapi
-}
-
-lazy_static::lazy_static! {
-    static ref DATA: web::Data<WishesImportInfos> = web::Data::new(WishesImportInfos::default());
+    ApiDoc::openapi()
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.app_data(DATA.clone())
-        .service(post_gi_wishes_import)
-        .configure(uid::configure);
+    cfg.service(post_gi_wishes_import);
 }
 
 #[derive(Deserialize)]
@@ -65,28 +55,6 @@ struct Entry {
     time: String,
 }
 
-type WishesImportInfos = Mutex<HashMap<i32, Arc<Mutex<WishesImportInfo>>>>;
-
-#[derive(Serialize, ToSchema, Clone)]
-#[serde(rename_all = "snake_case")]
-enum Status {
-    Pending,
-    Calculating,
-    Finished,
-    Error(String),
-}
-
-#[derive(Serialize, ToSchema, Clone)]
-struct WishesImportInfo {
-    gacha_type: GiGachaType,
-    beginner: usize,
-    standard: usize,
-    character: usize,
-    weapon: usize,
-    chronicled: usize,
-    status: Status,
-}
-
 #[derive(Deserialize, ToSchema)]
 struct WishesImportParams {
     url: String,
@@ -96,6 +64,9 @@ struct WishesImportParams {
 
 #[derive(Serialize, ToSchema)]
 struct WishesImport {
+    /// Poll /api/import-jobs/{id} for progress
+    #[schema(value_type = String)]
+    id: Uuid,
     uid: i32,
 }
 
@@ -111,9 +82,8 @@ struct WishesImport {
 )]
 #[post("/api/gi/wishes-import")]
 async fn post_gi_wishes_import(
-    session: Session,
+    auth: Auth,
     params: web::Json<WishesImportParams>,
-    wishes_import_infos: web::Data<WishesImportInfos>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
     let url = match validate_import_url(&params.url) {
@@ -142,48 +112,14 @@ async fn post_gi_wishes_import(
         .extend_pairs(&[("lang", "en"), ("size", "20")])
         .finish();
 
-    let mut uid = 0;
-    let mut import_error = None;
-
-    for gacha_type in [100, 200, 301, 302, 500] {
-        // User-provided wish URLs can point at expired or malformed upstream responses.
-        // Keep those as import status errors instead of bubbling reqwest decode errors.
-        let gacha_log = match reqwest::get(format!("{url}&gacha_type={gacha_type}&end_id=0")).await
-        {
-            Ok(response) => match response.json::<GachaLog>().await {
-                Ok(gacha_log) => gacha_log,
-                Err(_) => {
-                    import_error = Some("Unable to fetch wish history".to_string());
-                    continue;
-                }
-            },
-            Err(_) => {
-                import_error = Some("Unable to fetch wish history".to_string());
-                continue;
-            }
-        };
+    let uid = match find_uid(&Upstream, &url).await {
+        Ok(uid) => uid,
+        Err(error) => {
+            let id = import_jobs::insert_failed(Game::Gi, &error, &pool).await?;
 
-        if let Some(entry) = gacha_log.data.list.first() {
-            uid = entry.uid.parse()?;
-            break;
+            return Ok(HttpResponse::Ok().json(WishesImport { id, uid: 0 }));
         }
-    }
-
-    if uid == 0 {
-        let info = Arc::new(Mutex::new(WishesImportInfo {
-            gacha_type: GiGachaType::Standard,
-            beginner: 0,
-            standard: 0,
-            character: 0,
-            weapon: 0,
-            chronicled: 0,
-            status: Status::Error(import_error.unwrap_or_else(|| "No data".to_string())),
-        }));
-
-        wishes_import_infos.lock().await.insert(uid, info.clone());
-
-        return Ok(HttpResponse::Ok().json(WishesImport { uid }));
-    }
+    };
 
     // Enka is only used to populate a display name. The import can continue without it.
     let name = match reqwest::Client::new()
@@ -202,8 +138,8 @@ async fn post_gi_wishes_import(
         Err(_) => String::new(),
     };
 
-    database::gi::profiles::set(&database::gi::profiles::DbProfile { uid, name }, &pool).await?;
-    if let Ok(Some(username)) = session.get::<String>("username") {
+    database::gi::profiles::set(&database::gi::profiles::DbProfile { uid, name }, &**pool).await?;
+    if let Some(username) = auth.username(Scope::WarpsImport) {
         let connection = database::gi::connections::DbConnection {
             uid,
             username,
@@ -211,71 +147,83 @@ async fn post_gi_wishes_import(
             private: false,
         };
 
-        database::gi::connections::set(&connection, &pool).await?;
+        database::gi::connections::set(&connection, &**pool).await?;
     }
 
-    if wishes_import_infos.lock().await.contains_key(&uid) {
-        return Ok(HttpResponse::Ok().json(WishesImport { uid }));
-    }
+    let id = import_jobs::enqueue(Game::Gi, uid, &url, params.ignore_timestamps, &pool).await?;
+
+    Ok(HttpResponse::Ok().json(WishesImport { id, uid }))
+}
+
+/// Checks the banners until one has a wish, which has the uid. User-provided wish URLs can point
+/// at expired or malformed upstream responses, those become the job's error instead of failing
+/// the request.
+async fn find_uid(fetcher: &impl GachaLogFetcher, url: &Url) -> Result<i32, String> {
+    let mut error = "No data".to_string();
 
-    let info = Arc::new(Mutex::new(WishesImportInfo {
-        gacha_type: GiGachaType::Standard,
-        beginner: 0,
-        standard: 0,
-        character: 0,
-        weapon: 0,
-        chronicled: 0,
-        status: Status::Pending,
-    }));
+    for gacha_type in [100, 200, 301, 302, 500] {
+        let gacha_log: GachaLog = match import_jobs::get_gacha_log(
+            fetcher,
+            &format!("{url}&gacha_type={gacha_type}&end_id=0"),
+        )
+        .await
+        {
+            Ok(gacha_log) => gacha_log,
+            Err(_) => {
+                error = "Unable to fetch wish history".to_string();
+                continue;
+            }
+        };
 
-    wishes_import_infos.lock().await.insert(uid, info.clone());
+        if let Some(entry) = gacha_log.data.list.first() {
+            return entry
+                .uid
+                .parse()
+                .map_err(|_| "Unable to fetch wish history".to_string());
+        }
+    }
 
-    rt::spawn(async move {
-        let mut error = Ok(());
+    Err(error)
+}
 
-        for gacha_type in GiGachaType::iter() {
-            info.lock().await.gacha_type = gacha_type;
+pub async fn run(
+    job: &database::import_jobs::DbImportJob,
+    fetcher: &impl GachaLogFetcher,
+    pool: &PgPool,
+) -> ApiResult<()> {
+    // A job reclaimed while calculating has fetched everything and dropped its url already
+    if job.status != import_jobs::Status::Calculating.to_string() {
+        let Some(url) = &job.url else {
+            return Err(anyhow::anyhow!("Missing url").into());
+        };
+        let url = Url::parse(url)?;
 
-            if let Err(e) = import_wishes(
-                uid,
+        for gacha_type in import_jobs::remaining_gacha_types(job) {
+            import_wishes(
+                job.id,
+                fetcher,
+                job.uid,
                 &url,
-                params.ignore_timestamps,
+                job.ignore_timestamps,
                 gacha_type,
-                &info,
-                &pool,
+                pool,
             )
-            .await
-            {
-                error = Err(e);
-
-                break;
-            }
-        }
-
-        if let Err(e) = error {
-            info.lock().await.status = Status::Error(e.to_string());
-        } else if let Err(e) = calculate_stats(uid, &info, &pool).await {
-            info.lock().await.status = Status::Error(e.to_string());
-        } else {
-            info.lock().await.status = Status::Finished;
+            .await?;
         }
+    }
 
-        rt::spawn(async move {
-            rt::time::sleep(Duration::from_secs(60)).await;
+    calculate_stats(job.id, job.uid, pool).await?;
 
-            wishes_import_infos.lock().await.remove(&uid);
-        });
-    });
-
-    Ok(HttpResponse::Ok().json(WishesImport { uid }))
+    Ok(())
 }
 
 async fn import_wishes(
+    job_id: Uuid,
+    fetcher: &impl GachaLogFetcher,
     uid: i32,
     url: &Url,
     ignore_timestamps: bool,
     gacha_type: GiGachaType,
-    info: &Arc<Mutex<WishesImportInfo>>,
     pool: &PgPool,
 ) -> ApiResult<()> {
     let mut url = url.clone();
@@ -314,22 +262,12 @@ async fn import_wishes(
         }
     };
 
-    'outer: loop {
-        let mut i = 0;
-        let gacha_log = loop {
-            let response = reqwest::get(format!("{url}&end_id={end_id}")).await?;
-            if let Ok(gacha_log) = response.json::<GachaLog>().await {
-                break gacha_log;
-            }
-
-            if i > 2 {
-                return Err(anyhow::anyhow!("Unsure").into());
-            }
-
-            rt::time::sleep(Duration::from_secs(1)).await;
+    let gacha_type_name = gacha_type.to_string();
+    database::import_jobs::set_progress(job_id, &gacha_type_name, 0, pool).await?;
 
-            i += 1;
-        };
+    'outer: loop {
+        let gacha_log: GachaLog =
+            import_jobs::get_gacha_log(fetcher, &format!("{url}&end_id={end_id}")).await?;
 
         if gacha_log.data.list.is_empty() {
             break;
@@ -337,7 +275,7 @@ async fn import_wishes(
 
         let region_time_zone = match gacha_log.data.region.as_str() {
             "os_usa" => -5,
-            "os_eu" => 1,
+            "os_euro" => 1,
             _ => 8,
         };
 
@@ -386,17 +324,20 @@ async fn import_wishes(
             set_all.weapon.push(weapon);
             set_all.timestamp.push(timestamp);
             set_all.official.push(true);
-
-            match gacha_type {
-                GiGachaType::Beginner => info.lock().await.beginner += 1,
-                GiGachaType::Standard => info.lock().await.standard += 1,
-                GiGachaType::Character => info.lock().await.character += 1,
-                GiGachaType::Weapon => info.lock().await.weapon += 1,
-                GiGachaType::Chronicled => info.lock().await.chronicled += 1,
-            }
         }
+
+        database::import_jobs::set_progress(
+            job_id,
+            &gacha_type_name,
+            set_all.id.len() as i32,
+            pool,
+        )
+        .await?;
     }
 
+    database::import_jobs::set_progress(job_id, &gacha_type_name, set_all.id.len() as i32, pool)
+        .await?;
+
     match gacha_type {
         GiGachaType::Beginner => database::gi::wishes::beginner::set_all(&set_all, pool).await?,
         GiGachaType::Standard => database::gi::wishes::standard::set_all(&set_all, pool).await?,
@@ -410,21 +351,14 @@ async fn import_wishes(
     Ok(())
 }
 
-async fn calculate_stats(
-    uid: i32,
-    info: &Arc<Mutex<WishesImportInfo>>,
-    pool: &PgPool,
-) -> anyhow::Result<()> {
-    info.lock().await.status = Status::Calculating;
+async fn calculate_stats(job_id: Uuid, uid: i32, pool: &PgPool) -> anyhow::Result<()> {
+    database::import_jobs::set_calculating(job_id, pool).await?;
 
-    info.lock().await.gacha_type = GiGachaType::Standard;
     calculate_stats_standard(uid, pool).await?;
-    info.lock().await.gacha_type = GiGachaType::Character;
     calculate_stats_character(uid, pool).await?;
-    info.lock().await.gacha_type = GiGachaType::Weapon;
     calculate_stats_weapon(uid, pool).await?;
-    info.lock().await.gacha_type = GiGachaType::Chronicled;
     calculate_stats_chronicled(uid, pool).await?;
+    calculate_stats_beginner(uid, pool).await?;
 
     Ok(())
 }
@@ -503,7 +437,7 @@ async fn calculate_stats_character(uid: i32, pool: &PgPool) -> anyhow::Result<()
     let mut sum_5 = 0;
     let mut count_5 = 0;
 
-    let mut guarantee = false;
+    let mut radiance = RadianceState::default();
 
     let mut sum_win = 0;
     let mut count_win = 0;
@@ -529,28 +463,36 @@ async fn calculate_stats_character(uid: i32, pool: &PgPool) -> anyhow::Result<()
                 sum_5 += pull_5;
                 pull_5 = 0;
 
-                if guarantee {
-                    guarantee = false;
-                } else {
-                    count_win += 1;
-
-                    if is_win(wish.character.unwrap(), wish.timestamp) {
+                // Guarantees and Capturing Radiance are not 50/50s, but radiance still ends a
+                // loss streak.
+                match radiance.pull(
+                    is_win(wish.character.unwrap(), wish.timestamp),
+                    wish.timestamp,
+                ) {
+                    WinOutcome::Guarantee => {}
+                    WinOutcome::Win => {
+                        count_win += 1;
                         sum_win += 1;
 
                         loss_streak = 0;
 
                         win_streak += 1;
                         max_win_streak = max_win_streak.max(win_streak);
-
-                        continue;
                     }
+                    WinOutcome::Radiance => {
+                        loss_streak = 0;
 
-                    win_streak = 0;
+                        win_streak += 1;
+                        max_win_streak = max_win_streak.max(win_streak);
+                    }
+                    WinOutcome::Loss => {
+                        count_win += 1;
 
-                    loss_streak += 1;
-                    max_loss_streak = max_loss_streak.max(loss_streak);
+                        win_streak = 0;
 
-                    guarantee = true;
+                        loss_streak += 1;
+                        max_loss_streak = max_loss_streak.max(loss_streak);
+                    }
                 }
             }
             _ => {}
@@ -724,3 +666,238 @@ async fn calculate_stats_chronicled(uid: i32, pool: &PgPool) -> anyhow::Result<(
 
     Ok(())
 }
+
+async fn calculate_stats_beginner(uid: i32, pool: &PgPool) -> anyhow::Result<()> {
+    let wishes = database::gi::wishes::beginner::get_infos_by_uid(uid, pool).await?;
+
+    let mut pull_4 = 0;
+    let mut sum_4 = 0;
+    let mut count_4 = 0;
+
+    let mut pull_5 = 0;
+    let mut sum_5 = 0;
+    let mut count_5 = 0;
+
+    for wish in &wishes {
+        pull_4 += 1;
+        pull_5 += 1;
+
+        match wish.rarity.unwrap() {
+            4 => {
+                count_4 += 1;
+                sum_4 += pull_4;
+                pull_4 = 0;
+            }
+            5 => {
+                count_5 += 1;
+                sum_5 += pull_5;
+                pull_5 = 0;
+            }
+            _ => {}
+        }
+    }
+
+    let luck_4 = if count_4 != 0 {
+        sum_4 as f64 / count_4 as f64
+    } else {
+        0.0
+    };
+    let luck_5 = if count_5 != 0 {
+        sum_5 as f64 / count_5 as f64
+    } else {
+        0.0
+    };
+
+    let stat = database::gi::wishes_stats::beginner::DbWishesStatBeginner {
+        uid,
+        luck_4,
+        luck_5,
+    };
+    database::gi::wishes_stats::beginner::set(&stat, pool).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        api::import_jobs::mock::{self, MockUpstream},
+        test_util::test_pool,
+        Language,
+    };
+
+    const UID: i32 = 700_000_002;
+
+    fn entry(id: &str, gacha_type: &str, name: &str, item_type: &str, time: &str) -> Value {
+        json!({
+            "uid": UID.to_string(),
+            "gacha_type": gacha_type,
+            "item_id": "",
+            "count": "1",
+            "time": time,
+            "name": name,
+            "lang": "en-us",
+            "item_type": item_type,
+            "rank_type": "3",
+            "id": id,
+        })
+    }
+
+    #[actix_web::test]
+    async fn imports_wishes_by_name() {
+        let pool = test_pool().await;
+
+        sqlx::query("DELETE FROM import_jobs WHERE game = 'gi' AND uid = $1")
+            .bind(UID)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM gi_profiles WHERE uid = $1")
+            .bind(UID)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        database::gi::characters::set_all(&[10000021], &[4], &pool)
+            .await
+            .unwrap();
+        database::gi::characters_text::set_all(
+            &[10000021],
+            &[Language::En],
+            &["Amber".to_string()],
+            &pool,
+        )
+        .await
+        .unwrap();
+        database::gi::weapons::set_all(&[11301], &[3], &pool)
+            .await
+            .unwrap();
+        database::gi::weapons_text::set_all(
+            &[11301],
+            &[Language::En],
+            &["Cool Steel".to_string()],
+            &pool,
+        )
+        .await
+        .unwrap();
+        database::gi::profiles::set(
+            &database::gi::profiles::DbProfile {
+                uid: UID,
+                name: String::new(),
+            },
+            &pool,
+        )
+        .await
+        .unwrap();
+
+        let upstream = MockUpstream::start().await;
+        upstream.page(
+            200,
+            "0",
+            mock::gacha_log(vec![
+                entry(
+                    "1700000000000000002",
+                    "200",
+                    "Amber",
+                    "Character",
+                    "2024-01-02 12:00:00",
+                ),
+                entry(
+                    "1700000000000000001",
+                    "200",
+                    "Cool Steel",
+                    "Weapon",
+                    "2024-01-01 12:00:00",
+                ),
+            ]),
+        );
+        upstream.page(
+            301,
+            "0",
+            mock::gacha_log(vec![entry(
+                "1700000000000000003",
+                "301",
+                "Amber",
+                "Character",
+                "2024-01-03 12:00:00",
+            )]),
+        );
+
+        let url = Url::parse(
+            "https://public-operation-hk4e-sg.hoyoverse.com/gacha_info/api/getGachaLog?authkey=key&lang=en&size=20",
+        )
+        .unwrap();
+        let id = import_jobs::enqueue(Game::Gi, UID, &url, false, &pool)
+            .await
+            .unwrap();
+        let job = database::import_jobs::get_by_id(id, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+
+        run(&job, &upstream, &pool).await.unwrap();
+        database::import_jobs::finish(id, &pool).await.unwrap();
+
+        let job = database::import_jobs::get_by_id(id, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(job.counts["standard"], 2);
+        assert_eq!(job.counts["character"], 1);
+
+        let wishes = database::gi::wishes::standard::get_infos_by_uid(UID, &pool)
+            .await
+            .unwrap();
+        assert_eq!(wishes.len(), 2);
+        assert!(wishes
+            .iter()
+            .any(|w| w.character == Some(10000021) && w.rarity == Some(4)));
+        assert!(wishes
+            .iter()
+            .any(|w| w.weapon == Some(11301) && w.rarity == Some(3)));
+
+        // os_euro is UTC+1
+        assert_eq!(
+            wishes
+                .iter()
+                .map(|w| w.timestamp)
+                .min()
+                .unwrap()
+                .to_rfc3339(),
+            "2024-01-01T11:00:00+00:00"
+        );
+    }
+
+    #[actix_web::test]
+    async fn the_uid_comes_from_the_first_banner_with_wishes() {
+        let upstream = MockUpstream::start().await;
+        let url = Url::parse(
+            "https://public-operation-hk4e-sg.hoyoverse.com/gacha_info/api/getGachaLog?authkey=key&lang=en&size=20",
+        )
+        .unwrap();
+
+        assert_eq!(find_uid(&upstream, &url).await, Err("No data".to_string()));
+
+        upstream.page(100, "0", mock::error(-101, "authkey timeout"));
+        assert_eq!(
+            find_uid(&upstream, &url).await,
+            Err("Unable to fetch wish history".to_string())
+        );
+
+        upstream.page(
+            301,
+            "0",
+            mock::gacha_log(vec![entry(
+                "1700000000000000001",
+                "301",
+                "Amber",
+                "Character",
+                "2024-01-01 12:00:00",
+            )]),
+        );
+        assert_eq!(find_uid(&upstream, &url).await, Ok(UID));
+    }
+}
@@ -0,0 +1,39 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::{
+    api::{import_jobs::ImportJob, ApiResult},
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(tags((name = "import-jobs/{id}")), paths(get_import_job))]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_import_job);
+}
+
+#[utoipa::path(
+    tag = "import-jobs/{id}",
+    get,
+    path = "/api/import-jobs/{id}",
+    responses(
+        (status = 200, description = "ImportJob", body = ImportJob),
+        (status = 404, description = "Unknown or expired job"),
+    )
+)]
+#[get("/api/import-jobs/{id}")]
+async fn get_import_job(id: web::Path<Uuid>, pool: web::Data<PgPool>) -> ApiResult<impl Responder> {
+    let Some(job) = database::import_jobs::get_by_id(*id, &pool).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    Ok(HttpResponse::Ok().json(ImportJob::try_from(job)?))
+}
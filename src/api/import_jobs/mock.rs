@@ -0,0 +1,137 @@
+//! A local stand-in for the Hoyoverse gacha log api that replays recorded pages
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use actix_web::{rt, web, App, HttpRequest, HttpResponse, HttpServer};
+use serde_json::{json, Value};
+use url::Url;
+
+use super::GachaLogFetcher;
+
+type Key = (String, String);
+
+#[derive(Default)]
+struct State {
+    pages: HashMap<Key, Value>,
+    throttled: HashMap<Key, usize>,
+    requests: Vec<Url>,
+}
+
+/// Serves pages by gacha type and `end_id`. Anything that wasn't recorded is an empty page,
+/// which is where the importers stop paginating.
+#[derive(Clone)]
+pub struct MockUpstream {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockUpstream {
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let data = web::Data::new(state.clone());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(data.clone())
+                .default_service(web::to(serve))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .expect("mock upstream should bind");
+
+        let addr = server.addrs()[0];
+        rt::spawn(server.run());
+
+        Self { addr, state }
+    }
+
+    pub fn page(&self, gacha_type: i32, end_id: &str, page: Value) {
+        self.state
+            .lock()
+            .unwrap()
+            .pages
+            .insert((gacha_type.to_string(), end_id.to_string()), page);
+    }
+
+    /// Answers the next `times` requests for this page with "visit too frequently"
+    pub fn throttle(&self, gacha_type: i32, end_id: &str, times: usize) {
+        self.state
+            .lock()
+            .unwrap()
+            .throttled
+            .insert((gacha_type.to_string(), end_id.to_string()), times);
+    }
+
+    pub fn requests(&self) -> Vec<Url> {
+        self.state.lock().unwrap().requests.clone()
+    }
+}
+
+impl GachaLogFetcher for MockUpstream {
+    async fn get(&self, url: &str) -> reqwest::Result<reqwest::Response> {
+        let mut url = Url::parse(url).expect("importers should build valid urls");
+        url.set_scheme("http").unwrap();
+        url.set_host(Some(&self.addr.ip().to_string())).unwrap();
+        url.set_port(Some(self.addr.port())).unwrap();
+
+        reqwest::get(url).await
+    }
+}
+
+async fn serve(request: HttpRequest, state: web::Data<Arc<Mutex<State>>>) -> HttpResponse {
+    let url = request.full_url();
+    let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+    // ZZZ uses real_gacha_type, the others gacha_type
+    let gacha_type = query
+        .get("real_gacha_type")
+        .or_else(|| query.get("gacha_type"))
+        .cloned()
+        .unwrap_or_default();
+    let end_id = query.get("end_id").cloned().unwrap_or_default();
+    let key = (gacha_type, end_id);
+
+    let mut state = state.lock().unwrap();
+    state.requests.push(url);
+
+    if let Some(times) = state.throttled.get_mut(&key).filter(|times| **times > 0) {
+        *times -= 1;
+
+        return HttpResponse::Ok().json(error(-110, "visit too frequently"));
+    }
+
+    let page = state
+        .pages
+        .get(&key)
+        .cloned()
+        .unwrap_or_else(|| gacha_log(Vec::new()));
+
+    HttpResponse::Ok().json(page)
+}
+
+/// A page in the shape the real api sends it
+pub fn gacha_log(list: Vec<Value>) -> Value {
+    json!({
+        "retcode": 0,
+        "message": "OK",
+        "data": {
+            "page": "1",
+            "size": "20",
+            "list": list,
+            "region": "os_euro",
+            "region_time_zone": 1,
+        },
+    })
+}
+
+pub fn error(retcode: i32, message: &str) -> Value {
+    json!({
+        "retcode": retcode,
+        "message": message,
+        "data": null,
+    })
+}
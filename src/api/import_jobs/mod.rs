@@ -0,0 +1,363 @@
+mod id;
+#[cfg(test)]
+pub mod mock;
+
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use actix_web::{
+    rt::{self, Runtime},
+    web,
+};
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+use sqlx::PgPool;
+use strum::IntoEnumIterator;
+use url::Url;
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        gi,
+        uigf_import::{self, UigfImportReport},
+        warps_import, zzz, ApiResult,
+    },
+    app_config::AppConfig,
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(components(schemas(ImportJob, Game, Status)))]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(id::openapi());
+    openapi
+}
+
+lazy_static::lazy_static! {
+    static ref WORKER: Mutex<Option<()>> = Mutex::new(None);
+}
+
+pub fn configure(
+    cfg: &mut web::ServiceConfig,
+    pool: PgPool,
+    app_config: web::Data<Arc<AppConfig>>,
+) {
+    if app_config.enable_import_jobs_worker {
+        WORKER.lock().unwrap().get_or_insert_with(|| spawn(pool));
+    }
+    cfg.configure(id::configure);
+}
+
+const WORKERS: usize = 4;
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+pub(crate) const MAX_JOB_ATTEMPTS: i32 = 5;
+const JOB_RETRY_DELAY_SECS: f64 = 30.0;
+const FETCH_ATTEMPTS: usize = 5;
+const FETCH_RETRY_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Copy, strum::Display, strum::EnumString, Serialize, ToSchema)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Game {
+    Hsr,
+    Gi,
+    Zzz,
+    Uigf,
+}
+
+#[derive(Clone, Copy, strum::Display, strum::EnumString, Serialize, ToSchema)]
+#[strum(serialize_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum Status {
+    Pending,
+    Running,
+    Calculating,
+    Finished,
+    Error,
+}
+
+#[derive(Serialize, ToSchema)]
+struct ImportJob {
+    #[schema(value_type = String)]
+    id: Uuid,
+    game: Game,
+    uid: i32,
+    status: Status,
+    gacha_type: Option<String>,
+    /// Pulls fetched so far by gacha type
+    counts: HashMap<String, i32>,
+    error: Option<String>,
+    attempts: i32,
+    /// Only set for UIGF imports
+    dry_run: bool,
+    /// What a UIGF import did so far, by uid
+    report: Option<UigfImportReport>,
+    /// When a pending job gets picked up. Pushed back when retrying
+    run_after: DateTime<Utc>,
+    created: DateTime<Utc>,
+    updated: DateTime<Utc>,
+}
+
+impl TryFrom<database::import_jobs::DbImportJob> for ImportJob {
+    type Error = anyhow::Error;
+
+    fn try_from(job: database::import_jobs::DbImportJob) -> Result<Self, Self::Error> {
+        Ok(Self {
+            id: job.id,
+            game: job.game.parse()?,
+            uid: job.uid,
+            status: job.status.parse()?,
+            gacha_type: job.gacha_type,
+            counts: serde_json::from_value(job.counts)?,
+            error: job.error,
+            attempts: job.attempts,
+            dry_run: job.dry_run,
+            report: job.report.map(serde_json::from_value).transpose()?,
+            run_after: job.run_after,
+            created: job.created,
+            updated: job.updated,
+        })
+    }
+}
+
+/// An upstream failure that is worth retrying later
+#[derive(Debug)]
+pub struct TransientError(String);
+
+impl fmt::Display for TransientError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for TransientError {}
+
+/// Queues an import for the uid. If one is already pending or running its id is returned instead.
+pub async fn enqueue(
+    game: Game,
+    uid: i32,
+    url: &Url,
+    ignore_timestamps: bool,
+    pool: &PgPool,
+) -> anyhow::Result<Uuid> {
+    let game = game.to_string();
+
+    loop {
+        if let Some(id) = database::import_jobs::insert(
+            &game,
+            uid,
+            Some(url.as_str()),
+            ignore_timestamps,
+            &Status::Pending.to_string(),
+            None,
+            pool,
+        )
+        .await?
+        {
+            return Ok(id);
+        }
+
+        // The active job might have finished in between, so just try again if it's gone
+        if let Some(id) =
+            database::import_jobs::get_active_id_by_game_and_uid(&game, uid, pool).await?
+        {
+            return Ok(id);
+        }
+    }
+}
+
+/// Records a job that failed before it could be queued, so it can be polled like any other
+pub async fn insert_failed(game: Game, error: &str, pool: &PgPool) -> anyhow::Result<Uuid> {
+    database::import_jobs::insert(
+        &game.to_string(),
+        0,
+        None,
+        false,
+        &Status::Error.to_string(),
+        Some(error),
+        pool,
+    )
+    .await?
+    .ok_or_else(|| anyhow::anyhow!("Failed job wasn't inserted"))
+}
+
+/// The gacha types that are left to import, starting at the one a previous run stopped at
+pub fn remaining_gacha_types<T: IntoEnumIterator + fmt::Display>(
+    job: &database::import_jobs::DbImportJob,
+) -> Vec<T> {
+    T::iter()
+        .skip_while(|gacha_type| {
+            job.gacha_type
+                .as_ref()
+                .is_some_and(|resume| *resume != gacha_type.to_string())
+        })
+        .collect()
+}
+
+/// Where gacha log pages come from. Tests point this at a local server instead of Hoyoverse.
+pub trait GachaLogFetcher {
+    async fn get(&self, url: &str) -> reqwest::Result<reqwest::Response>;
+}
+
+pub struct Upstream;
+
+impl GachaLogFetcher for Upstream {
+    async fn get(&self, url: &str) -> reqwest::Result<reqwest::Response> {
+        reqwest::get(url).await
+    }
+}
+
+/// Fetches one page of a gacha log, backing off on rate limits and network errors
+pub async fn get_gacha_log<T: DeserializeOwned>(
+    fetcher: &impl GachaLogFetcher,
+    url: &str,
+) -> ApiResult<T> {
+    let mut delay = FETCH_RETRY_DELAY;
+
+    for _ in 1..FETCH_ATTEMPTS {
+        match try_get_gacha_log(fetcher, url).await {
+            Err(e) if e.is::<TransientError>() => {
+                rt::time::sleep(delay).await;
+
+                delay *= 2;
+            }
+            result => return result,
+        }
+    }
+
+    try_get_gacha_log(fetcher, url).await
+}
+
+async fn try_get_gacha_log<T: DeserializeOwned>(
+    fetcher: &impl GachaLogFetcher,
+    url: &str,
+) -> ApiResult<T> {
+    // The url contains the authkey, so it's kept out of errors that get stored
+    let response = match fetcher.get(url).await {
+        Ok(response) => response,
+        Err(e) => return Err(TransientError(e.without_url().to_string()).into()),
+    };
+
+    let status = response.status();
+
+    if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        return Err(TransientError(format!("Upstream responded with {status}")).into());
+    }
+
+    if !status.is_success() {
+        return Err(anyhow::anyhow!("Upstream responded with {status}").into());
+    }
+
+    let json: serde_json::Value = match response.json().await {
+        Ok(json) => json,
+        Err(e) => return Err(TransientError(e.without_url().to_string()).into()),
+    };
+
+    let message = json["message"].as_str().unwrap_or_default().to_string();
+
+    // -110 is "visit too frequently". Everything else, like an expired authkey, won't fix itself
+    match json["retcode"].as_i64() {
+        Some(0) => {}
+        Some(-110) => return Err(TransientError(message).into()),
+        _ => return Err(anyhow::anyhow!("{message}").into()),
+    }
+
+    Ok(serde_json::from_value(json)?)
+}
+
+fn spawn(pool: PgPool) {
+    std::thread::spawn(move || {
+        let rt = Runtime::new().unwrap();
+
+        for _ in 0..WORKERS {
+            rt.spawn(work(pool.clone()));
+        }
+
+        rt.block_on(cleanup(pool));
+    });
+}
+
+async fn work(pool: PgPool) {
+    loop {
+        match database::import_jobs::claim(MAX_JOB_ATTEMPTS, &pool).await {
+            Ok(Some(job)) => run(job, &pool).await,
+            Ok(None) => rt::time::sleep(POLL_INTERVAL).await,
+            Err(e) => {
+                error!("Claiming import job failed with {e}");
+
+                rt::time::sleep(POLL_INTERVAL).await;
+            }
+        }
+    }
+}
+
+async fn run(job: database::import_jobs::DbImportJob, pool: &PgPool) {
+    let start = Instant::now();
+
+    let result = match job.game.parse() {
+        Ok(Game::Hsr) => warps_import::run(&job, &Upstream, pool).await,
+        Ok(Game::Gi) => gi::wishes_import::run(&job, &Upstream, pool).await,
+        Ok(Game::Zzz) => zzz::signals_import::run(&job, &Upstream, pool).await,
+        Ok(Game::Uigf) => uigf_import::run(&job, pool).await,
+        Err(e) => Err(e.into()),
+    };
+
+    let result = match result {
+        Ok(()) => {
+            info!(
+                "Import job {} for {} succeeded in {}s",
+                job.id,
+                job.uid,
+                start.elapsed().as_secs_f64()
+            );
+
+            database::import_jobs::finish(job.id, pool).await
+        }
+        Err(e) if e.is::<TransientError>() && job.attempts < MAX_JOB_ATTEMPTS => {
+            let delay = JOB_RETRY_DELAY_SECS * 2f64.powi(job.attempts - 1);
+
+            warn!(
+                "Import job {} for {} failed with {e}, retrying in {delay}s",
+                job.id, job.uid
+            );
+
+            database::import_jobs::retry(job.id, &e.to_string(), delay, pool).await
+        }
+        Err(e) => {
+            warn!(
+                "Import job {} for {} failed with {e} in {}s",
+                job.id,
+                job.uid,
+                start.elapsed().as_secs_f64()
+            );
+
+            database::import_jobs::fail(job.id, &e.to_string(), pool).await
+        }
+    };
+
+    if let Err(e) = result {
+        error!("Updating import job {} failed with {e}", job.id);
+    }
+}
+
+async fn cleanup(pool: PgPool) {
+    let mut interval = rt::time::interval(Duration::from_secs(60 * 60));
+
+    loop {
+        interval.tick().await;
+
+        let before = Utc::now() - chrono::Duration::days(1);
+
+        if let Err(e) = database::import_jobs::delete_done_before(before, &pool).await {
+            error!("Import jobs cleanup failed with {e}");
+        }
+    }
+}
@@ -1,42 +1,54 @@
 mod achievement_series;
+mod achievement_timeline;
 mod achievements;
 mod admin;
-mod banner_helpers;
+mod auth;
+pub mod banner_helpers;
+mod banner_stats;
 mod banners;
 mod characters;
+mod export;
+mod export_key;
 mod gi;
 mod import_achievements;
 mod import_gi_achievements;
+mod import_jobs;
 mod import_zzz_achievements;
 mod languages;
 mod light_cones;
 mod mihomo;
 mod ntehelper;
 mod pages;
+mod pity_curves;
 mod pom_warps_import;
+mod pull_simulator;
+pub mod rate_limit;
 mod scores;
 mod select_all;
+pub mod service_keys;
+mod simulate;
 mod sitemap;
 mod srgf_warps_import;
 mod srs_warps_import;
+mod stats;
 mod uigf_import;
 mod users;
 mod warps;
 mod warps_import;
 mod zzz;
 
-use std::env;
-
 use crate::app_config::AppConfig;
 use actix_multipart::form::{tempfile::TempFile, MultipartForm};
 use actix_web::{guard, web, HttpResponse};
+use ed25519_dalek::SigningKey;
+use futures::lock::Mutex;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use std::sync::Arc;
 use strum::{Display, EnumString};
 use url::Url;
 use utoipa::{
-    openapi::security::{ApiKey, ApiKeyValue, SecurityScheme},
+    openapi::security::{ApiKey, ApiKeyValue, Http, HttpAuthScheme, SecurityScheme},
     IntoParams, Modify, OpenApi, ToSchema,
 };
 
@@ -69,6 +81,10 @@ impl Modify for PrivateAddon {
             "api_key",
             SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("x-api-key"))),
         );
+        components.add_security_scheme(
+            "access_token",
+            SecurityScheme::Http(Http::new(HttpAuthScheme::Bearer)),
+        );
     }
 }
 
@@ -95,12 +111,23 @@ struct File {
 }
 
 fn private(ctx: &guard::GuardContext) -> bool {
-    if cfg!(debug_assertions) {
+    if ctx
+        .app_data::<web::Data<Arc<AppConfig>>>()
+        .is_some_and(|app_config| !app_config.require_service_keys)
+    {
         return true;
     }
 
-    Some(env::var("API_KEY").unwrap().as_bytes())
-        == ctx.head().headers().get("x-api-key").map(|h| h.as_bytes())
+    let Some(key) = ctx
+        .head()
+        .headers()
+        .get("x-api-key")
+        .and_then(|h| h.to_str().ok())
+    else {
+        return false;
+    };
+
+    service_keys::check(key, ctx.head().uri.path())
 }
 
 pub fn openapi() -> utoipa::openapi::OpenApi {
@@ -110,9 +137,12 @@ pub fn openapi() -> utoipa::openapi::OpenApi {
     openapi.merge(achievements::openapi());
     openapi.merge(banners::openapi());
     openapi.merge(characters::openapi());
+    openapi.merge(export::openapi());
+    openapi.merge(export_key::openapi());
     openapi.merge(gi::openapi());
     openapi.merge(import_achievements::openapi());
     openapi.merge(import_gi_achievements::openapi());
+    openapi.merge(import_jobs::openapi());
     openapi.merge(import_zzz_achievements::openapi());
     openapi.merge(languages::openapi());
     openapi.merge(light_cones::openapi());
@@ -122,9 +152,11 @@ pub fn openapi() -> utoipa::openapi::OpenApi {
     openapi.merge(pom_warps_import::openapi());
     openapi.merge(scores::openapi());
     openapi.merge(select_all::openapi());
+    openapi.merge(simulate::openapi());
     openapi.merge(sitemap::openapi());
     openapi.merge(srgf_warps_import::openapi());
     openapi.merge(srs_warps_import::openapi());
+    openapi.merge(stats::openapi());
     openapi.merge(uigf_import::openapi());
     openapi.merge(users::openapi());
     openapi.merge(warps::openapi());
@@ -137,15 +169,21 @@ pub fn configure(
     cfg: &mut web::ServiceConfig,
     pool: PgPool,
     app_config: web::Data<Arc<AppConfig>>,
+    signing_key: web::Data<Mutex<SigningKey>>,
 ) {
+    service_keys::configure(pool.clone());
+
     cfg.configure(admin::configure)
         .configure(achievement_series::configure)
         .configure(achievements::configure)
         .configure(banners::configure)
         .configure(characters::configure)
+        .configure(export::configure)
+        .configure(export_key::configure)
         .configure(gi::configure)
         .configure(import_achievements::configure)
         .configure(import_gi_achievements::configure)
+        .configure(|sc| import_jobs::configure(sc, pool.clone(), app_config.clone()))
         .configure(import_zzz_achievements::configure)
         .configure(languages::configure)
         .configure(light_cones::configure)
@@ -155,11 +193,13 @@ pub fn configure(
         .configure(pom_warps_import::configure)
         .configure(scores::configure)
         .configure(select_all::configure)
+        .configure(simulate::configure)
         .configure(|sc| sitemap::configure(sc, pool.clone(), app_config.clone()))
         .configure(srgf_warps_import::configure)
         .configure(srs_warps_import::configure)
+        .configure(stats::configure)
         .configure(uigf_import::configure)
-        .configure(users::configure)
+        .configure(|sc| users::configure(sc, pool.clone(), &app_config, signing_key.clone()))
         .configure(warps::configure)
         .configure(warps_import::configure)
         .configure(zzz::configure);
@@ -9,7 +9,7 @@ use utoipa::OpenApi;
 
 use crate::{
     api::{
-        banner_helpers::{self, GI_STANDARD},
+        banner_helpers::{self, RadianceState, WinOutcome, GI_STANDARD},
         private, ApiResult, LanguageParams,
     },
     database,
@@ -54,6 +54,18 @@ enum WinType {
     Win,
     Loss,
     Guarantee,
+    Radiance,
+}
+
+impl From<WinOutcome> for WinType {
+    fn from(outcome: WinOutcome) -> Self {
+        match outcome {
+            WinOutcome::Win => WinType::Win,
+            WinOutcome::Loss => WinType::Loss,
+            WinOutcome::Guarantee => WinType::Guarantee,
+            WinOutcome::Radiance => WinType::Radiance,
+        }
+    }
 }
 
 impl From<database::gi::wishes::DbWish> for Wish {
@@ -87,6 +99,7 @@ struct WishTracker {
     character: Wishes,
     weapon: Wishes,
     chronicled: Wishes,
+    epitomized_paths: Vec<EpitomizedPath>,
 }
 
 #[derive(Default, Serialize)]
@@ -99,9 +112,26 @@ struct Wishes {
     max_pull_4: usize,
     max_pull_5: usize,
     count: usize,
+    // Consecutive lost 50/50s counting towards Capturing Radiance. Character banner only.
+    radiance_losses: Option<i32>,
     stats: Option<Stats>,
 }
 
+#[derive(Serialize)]
+struct EpitomizedPath {
+    banner: i32,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+    weapons: Vec<i32>,
+    chart: Option<i32>,
+    fate_points: i32,
+    max_fate_points: i32,
+    // 5★ outcomes on this banner: the charted weapon, the other featured weapon, a standard weapon
+    charted: usize,
+    featured: usize,
+    standard: usize,
+}
+
 #[derive(Default, Serialize)]
 struct Stats {
     luck_4: f64,
@@ -165,14 +195,16 @@ async fn get_wish_tracker(
 
     let language = language_params.lang;
 
-    let Some(profile) = database::gi::profiles::get_by_uid(uid, &pool).await? else {
+    let Some(profile) = database::gi::profiles::get_by_uid(uid, &**pool).await? else {
         return Ok(HttpResponse::NotFound().finish());
     };
     let name = profile.name;
 
+    let gi_banners = database::gi::banners::get_all(&pool).await?;
+
     let mut banners: HashMap<_, Vec<_>> = HashMap::new();
 
-    for banner in database::gi::banners::get_all(&pool).await? {
+    for banner in &gi_banners {
         if let Some(character) = banner.character {
             banners
                 .entry(character)
@@ -281,7 +313,7 @@ async fn get_wish_tracker(
     let mut character_pull = 0;
     let mut character_pull_4 = 0;
     let mut character_pull_5 = 0;
-    let mut guarantee = false;
+    let mut radiance = RadianceState::default();
 
     for wish in database::gi::wishes::character::get_by_uid(uid, language, &pool).await? {
         let mut wish: Wish = wish.into();
@@ -299,15 +331,11 @@ async fn get_wish_tracker(
             5 => {
                 character_pull_5 = 0;
 
-                wish.win = Some(if guarantee {
-                    guarantee = false;
-                    WinType::Guarantee
-                } else if is_win(wish.item_id, wish.timestamp) {
-                    WinType::Win
-                } else {
-                    guarantee = true;
-                    WinType::Loss
-                });
+                wish.win = Some(
+                    radiance
+                        .pull(is_win(wish.item_id, wish.timestamp), wish.timestamp)
+                        .into(),
+                );
             }
             _ => {}
         }
@@ -328,6 +356,7 @@ async fn get_wish_tracker(
     };
 
     character.count = character.wishes.len();
+    character.radiance_losses = Some(radiance.losses);
     // Character
 
     // Weapon
@@ -384,6 +413,65 @@ async fn get_wish_tracker(
     weapon.count = weapon.wishes.len();
     // Weapon
 
+    // Epitomized Path
+    let charts: HashMap<_, _> = database::gi::weapon_charts::get_by_uid(uid, &pool)
+        .await?
+        .into_iter()
+        .map(|chart| (chart.banner, chart.weapon))
+        .collect();
+
+    let mut epitomized_paths = Vec::new();
+
+    for weapon_banner in banner_helpers::gi_weapon_banners(&gi_banners) {
+        let range = weapon_banner.start..weapon_banner.end;
+
+        if !weapon.wishes.iter().any(|w| range.contains(&w.timestamp)) {
+            continue;
+        }
+
+        let chart = charts.get(&weapon_banner.id).copied();
+
+        let mut epitomized_path = EpitomizedPath {
+            banner: weapon_banner.id,
+            start: weapon_banner.start,
+            end: weapon_banner.end,
+            weapons: weapon_banner.weapons,
+            chart,
+            fate_points: 0,
+            max_fate_points: banner_helpers::gi_max_fate_points(weapon_banner.start),
+            charted: 0,
+            featured: 0,
+            standard: 0,
+        };
+
+        // Fate points expire with the banner, so every window starts from zero.
+        for wish in weapon
+            .wishes
+            .iter()
+            .filter(|w| w.rarity == 5 && range.contains(&w.timestamp))
+        {
+            if Some(wish.item_id) == chart {
+                epitomized_path.charted += 1;
+                epitomized_path.fate_points = 0;
+                continue;
+            }
+
+            if epitomized_path.weapons.contains(&wish.item_id) {
+                epitomized_path.featured += 1;
+            } else {
+                epitomized_path.standard += 1;
+            }
+
+            if chart.is_some() {
+                epitomized_path.fate_points =
+                    (epitomized_path.fate_points + 1).min(epitomized_path.max_fate_points);
+            }
+        }
+
+        epitomized_paths.push(epitomized_path);
+    }
+    // Epitomized Path
+
     // Chronicled
     let mut chronicled = Wishes::default();
     let mut chronicled_pull = 0;
@@ -444,6 +532,23 @@ async fn get_wish_tracker(
         })
     }
 
+    if let Some(stats) = database::gi::wishes_stats::beginner::get_by_uid(uid, &pool).await? {
+        let global_stats = database::gi::wishes_stats_global::beginner::get_by_uid(uid, &pool)
+            .await?
+            .map(|stats| GlobalStats {
+                count_percentile: stats.count_percentile,
+                luck_4_percentile: stats.luck_4_percentile,
+                luck_5_percentile: stats.luck_5_percentile,
+            });
+
+        beginner.stats = Some(Stats {
+            luck_4: stats.luck_4,
+            luck_5: stats.luck_5,
+            win_stats: None,
+            global_stats,
+        })
+    }
+
     if let Some(stats) = database::gi::wishes_stats::character::get_by_uid(uid, &pool).await? {
         let win_stats = Some(WinStats {
             win_rate: stats.win_rate,
@@ -514,6 +619,7 @@ async fn get_wish_tracker(
         character,
         weapon,
         chronicled,
+        epitomized_paths,
     };
 
     Ok(HttpResponse::Ok().json(wish_tracker))
@@ -41,7 +41,7 @@ async fn get_leaderboard_entry(
     }
 
     // Wacky way to update the database in case the uid isn't in there
-    if !database::mihomo::exists(uid, &pool).await?
+    if !database::mihomo::exists(uid, &**pool).await?
         && mihomo::get(uid, Language::En, &pool).await?.is_none()
     {
         let region = match uid.to_string().chars().next() {
@@ -58,7 +58,7 @@ async fn get_leaderboard_entry(
             ..Default::default()
         };
 
-        database::mihomo::set(&db_mihomo, &pool).await?;
+        database::mihomo::set(&db_mihomo, &**pool).await?;
     }
 
     let Some(score) = database::achievement_scores::get_by_uid(uid, &pool).await? else {
@@ -166,7 +166,7 @@ async fn get_warp_tracker(
 
     let language = language_params.lang;
 
-    let Some(mihomo) = database::mihomo::get_one_by_uid(uid, &pool).await? else {
+    let Some(mihomo) = database::mihomo::get_one_by_uid(uid, &**pool).await? else {
         return Ok(HttpResponse::NotFound().finish());
     };
     let name = mihomo.name;
@@ -495,6 +495,23 @@ async fn get_warp_tracker(
         });
     }
 
+    if let Some(stats) = database::warps_stats::departure::get_by_uid(uid, &pool).await? {
+        let global_stats = database::warps_stats_global::departure::get_by_uid(uid, &pool)
+            .await?
+            .map(|stats| GlobalStats {
+                count_percentile: stats.count_percentile,
+                luck_4_percentile: stats.luck_4_percentile,
+                luck_5_percentile: stats.luck_5_percentile,
+            });
+
+        departure.stats = Some(Stats {
+            luck_4: stats.luck_4,
+            luck_5: stats.luck_5,
+            win_stats: None,
+            global_stats,
+        });
+    }
+
     if let Some(stats) = database::warps_stats::special::get_by_uid(uid, &pool).await? {
         let win_stats = Some(WinStats {
             win_rate: stats.win_rate,
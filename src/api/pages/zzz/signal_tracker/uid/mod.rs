@@ -6,13 +6,13 @@ use sqlx::PgPool;
 use utoipa::OpenApi;
 
 use crate::{
-    api::{private, ApiResult, LanguageParams},
+    api::{
+        banner_helpers::{self, ZZZ_STANDARD},
+        private, ApiResult, LanguageParams,
+    },
     database,
 };
 
-const AGENT_LOSS_IDS: &[i32] = &[1021, 1041, 1101, 1141, 1181, 1211];
-const W_ENGINE_LOSS_IDS: &[i32] = &[14102, 14104, 14110, 14114, 14118, 14121];
-
 #[derive(OpenApi)]
 #[openapi(paths(get_signal_tracker))]
 struct ApiDoc;
@@ -170,6 +170,10 @@ async fn get_signal_tracker(
 
     let language = language_params.lang;
 
+    let banners = banner_helpers::zzz_banner_ranges(&pool).await?;
+
+    let is_win = banner_helpers::is_win_fn(&banners, ZZZ_STANDARD);
+
     // Standard
     let mut standard = Signals::default();
     let mut standard_pull = 0;
@@ -242,12 +246,12 @@ async fn get_signal_tracker(
                     guarantee = false;
 
                     Some(WinType::Guarantee)
-                } else if AGENT_LOSS_IDS.contains(&signal.item_id) {
+                } else if is_win(signal.item_id, signal.timestamp) {
+                    Some(WinType::Win)
+                } else {
                     guarantee = true;
 
                     Some(WinType::Loss)
-                } else {
-                    Some(WinType::Win)
                 };
             }
             _ => {}
@@ -299,12 +303,12 @@ async fn get_signal_tracker(
                     guarantee = false;
 
                     Some(WinType::Guarantee)
-                } else if W_ENGINE_LOSS_IDS.contains(&signal.item_id) {
+                } else if is_win(signal.item_id, signal.timestamp) {
+                    Some(WinType::Win)
+                } else {
                     guarantee = true;
 
                     Some(WinType::Loss)
-                } else {
-                    Some(WinType::Win)
                 };
             }
             _ => {}
@@ -402,12 +406,12 @@ async fn get_signal_tracker(
                     guarantee = false;
 
                     Some(WinType::Guarantee)
-                } else if AGENT_LOSS_IDS.contains(&signal.item_id) {
+                } else if is_win(signal.item_id, signal.timestamp) {
+                    Some(WinType::Win)
+                } else {
                     guarantee = true;
 
                     Some(WinType::Loss)
-                } else {
-                    Some(WinType::Win)
                 };
             }
             _ => {}
@@ -465,12 +469,12 @@ async fn get_signal_tracker(
                     guarantee = false;
 
                     Some(WinType::Guarantee)
-                } else if W_ENGINE_LOSS_IDS.contains(&signal.item_id) {
+                } else if is_win(signal.item_id, signal.timestamp) {
+                    Some(WinType::Win)
+                } else {
                     guarantee = true;
 
                     Some(WinType::Loss)
-                } else {
-                    Some(WinType::Win)
                 };
             }
             _ => {}
@@ -578,6 +582,58 @@ async fn get_signal_tracker(
         };
     }
 
+    if let Some(stats) =
+        database::zzz::signals_stats::exclusive_rescreening::get_by_uid(uid, &pool).await?
+    {
+        let win_stats = Some(WinStats {
+            win_rate: stats.win_rate,
+            win_streak: stats.win_streak,
+            loss_streak: stats.loss_streak,
+        });
+
+        let global_stats =
+            database::zzz::signals_stats_global::exclusive_rescreening::get_by_uid(uid, &pool)
+                .await?
+                .map(|stats| GlobalStats {
+                    count_percentile: stats.count_percentile,
+                    luck_4_percentile: stats.luck_a_percentile,
+                    luck_5_percentile: stats.luck_s_percentile,
+                });
+
+        exclusive_rescreening.stats = Stats {
+            luck_4: stats.luck_a,
+            luck_5: stats.luck_s,
+            win_stats,
+            global_stats,
+        };
+    }
+
+    if let Some(stats) =
+        database::zzz::signals_stats::w_engine_reverberation::get_by_uid(uid, &pool).await?
+    {
+        let win_stats = Some(WinStats {
+            win_rate: stats.win_rate,
+            win_streak: stats.win_streak,
+            loss_streak: stats.loss_streak,
+        });
+
+        let global_stats =
+            database::zzz::signals_stats_global::w_engine_reverberation::get_by_uid(uid, &pool)
+                .await?
+                .map(|stats| GlobalStats {
+                    count_percentile: stats.count_percentile,
+                    luck_4_percentile: stats.luck_a_percentile,
+                    luck_5_percentile: stats.luck_s_percentile,
+                });
+
+        w_engine_reverberation.stats = Stats {
+            luck_4: stats.luck_a,
+            luck_5: stats.luck_s,
+            win_stats,
+            global_stats,
+        };
+    }
+
     let signal_tracker = SignalTracker {
         standard,
         special,
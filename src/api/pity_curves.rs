@@ -0,0 +1,58 @@
+//! Observed hit rates by pity, calculated by `update::pity_curves`. Shared by the pity curve endpoints
+//! of every game.
+
+use serde::Serialize;
+use utoipa::ToSchema;
+
+use crate::database::pity_curves::DbPityCurve;
+
+#[derive(Serialize, ToSchema)]
+pub struct PityCurve {
+    banner: String,
+    /// 5 or 4. S-Rank and A-Rank in ZZZ
+    rarity: i32,
+    points: Vec<PityPoint>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct PityPoint {
+    /// 1 is the first pull after the last hit
+    pity: i32,
+    /// Official pulls made at this pity
+    pulls: i64,
+    /// How many of them hit
+    hits: i64,
+    rate: f64,
+    /// 95% confidence interval of the rate
+    lower: f64,
+    upper: f64,
+}
+
+/// Expects the curves ordered by banner, rarity and pity
+pub fn pity_curves(curves: Vec<DbPityCurve>) -> Vec<PityCurve> {
+    let mut pity_curves: Vec<PityCurve> = Vec::new();
+
+    for curve in curves {
+        let point = PityPoint {
+            pity: curve.pity,
+            pulls: curve.pulls,
+            hits: curve.hits,
+            rate: curve.rate,
+            lower: curve.lower,
+            upper: curve.upper,
+        };
+
+        match pity_curves.last_mut() {
+            Some(last) if last.banner == curve.banner && last.rarity == curve.rarity => {
+                last.points.push(point)
+            }
+            _ => pity_curves.push(PityCurve {
+                banner: curve.banner,
+                rarity: curve.rarity,
+                points: vec![point],
+            }),
+        }
+    }
+
+    pity_curves
+}
@@ -1,10 +1,15 @@
-use actix_session::Session;
 use actix_web::{post, web, HttpResponse, Responder};
 use chrono::NaiveDateTime;
 use sqlx::PgPool;
 use utoipa::OpenApi;
 
-use crate::{api::ApiResult, database, mihomo, GachaType, Language};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        ApiResult,
+    },
+    database, mihomo, GachaType, Language,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -65,18 +70,18 @@ struct Warp {
 )]
 #[post("/api/pom-warps-import/{uid}")]
 async fn post_pom_warps_import(
-    session: Session,
+    auth: Auth,
     uid: web::Path<i32>,
     params: web::Json<PomWarpsImportParams>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::WarpsImport) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
     let uid = *uid;
 
-    let admin = database::admins::exists(&username, &pool).await?;
+    let admin = auth.is_admin(&pool).await?;
 
     let allowed = admin
         || database::connections::get_by_username(&username, &pool)
@@ -91,7 +96,7 @@ async fn post_pom_warps_import(
     }
 
     // Wacky way to update the database in case the uid isn't in there
-    if !database::mihomo::exists(uid, &pool).await?
+    if !database::mihomo::exists(uid, &**pool).await?
         && mihomo::get(uid, Language::En, &pool).await?.is_none()
     {
         let region = match uid.to_string().chars().next() {
@@ -108,7 +113,7 @@ async fn post_pom_warps_import(
             ..std::default::Default::default()
         };
 
-        database::mihomo::set(&db_mihomo, &pool).await?;
+        database::mihomo::set(&db_mihomo, &**pool).await?;
     }
 
     let timestamp_offset = chrono::Duration::hours(match uid.to_string().chars().next() {
@@ -205,12 +210,12 @@ async fn post_pom_warps_import(
         }
     }
 
-    database::warps::departure::set_all(&set_all_departure, &pool).await?;
-    database::warps::standard::set_all(&set_all_standard, &pool).await?;
-    database::warps::special::set_all(&set_all_special, &pool).await?;
-    database::warps::lc::set_all(&set_all_lc, &pool).await?;
-    database::warps::collab::set_all(&set_all_collab, &pool).await?;
-    database::warps::collab_lc::set_all(&set_all_collab_lc, &pool).await?;
+    database::warps::departure::set_all(&set_all_departure, &**pool).await?;
+    database::warps::standard::set_all(&set_all_standard, &**pool).await?;
+    database::warps::special::set_all(&set_all_special, &**pool).await?;
+    database::warps::lc::set_all(&set_all_lc, &**pool).await?;
+    database::warps::collab::set_all(&set_all_collab, &**pool).await?;
+    database::warps::collab_lc::set_all(&set_all_collab_lc, &**pool).await?;
 
     Ok(HttpResponse::Ok().finish())
 }
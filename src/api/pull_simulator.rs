@@ -0,0 +1,339 @@
+//! Monte Carlo simulation of limited banners. Shared by the simulate endpoints of every game.
+
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::api::banner_helpers::GI_RADIANCE_LOSSES;
+
+pub const RUNS: usize = 10_000;
+
+/// Price of a single pull in jades, primogems or polychromes
+pub const PULL_COST: i32 = 160;
+
+/// The chance of the top rarity, the same numbers the trackers show
+pub struct Pity {
+    /// In percent
+    pub base: f64,
+    /// Pity after which every pull adds `step`
+    pub soft: usize,
+    /// In percent
+    pub step: f64,
+    /// The pull that always hits
+    pub hard: usize,
+}
+
+impl Pity {
+    pub const HSR_CHARACTER: Pity = Pity {
+        base: 0.6,
+        soft: 72,
+        step: 6.0,
+        hard: 90,
+    };
+    pub const HSR_LIGHT_CONE: Pity = Pity {
+        base: 0.8,
+        soft: 64,
+        step: 7.0,
+        hard: 80,
+    };
+    pub const GI: Pity = Pity::HSR_CHARACTER;
+    /// Epitomized Path. The rate starts rising at pull 63
+    pub const GI_WEAPON: Pity = Pity {
+        base: 0.7,
+        soft: 61,
+        step: 7.0,
+        hard: 80,
+    };
+    pub const ZZZ_AGENT: Pity = Pity::HSR_CHARACTER;
+    pub const ZZZ_W_ENGINE: Pity = Pity {
+        base: 1.0,
+        soft: 64,
+        step: 7.0,
+        hard: 80,
+    };
+
+    /// `pity` is the number of pulls since the last hit
+    fn chance(&self, pity: usize) -> f64 {
+        if pity + 1 >= self.hard {
+            1.0
+        } else {
+            (self.base + self.step * pity.saturating_sub(self.soft) as f64) / 100.0
+        }
+    }
+}
+
+pub struct Rules {
+    pub pity: Pity,
+    /// Chance that a hit is featured, 0.5 or 0.75
+    pub win_rate: f64,
+    /// Genshin's Capturing Radiance
+    pub radiance: bool,
+    /// Genshin's Epitomized Path. Only one of the two featured weapons is the target, charted with a
+    /// single fate point
+    pub epitomized: bool,
+}
+
+#[derive(Clone, Copy, Default, Deserialize, Serialize, ToSchema)]
+pub struct State {
+    /// Pulls since the last hit
+    pub pull: usize,
+    /// Whether the next hit is featured
+    pub guarantee: bool,
+    /// Consecutive lost 50/50s counting towards Capturing Radiance
+    pub losses: i32,
+    /// Epitomized Path fate points
+    pub fate_points: i32,
+}
+
+impl State {
+    /// Returns whether it hit the target
+    fn pull(&mut self, rules: &Rules, rng: &mut impl Rng) -> bool {
+        if rng.random::<f64>() >= rules.pity.chance(self.pull) {
+            self.pull += 1;
+            return false;
+        }
+
+        self.pull = 0;
+
+        if rules.epitomized {
+            if self.fate_points >= 1 {
+                self.fate_points = 0;
+                self.guarantee = false;
+                return true;
+            }
+
+            let featured = self.guarantee || rng.random::<f64>() < rules.win_rate;
+            self.guarantee = !featured;
+
+            if featured && rng.random::<bool>() {
+                return true;
+            }
+
+            self.fate_points += 1;
+            return false;
+        }
+
+        if self.guarantee {
+            self.guarantee = false;
+            return true;
+        }
+
+        if (rules.radiance && self.losses >= GI_RADIANCE_LOSSES)
+            || rng.random::<f64>() < rules.win_rate
+        {
+            self.losses = 0;
+            true
+        } else {
+            if rules.radiance {
+                self.losses += 1;
+            }
+
+            self.guarantee = true;
+            false
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Simulation {
+    /// Pulls the budget buys
+    pulls: i32,
+    /// Starting from 0 copies
+    copies: Vec<SimulatedCopies>,
+    expected_copies: f64,
+    /// Starting from 1 copy
+    spend: Vec<SimulatedSpend>,
+    /// What the simulation started with
+    state: State,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SimulatedCopies {
+    copies: usize,
+    /// Of ending with exactly this many
+    probability: f64,
+    /// Of ending with this many or more
+    probability_at_least: f64,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SimulatedSpend {
+    copies: usize,
+    /// Until this many copies, ignoring the budget
+    expected_pulls: f64,
+    /// The currency that is still needed after spending the passes
+    expected_currency: f64,
+}
+
+/// Simulates pulling for `target` copies `RUNS` times
+pub fn simulate(
+    rules: &Rules,
+    state: State,
+    currency: i32,
+    passes: i32,
+    target: usize,
+    rng: &mut impl Rng,
+) -> Simulation {
+    let pulls = passes.saturating_add(currency / PULL_COST);
+
+    let mut ended_with = vec![0usize; target + 1];
+    let mut spent_pulls = vec![0u64; target];
+    let mut spent_currency = vec![0u64; target];
+
+    for _ in 0..RUNS {
+        let mut run = state;
+        let mut pulled = 0;
+        let mut copies = 0;
+        let mut copies_in_budget = 0;
+
+        while copies < target {
+            pulled += 1;
+
+            if !run.pull(rules, rng) {
+                continue;
+            }
+
+            spent_pulls[copies] += pulled as u64;
+            spent_currency[copies] += ((pulled - passes).max(0) * PULL_COST) as u64;
+            copies += 1;
+
+            if pulled <= pulls {
+                copies_in_budget = copies;
+            }
+        }
+
+        ended_with[copies_in_budget] += 1;
+    }
+
+    let runs = RUNS as f64;
+
+    let mut at_least = 1.0;
+    let copies = ended_with
+        .iter()
+        .enumerate()
+        .map(|(copies, &count)| {
+            let probability = count as f64 / runs;
+            let copies = SimulatedCopies {
+                copies,
+                probability,
+                probability_at_least: at_least,
+            };
+            at_least = (at_least - probability).max(0.0);
+            copies
+        })
+        .collect();
+
+    let expected_copies = ended_with
+        .iter()
+        .enumerate()
+        .map(|(copies, &count)| copies as f64 * count as f64)
+        .sum::<f64>()
+        / runs;
+
+    let spend = spent_pulls
+        .iter()
+        .zip(&spent_currency)
+        .enumerate()
+        .map(|(i, (&pulls, &currency))| SimulatedSpend {
+            copies: i + 1,
+            expected_pulls: pulls as f64 / runs,
+            expected_currency: currency as f64 / runs,
+        })
+        .collect();
+
+    Simulation {
+        pulls,
+        copies,
+        expected_copies,
+        spend,
+        state,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::*;
+
+    fn rules(win_rate: f64) -> Rules {
+        Rules {
+            pity: Pity::HSR_CHARACTER,
+            win_rate,
+            radiance: false,
+            epitomized: false,
+        }
+    }
+
+    #[test]
+    fn hard_pity_on_guarantee_always_hits() {
+        let state = State {
+            pull: 89,
+            guarantee: true,
+            ..Default::default()
+        };
+
+        let simulation = simulate(&rules(0.5), state, 0, 1, 1, &mut StdRng::seed_from_u64(0));
+
+        assert_eq!(simulation.copies[1].probability, 1.0);
+        assert_eq!(simulation.spend[0].expected_pulls, 1.0);
+        assert_eq!(simulation.spend[0].expected_currency, 0.0);
+    }
+
+    #[test]
+    fn distribution_adds_up() {
+        let simulation = simulate(
+            &rules(0.5),
+            State::default(),
+            160 * 160,
+            20,
+            3,
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        assert_eq!(simulation.pulls, 180);
+
+        let total: f64 = simulation.copies.iter().map(|c| c.probability).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        assert_eq!(simulation.copies[0].probability_at_least, 1.0);
+
+        // A copy takes about 93.75 pulls on average with 50/50s
+        let expected = simulation.spend[0].expected_pulls;
+        assert!((85.0..100.0).contains(&expected), "{expected}");
+        assert!(simulation.spend[1].expected_pulls > expected);
+    }
+
+    #[test]
+    fn huge_budgets_saturate() {
+        let simulation = simulate(
+            &rules(0.5),
+            State::default(),
+            PULL_COST,
+            i32::MAX,
+            1,
+            &mut StdRng::seed_from_u64(0),
+        );
+
+        assert_eq!(simulation.pulls, i32::MAX);
+        assert_eq!(simulation.copies[1].probability, 1.0);
+    }
+
+    #[test]
+    fn fate_point_guarantees_the_charted_weapon() {
+        let rules = Rules {
+            pity: Pity::GI_WEAPON,
+            win_rate: 0.75,
+            radiance: false,
+            epitomized: true,
+        };
+        let state = State {
+            pull: 79,
+            fate_points: 1,
+            ..Default::default()
+        };
+
+        let simulation = simulate(&rules, state, 0, 1, 1, &mut StdRng::seed_from_u64(0));
+
+        assert_eq!(simulation.copies[1].probability, 1.0);
+    }
+}
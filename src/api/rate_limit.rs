@@ -0,0 +1,538 @@
+//! Token buckets in front of the routes that are expensive or abusable: logins, mails, imports
+//! (which fan out to upstream apis), mihomo refreshes and writes.
+//!
+//! Every route group has a bucket per ip and, once someone is logged in, one per user. Forwarded
+//! client ips are only used from trusted proxies, so our frontend has to be one of them for its
+//! users to get their own buckets. A service key doesn't change that. With the postgres store the
+//! buckets are shared by all instances.
+
+use std::{
+    collections::HashMap,
+    str::FromStr,
+    sync::{Arc, Mutex},
+    time::{Duration as StdDuration, Instant},
+};
+
+use actix_web::{
+    body::MessageBody,
+    dev::{ServiceRequest, ServiceResponse},
+    http::{header, Method},
+    middleware::Next,
+    rt::{self, Runtime},
+    web, FromRequest, HttpResponse,
+};
+use chrono::{Duration, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    api::{auth::Auth, users::sessions},
+    app_config::{AppConfig, RateLimit, RateLimitStore, RateLimits},
+    database,
+};
+
+/// Untouched buckets are full again long before this
+const STALE_AFTER: StdDuration = StdDuration::from_secs(60 * 60);
+const PRUNE_INTERVAL: StdDuration = StdDuration::from_secs(10 * 60);
+/// The memory store drops stale buckets once it holds this many
+const MAX_MEMORY_BUCKETS: usize = 100_000;
+
+lazy_static::lazy_static! {
+    static ref WORKER: Mutex<Option<()>> = Mutex::new(None);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, strum::Display, strum::EnumString)]
+#[strum(serialize_all = "snake_case")]
+pub enum RouteGroup {
+    /// Logging in, registering and everything else that guesses credentials
+    Auth,
+    /// Routes sending mails
+    Mail,
+    Import,
+    /// Routes fetching profiles from mihomo
+    Mihomo,
+    Write,
+}
+
+impl RouteGroup {
+    const ALL: [RouteGroup; 5] = [
+        RouteGroup::Auth,
+        RouteGroup::Mail,
+        RouteGroup::Import,
+        RouteGroup::Mihomo,
+        RouteGroup::Write,
+    ];
+
+    fn default_limits(self) -> RateLimits {
+        let limit = |capacity, per_minute| {
+            Some(RateLimit {
+                capacity,
+                per_minute,
+            })
+        };
+
+        match self {
+            RouteGroup::Auth => RateLimits {
+                ip: limit(20, 10),
+                user: None,
+            },
+            RouteGroup::Mail => RateLimits {
+                ip: limit(5, 2),
+                user: limit(5, 1),
+            },
+            RouteGroup::Import => RateLimits {
+                ip: limit(10, 5),
+                user: limit(10, 5),
+            },
+            RouteGroup::Mihomo => RateLimits {
+                ip: limit(10, 5),
+                user: None,
+            },
+            RouteGroup::Write => RateLimits {
+                ip: limit(120, 60),
+                user: limit(120, 60),
+            },
+        }
+    }
+
+    fn of(method: &Method, path: &str) -> Option<RouteGroup> {
+        let path = path.strip_prefix("/api/")?.trim_end_matches('/');
+        let write = matches!(
+            *method,
+            Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+        );
+
+        match path {
+            "users/auth/request-token"
+            | "users/auth/request-password-reset"
+            | "users/me/email"
+            | "users/me/email/verify"
+                if write =>
+            {
+                Some(RouteGroup::Mail)
+            }
+            // Includes the GETs starting and finishing OIDC logins
+            _ if path.starts_with("users/auth/") => Some(RouteGroup::Auth),
+            "users/deleted-export" if write => Some(RouteGroup::Auth),
+            _ if !write => None,
+            _ if path.starts_with("mihomo/") || path.starts_with("scores/") => {
+                Some(RouteGroup::Mihomo)
+            }
+            _ if path.split('/').any(|segment| segment.ends_with("import")) => {
+                Some(RouteGroup::Import)
+            }
+            _ if path == "users/me"
+                || path.starts_with("users/me/")
+                || path.starts_with("ntehelper/") =>
+            {
+                Some(RouteGroup::Write)
+            }
+            _ => None,
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+enum Store {
+    Memory(Mutex<HashMap<String, Bucket>>),
+    Postgres(PgPool),
+}
+
+pub struct RateLimiter {
+    limits: HashMap<RouteGroup, RateLimits>,
+    store: Store,
+}
+
+impl RateLimiter {
+    pub fn new(pool: PgPool, app_config: &AppConfig) -> Self {
+        let mut limits: HashMap<_, _> = RouteGroup::ALL
+            .into_iter()
+            .map(|group| (group, group.default_limits()))
+            .collect();
+
+        for (group, group_limits) in &app_config.rate_limits {
+            match RouteGroup::from_str(group) {
+                Ok(group) => {
+                    limits.insert(group, *group_limits);
+                }
+                Err(_) => warn!("Ignoring rate limits for unknown route group {group}"),
+            }
+        }
+
+        // A bucket that never refills would lock people out for good
+        for group_limits in limits.values_mut() {
+            for limit in [&mut group_limits.ip, &mut group_limits.user] {
+                if limit.is_some_and(|limit| limit.per_minute == 0) {
+                    warn!("Ignoring rate limit {limit:?}, per_minute has to be positive");
+                    *limit = None;
+                }
+            }
+        }
+
+        let store = match app_config.rate_limit_store {
+            RateLimitStore::Memory => Store::Memory(Mutex::new(HashMap::new())),
+            RateLimitStore::Postgres => {
+                WORKER
+                    .lock()
+                    .unwrap()
+                    .get_or_insert_with(|| spawn(pool.clone()));
+
+                Store::Postgres(pool)
+            }
+        };
+
+        Self { limits, store }
+    }
+
+    /// The seconds until `key` may make a request again, if it may not now
+    async fn take(&self, key: &str, limit: RateLimit) -> anyhow::Result<Option<f64>> {
+        let capacity = limit.capacity as f64;
+        let per_second = limit.per_minute as f64 / 60.0;
+
+        match &self.store {
+            Store::Memory(buckets) => {
+                let now = Instant::now();
+                let mut buckets = buckets.lock().unwrap();
+
+                if buckets.len() >= MAX_MEMORY_BUCKETS {
+                    buckets.retain(|_, bucket| now - bucket.updated < STALE_AFTER);
+                }
+
+                let bucket = buckets.entry(key.to_string()).or_insert(Bucket {
+                    tokens: capacity,
+                    updated: now,
+                });
+
+                let tokens = (bucket.tokens
+                    + now.duration_since(bucket.updated).as_secs_f64() * per_second)
+                    .min(capacity);
+                bucket.updated = now;
+
+                if tokens >= 1.0 {
+                    bucket.tokens = tokens - 1.0;
+                    Ok(None)
+                } else {
+                    bucket.tokens = tokens;
+                    Ok(Some((1.0 - tokens) / per_second))
+                }
+            }
+            Store::Postgres(pool) => {
+                if database::rate_limits::take(key, capacity, per_second, pool).await? {
+                    return Ok(None);
+                }
+
+                let retry_after =
+                    database::rate_limits::get_retry_after_by_key(key, capacity, per_second, pool)
+                        .await?;
+
+                // The bucket was pruned in between, so it's full
+                Ok(Some(retry_after.unwrap_or_default()))
+            }
+        }
+    }
+
+    /// Takes from the ip bucket, then from the user bucket. Returns the seconds to wait if either
+    /// is empty.
+    async fn check(
+        &self,
+        group: RouteGroup,
+        ip: Option<&str>,
+        user: Option<&str>,
+    ) -> anyhow::Result<Option<f64>> {
+        let limits = self.limits[&group];
+
+        if let (Some(limit), Some(ip)) = (limits.ip, ip) {
+            if let Some(retry_after) = self.take(&format!("{group}:ip:{ip}"), limit).await? {
+                return Ok(Some(retry_after));
+            }
+        }
+
+        if let (Some(limit), Some(user)) = (limits.user, user) {
+            if let Some(retry_after) = self.take(&format!("{group}:user:{user}"), limit).await? {
+                return Ok(Some(retry_after));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+fn spawn(pool: PgPool) {
+    std::thread::spawn(move || {
+        Runtime::new().unwrap().block_on(async {
+            loop {
+                let before = Utc::now() - Duration::from_std(STALE_AFTER).unwrap();
+
+                if let Err(e) = database::rate_limits::delete_stale(before, &pool).await {
+                    error!("Pruning rate limits failed with {e}");
+                }
+
+                rt::time::sleep(PRUNE_INTERVAL).await;
+            }
+        })
+    });
+}
+
+/// Wrap it inside the session middleware, so logged in users are known. Needs the [`RateLimiter`]
+/// as app data.
+pub async fn middleware(
+    request: ServiceRequest,
+    next: Next<impl MessageBody + 'static>,
+) -> Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let Some(group) = RouteGroup::of(request.method(), request.path()) else {
+        return Ok(next.call(request).await?.map_into_left_body());
+    };
+
+    let (Some(rate_limiter), Some(app_config)) = (
+        request.app_data::<web::Data<RateLimiter>>(),
+        request.app_data::<web::Data<Arc<AppConfig>>>(),
+    ) else {
+        return Ok(next.call(request).await?.map_into_left_body());
+    };
+
+    let ip = sessions::client_ip(&request.connection_info(), app_config)
+        .map(|ip| sessions::ip_hash(ip, app_config));
+
+    // Invalid tokens get no user bucket, so random tokens can't dodge the limits
+    let user = Auth::extract(request.request())
+        .await
+        .ok()
+        .and_then(|auth| auth.owner().map(str::to_string));
+
+    match rate_limiter
+        .check(group, ip.as_deref(), user.as_deref())
+        .await
+    {
+        Ok(None) => {}
+        Ok(Some(retry_after)) => {
+            let retry_after = retry_after.ceil().max(1.0) as u64;
+
+            let response = HttpResponse::TooManyRequests()
+                .append_header((header::RETRY_AFTER, retry_after.to_string()))
+                .finish();
+
+            return Ok(request.into_response(response).map_into_right_body());
+        }
+        // Better to let requests through than to take the site down with the store
+        Err(e) => error!("Rate limiting failed with {e}"),
+    }
+
+    Ok(next.call(request).await?.map_into_left_body())
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{
+        middleware::from_fn,
+        test::{call_service, init_service, TestRequest},
+        App,
+    };
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::test_util::test_pool;
+
+    fn app_config(store: &str) -> AppConfig {
+        envy::from_iter([
+            ("RATE_LIMIT_STORE".to_string(), store.to_string()),
+            ("TRUSTED_PROXIES".to_string(), "127.0.0.9".to_string()),
+            (
+                "RATE_LIMITS".to_string(),
+                r#"{"auth": {"ip": {"capacity": 2, "per_minute": 1}}}"#.to_string(),
+            ),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn routes_are_grouped_by_method_and_path() {
+        let group = |method, path| RouteGroup::of(&method, path);
+
+        assert_eq!(
+            group(Method::POST, "/api/users/auth/login"),
+            Some(RouteGroup::Auth)
+        );
+        assert_eq!(
+            group(Method::GET, "/api/users/auth/oidc/discord"),
+            Some(RouteGroup::Auth)
+        );
+        assert_eq!(
+            group(Method::POST, "/api/users/auth/request-token"),
+            Some(RouteGroup::Mail)
+        );
+        assert_eq!(
+            group(Method::PUT, "/api/users/me/email"),
+            Some(RouteGroup::Mail)
+        );
+        assert_eq!(
+            group(Method::POST, "/api/gi/wishes-import"),
+            Some(RouteGroup::Import)
+        );
+        assert_eq!(
+            group(Method::POST, "/api/srs-warps-import/123"),
+            Some(RouteGroup::Import)
+        );
+        assert_eq!(
+            group(Method::PUT, "/api/users/me/import"),
+            Some(RouteGroup::Import)
+        );
+        assert_eq!(
+            group(Method::PUT, "/api/mihomo/123"),
+            Some(RouteGroup::Mihomo)
+        );
+        assert_eq!(
+            group(Method::DELETE, "/api/users/me"),
+            Some(RouteGroup::Write)
+        );
+        assert_eq!(
+            group(Method::PATCH, "/api/ntehelper/completions"),
+            Some(RouteGroup::Write)
+        );
+        assert_eq!(group(Method::GET, "/api/mihomo/123"), None);
+        assert_eq!(group(Method::GET, "/api/users/me"), None);
+        assert_eq!(group(Method::PUT, "/api/pages/profiles/123"), None);
+    }
+
+    #[actix_web::test]
+    async fn limits_can_be_overridden() {
+        let pool = test_pool().await;
+        let rate_limiter = RateLimiter::new(pool, &app_config("memory"));
+
+        assert_eq!(
+            rate_limiter.limits[&RouteGroup::Auth].ip,
+            Some(RateLimit {
+                capacity: 2,
+                per_minute: 1
+            })
+        );
+        assert_eq!(
+            rate_limiter.limits[&RouteGroup::Mail].ip,
+            RouteGroup::Mail.default_limits().ip
+        );
+    }
+
+    #[actix_web::test]
+    async fn buckets_run_empty() {
+        let pool = test_pool().await;
+        let limit = RateLimit {
+            capacity: 2,
+            per_minute: 1,
+        };
+
+        for store in ["memory", "postgres"] {
+            let rate_limiter = RateLimiter::new(pool.clone(), &app_config(store));
+            let key = format!("test:{}", Uuid::new_v4());
+
+            assert_eq!(rate_limiter.take(&key, limit).await.unwrap(), None);
+            assert_eq!(rate_limiter.take(&key, limit).await.unwrap(), None);
+
+            let retry_after = rate_limiter.take(&key, limit).await.unwrap().unwrap();
+            assert!(retry_after > 55.0 && retry_after <= 60.0, "{store}");
+
+            let other = format!("test:{}", Uuid::new_v4());
+            assert_eq!(rate_limiter.take(&other, limit).await.unwrap(), None);
+        }
+    }
+
+    #[actix_web::test]
+    async fn limited_requests_get_429() {
+        let pool = test_pool().await;
+        let app_config = Arc::new(app_config("memory"));
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(RateLimiter::new(pool, &app_config)))
+                .app_data(web::Data::new(app_config))
+                .wrap(from_fn(middleware))
+                .route("/api/users/auth/login", web::post().to(HttpResponse::Ok))
+                .route("/api/mihomo/{uid}", web::get().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let login = || {
+            TestRequest::post()
+                .uri("/api/users/auth/login")
+                .peer_addr("127.0.0.1:1234".parse().unwrap())
+                .to_request()
+        };
+
+        assert!(call_service(&app, login()).await.status().is_success());
+        assert!(call_service(&app, login()).await.status().is_success());
+
+        let response = call_service(&app, login()).await;
+        assert_eq!(response.status(), 429);
+        let retry_after: u64 = response
+            .headers()
+            .get(header::RETRY_AFTER)
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert!((55..=60).contains(&retry_after));
+
+        // Forwarded headers from untrusted peers are ignored
+        let spoofed = TestRequest::post()
+            .uri("/api/users/auth/login")
+            .peer_addr("127.0.0.1:1234".parse().unwrap())
+            .insert_header(("x-forwarded-for", "10.0.0.1"))
+            .to_request();
+        assert_eq!(call_service(&app, spoofed).await.status(), 429);
+
+        // Other ips and unlimited routes are unaffected
+        let other_ip = TestRequest::post()
+            .uri("/api/users/auth/login")
+            .peer_addr("127.0.0.2:1234".parse().unwrap())
+            .to_request();
+        assert!(call_service(&app, other_ip).await.status().is_success());
+
+        for _ in 0..5 {
+            let unlimited = TestRequest::get()
+                .uri("/api/mihomo/123")
+                .peer_addr("127.0.0.1:1234".parse().unwrap())
+                .to_request();
+            assert!(call_service(&app, unlimited).await.status().is_success());
+        }
+    }
+
+    #[actix_web::test]
+    async fn trusted_proxies_forward_the_client_ip() {
+        let pool = test_pool().await;
+        let app_config = Arc::new(app_config("memory"));
+
+        let app = init_service(
+            App::new()
+                .app_data(web::Data::new(RateLimiter::new(pool, &app_config)))
+                .app_data(web::Data::new(app_config))
+                .wrap(from_fn(middleware))
+                .route("/api/users/auth/login", web::post().to(HttpResponse::Ok)),
+        )
+        .await;
+
+        let login = |client| {
+            TestRequest::post()
+                .uri("/api/users/auth/login")
+                .peer_addr("127.0.0.9:1234".parse().unwrap())
+                .insert_header(("x-forwarded-for", client))
+                .to_request()
+        };
+
+        assert!(call_service(&app, login("10.0.0.1"))
+            .await
+            .status()
+            .is_success());
+        assert!(call_service(&app, login("10.0.0.1"))
+            .await
+            .status()
+            .is_success());
+        assert_eq!(call_service(&app, login("10.0.0.1")).await.status(), 429);
+
+        assert!(call_service(&app, login("10.0.0.2"))
+            .await
+            .status()
+            .is_success());
+    }
+}
@@ -0,0 +1,295 @@
+//! Keys for partner sites and our own frontend to reach the `private` routes.
+//!
+//! Keys live in the database and are cached here, so every instance picks up new, rotated and
+//! deleted keys within [`SYNC_INTERVAL`] without a restart. Usage is counted in memory and
+//! flushed on the same interval, so quotas are per instance: behind a load balancer a key gets its
+//! quota once for every instance.
+
+use std::{
+    collections::HashMap,
+    env,
+    sync::{Mutex, RwLock},
+    time::Duration,
+};
+
+use actix_web::rt::{self, Runtime};
+use chrono::Utc;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::database;
+
+pub const KEY_PREFIX: &str = "sdbk_";
+
+const SYNC_INTERVAL: Duration = Duration::from_secs(30);
+
+lazy_static::lazy_static! {
+    static ref KEYS: RwLock<Vec<ServiceKey>> = RwLock::new(Vec::new());
+    static ref USAGE: Mutex<HashMap<Uuid, Usage>> = Mutex::new(HashMap::new());
+    static ref WORKER: Mutex<Option<()>> = Mutex::new(None);
+}
+
+struct ServiceKey {
+    id: Uuid,
+    hash: Vec<u8>,
+    route_groups: Vec<String>,
+    quota_per_minute: Option<i32>,
+}
+
+#[derive(Default, Clone, Copy)]
+pub struct Usage {
+    minute: i64,
+    /// Accepted requests in the current minute
+    pub count: i64,
+    /// Accepted requests not yet added to the database
+    unflushed: i64,
+    /// Requests rejected for the quota or route group since startup
+    pub rejected: i64,
+}
+
+/// Call before the server binds, so the keys are known from the first request on
+pub async fn load(pool: &PgPool) -> anyhow::Result<()> {
+    bootstrap(pool).await?;
+    reload(pool).await
+}
+
+pub fn configure(pool: PgPool) {
+    WORKER.lock().unwrap().get_or_insert_with(|| spawn(pool));
+}
+
+fn spawn(pool: PgPool) {
+    std::thread::spawn(move || {
+        Runtime::new().unwrap().block_on(async {
+            loop {
+                if let Err(e) = flush(&pool).await {
+                    error!("Flushing service key usage failed with {e}");
+                }
+
+                if let Err(e) = reload(&pool).await {
+                    error!("Reloading service keys failed with {e}");
+                }
+
+                rt::time::sleep(SYNC_INTERVAL).await;
+            }
+        })
+    });
+}
+
+/// Carries the old `API_KEY` env var over as a key named `default`. Once it exists it's managed
+/// like every other key, so changing the env var afterwards does nothing.
+async fn bootstrap(pool: &PgPool) -> anyhow::Result<()> {
+    let Ok(key) = env::var("API_KEY") else {
+        return Ok(());
+    };
+
+    let route_groups = vec!["pages".to_string(), "users".to_string()];
+    database::service_keys::set("default", "stardb", &hash(&key), &route_groups, None, pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Call after changing keys, so this instance doesn't wait for the next sync
+pub async fn reload(pool: &PgPool) -> anyhow::Result<()> {
+    let keys: Vec<_> = database::service_keys::get_all(pool)
+        .await?
+        .into_iter()
+        .map(|key| ServiceKey {
+            id: key.id,
+            hash: key.hash,
+            route_groups: key.route_groups,
+            quota_per_minute: key.quota_per_minute,
+        })
+        .collect();
+
+    USAGE
+        .lock()
+        .unwrap()
+        .retain(|id, _| keys.iter().any(|key| key.id == *id));
+    *KEYS.write().unwrap() = keys;
+
+    Ok(())
+}
+
+async fn flush(pool: &PgPool) -> anyhow::Result<()> {
+    let unflushed: Vec<_> = USAGE
+        .lock()
+        .unwrap()
+        .iter_mut()
+        .filter(|(_, usage)| usage.unflushed > 0)
+        .map(|(id, usage)| (*id, std::mem::take(&mut usage.unflushed)))
+        .collect();
+
+    let now = Utc::now();
+    for (i, &(id, requests)) in unflushed.iter().enumerate() {
+        if let Err(e) = database::service_keys::add_requests_by_id(id, requests, now, pool).await {
+            // Keep the counts for the next try
+            let mut usage = USAGE.lock().unwrap();
+            for &(id, requests) in &unflushed[i..] {
+                usage.entry(id).or_default().unflushed += requests;
+            }
+
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
+
+pub fn usage(id: Uuid) -> Usage {
+    let usage = USAGE.lock().unwrap().get(&id).copied().unwrap_or_default();
+
+    if usage.minute == minute() {
+        usage
+    } else {
+        Usage { count: 0, ..usage }
+    }
+}
+
+pub fn hash(key: &str) -> Vec<u8> {
+    Sha256::digest(key.as_bytes()).to_vec()
+}
+
+/// Whether `key` may request `path`. Counts the request against the key's quota.
+pub fn check(key: &str, path: &str) -> bool {
+    find(key, |key| {
+        let route_group = route_group(path);
+        let allowed = key.route_groups.iter().any(|g| g == route_group);
+
+        record(key.id, allowed, key.quota_per_minute, minute())
+    })
+    .unwrap_or(false)
+}
+
+fn find<T>(key: &str, f: impl FnOnce(&ServiceKey) -> T) -> Option<T> {
+    let hash = hash(key);
+
+    let keys = KEYS.read().unwrap();
+
+    // Compare against every key without stopping early, so the timing says nothing about which
+    // or how many bytes matched
+    let mut found = None;
+    for key in keys.iter() {
+        if constant_time_eq(&key.hash, &hash) {
+            found = Some(key);
+        }
+    }
+
+    found.map(f)
+}
+
+fn record(id: Uuid, allowed: bool, quota_per_minute: Option<i32>, minute: i64) -> bool {
+    let mut usage = USAGE.lock().unwrap();
+    let usage = usage.entry(id).or_default();
+
+    if usage.minute != minute {
+        usage.minute = minute;
+        usage.count = 0;
+    }
+
+    if !allowed || quota_per_minute.is_some_and(|quota| usage.count >= quota as i64) {
+        usage.rejected += 1;
+        return false;
+    }
+
+    usage.count += 1;
+    usage.unflushed += 1;
+
+    true
+}
+
+fn minute() -> i64 {
+    Utc::now().timestamp() / 60
+}
+
+/// `/api/pages/leaderboard` is in the `pages` group
+fn route_group(path: &str) -> &str {
+    path.trim_start_matches("/api/")
+        .split('/')
+        .next()
+        .unwrap_or_default()
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn route_groups_are_the_first_segment() {
+        assert_eq!(route_group("/api/pages/leaderboard"), "pages");
+        assert_eq!(route_group("/api/users/auth/register"), "users");
+        assert_eq!(route_group("/api/"), "");
+    }
+
+    #[test]
+    fn keys_compare_by_hash() {
+        assert!(constant_time_eq(&hash("a"), &hash("a")));
+        assert!(!constant_time_eq(&hash("a"), &hash("b")));
+        assert!(!constant_time_eq(&hash("a"), &hash("a")[1..]));
+    }
+
+    #[test]
+    fn quotas_reset_every_minute() {
+        let id = Uuid::new_v4();
+
+        assert!(record(id, true, Some(2), 1));
+        assert!(record(id, true, Some(2), 1));
+        assert!(!record(id, true, Some(2), 1));
+        assert!(!record(id, false, Some(2), 2));
+        assert!(record(id, true, Some(2), 2));
+
+        let usage = USAGE.lock().unwrap()[&id];
+        assert_eq!(usage.count, 1);
+        assert_eq!(usage.unflushed, 3);
+        assert_eq!(usage.rejected, 2);
+    }
+
+    #[actix_web::test]
+    async fn keys_are_checked_after_reloading() {
+        let pool = crate::test_util::test_pool().await;
+
+        let key = format!("{KEY_PREFIX}{}", Uuid::new_v4().simple());
+        let name = Uuid::new_v4().to_string();
+        let groups = vec!["pages".to_string()];
+        let id = database::service_keys::set(&name, "test", &hash(&key), &groups, Some(1), &pool)
+            .await
+            .unwrap()
+            .unwrap();
+
+        assert!(!check(&key, "/api/pages/leaderboard"));
+        assert!(find(&key, |_| ()).is_none());
+
+        reload(&pool).await.unwrap();
+
+        assert!(find(&key, |_| ()).is_some());
+
+        assert!(!check(&key, "/api/users/auth/register"));
+        assert!(check(&key, "/api/pages/leaderboard"));
+
+        flush(&pool).await.unwrap();
+        let db_key = database::service_keys::get_all(&pool)
+            .await
+            .unwrap()
+            .into_iter()
+            .find(|key| key.id == id)
+            .unwrap();
+        assert_eq!(db_key.requests, 1);
+
+        let rotated = format!("{KEY_PREFIX}{}", Uuid::new_v4().simple());
+        database::service_keys::update_hash_by_id(id, &hash(&rotated), &pool)
+            .await
+            .unwrap();
+        reload(&pool).await.unwrap();
+
+        assert!(!check(&key, "/api/pages/leaderboard"));
+
+        database::service_keys::delete_by_id(id, &pool)
+            .await
+            .unwrap();
+    }
+}
@@ -0,0 +1,175 @@
+use std::collections::HashMap;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        banner_helpers::{self, HSR_STANDARD},
+        private,
+        pull_simulator::{self, Pity, Rules, SimulatedCopies, SimulatedSpend, Simulation, State},
+        ApiResult,
+    },
+    database, Language,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "simulate")),
+    paths(get_simulate),
+    components(schemas(SimulateBanner, Simulation, SimulatedCopies, SimulatedSpend, State))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_simulate);
+}
+
+#[derive(Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulateBanner {
+    Character,
+    LightCone,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SimulateParams {
+    banner: SimulateBanner,
+    /// Start from the pity and guarantee of this uid's warps
+    uid: Option<i32>,
+    /// Overrides the pity of the uid
+    pull: Option<usize>,
+    /// Overrides the guarantee of the uid
+    guarantee: Option<bool>,
+    /// Stellar Jade
+    #[serde(default)]
+    currency: i32,
+    /// Special Passes
+    #[serde(default)]
+    passes: i32,
+    /// Defaults to E6 or S5
+    copies: Option<usize>,
+}
+
+#[utoipa::path(
+    tag = "simulate",
+    get,
+    path = "/api/simulate",
+    params(SimulateParams),
+    responses(
+        (status = 200, description = "Simulated copies of the featured character or light cone", body = Simulation),
+        (status = 403, description = "The uid is private"),
+    )
+)]
+#[get("/api/simulate", guard = "private")]
+async fn get_simulate(
+    auth: Auth,
+    params: web::Query<SimulateParams>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let mut state = State::default();
+
+    if let Some(uid) = params.uid {
+        let mut forbidden = database::connections::get_by_uid(uid, &pool)
+            .await?
+            .iter()
+            .any(|c| c.private);
+
+        if forbidden {
+            if let Some(username) = auth.username(Scope::ReadPrivate) {
+                if let Ok(connection) =
+                    database::connections::get_by_uid_and_username(uid, &username, &pool).await
+                {
+                    forbidden = !connection.verified;
+                }
+            }
+        }
+
+        if forbidden {
+            return Ok(HttpResponse::Forbidden().finish());
+        }
+
+        let mut banners: HashMap<_, Vec<_>> = HashMap::new();
+
+        for banner in database::banners::get_all(&pool).await? {
+            if let Some(character) = banner.character {
+                banners
+                    .entry(character)
+                    .or_default()
+                    .push(banner.start..banner.end);
+            }
+
+            if let Some(light_cone) = banner.light_cone {
+                banners
+                    .entry(light_cone)
+                    .or_default()
+                    .push(banner.start..banner.end);
+            }
+        }
+
+        let is_win = banner_helpers::is_win_fn(&banners, HSR_STANDARD);
+
+        let warps = match params.banner {
+            SimulateBanner::Character => {
+                database::warps::special::get_by_uid(uid, Language::En, &pool).await?
+            }
+            SimulateBanner::LightCone => {
+                database::warps::lc::get_by_uid(uid, Language::En, &pool).await?
+            }
+        };
+
+        for warp in warps {
+            state.pull += 1;
+
+            if warp.rarity != Some(5) {
+                continue;
+            }
+
+            state.pull = 0;
+
+            let item_id = warp.character.or(warp.light_cone).unwrap();
+            state.guarantee = !state.guarantee && !is_win(item_id, warp.timestamp);
+        }
+    }
+
+    state.pull = params.pull.unwrap_or(state.pull);
+    state.guarantee = params.guarantee.unwrap_or(state.guarantee);
+
+    let (rules, max_copies) = match params.banner {
+        SimulateBanner::Character => (
+            Rules {
+                pity: Pity::HSR_CHARACTER,
+                win_rate: 0.5,
+                radiance: false,
+                epitomized: false,
+            },
+            7,
+        ),
+        SimulateBanner::LightCone => (
+            Rules {
+                pity: Pity::HSR_LIGHT_CONE,
+                win_rate: 0.75,
+                radiance: false,
+                epitomized: false,
+            },
+            5,
+        ),
+    };
+
+    let copies = params.copies.unwrap_or(max_copies).clamp(1, max_copies);
+    let (currency, passes) = (params.currency.max(0), params.passes.max(0));
+
+    let simulation = web::block(move || {
+        pull_simulator::simulate(&rules, state, currency, passes, copies, &mut rand::rng())
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(simulation))
+}
@@ -1,13 +1,18 @@
 use std::collections::HashMap;
 
-use actix_session::Session;
 use actix_web::{post, web, HttpResponse, Responder};
 use chrono::{DateTime, Utc};
 use rand::seq::IndexedRandom as _;
 use sqlx::PgPool;
 use utoipa::OpenApi;
 
-use crate::{api::ApiResult, database, mihomo, GachaType, Language};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        ApiResult,
+    },
+    database, mihomo, GachaType, Language,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -59,18 +64,18 @@ struct ParsedWarp {
 )]
 #[post("/api/srs-warps-import/{uid}")]
 async fn post_srs_warps_import(
-    session: Session,
+    auth: Auth,
     uid: web::Path<i32>,
     params: web::Json<SrsWarpsImportParams>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::WarpsImport) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
     let uid = *uid;
 
-    let admin = database::admins::exists(&username, &pool).await?;
+    let admin = auth.is_admin(&pool).await?;
 
     let allowed = admin
         || database::connections::get_by_username(&username, &pool)
@@ -85,7 +90,7 @@ async fn post_srs_warps_import(
     }
 
     // Wacky way to update the database in case the uid isn't in there
-    if !database::mihomo::exists(uid, &pool).await?
+    if !database::mihomo::exists(uid, &**pool).await?
         && mihomo::get(uid, Language::En, &pool).await?.is_none()
     {
         let region = match uid.to_string().chars().next() {
@@ -102,7 +107,7 @@ async fn post_srs_warps_import(
             ..Default::default()
         };
 
-        database::mihomo::set(&db_mihomo, &pool).await?;
+        database::mihomo::set(&db_mihomo, &**pool).await?;
     }
 
     let mut warps_map: HashMap<_, Vec<ParsedWarp>> = HashMap::new();
@@ -247,12 +252,12 @@ async fn post_srs_warps_import(
         }
     }
 
-    database::warps::departure::set_all(&set_all_departure, &pool).await?;
-    database::warps::standard::set_all(&set_all_standard, &pool).await?;
-    database::warps::special::set_all(&set_all_special, &pool).await?;
-    database::warps::lc::set_all(&set_all_lc, &pool).await?;
-    database::warps::collab::set_all(&set_all_collab, &pool).await?;
-    database::warps::collab_lc::set_all(&set_all_collab_lc, &pool).await?;
+    database::warps::departure::set_all(&set_all_departure, &**pool).await?;
+    database::warps::standard::set_all(&set_all_standard, &**pool).await?;
+    database::warps::special::set_all(&set_all_special, &**pool).await?;
+    database::warps::lc::set_all(&set_all_lc, &**pool).await?;
+    database::warps::collab::set_all(&set_all_collab, &**pool).await?;
+    database::warps::collab_lc::set_all(&set_all_collab_lc, &**pool).await?;
 
     Ok(HttpResponse::Ok().finish())
 }
@@ -1,22 +1,33 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
-use actix_session::Session;
 use actix_web::{post, web, HttpResponse, Responder};
 use chrono::{DateTime, FixedOffset, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
-use utoipa::OpenApi;
+use strum::IntoEnumIterator;
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
 
 use crate::{
-    api::ApiResult,
-    database,
-    {GachaType, GiGachaType, ZzzGachaType},
+    api::{
+        auth::{Auth, Scope},
+        ApiResult,
+    },
+    database, GachaType, GiGachaType, Language, ZzzGachaType,
 };
 
 #[derive(OpenApi)]
 #[openapi(
     tags((name = "uigf-import")),
     paths(post_uigf_import),
-    components(schemas(UigfImportParams)),
+    components(schemas(
+        UigfImportParams,
+        UigfImport,
+        UigfImportReport,
+        UigfImportUid,
+        UigfImportBanner,
+        UigfImportRejected,
+    )),
 )]
 struct ApiDoc;
 
@@ -31,6 +42,78 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
 #[derive(serde::Deserialize, utoipa::ToSchema)]
 struct UigfImportParams {
     data: String,
+    /// Report what would change without writing anything
+    #[serde(default)]
+    dry_run: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+struct UigfImport {
+    /// Poll /api/import-jobs/{id} for progress
+    #[schema(value_type = String)]
+    id: Uuid,
+}
+
+#[derive(Default, Serialize, Deserialize, ToSchema)]
+pub struct UigfImportReport {
+    hkrpg: Vec<UigfImportUid>,
+    nap: Vec<UigfImportUid>,
+    hk4e: Vec<UigfImportUid>,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+struct UigfImportUid {
+    uid: i32,
+    /// Uids that aren't verified for the user are skipped entirely
+    authorized: bool,
+    banners: Vec<UigfImportBanner>,
+    rejected: Vec<UigfImportRejected>,
+}
+
+impl UigfImportUid {
+    fn new(uid: i32) -> Self {
+        Self {
+            uid,
+            authorized: true,
+            banners: Vec::new(),
+            rejected: Vec::new(),
+        }
+    }
+
+    fn reject(&mut self, id: &str, item_id: &str, reason: impl Into<String>) {
+        self.rejected.push(UigfImportRejected {
+            id: id.to_string(),
+            item_id: item_id.to_string(),
+            reason: reason.into(),
+        });
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+struct UigfImportBanner {
+    gacha_type: String,
+    inserted: usize,
+    duplicate: usize,
+    /// Pulls newer than the earliest already stored pull
+    skipped: usize,
+}
+
+impl UigfImportBanner {
+    fn new(gacha_type: impl ToString) -> Self {
+        Self {
+            gacha_type: gacha_type.to_string(),
+            inserted: 0,
+            duplicate: 0,
+            skipped: 0,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+struct UigfImportRejected {
+    id: String,
+    item_id: String,
+    reason: String,
 }
 
 #[derive(serde::Deserialize, Clone)]
@@ -113,12 +196,34 @@ struct UigfNapEntry {
     time: String,
 }
 
+/// id, character, light cone, time
+type WarpPull = (i64, Option<i32>, Option<i32>, DateTime<Utc>);
+/// id, character, w-engine, bangboo, time
+type SignalPull = (i64, Option<i32>, Option<i32>, Option<i32>, DateTime<Utc>);
+/// id, character, weapon, time
+type WishPull = (i64, Option<i32>, Option<i32>, DateTime<Utc>);
+
 fn parse_uigf_version(version: &str) -> Option<(u32, u32)> {
     let version = version.strip_prefix('v')?;
     let (major, minor) = version.split_once('.')?;
     Some((major.parse().ok()?, minor.parse().ok()?))
 }
 
+/// UIGF timezones are whole hours from UTC
+fn timezone(timezone: i32) -> Option<FixedOffset> {
+    FixedOffset::east_opt(timezone.checked_mul(3600)?)
+}
+
+fn parse_time(time: &str, tz: FixedOffset) -> Option<DateTime<Utc>> {
+    Some(
+        NaiveDateTime::parse_from_str(time, "%Y-%m-%d %H:%M:%S")
+            .ok()?
+            .and_local_timezone(tz)
+            .single()?
+            .to_utc(),
+    )
+}
+
 async fn check_hsr_auth(admin: bool, username: &str, uid: i32, pool: &PgPool) -> ApiResult<bool> {
     let allowed = admin
         || database::connections::get_by_username(username, pool)
@@ -158,335 +263,632 @@ async fn check_gi_auth(admin: bool, username: &str, uid: i32, pool: &PgPool) ->
     path = "/api/uigf-import",
     request_body = UigfImportParams,
     responses(
-        (status = 200, description = "UIGF imported"),
-        (status = 400, description = "Invalid data or version"),
-        (status = 403, description = "Not authorized"),
+        (status = 200, description = "UigfImport", body = UigfImport),
+        (status = 400, description = "Invalid data, version or timezone"),
     )
 )]
 #[post("/api/uigf-import")]
 async fn post_uigf_import(
-    session: Session,
+    auth: Auth,
     params: web::Json<UigfImportParams>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::WarpsImport) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
-    let uigf: Uigf = serde_json::from_str(&params.data)?;
+    let Ok(uigf) = serde_json::from_str::<Uigf>(&params.data) else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
 
     match parse_uigf_version(&uigf.info.version) {
         Some((major, _minor)) if major >= 4 => {}
         _ => return Ok(HttpResponse::BadRequest().finish()),
     }
 
-    let admin = database::admins::exists(&username, &pool).await?;
+    let uids_valid = uigf.hkrpg.iter().all(|e| e.uid.clone().parse().is_ok())
+        && uigf.nap.iter().all(|e| e.uid.clone().parse().is_ok())
+        && uigf.hk4e.iter().all(|e| e.uid.clone().parse().is_ok());
+
+    let timezones_valid = uigf.hkrpg.iter().all(|e| timezone(e.timezone).is_some())
+        && uigf.nap.iter().all(|e| timezone(e.timezone).is_some())
+        && uigf.hk4e.iter().all(|e| timezone(e.timezone).is_some());
+
+    if !uids_valid || !timezones_valid {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    let admin = auth.is_admin(&pool).await?;
+
+    let id =
+        database::import_jobs::insert_uigf(&username, &params.data, admin, params.dry_run, &pool)
+            .await?;
+
+    Ok(HttpResponse::Ok().json(UigfImport { id }))
+}
+
+/// Runs a queued import. The report is stored after every uid, so it can be polled while the
+/// import is still going.
+pub async fn run(job: &database::import_jobs::DbImportJob, pool: &PgPool) -> ApiResult<()> {
+    let (Some(username), Some(data)) = (&job.username, &job.data) else {
+        return Err(anyhow::anyhow!("No data").into());
+    };
+
+    let uigf = serde_json::from_str(data)?;
+
+    import_uigf(job.id, uigf, username, job.admin, job.dry_run, pool).await
+}
+
+async fn import_uigf(
+    id: Uuid,
+    uigf: Uigf,
+    username: &str,
+    admin: bool,
+    dry_run: bool,
+    pool: &PgPool,
+) -> ApiResult<()> {
+    // A reclaimed job starts over, pulls it already inserted count as duplicates
+    let mut report = UigfImportReport::default();
+
+    database::import_jobs::set_report(id, &serde_json::to_value(&report)?, pool).await?;
+
+    if !uigf.hkrpg.is_empty() {
+        let characters: HashSet<i32> = database::characters::get_ids(pool)
+            .await?
+            .into_iter()
+            .collect();
+        let light_cones: HashSet<i32> = database::light_cones::get_ids(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+        for entry in &uigf.hkrpg {
+            let uid = import_hkrpg(
+                entry,
+                &characters,
+                &light_cones,
+                username,
+                admin,
+                dry_run,
+                pool,
+            )
+            .await?;
+
+            report.hkrpg.push(uid);
+
+            database::import_jobs::set_report(id, &serde_json::to_value(&report)?, pool).await?;
+        }
+    }
+
+    if !uigf.nap.is_empty() {
+        let characters: HashSet<i32> = database::zzz::characters::get_ids(pool)
+            .await?
+            .into_iter()
+            .collect();
+        let w_engines: HashSet<i32> = database::zzz::w_engines::get_ids(pool)
+            .await?
+            .into_iter()
+            .collect();
+        let bangboos: HashSet<i32> = database::zzz::bangboos::get_ids(pool)
+            .await?
+            .into_iter()
+            .collect();
+
+        for entry in &uigf.nap {
+            let uid = import_nap(
+                entry,
+                &characters,
+                &w_engines,
+                &bangboos,
+                username,
+                admin,
+                dry_run,
+                pool,
+            )
+            .await?;
+
+            report.nap.push(uid);
+
+            database::import_jobs::set_report(id, &serde_json::to_value(&report)?, pool).await?;
+        }
+    }
+
+    if !uigf.hk4e.is_empty() {
+        let characters: HashSet<i32> = database::gi::characters::get_ids(pool)
+            .await?
+            .into_iter()
+            .collect();
+        let weapons: HashSet<i32> = database::gi::weapons::get_all(pool)
+            .await?
+            .into_iter()
+            .map(|w| w.id)
+            .collect();
+
+        for entry in &uigf.hk4e {
+            let uid =
+                import_hk4e(entry, &characters, &weapons, username, admin, dry_run, pool).await?;
+
+            report.hk4e.push(uid);
+
+            database::import_jobs::set_report(id, &serde_json::to_value(&report)?, pool).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn import_hkrpg(
+    entry: &UigfHkrpg,
+    characters: &HashSet<i32>,
+    light_cones: &HashSet<i32>,
+    username: &str,
+    admin: bool,
+    dry_run: bool,
+    pool: &PgPool,
+) -> ApiResult<UigfImportUid> {
+    let uid = entry.uid.clone().parse()?;
+
+    let mut report = UigfImportUid::new(uid);
+
+    if !check_hsr_auth(admin, username, uid, pool).await? {
+        report.authorized = false;
+
+        return Ok(report);
+    }
+
+    let Some(tz) = timezone(entry.timezone) else {
+        return Err(anyhow::anyhow!("Invalid timezone {}", entry.timezone).into());
+    };
+
+    let mut warps_map: HashMap<GachaType, Vec<WarpPull>> = HashMap::new();
+
+    for pull in &entry.list {
+        let gacha_type = match pull.gacha_type.as_str() {
+            "1" => GachaType::Standard,
+            "2" => GachaType::Departure,
+            "11" => GachaType::Special,
+            "12" => GachaType::Lc,
+            "21" => GachaType::Collab,
+            "22" => GachaType::CollabLc,
+            _ => {
+                report.reject(&pull.id, &pull.item_id, "Unknown gacha type");
+                continue;
+            }
+        };
+
+        let Some(time) = parse_time(&pull.time, tz) else {
+            report.reject(&pull.id, &pull.item_id, "Invalid time");
+            continue;
+        };
+
+        let (Ok(id), Ok(item_id)) = (pull.id.parse::<i64>(), pull.item_id.parse::<i32>()) else {
+            report.reject(&pull.id, &pull.item_id, "Invalid id");
+            continue;
+        };
+
+        let (character, light_cone) = if pull.item_type == "Character" || pull.item_type == "角色"
+        {
+            (Some(item_id), None)
+        } else if pull.item_type == "Light Cone"
+            || pull.item_type == "光锥"
+            || pull.item_type == "光錐"
+        {
+            (None, Some(item_id))
+        } else {
+            report.reject(&pull.id, &pull.item_id, "Unknown item type");
+            continue;
+        };
 
-    // HSR (hkrpg)
-    for entry in &uigf.hkrpg {
-        let uid = entry.uid.clone().parse()?;
-        if !check_hsr_auth(admin, &username, uid, &pool).await? {
+        if character.is_some_and(|id| !characters.contains(&id))
+            || light_cone.is_some_and(|id| !light_cones.contains(&id))
+        {
+            report.reject(&pull.id, &pull.item_id, "Unknown item id");
             continue;
         }
 
-        let tz = FixedOffset::east_opt(3600 * entry.timezone).unwrap();
-
-        let mut warps_map: HashMap<GachaType, Vec<(i64, Option<i32>, Option<i32>, DateTime<Utc>)>> =
-            HashMap::new();
-
-        for pull in &entry.list {
-            let gacha_type = match pull.gacha_type.as_str() {
-                "1" => GachaType::Standard,
-                "2" => GachaType::Departure,
-                "11" => GachaType::Special,
-                "12" => GachaType::Lc,
-                "21" => GachaType::Collab,
-                "22" => GachaType::CollabLc,
-                _ => return Ok(HttpResponse::BadRequest().finish()),
-            };
-
-            let time = NaiveDateTime::parse_from_str(&pull.time, "%Y-%m-%d %H:%M:%S")?
-                .and_local_timezone(tz)
-                .unwrap()
-                .to_utc();
-
-            let id: i64 = pull.id.parse()?;
-            let item_id: i32 = pull.item_id.parse()?;
-
-            let (character, light_cone) = if pull.item_type == "Character"
-                || pull.item_type == "角色"
-            {
-                (Some(item_id), None)
-            } else if pull.item_type == "Light Cone"
-                || pull.item_type == "光锥"
-                || pull.item_type == "光錐"
-            {
-                (None, Some(item_id))
-            } else {
-                return Ok(HttpResponse::BadRequest().finish());
-            };
-
-            warps_map
-                .entry(gacha_type)
-                .or_default()
-                .push((id, character, light_cone, time));
+        warps_map
+            .entry(gacha_type)
+            .or_default()
+            .push((id, character, light_cone, time));
+    }
+
+    for gacha_type in GachaType::iter() {
+        let Some(pulls) = warps_map.get(&gacha_type) else {
+            continue;
+        };
+
+        let (earliest_timestamp, existing) = match gacha_type {
+            GachaType::Departure => (
+                database::warps::departure::get_earliest_timestamp_by_uid(uid, pool).await?,
+                database::warps::departure::get_by_uid(uid, Language::En, pool).await?,
+            ),
+            GachaType::Standard => (
+                database::warps::standard::get_earliest_timestamp_by_uid(uid, pool).await?,
+                database::warps::standard::get_by_uid(uid, Language::En, pool).await?,
+            ),
+            GachaType::Special => (
+                database::warps::special::get_earliest_timestamp_by_uid(uid, pool).await?,
+                database::warps::special::get_by_uid(uid, Language::En, pool).await?,
+            ),
+            GachaType::Lc => (
+                database::warps::lc::get_earliest_timestamp_by_uid(uid, pool).await?,
+                database::warps::lc::get_by_uid(uid, Language::En, pool).await?,
+            ),
+            GachaType::Collab => (
+                database::warps::collab::get_earliest_timestamp_by_uid(uid, pool).await?,
+                database::warps::collab::get_by_uid(uid, Language::En, pool).await?,
+            ),
+            GachaType::CollabLc => (
+                database::warps::collab_lc::get_earliest_timestamp_by_uid(uid, pool).await?,
+                database::warps::collab_lc::get_by_uid(uid, Language::En, pool).await?,
+            ),
+        };
+
+        let mut existing: HashSet<i64> = existing.into_iter().map(|w| w.id).collect();
+
+        let mut banner = UigfImportBanner::new(gacha_type);
+        let mut set_all = database::warps::SetAll::default();
+        let mut skipping = false;
+
+        for &(id, character, light_cone, time) in pulls {
+            if !admin && earliest_timestamp.is_some_and(|t| time >= t) {
+                skipping = true;
+            }
+
+            if !existing.insert(id) {
+                banner.duplicate += 1;
+                continue;
+            }
+
+            if skipping {
+                banner.skipped += 1;
+                continue;
+            }
+
+            banner.inserted += 1;
+
+            set_all.id.push(id);
+            set_all.uid.push(uid);
+            set_all.character.push(character);
+            set_all.light_cone.push(light_cone);
+            set_all.timestamp.push(time);
+            set_all.official.push(false);
         }
 
-        let mut set_all_departure = database::warps::SetAll::default();
-        let mut set_all_standard = database::warps::SetAll::default();
-        let mut set_all_special = database::warps::SetAll::default();
-        let mut set_all_lc = database::warps::SetAll::default();
-        let mut set_all_collab = database::warps::SetAll::default();
-        let mut set_all_collab_lc = database::warps::SetAll::default();
-
-        for (gacha_type, pulls) in &warps_map {
-            let earliest_timestamp = match gacha_type {
-                GachaType::Departure => {
-                    database::warps::departure::get_earliest_timestamp_by_uid(uid, &pool).await?
-                }
-                GachaType::Standard => {
-                    database::warps::standard::get_earliest_timestamp_by_uid(uid, &pool).await?
-                }
-                GachaType::Special => {
-                    database::warps::special::get_earliest_timestamp_by_uid(uid, &pool).await?
-                }
-                GachaType::Lc => {
-                    database::warps::lc::get_earliest_timestamp_by_uid(uid, &pool).await?
-                }
-                GachaType::Collab => {
-                    database::warps::collab::get_earliest_timestamp_by_uid(uid, &pool).await?
-                }
-                GachaType::CollabLc => {
-                    database::warps::collab_lc::get_earliest_timestamp_by_uid(uid, &pool).await?
-                }
-            };
-
-            for (id, character, light_cone, time) in pulls {
-                if !admin {
-                    if let Some(earliest_timestamp) = earliest_timestamp {
-                        if *time >= earliest_timestamp {
-                            break;
-                        }
-                    }
-                }
-                let set_all = match gacha_type {
-                    GachaType::Departure => &mut set_all_departure,
-                    GachaType::Standard => &mut set_all_standard,
-                    GachaType::Special => &mut set_all_special,
-                    GachaType::Lc => &mut set_all_lc,
-                    GachaType::Collab => &mut set_all_collab,
-                    GachaType::CollabLc => &mut set_all_collab_lc,
-                };
-
-                set_all.id.push(*id);
-                set_all.uid.push(uid);
-                set_all.character.push(*character);
-                set_all.light_cone.push(*light_cone);
-                set_all.timestamp.push(*time);
-                set_all.official.push(false);
+        if !dry_run {
+            match gacha_type {
+                GachaType::Departure => database::warps::departure::set_all(&set_all, pool).await?,
+                GachaType::Standard => database::warps::standard::set_all(&set_all, pool).await?,
+                GachaType::Special => database::warps::special::set_all(&set_all, pool).await?,
+                GachaType::Lc => database::warps::lc::set_all(&set_all, pool).await?,
+                GachaType::Collab => database::warps::collab::set_all(&set_all, pool).await?,
+                GachaType::CollabLc => database::warps::collab_lc::set_all(&set_all, pool).await?,
             }
         }
 
-        database::warps::departure::set_all(&set_all_departure, &pool).await?;
-        database::warps::standard::set_all(&set_all_standard, &pool).await?;
-        database::warps::special::set_all(&set_all_special, &pool).await?;
-        database::warps::lc::set_all(&set_all_lc, &pool).await?;
-        database::warps::collab::set_all(&set_all_collab, &pool).await?;
-        database::warps::collab_lc::set_all(&set_all_collab_lc, &pool).await?;
+        report.banners.push(banner);
+    }
+
+    Ok(report)
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn import_nap(
+    entry: &UigfNap,
+    characters: &HashSet<i32>,
+    w_engines: &HashSet<i32>,
+    bangboos: &HashSet<i32>,
+    username: &str,
+    admin: bool,
+    dry_run: bool,
+    pool: &PgPool,
+) -> ApiResult<UigfImportUid> {
+    let uid = entry.uid.clone().parse()?;
+
+    let mut report = UigfImportUid::new(uid);
+
+    if !check_zzz_auth(admin, username, uid, pool).await? {
+        report.authorized = false;
+
+        return Ok(report);
     }
 
-    // ZZZ (nap)
-    for entry in &uigf.nap {
-        let uid = entry.uid.clone().parse()?;
-        if !check_zzz_auth(admin, &username, uid, &pool).await? {
+    let Some(tz) = timezone(entry.timezone) else {
+        return Err(anyhow::anyhow!("Invalid timezone {}", entry.timezone).into());
+    };
+
+    let mut signals_map: HashMap<i32, Vec<SignalPull>> = HashMap::new();
+
+    for pull in &entry.list {
+        let gacha_type_id = match pull.gacha_type.as_str() {
+            "1" => ZzzGachaType::Standard,
+            "2" => ZzzGachaType::Special,
+            "3" => ZzzGachaType::WEngine,
+            "5" => ZzzGachaType::Bangboo,
+            _ => {
+                report.reject(&pull.id, &pull.item_id, "Unknown gacha type");
+                continue;
+            }
+        }
+        .id();
+
+        let Some(time) = parse_time(&pull.time, tz) else {
+            report.reject(&pull.id, &pull.item_id, "Invalid time");
+            continue;
+        };
+
+        let (Ok(id), Ok(item_id)) = (pull.id.parse::<i64>(), pull.item_id.parse::<i32>()) else {
+            report.reject(&pull.id, &pull.item_id, "Invalid id");
+            continue;
+        };
+
+        let (character, w_engine, bangboo) = if pull.item_type == "Character"
+            || pull.item_type == "Agents"
+            || pull.item_type == "代理人"
+        {
+            (Some(item_id), None, None)
+        } else if pull.item_type == "W-Engines" || pull.item_type == "音擎" {
+            (None, Some(item_id), None)
+        } else if pull.item_type == "Bangboo" || pull.item_type == "邦布" {
+            (None, None, Some(item_id))
+        } else {
+            report.reject(&pull.id, &pull.item_id, "Unknown item type");
+            continue;
+        };
+
+        if character.is_some_and(|id| !characters.contains(&id))
+            || w_engine.is_some_and(|id| !w_engines.contains(&id))
+            || bangboo.is_some_and(|id| !bangboos.contains(&id))
+        {
+            report.reject(&pull.id, &pull.item_id, "Unknown item id");
             continue;
         }
 
-        let tz = FixedOffset::east_opt(3600 * entry.timezone).unwrap();
+        signals_map
+            .entry(gacha_type_id)
+            .or_default()
+            .push((id, character, w_engine, bangboo, time));
+    }
 
-        let mut signals_map: HashMap<
-            i32,
-            Vec<(i64, Option<i32>, Option<i32>, Option<i32>, DateTime<Utc>)>,
-        > = HashMap::new();
+    for gacha_type in [
+        ZzzGachaType::Standard,
+        ZzzGachaType::Special,
+        ZzzGachaType::WEngine,
+        ZzzGachaType::Bangboo,
+    ] {
+        let Some(pulls) = signals_map.get(&gacha_type.id()) else {
+            continue;
+        };
 
-        for pull in &entry.list {
-            let gacha_type_id = match pull.gacha_type.as_str() {
-                "1" => ZzzGachaType::Standard,
-                "2" => ZzzGachaType::Special,
-                "3" => ZzzGachaType::WEngine,
-                "5" => ZzzGachaType::Bangboo,
-                _ => return Ok(HttpResponse::BadRequest().finish()),
+        let existing = match gacha_type {
+            ZzzGachaType::Standard => {
+                database::zzz::signals::standard::get_by_uid(uid, Language::En, pool).await?
+            }
+            ZzzGachaType::Special => {
+                database::zzz::signals::special::get_by_uid(uid, Language::En, pool).await?
             }
-            .id();
-
-            let time = NaiveDateTime::parse_from_str(&pull.time, "%Y-%m-%d %H:%M:%S")?
-                .and_local_timezone(tz)
-                .unwrap()
-                .to_utc();
-
-            let id: i64 = pull.id.parse()?;
-            let item_id: i32 = pull.item_id.parse()?;
-
-            let (character, w_engine, bangboo) = if pull.item_type == "Character"
-                || pull.item_type == "Agents"
-                || pull.item_type == "代理人"
-            {
-                (Some(item_id), None, None)
-            } else if pull.item_type == "W-Engines" || pull.item_type == "音擎" {
-                (None, Some(item_id), None)
-            } else if pull.item_type == "Bangboo" || pull.item_type == "邦布" {
-                (None, None, Some(item_id))
-            } else {
-                return Ok(HttpResponse::BadRequest().finish());
-            };
-
-            signals_map
-                .entry(gacha_type_id)
-                .or_default()
-                .push((id, character, w_engine, bangboo, time));
+            ZzzGachaType::WEngine => {
+                database::zzz::signals::w_engine::get_by_uid(uid, Language::En, pool).await?
+            }
+            ZzzGachaType::Bangboo => {
+                database::zzz::signals::bangboo::get_by_uid(uid, Language::En, pool).await?
+            }
+            _ => continue,
+        };
+
+        let mut existing: HashSet<i64> = existing.into_iter().map(|s| s.id).collect();
+
+        let mut banner = UigfImportBanner::new(gacha_type);
+        let mut set_all = database::zzz::signals::SetAll::default();
+
+        for &(id, character, w_engine, bangboo, time) in pulls {
+            if !existing.insert(id) {
+                banner.duplicate += 1;
+                continue;
+            }
+
+            banner.inserted += 1;
+
+            set_all.id.push(id);
+            set_all.uid.push(uid);
+            set_all.character.push(character);
+            set_all.w_engine.push(w_engine);
+            set_all.bangboo.push(bangboo);
+            set_all.timestamp.push(time);
+            set_all.official.push(false);
         }
 
-        let mut set_all_standard = database::zzz::signals::SetAll::default();
-        let mut set_all_special = database::zzz::signals::SetAll::default();
-        let mut set_all_w_engine = database::zzz::signals::SetAll::default();
-        let mut set_all_bangboo = database::zzz::signals::SetAll::default();
-
-        for (gacha_type_id, pulls) in &signals_map {
-            for (id, character, w_engine, bangboo, time) in pulls {
-                let set_all = match *gacha_type_id {
-                    x if x == ZzzGachaType::Standard.id() => &mut set_all_standard,
-                    x if x == ZzzGachaType::Special.id() => &mut set_all_special,
-                    x if x == ZzzGachaType::WEngine.id() => &mut set_all_w_engine,
-                    x if x == ZzzGachaType::Bangboo.id() => &mut set_all_bangboo,
-                    _ => return Ok(HttpResponse::BadRequest().finish()),
-                };
-
-                set_all.id.push(*id);
-                set_all.uid.push(uid);
-                set_all.character.push(*character);
-                set_all.w_engine.push(*w_engine);
-                set_all.bangboo.push(*bangboo);
-                set_all.timestamp.push(*time);
-                set_all.official.push(false);
+        if !dry_run {
+            match gacha_type {
+                ZzzGachaType::Standard => {
+                    database::zzz::signals::standard::set_all(&set_all, pool).await?
+                }
+                ZzzGachaType::Special => {
+                    database::zzz::signals::special::set_all(&set_all, pool).await?
+                }
+                ZzzGachaType::WEngine => {
+                    database::zzz::signals::w_engine::set_all(&set_all, pool).await?
+                }
+                ZzzGachaType::Bangboo => {
+                    database::zzz::signals::bangboo::set_all(&set_all, pool).await?
+                }
+                _ => {}
             }
         }
 
-        database::zzz::signals::standard::set_all(&set_all_standard, &pool).await?;
-        database::zzz::signals::special::set_all(&set_all_special, &pool).await?;
-        database::zzz::signals::w_engine::set_all(&set_all_w_engine, &pool).await?;
-        database::zzz::signals::bangboo::set_all(&set_all_bangboo, &pool).await?;
+        report.banners.push(banner);
+    }
+
+    Ok(report)
+}
+
+async fn import_hk4e(
+    entry: &UigfHk4e,
+    characters: &HashSet<i32>,
+    weapons: &HashSet<i32>,
+    username: &str,
+    admin: bool,
+    dry_run: bool,
+    pool: &PgPool,
+) -> ApiResult<UigfImportUid> {
+    let uid = entry.uid.clone().parse()?;
+
+    let mut report = UigfImportUid::new(uid);
+
+    if !check_gi_auth(admin, username, uid, pool).await? {
+        report.authorized = false;
+
+        return Ok(report);
     }
 
-    // GI (hk4e)
-    for entry in &uigf.hk4e {
-        let uid = entry.uid.clone().parse()?;
-        if !check_gi_auth(admin, &username, uid, &pool).await? {
+    let Some(tz) = timezone(entry.timezone) else {
+        return Err(anyhow::anyhow!("Invalid timezone {}", entry.timezone).into());
+    };
+
+    let mut wishes_map: HashMap<GiGachaType, Vec<WishPull>> = HashMap::new();
+
+    for pull in &entry.list {
+        let gacha_type = match pull.uigf_gacha_type.as_str() {
+            "100" => GiGachaType::Beginner,
+            "200" => GiGachaType::Standard,
+            "301" => GiGachaType::Character,
+            "302" => GiGachaType::Weapon,
+            "500" => GiGachaType::Chronicled,
+            _ => {
+                report.reject(&pull.id, &pull.item_id, "Unknown gacha type");
+                continue;
+            }
+        };
+
+        let Some(time) = parse_time(&pull.time, tz) else {
+            report.reject(&pull.id, &pull.item_id, "Invalid time");
             continue;
-        }
+        };
 
-        let tz = FixedOffset::east_opt(3600 * entry.timezone).unwrap();
-
-        let mut wishes_map: HashMap<GiGachaType, Vec<(i64, Option<i32>, Option<i32>, DateTime<Utc>)>> =
-            HashMap::new();
-
-        for pull in &entry.list {
-            let gacha_type = match pull.uigf_gacha_type.as_str() {
-                "100" => GiGachaType::Beginner,
-                "200" => GiGachaType::Standard,
-                "301" => GiGachaType::Character,
-                "302" => GiGachaType::Weapon,
-                "500" => GiGachaType::Chronicled,
-                _ => return Ok(HttpResponse::BadRequest().finish()),
-            };
-
-            let time = NaiveDateTime::parse_from_str(&pull.time, "%Y-%m-%d %H:%M:%S")?
-                .and_local_timezone(tz)
-                .unwrap()
-                .to_utc();
-
-            let id: i64 = pull.id.parse()?;
-            let item_id: i32 = pull.item_id.parse()?;
-
-            let (character, weapon) = if pull.item_type == "Character"
-                || pull.item_type == "角色"
-            {
-                (Some(item_id), None)
-            } else if pull.item_type == "Weapon"
-                || pull.item_type == "Weapons"
-                || pull.item_type == "武器"
-            {
-                (None, Some(item_id))
-            } else {
-                return Ok(HttpResponse::BadRequest().finish());
-            };
-
-            wishes_map
-                .entry(gacha_type)
-                .or_default()
-                .push((id, character, weapon, time));
+        let (Ok(id), Ok(item_id)) = (pull.id.parse::<i64>(), pull.item_id.parse::<i32>()) else {
+            report.reject(&pull.id, &pull.item_id, "Invalid id");
+            continue;
+        };
+
+        let (character, weapon) = if pull.item_type == "Character" || pull.item_type == "角色" {
+            (Some(item_id), None)
+        } else if pull.item_type == "Weapon"
+            || pull.item_type == "Weapons"
+            || pull.item_type == "武器"
+        {
+            (None, Some(item_id))
+        } else {
+            report.reject(&pull.id, &pull.item_id, "Unknown item type");
+            continue;
+        };
+
+        if character.is_some_and(|id| !characters.contains(&id))
+            || weapon.is_some_and(|id| !weapons.contains(&id))
+        {
+            report.reject(&pull.id, &pull.item_id, "Unknown item id");
+            continue;
         }
 
-        let mut set_all_beginner = database::gi::wishes::SetAll::default();
-        let mut set_all_standard = database::gi::wishes::SetAll::default();
-        let mut set_all_character = database::gi::wishes::SetAll::default();
-        let mut set_all_weapon = database::gi::wishes::SetAll::default();
-        let mut set_all_chronicled = database::gi::wishes::SetAll::default();
+        wishes_map
+            .entry(gacha_type)
+            .or_default()
+            .push((id, character, weapon, time));
+    }
+
+    for gacha_type in GiGachaType::iter() {
+        let Some(pulls) = wishes_map.get(&gacha_type) else {
+            continue;
+        };
+
+        let (earliest_timestamp, existing) = match gacha_type {
+            GiGachaType::Beginner => (
+                database::gi::wishes::beginner::get_earliest_timestamp_by_uid(uid, pool).await?,
+                database::gi::wishes::beginner::get_by_uid(uid, Language::En, pool).await?,
+            ),
+            GiGachaType::Standard => (
+                database::gi::wishes::standard::get_earliest_timestamp_by_uid(uid, pool).await?,
+                database::gi::wishes::standard::get_by_uid(uid, Language::En, pool).await?,
+            ),
+            GiGachaType::Character => (
+                database::gi::wishes::character::get_earliest_timestamp_by_uid(uid, pool).await?,
+                database::gi::wishes::character::get_by_uid(uid, Language::En, pool).await?,
+            ),
+            GiGachaType::Weapon => (
+                database::gi::wishes::weapon::get_earliest_timestamp_by_uid(uid, pool).await?,
+                database::gi::wishes::weapon::get_by_uid(uid, Language::En, pool).await?,
+            ),
+            GiGachaType::Chronicled => (
+                database::gi::wishes::chronicled::get_earliest_timestamp_by_uid(uid, pool).await?,
+                database::gi::wishes::chronicled::get_by_uid(uid, Language::En, pool).await?,
+            ),
+        };
+
+        let mut existing: HashSet<i64> = existing.into_iter().map(|w| w.id).collect();
+
+        let mut banner = UigfImportBanner::new(gacha_type);
+        let mut set_all = database::gi::wishes::SetAll::default();
+        let mut skipping = false;
+
+        for &(id, character, weapon, time) in pulls {
+            if !admin && earliest_timestamp.is_some_and(|t| time >= t) {
+                skipping = true;
+            }
 
-        for (gacha_type, pulls) in &wishes_map {
-            let earliest_timestamp = match gacha_type {
+            if !existing.insert(id) {
+                banner.duplicate += 1;
+                continue;
+            }
+
+            if skipping {
+                banner.skipped += 1;
+                continue;
+            }
+
+            banner.inserted += 1;
+
+            set_all.id.push(id);
+            set_all.uid.push(uid);
+            set_all.character.push(character);
+            set_all.weapon.push(weapon);
+            set_all.timestamp.push(time);
+            set_all.official.push(false);
+        }
+
+        if !dry_run {
+            match gacha_type {
                 GiGachaType::Beginner => {
-                    database::gi::wishes::beginner::get_earliest_timestamp_by_uid(uid, &pool)
-                        .await?
+                    database::gi::wishes::beginner::set_all(&set_all, pool).await?
                 }
                 GiGachaType::Standard => {
-                    database::gi::wishes::standard::get_earliest_timestamp_by_uid(uid, &pool)
-                        .await?
+                    database::gi::wishes::standard::set_all(&set_all, pool).await?
                 }
                 GiGachaType::Character => {
-                    database::gi::wishes::character::get_earliest_timestamp_by_uid(uid, &pool)
-                        .await?
+                    database::gi::wishes::character::set_all(&set_all, pool).await?
                 }
                 GiGachaType::Weapon => {
-                    database::gi::wishes::weapon::get_earliest_timestamp_by_uid(uid, &pool)
-                        .await?
+                    database::gi::wishes::weapon::set_all(&set_all, pool).await?
                 }
                 GiGachaType::Chronicled => {
-                    database::gi::wishes::chronicled::get_earliest_timestamp_by_uid(uid, &pool)
-                        .await?
-                }
-            };
-
-            for (id, character, weapon, time) in pulls {
-                if !admin {
-                    if let Some(earliest_timestamp) = earliest_timestamp {
-                        if *time >= earliest_timestamp {
-                            break;
-                        }
-                    }
+                    database::gi::wishes::chronicled::set_all(&set_all, pool).await?
                 }
-                let set_all = match gacha_type {
-                    GiGachaType::Beginner => &mut set_all_beginner,
-                    GiGachaType::Standard => &mut set_all_standard,
-                    GiGachaType::Character => &mut set_all_character,
-                    GiGachaType::Weapon => &mut set_all_weapon,
-                    GiGachaType::Chronicled => &mut set_all_chronicled,
-                };
-
-                set_all.id.push(*id);
-                set_all.uid.push(uid);
-                set_all.character.push(*character);
-                set_all.weapon.push(*weapon);
-                set_all.timestamp.push(*time);
-                set_all.official.push(false);
             }
         }
 
-        database::gi::wishes::beginner::set_all(&set_all_beginner, &pool).await?;
-        database::gi::wishes::standard::set_all(&set_all_standard, &pool).await?;
-        database::gi::wishes::character::set_all(&set_all_character, &pool).await?;
-        database::gi::wishes::weapon::set_all(&set_all_weapon, &pool).await?;
-        database::gi::wishes::chronicled::set_all(&set_all_chronicled, &pool).await?;
+        report.banners.push(banner);
     }
 
-    Ok(HttpResponse::Ok().finish())
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn out_of_range_timezones_are_rejected() {
+        assert!(timezone(8).is_some());
+        assert!(timezone(-5).is_some());
+        assert!(timezone(24).is_none());
+        assert!(timezone(i32::MAX).is_none());
+    }
 }
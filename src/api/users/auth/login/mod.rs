@@ -1,20 +1,29 @@
-use std::collections::HashMap;
+use std::sync::Arc;
 
 use actix_session::Session;
-use actix_web::{post, web, HttpResponse, Responder};
-use futures::lock::Mutex;
+use actix_web::{post, web, HttpRequest, HttpResponse, Responder};
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use utoipa::{OpenApi, ToSchema};
-use uuid::Uuid;
 
-use crate::{api::ApiResult, database};
+use crate::{
+    api::{
+        users::{
+            deletions, sessions,
+            tokens::{self, Purpose},
+            totp,
+        },
+        ApiResult,
+    },
+    app_config::AppConfig,
+    database,
+};
 
 #[derive(OpenApi)]
 #[openapi(
     tags((name = "users/auth/login")),
     paths(login),
-    components(schemas(UserLogin))
+    components(schemas(UserLogin, TotpRequired))
 )]
 struct ApiDoc;
 
@@ -33,6 +42,12 @@ pub enum UserLogin {
     Token { token: String },
 }
 
+#[derive(Serialize, ToSchema)]
+pub struct TotpRequired {
+    /// Has to be sent to `/api/users/auth/totp` together with the code
+    token: String,
+}
+
 #[utoipa::path(
     tag = "users/auth/login",
     post,
@@ -40,19 +55,21 @@ pub enum UserLogin {
     request_body(content = UserLogin,
         examples(
             ("UsernamePassword" = (value = json!({"username": "meow", "password": "meow12"}))),
-            ("Token" = (value = json!({"token": "a3449001-8762-48e2-8813-3abe92a29884"})))
+            ("Token" = (value = json!({"token": "q0mZ2u3BGCtWtWbR1YdPl1nqQ1sJbT2sVtQ1Zr2Xg9M"})))
         )
     ),
     responses(
-        (status = 200, description = "Successfull login. The session id is returned in a cookie named `id`. You need to include this cookie in subsequent requests"),
+        (status = 200, description = "Successfull login. The session id is returned in a cookie named `id`. You need to include this cookie in subsequent requests. Cancels a scheduled account deletion"),
+        (status = 202, description = "2FA is enabled. The login has to be completed at `/api/users/auth/totp` within 5 minutes", body = TotpRequired),
         (status = 400, description = "Don't have an account")
     )
 )]
 #[post("/api/users/auth/login")]
 async fn login(
     session: Session,
+    request: HttpRequest,
     user_login: web::Json<UserLogin>,
-    tokens: web::Data<Mutex<HashMap<Uuid, String>>>,
+    app_config: web::Data<Arc<AppConfig>>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
     let username = match &*user_login {
@@ -70,15 +87,28 @@ async fn login(
             username.clone()
         }
         UserLogin::Token { token } => {
-            let Some(username) = tokens.lock().await.remove(&token.parse()?) else {
+            let Some(token) = tokens::consume(token, Purpose::Login, &pool).await? else {
                 return Ok(HttpResponse::BadRequest().finish());
             };
 
-            username.clone()
+            token.username
         }
     };
 
-    session.insert("username", username.to_lowercase())?;
+    let username = username.to_lowercase();
+
+    // Emergency login only replaces the password, not the second factor
+    if totp::is_enabled(&username, &pool).await? {
+        let Some(token) = tokens::create(&username, Purpose::Totp, None, &pool).await? else {
+            return Ok(HttpResponse::BadRequest().finish());
+        };
+
+        return Ok(HttpResponse::Accepted().json(TotpRequired { token }));
+    }
+
+    deletions::cancel(&username, &pool).await?;
+
+    sessions::start(&session, &request, username, &app_config)?;
 
     Ok(HttpResponse::Ok().finish())
 }
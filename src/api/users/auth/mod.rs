@@ -1,8 +1,13 @@
 mod login;
 mod logout;
+mod oidc;
 mod register;
 mod renew;
+mod request_password_reset;
 mod request_token;
+mod reset_password;
+mod totp;
+mod verify_email;
 
 use actix_web::web;
 use utoipa::OpenApi;
@@ -15,16 +20,26 @@ pub fn openapi() -> utoipa::openapi::OpenApi {
     let mut openapi = ApiDoc::openapi();
     openapi.merge(login::openapi());
     openapi.merge(logout::openapi());
+    openapi.merge(oidc::openapi());
     openapi.merge(renew::openapi());
     openapi.merge(register::openapi());
+    openapi.merge(request_password_reset::openapi());
     openapi.merge(request_token::openapi());
+    openapi.merge(reset_password::openapi());
+    openapi.merge(totp::openapi());
+    openapi.merge(verify_email::openapi());
     openapi
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.configure(login::configure)
         .configure(logout::configure)
+        .configure(oidc::configure)
         .configure(register::configure)
         .configure(renew::configure)
-        .configure(request_token::configure);
+        .configure(request_password_reset::configure)
+        .configure(request_token::configure)
+        .configure(reset_password::configure)
+        .configure(totp::configure)
+        .configure(verify_email::configure);
 }
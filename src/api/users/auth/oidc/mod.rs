@@ -0,0 +1,45 @@
+mod provider;
+
+use std::sync::Arc;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use utoipa::OpenApi;
+
+use crate::{api::ApiResult, app_config::AppConfig};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/auth/oidc")),
+    paths(get_oidc_providers)
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(provider::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_oidc_providers)
+        .configure(provider::configure);
+}
+
+#[utoipa::path(
+    tag = "users/auth/oidc",
+    get,
+    path = "/api/users/auth/oidc",
+    responses(
+        (status = 200, description = "Names of the providers that can be logged in with", body = Vec<String>),
+    )
+)]
+#[get("/api/users/auth/oidc")]
+async fn get_oidc_providers(app_config: web::Data<Arc<AppConfig>>) -> ApiResult<impl Responder> {
+    let providers: Vec<_> = app_config
+        .oidc_providers
+        .iter()
+        .map(|provider| provider.name.clone())
+        .collect();
+
+    Ok(HttpResponse::Ok().json(providers))
+}
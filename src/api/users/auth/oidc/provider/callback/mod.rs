@@ -0,0 +1,135 @@
+use std::sync::Arc;
+
+use actix_session::Session;
+use actix_web::{get, web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::{IntoParams, OpenApi};
+
+use crate::{
+    api::{
+        users::{
+            deletions, oidc, sessions,
+            tokens::{self, Purpose},
+            totp,
+        },
+        ApiResult,
+    },
+    app_config::AppConfig,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/auth/oidc/{provider}/callback")),
+    paths(get_oidc_callback)
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_oidc_callback);
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct OidcCallbackParams {
+    code: Option<String>,
+    state: Option<String>,
+    /// Set by the provider if the user declined
+    error: Option<String>,
+}
+
+#[utoipa::path(
+    tag = "users/auth/oidc/{provider}/callback",
+    get,
+    path = "/api/users/auth/oidc/{provider}/callback",
+    params(
+        ("provider" = String, Path, description = "Provider name"),
+        OidcCallbackParams
+    ),
+    responses(
+        (status = 302, description = "Redirect to the frontend. Logged in and at the requested path, or at `/login?totp_token=` if 2FA is enabled, which completes at `/api/users/auth/totp`. Links end with `oidc=linked` or `oidc_error=taken`, failures at `/login?oidc_error=failed`. Cancels a scheduled account deletion"),
+        (status = 404, description = "Unknown provider"),
+    )
+)]
+#[get("/api/users/auth/oidc/{provider}/callback")]
+async fn get_oidc_callback(
+    session: Session,
+    request: HttpRequest,
+    provider: web::Path<String>,
+    oidc_callback_params: web::Query<OidcCallbackParams>,
+    app_config: web::Data<Arc<AppConfig>>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(provider) = oidc::provider(&provider, &app_config) else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    let failed = oidc::to_frontend("/login?oidc_error=failed", &app_config);
+
+    let (Some(code), Some(state), None) = (
+        &oidc_callback_params.code,
+        &oidc_callback_params.state,
+        &oidc_callback_params.error,
+    ) else {
+        return Ok(failed);
+    };
+
+    // The state has to come back to the browser that started the login
+    if request
+        .cookie(oidc::STATE_COOKIE)
+        .is_none_or(|cookie| cookie.value() != state)
+    {
+        return Ok(failed);
+    }
+
+    let (pending, identity) = match oidc::finish(provider, code, state, &app_config, &pool).await {
+        Ok(Some(finished)) => finished,
+        Ok(None) => return Ok(failed),
+        Err(e) => {
+            warn!("OIDC login with {} failed with {e}", provider.name);
+
+            return Ok(failed);
+        }
+    };
+
+    let separator = if pending.redirect.contains('?') {
+        '&'
+    } else {
+        '?'
+    };
+
+    if let Some(username) = &pending.username {
+        let result = if oidc::link(provider, &identity, username, &pool).await? {
+            "oidc=linked"
+        } else {
+            "oidc_error=taken"
+        };
+
+        return Ok(oidc::to_frontend(
+            &format!("{}{separator}{result}", pending.redirect),
+            &app_config,
+        ));
+    }
+
+    let username = oidc::login(provider, &identity, &pool).await?;
+
+    if totp::is_enabled(&username, &pool).await? {
+        let Some(token) = tokens::create(&username, Purpose::Totp, None, &pool).await? else {
+            return Ok(failed);
+        };
+
+        return Ok(oidc::to_frontend(
+            &format!("/login?totp_token={token}"),
+            &app_config,
+        ));
+    }
+
+    deletions::cancel(&username, &pool).await?;
+
+    sessions::start(&session, &request, username, &app_config)?;
+
+    Ok(oidc::to_frontend(&pending.redirect, &app_config))
+}
@@ -0,0 +1,67 @@
+mod callback;
+
+use std::sync::Arc;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::{IntoParams, OpenApi};
+
+use crate::{
+    api::{users::oidc, ApiResult},
+    app_config::AppConfig,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/auth/oidc/{provider}")),
+    paths(get_oidc_login)
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(callback::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_oidc_login).configure(callback::configure);
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct OidcRedirectParams {
+    /// Path on the frontend to return to after logging in
+    pub redirect: Option<String>,
+}
+
+#[utoipa::path(
+    tag = "users/auth/oidc/{provider}",
+    get,
+    path = "/api/users/auth/oidc/{provider}",
+    params(
+        ("provider" = String, Path, description = "Provider name"),
+        OidcRedirectParams
+    ),
+    responses(
+        (status = 302, description = "Redirect to the provider. Navigate the browser here instead of fetching it"),
+        (status = 404, description = "Unknown provider"),
+    )
+)]
+#[get("/api/users/auth/oidc/{provider}")]
+async fn get_oidc_login(
+    provider: web::Path<String>,
+    oidc_redirect_params: web::Query<OidcRedirectParams>,
+    app_config: web::Data<Arc<AppConfig>>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(provider) = oidc::provider(&provider, &app_config) else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    let redirect = oidc::sanitize_redirect(oidc_redirect_params.redirect.as_deref());
+
+    let (url, state) = oidc::authorize(provider, None, &redirect, &app_config, &pool).await?;
+
+    Ok(oidc::to_provider(url, state))
+}
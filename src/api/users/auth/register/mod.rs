@@ -1,12 +1,19 @@
+use std::sync::Arc;
+
 use actix_session::Session;
-use actix_web::{post, web, HttpResponse, Responder};
+use actix_web::{post, web, HttpRequest, HttpResponse, Responder};
 use rand::Rng;
 use serde::Deserialize;
 use sqlx::PgPool;
 use utoipa::{OpenApi, ToSchema};
 
 use crate::{
-    api::{private, ApiResult},
+    api::{
+        private,
+        users::{sessions, tokens},
+        ApiResult,
+    },
+    app_config::AppConfig,
     database,
 };
 
@@ -47,7 +54,7 @@ pub struct UserRegister {
         ("api_key" = [])
     ),
     responses(
-        (status = 200, description = "Successfull register. The session id is returned in a cookie named `id`. You need to include this cookie in subsequent requests"),
+        (status = 200, description = "Successfull register. The session id is returned in a cookie named `id`. You need to include this cookie in subsequent requests. If an email was given, a verification mail is sent to it"),
         (status = 400, description = "Credentials too long"),
         (status = 409, description = "Account already exists")
     )
@@ -55,7 +62,9 @@ pub struct UserRegister {
 #[post("/api/users/auth/register", guard = "private")]
 async fn register(
     session: Session,
+    request: HttpRequest,
     user_register: web::Json<UserRegister>,
+    app_config: web::Data<Arc<AppConfig>>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
     let username = user_register.username.trim().to_lowercase();
@@ -86,15 +95,23 @@ async fn register(
 
     {
         let username = username.clone();
+        let email = email.clone();
         let user = database::users::DbUser {
             username,
             password,
             email,
+            email_verified: false,
         };
         database::users::set(&user, &pool).await?;
     }
 
-    session.insert("username", username)?;
+    if let Some(email) = &email {
+        if let Err(e) = tokens::send_verification(&username, email, &app_config, &pool).await {
+            error!("Creating the verification token for {username} failed with {e}");
+        }
+    }
+
+    sessions::start(&session, &request, username, &app_config)?;
 
     Ok(HttpResponse::Ok().finish())
 }
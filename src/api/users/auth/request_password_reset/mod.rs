@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use actix_web::{post, rt, web, HttpResponse, Responder};
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    api::{
+        users::tokens::{self, Purpose},
+        ApiResult,
+    },
+    app_config::AppConfig,
+    database, mail,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/auth/request-password-reset")),
+    paths(request_password_reset),
+    components(schemas(RequestPasswordReset))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(request_password_reset);
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct RequestPasswordReset {
+    email: String,
+}
+
+#[utoipa::path(
+    tag = "users/auth/request-password-reset",
+    post,
+    path = "/api/users/auth/request-password-reset",
+    request_body = RequestPasswordReset,
+    responses(
+        (status = 200, description = "Send mail with password reset link"),
+    )
+)]
+#[post("/api/users/auth/request-password-reset")]
+async fn request_password_reset(
+    request_password_reset: web::Json<RequestPasswordReset>,
+    app_config: web::Data<Arc<AppConfig>>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let email = request_password_reset.email.trim().to_string();
+
+    // Always succeeds, so accounts can't be found by their email
+    for user in database::users::get_by_email(&email, &pool).await? {
+        let username = user.username;
+
+        let Some(token) = tokens::create(&username, Purpose::ResetPassword, None, &pool).await?
+        else {
+            continue;
+        };
+
+        let to = format!("{username} <{email}>");
+        let body = format!(
+            "Use the following link to reset your password:\n{}/reset-password?token={token}",
+            app_config.public_url
+        );
+        let app_config = app_config.get_ref().clone();
+
+        rt::spawn(async move {
+            if let Err(e) = mail::send(&to, "StarDB.GG Password Reset", body, &app_config).await {
+                error!("Sending the password reset mail to {username} failed with {e}");
+            }
+        });
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
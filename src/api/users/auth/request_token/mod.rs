@@ -1,20 +1,18 @@
-use std::{collections::HashMap, env};
+use std::sync::Arc;
 
 use actix_web::{post, rt, web, HttpResponse, Responder};
-use futures::lock::Mutex;
-use lettre::{transport::smtp::authentication::Credentials, Message, SmtpTransport, Transport};
 use serde::Deserialize;
 use sqlx::PgPool;
 use utoipa::{OpenApi, ToSchema};
-use uuid::Uuid;
 
-use crate::{api::ApiResult, database};
-
-type TokenMap = Mutex<HashMap<Uuid, String>>;
-
-lazy_static::lazy_static! {
-    static ref CACHE: std::sync::Mutex<Option<web::Data<TokenMap>>> = std::sync::Mutex::new(None);
-}
+use crate::{
+    api::{
+        users::tokens::{self, Purpose},
+        ApiResult,
+    },
+    app_config::AppConfig,
+    database, mail,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -29,13 +27,7 @@ pub fn openapi() -> utoipa::openapi::OpenApi {
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
-    let data = CACHE
-        .lock()
-        .unwrap()
-        .get_or_insert_with(web::Data::default)
-        .clone();
-
-    cfg.service(request_token).app_data(data);
+    cfg.service(request_token);
 }
 
 #[derive(Deserialize, ToSchema)]
@@ -50,56 +42,35 @@ pub struct RequestToken {
     request_body = RequestToken,
     responses(
         (status = 200, description = "Send mail with emergency login"),
-        (status = 400, description = "No email connected"),
     )
 )]
 #[post("/api/users/auth/request-token")]
 async fn request_token(
     request_token: web::Json<RequestToken>,
-    tokens: web::Data<Mutex<HashMap<Uuid, String>>>,
+    app_config: web::Data<Arc<AppConfig>>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
     let email = request_token.email.trim().to_string();
 
-    let users = database::users::get_by_email(&email, &pool).await?;
-
-    if users.is_empty() {
-        return Ok(HttpResponse::BadRequest().finish());
-    };
-
-    for user in users {
-        let username = user.username.clone();
+    // Always succeeds, so accounts can't be found by their email
+    for user in database::users::get_by_email(&email, &pool).await? {
+        let username = user.username;
 
-        if tokens.lock().await.values().any(|s| s == &username) {
+        let Some(token) = tokens::create(&username, Purpose::Login, None, &pool).await? else {
             continue;
-        }
+        };
 
-        let to = format!("{username} <{email}>").parse()?;
+        let to = format!("{username} <{email}>");
+        let body = format!(
+            "Use the following link to login:\n{}/login?token={token}",
+            app_config.public_url
+        );
+        let app_config = app_config.get_ref().clone();
 
-        let token = Uuid::new_v4();
-        let email = Message::builder()
-            .from("stardb <noreply@stardb.gg>".parse()?)
-            .to(to)
-            .subject("StarDB.GG Emergency Login")
-            .body(format!(
-                "Use the following link to login:\nhttps://stardb.gg/login?token={token}"
-            ))?;
-
-        let credentials = Credentials::new(env::var("SMTP_USERNAME")?, env::var("SMTP_PASSWORD")?);
-
-        let mailer = SmtpTransport::relay("smtppro.zoho.eu")?
-            .credentials(credentials)
-            .build();
-
-        mailer.send(&email)?;
-
-        tokens.lock().await.insert(token, username.clone());
-
-        let tokens = tokens.clone();
         rt::spawn(async move {
-            rt::time::sleep(std::time::Duration::from_secs(5 * 60)).await;
-
-            tokens.lock().await.remove(&token);
+            if let Err(e) = mail::send(&to, "StarDB.GG Emergency Login", body, &app_config).await {
+                error!("Sending the login mail to {username} failed with {e}");
+            }
         });
     }
 
@@ -0,0 +1,75 @@
+use actix_web::{post, web, HttpResponse, Responder};
+use argon2::Config;
+use rand::Rng;
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    api::{
+        users::tokens::{self, Purpose},
+        ApiResult,
+    },
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/auth/reset-password")),
+    paths(reset_password),
+    components(schemas(PasswordReset))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(reset_password);
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct PasswordReset {
+    token: String,
+    password: String,
+}
+
+#[utoipa::path(
+    tag = "users/auth/reset-password",
+    post,
+    path = "/api/users/auth/reset-password",
+    request_body = PasswordReset,
+    responses(
+        (status = 200, description = "Password was reset. All sessions are logged out"),
+        (status = 400, description = "Invalid token or password too long"),
+    )
+)]
+#[post("/api/users/auth/reset-password")]
+async fn reset_password(
+    password_reset: web::Json<PasswordReset>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    if password_reset.password.len() > 64 {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    let Some(token) = tokens::consume(&password_reset.token, Purpose::ResetPassword, &pool).await?
+    else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let salt = rand::rng().random::<[u8; 32]>();
+
+    let password = argon2::hash_encoded(
+        password_reset.password.as_bytes(),
+        &salt,
+        &Config::rfc9106_low_mem(),
+    )?;
+
+    database::users::update_password_by_username(&token.username, &password, &pool).await?;
+
+    database::sessions::delete_by_username_except_id(&token.username, None, &pool).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,90 @@
+use std::sync::Arc;
+
+use actix_session::Session;
+use actix_web::{post, web, HttpRequest, HttpResponse, Responder};
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    api::{
+        users::{
+            deletions, sessions,
+            tokens::{self, Purpose},
+            totp,
+        },
+        ApiResult,
+    },
+    app_config::AppConfig,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/auth/totp")),
+    paths(totp_login),
+    components(schemas(TotpLogin))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(totp_login);
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TotpLogin {
+    /// The token from `/api/users/auth/login`
+    token: String,
+    /// A code from the authenticator app or a recovery code
+    code: String,
+}
+
+#[utoipa::path(
+    tag = "users/auth/totp",
+    post,
+    path = "/api/users/auth/totp",
+    request_body(content = TotpLogin,
+        examples(
+            ("Totp" = (value = json!({"token": "q0mZ2u3BGCtWtWbR1YdPl1nqQ1sJbT2sVtQ1Zr2Xg9M", "code": "123456"}))),
+            ("RecoveryCode" = (value = json!({"token": "q0mZ2u3BGCtWtWbR1YdPl1nqQ1sJbT2sVtQ1Zr2Xg9M", "code": "0a1b2-c3d4e-5f6a7-b8c9d"})))
+        )
+    ),
+    responses(
+        (status = 200, description = "Successfull login. The session id is returned in a cookie named `id`. You need to include this cookie in subsequent requests. Cancels a scheduled account deletion"),
+        (status = 400, description = "Invalid token or code. After 5 invalid codes, the login has to be started again. After 10 invalid codes within 15 minutes, no code is accepted until they passed")
+    )
+)]
+#[post("/api/users/auth/totp")]
+async fn totp_login(
+    session: Session,
+    request: HttpRequest,
+    totp_login: web::Json<TotpLogin>,
+    app_config: web::Data<Arc<AppConfig>>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(token) = tokens::get(&totp_login.token, Purpose::Totp, &pool).await? else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    if !totp::verify(&token.username, &totp_login.code, &pool).await? {
+        tokens::fail(&totp_login.token, &pool).await?;
+
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    if tokens::consume(&totp_login.token, Purpose::Totp, &pool)
+        .await?
+        .is_none()
+    {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    deletions::cancel(&token.username, &pool).await?;
+
+    sessions::start(&session, &request, token.username, &app_config)?;
+
+    Ok(HttpResponse::Ok().finish())
+}
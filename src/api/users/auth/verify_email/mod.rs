@@ -0,0 +1,65 @@
+use actix_web::{post, web, HttpResponse, Responder};
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    api::{
+        users::tokens::{self, Purpose},
+        ApiResult,
+    },
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/auth/verify-email")),
+    paths(verify_email),
+    components(schemas(EmailVerification))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(verify_email);
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct EmailVerification {
+    token: String,
+}
+
+#[utoipa::path(
+    tag = "users/auth/verify-email",
+    post,
+    path = "/api/users/auth/verify-email",
+    request_body = EmailVerification,
+    responses(
+        (status = 200, description = "Email verified"),
+        (status = 400, description = "Invalid token or the email changed since"),
+    )
+)]
+#[post("/api/users/auth/verify-email")]
+async fn verify_email(
+    email_verification: web::Json<EmailVerification>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(token) =
+        tokens::consume(&email_verification.token, Purpose::VerifyEmail, &pool).await?
+    else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let Some(email) = token.email else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    if !database::users::verify_email_by_username(&token.username, &email, &pool).await? {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,51 @@
+use actix_web::{post, web, HttpResponse, Responder};
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::api::{export::Export, users::deletions, ApiResult};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/deleted-export")),
+    paths(post_deleted_export),
+    components(schemas(DeletedExportRequest))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_deleted_export);
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct DeletedExportRequest {
+    token: String,
+}
+
+#[utoipa::path(
+    tag = "users/deleted-export",
+    post,
+    path = "/api/users/deleted-export",
+    request_body = DeletedExportRequest,
+    responses(
+        (status = 200, description = "Final export of a deleted account", body = Export),
+        (status = 404, description = "Invalid or expired token, or the account isn't deleted yet"),
+    )
+)]
+#[post("/api/users/deleted-export")]
+async fn post_deleted_export(
+    deleted_export_request: web::Json<DeletedExportRequest>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(export) = deletions::final_export(&deleted_export_request.token, &pool).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    Ok(HttpResponse::Ok()
+        .content_type("application/json")
+        .body(export))
+}
@@ -0,0 +1,273 @@
+//! Accounts are deleted after a grace period, so a deletion can be undone by logging in. Right
+//! before the account is gone, a final signed export is stored, which can be downloaded with the
+//! token handed out when the deletion was scheduled.
+
+use std::{sync::Mutex, time::Duration as StdDuration};
+
+use actix_web::{
+    rt::{self, Runtime},
+    web,
+};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use ed25519_dalek::SigningKey;
+use futures::lock::Mutex as AsyncMutex;
+use rand::Rng;
+use sqlx::PgPool;
+
+use crate::{
+    api::users::{me::export, tokens},
+    database, signing,
+};
+
+const GRACE_PERIOD_DAYS: i64 = 14;
+const EXPORT_LIFETIME_DAYS: i64 = 30;
+const POLL_INTERVAL: StdDuration = StdDuration::from_secs(10 * 60);
+
+lazy_static::lazy_static! {
+    static ref WORKER: Mutex<Option<()>> = Mutex::new(None);
+}
+
+/// Final exports are signed with the app's key, so they verify like every other export
+pub fn configure(pool: PgPool, signing_key: web::Data<AsyncMutex<SigningKey>>) {
+    WORKER
+        .lock()
+        .unwrap()
+        .get_or_insert_with(|| spawn(pool, signing_key));
+}
+
+fn spawn(pool: PgPool, signing_key: web::Data<AsyncMutex<SigningKey>>) {
+    std::thread::spawn(move || {
+        Runtime::new().unwrap().block_on(async {
+            loop {
+                if let Err(e) = run_due(&signing_key, &pool).await {
+                    error!("Account deletions failed with {e}");
+                }
+
+                rt::time::sleep(POLL_INTERVAL).await;
+            }
+        })
+    });
+}
+
+/// Replaces an already scheduled deletion. Returns when the account will be deleted and the
+/// token to download the final export with.
+pub async fn schedule(
+    username: &str,
+    delete_pulls: bool,
+    pool: &PgPool,
+) -> anyhow::Result<(DateTime<Utc>, String)> {
+    let token = BASE64_URL_SAFE_NO_PAD.encode(rand::rng().random::<[u8; 32]>());
+    let scheduled = Utc::now() + Duration::days(GRACE_PERIOD_DAYS);
+
+    let deletion = database::users_deletions::DbUserDeletion {
+        username: username.to_string(),
+        delete_pulls,
+        export_hash: tokens::hash(&token),
+    };
+    database::users_deletions::set(&deletion, scheduled, pool).await?;
+
+    Ok((scheduled, token))
+}
+
+/// Returns whether a deletion was scheduled
+pub async fn cancel(username: &str, pool: &PgPool) -> anyhow::Result<bool> {
+    database::users_deletions::delete_by_username(username, pool).await
+}
+
+/// The final export of a deleted account as json
+pub async fn final_export(token: &str, pool: &PgPool) -> anyhow::Result<Option<String>> {
+    database::deleted_users_exports::get_one_by_hash(&tokens::hash(token), pool).await
+}
+
+async fn run_due(signing_key: &AsyncMutex<SigningKey>, pool: &PgPool) -> anyhow::Result<()> {
+    database::deleted_users_exports::delete_expired(pool).await?;
+
+    let deletions = database::users_deletions::get_due(pool).await?;
+
+    for deletion in deletions {
+        let username = deletion.username.clone();
+
+        if let Err(e) = run(deletion, signing_key, pool).await {
+            error!("Deleting {username} failed with {e}");
+        } else {
+            info!("Deleted {username}");
+        }
+    }
+
+    Ok(())
+}
+
+async fn run(
+    deletion: database::users_deletions::DbUserDeletion,
+    signing_key: &AsyncMutex<SigningKey>,
+    pool: &PgPool,
+) -> anyhow::Result<()> {
+    let username = deletion.username;
+
+    let user = export::user_export(username.clone(), pool).await?;
+    let export = export::sign(user, &mut *signing::active(signing_key, pool).await?)?;
+
+    // All or nothing, so a failed deletion is retried from the start
+    let mut tx = pool.begin().await?;
+
+    database::deleted_users_exports::set(
+        &deletion.export_hash,
+        &serde_json::to_string(&export)?,
+        Utc::now() + Duration::days(EXPORT_LIFETIME_DAYS),
+        &mut *tx,
+    )
+    .await?;
+
+    if deletion.delete_pulls {
+        database::users_deletions::delete_warps_by_username(&username, &mut *tx).await?;
+        database::users_deletions::delete_wishes_by_username(&username, &mut *tx).await?;
+        database::users_deletions::delete_signals_by_username(&username, &mut *tx).await?;
+    }
+
+    database::users_deletions::delete_tracker_claims_by_username(&username, &mut *tx).await?;
+
+    // Sessions, favorites, completions, connections, ntehelper state and marker comments cascade.
+    // Tracker claims assigned by admins are detached.
+    database::users::delete_by_username(&username, &mut *tx).await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{create_test_user, test_pool};
+
+    #[actix_web::test]
+    async fn deletions_can_be_cancelled_and_leave_an_export() {
+        let pool = test_pool().await;
+        let signing_key = AsyncMutex::new(signing::load(&pool).await.unwrap());
+
+        let username = create_test_user("deletions_test", &pool).await;
+        schedule(&username, false, &pool).await.unwrap();
+        assert!(cancel(&username, &pool).await.unwrap());
+        assert!(!cancel(&username, &pool).await.unwrap());
+
+        let (scheduled, token) = schedule(&username, true, &pool).await.unwrap();
+        assert!(scheduled > Utc::now());
+        assert_eq!(
+            database::users_deletions::get_scheduled_by_username(&username, &pool)
+                .await
+                .unwrap()
+                .map(|s| s.timestamp()),
+            Some(scheduled.timestamp())
+        );
+
+        let deletion = database::users_deletions::DbUserDeletion {
+            username: username.clone(),
+            delete_pulls: true,
+            export_hash: tokens::hash(&token),
+        };
+        run(deletion, &signing_key, &pool).await.unwrap();
+
+        assert!(database::users::get_one_by_username(&username, &pool)
+            .await
+            .is_err());
+        assert!(
+            database::users_deletions::get_scheduled_by_username(&username, &pool)
+                .await
+                .unwrap()
+                .is_none()
+        );
+
+        let export = final_export(&token, &pool).await.unwrap().unwrap();
+        let export: serde_json::Value = serde_json::from_str(&export).unwrap();
+        assert_eq!(export["user"]["username"], username.as_str());
+        assert!(final_export("wrong", &pool).await.unwrap().is_none());
+    }
+
+    #[actix_web::test]
+    async fn tracker_claims_are_deleted_even_when_pulls_are_kept() {
+        let pool = test_pool().await;
+        let signing_key = AsyncMutex::new(signing::load(&pool).await.unwrap());
+
+        let username = create_test_user("deletions_test", &pool).await;
+        let user_id = database::ntehelper_tracker::get_tracker_user_id(&username, &pool)
+            .await
+            .unwrap();
+        let uid = rand::rng().random_range(220_000_000_000..230_000_000_000);
+        database::ntehelper_tracker::claim_tracker_uid(user_id, uid, 3, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let (_, token) = schedule(&username, false, &pool).await.unwrap();
+        let deletion = database::users_deletions::DbUserDeletion {
+            username,
+            delete_pulls: false,
+            export_hash: tokens::hash(&token),
+        };
+        run(deletion, &signing_key, &pool).await.unwrap();
+
+        assert!(database::ntehelper_tracker::get_tracker_claim(uid, &pool)
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[actix_web::test]
+    async fn deleting_pulls_removes_their_stats() {
+        let pool = test_pool().await;
+        let signing_key = AsyncMutex::new(signing::load(&pool).await.unwrap());
+
+        let username = create_test_user("deletions_test", &pool).await;
+        let uid = rand::rng().random_range(1_000_000_000..i32::MAX);
+        database::zzz::uids::set(&database::zzz::uids::DbUid { uid }, &pool)
+            .await
+            .unwrap();
+        let connection = database::zzz::connections::DbConnection {
+            uid,
+            username: username.clone(),
+            verified: true,
+            private: false,
+        };
+        database::zzz::connections::set(&connection, &pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO zzz_signals_stats_exclusive_rescreening(uid, luck_a, luck_s, win_rate, win_streak, loss_streak) VALUES($1, 0, 0, 0, 0, 0)")
+            .bind(uid)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO zzz_signals_stats_global_exclusive_rescreening(uid, count_percentile, luck_a_percentile, luck_s_percentile) VALUES($1, 0, 0, 0)")
+            .bind(uid)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (_, token) = schedule(&username, true, &pool).await.unwrap();
+        let deletion = database::users_deletions::DbUserDeletion {
+            username: username.clone(),
+            delete_pulls: true,
+            export_hash: tokens::hash(&token),
+        };
+        run(deletion, &signing_key, &pool).await.unwrap();
+
+        for table in [
+            "zzz_signals_stats_exclusive_rescreening",
+            "zzz_signals_stats_global_exclusive_rescreening",
+        ] {
+            let count: i64 =
+                sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table} WHERE uid = $1"))
+                    .bind(uid)
+                    .fetch_one(&pool)
+                    .await
+                    .unwrap();
+            assert_eq!(count, 0, "{table}");
+        }
+
+        sqlx::query("DELETE FROM zzz_uids WHERE uid = $1")
+            .bind(uid)
+            .execute(&pool)
+            .await
+            .unwrap();
+    }
+}
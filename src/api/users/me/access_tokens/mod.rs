@@ -0,0 +1,175 @@
+mod id;
+
+use actix_session::Session;
+use actix_web::{get, post, web, HttpResponse, Responder};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Duration, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    api::{
+        auth::{self, Scope},
+        ApiResult,
+    },
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/access-tokens")),
+    paths(get_access_tokens, post_access_token),
+    components(schemas(
+        Scope,
+        AccessToken,
+        AccessTokenCreate,
+        CreatedAccessToken,
+    ))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(id::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_access_tokens)
+        .service(post_access_token)
+        .configure(id::configure);
+}
+
+const MAX_TOKENS: i64 = 20;
+
+#[derive(Serialize, ToSchema)]
+pub struct AccessToken {
+    #[schema(value_type = String)]
+    id: Uuid,
+    name: String,
+    scopes: Vec<Scope>,
+    expires: Option<DateTime<Utc>>,
+    last_used: Option<DateTime<Utc>>,
+    created: DateTime<Utc>,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct AccessTokenCreate {
+    name: String,
+    scopes: Vec<Scope>,
+    /// Between 1 and 365. Never expires if missing
+    expires_in_days: Option<i64>,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct CreatedAccessToken {
+    #[schema(value_type = String)]
+    id: Uuid,
+    /// Only shown once
+    token: String,
+}
+
+#[utoipa::path(
+    tag = "users/me/access-tokens",
+    get,
+    path = "/api/users/me/access-tokens",
+    responses(
+        (status = 200, description = "Personal access tokens", body = Vec<AccessToken>),
+        (status = 400, description = "Not logged in"),
+    )
+)]
+#[get("/api/users/me/access-tokens")]
+async fn get_access_tokens(session: Session, pool: web::Data<PgPool>) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let access_tokens: Vec<_> = database::users_access_tokens::get_by_username(&username, &pool)
+        .await?
+        .into_iter()
+        .map(|t| AccessToken {
+            id: t.id,
+            name: t.name,
+            scopes: t.scopes.iter().filter_map(|s| s.parse().ok()).collect(),
+            expires: t.expires,
+            last_used: t.last_used,
+            created: t.created,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(access_tokens))
+}
+
+#[utoipa::path(
+    tag = "users/me/access-tokens",
+    post,
+    path = "/api/users/me/access-tokens",
+    request_body(content = AccessTokenCreate,
+        examples(
+            ("DiscordBot" = (value = json!({"name": "Discord bot", "scopes": ["read:private", "achievements:write"], "expires_in_days": 90}))),
+        )
+    ),
+    responses(
+        (status = 200, description = "Created token. Send it as `Authorization: Bearer <token>`", body = CreatedAccessToken),
+        (status = 400, description = "Not logged in, invalid name, scopes or expiry, or too many tokens"),
+    )
+)]
+#[post("/api/users/me/access-tokens")]
+async fn post_access_token(
+    session: Session,
+    access_token_create: web::Json<AccessTokenCreate>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let name = access_token_create.name.trim();
+
+    if name.is_empty()
+        || name.len() > 64
+        || access_token_create.scopes.is_empty()
+        || access_token_create
+            .expires_in_days
+            .is_some_and(|days| !(1..=365).contains(&days))
+    {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    if database::users_access_tokens::get_count_by_username(&username, &pool).await? >= MAX_TOKENS {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    let mut scopes: Vec<_> = access_token_create
+        .scopes
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    scopes.sort();
+    scopes.dedup();
+
+    let expires = access_token_create
+        .expires_in_days
+        .map(|days| Utc::now() + Duration::days(days));
+
+    let token = format!(
+        "{}{}",
+        auth::TOKEN_PREFIX,
+        BASE64_URL_SAFE_NO_PAD.encode(rand::rng().random::<[u8; 32]>())
+    );
+
+    let id = database::users_access_tokens::set(
+        &username,
+        name,
+        &auth::hash(&token),
+        &scopes,
+        expires,
+        &pool,
+    )
+    .await?;
+
+    Ok(HttpResponse::Ok().json(CreatedAccessToken { id, token }))
+}
@@ -1,5 +1,6 @@
 mod completed;
 mod favorites;
+mod timeline;
 
 use actix_web::web;
 use utoipa::OpenApi;
@@ -12,10 +13,12 @@ pub fn openapi() -> utoipa::openapi::OpenApi {
     let mut openapi = ApiDoc::openapi();
     openapi.merge(completed::openapi());
     openapi.merge(favorites::openapi());
+    openapi.merge(timeline::openapi());
     openapi
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.configure(completed::configure)
-        .configure(favorites::configure);
+        .configure(favorites::configure)
+        .configure(timeline::configure);
 }
@@ -0,0 +1,60 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+
+use crate::{
+    api::{
+        achievement_timeline::{self, Completion, Timeline, TimelineDay, TimelineVersion},
+        auth::{Auth, Scope},
+        ApiResult,
+    },
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/achievements/timeline")),
+    paths(get_user_achievements_timeline),
+    components(schemas(Timeline, TimelineDay, TimelineVersion))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_user_achievements_timeline);
+}
+
+#[utoipa::path(
+    tag = "users/me/achievements/timeline",
+    get,
+    path = "/api/users/me/achievements/timeline",
+    responses(
+        (status = 200, description = "Completions by day and version", body = Timeline),
+        (status = 400, description = "Not logged in"),
+    )
+)]
+#[get("/api/users/me/achievements/timeline")]
+async fn get_user_achievements_timeline(
+    auth: Auth,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(username) = auth.username(Scope::ReadPrivate) else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let completions =
+        database::users_achievements_completed::get_timeline_by_username(&username, &pool)
+            .await?
+            .into_iter()
+            .map(|c| Completion {
+                completed_at: c.completed_at,
+                imported: c.imported,
+                version: c.version,
+                rewards: c.rewards,
+            });
+
+    Ok(HttpResponse::Ok().json(achievement_timeline::timeline(completions)))
+}
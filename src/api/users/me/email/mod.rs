@@ -1,10 +1,18 @@
+mod verify;
+
+use std::sync::Arc;
+
 use actix_session::Session;
 use actix_web::{delete, get, put, web, HttpResponse, Responder};
 use serde::Deserialize;
 use sqlx::PgPool;
 use utoipa::{OpenApi, ToSchema};
 
-use crate::{api::ApiResult, database};
+use crate::{
+    api::{users::tokens, ApiResult},
+    app_config::AppConfig,
+    database,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -17,13 +25,16 @@ use crate::{api::ApiResult, database};
 struct ApiDoc;
 
 pub fn openapi() -> utoipa::openapi::OpenApi {
-    ApiDoc::openapi()
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(verify::openapi());
+    openapi
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_email)
         .service(put_email)
-        .service(delete_email);
+        .service(delete_email)
+        .configure(verify::configure);
 }
 
 #[utoipa::path(
@@ -57,7 +68,7 @@ pub struct EmailUpdate {
     path = "/api/users/me/email",
     request_body = EmailUpdate,
     responses(
-        (status = 200, description = "Updated email"),
+        (status = 200, description = "Updated email. A verification mail is sent to the new address"),
         (status = 400, description = "Not logged in"),
     )
 )]
@@ -65,6 +76,7 @@ pub struct EmailUpdate {
 async fn put_email(
     session: Session,
     email_update: web::Json<EmailUpdate>,
+    app_config: web::Data<Arc<AppConfig>>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
     let Ok(Some(username)) = session.get::<String>("username") else {
@@ -73,6 +85,12 @@ async fn put_email(
 
     database::users::update_email_by_username(&username, &email_update.email, &pool).await?;
 
+    if let Err(e) =
+        tokens::send_verification(&username, &email_update.email, &app_config, &pool).await
+    {
+        error!("Creating the verification token for {username} failed with {e}");
+    }
+
     Ok(HttpResponse::Ok().finish())
 }
 
@@ -0,0 +1,58 @@
+use std::sync::Arc;
+
+use actix_session::Session;
+use actix_web::{post, web, HttpResponse, Responder};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+
+use crate::{
+    api::{users::tokens, ApiResult},
+    app_config::AppConfig,
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(tags((name = "users/me/email/verify")), paths(post_email_verify))]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_email_verify);
+}
+
+#[utoipa::path(
+    tag = "users/me/email/verify",
+    post,
+    path = "/api/users/me/email/verify",
+    responses(
+        (status = 200, description = "Sent verification mail"),
+        (status = 400, description = "Not logged in or no email connected"),
+    )
+)]
+#[post("/api/users/me/email/verify")]
+async fn post_email_verify(
+    session: Session,
+    app_config: web::Data<Arc<AppConfig>>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let user = database::users::get_one_by_username(&username, &pool).await?;
+
+    let Some(email) = user.email else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    if user.email_verified {
+        return Ok(HttpResponse::Ok().finish());
+    }
+
+    tokens::send_verification(&username, &email, &app_config, &pool).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,169 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use chrono::Utc;
+use sqlx::PgPool;
+use utoipa::OpenApi;
+
+use super::export_uigf::{format_uigf_time, get_timezone_offset, localized_item_type};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        banner_helpers, ApiResult, LanguageParams,
+    },
+    database, Language,
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    tags((name = "users/me/export-srgf/{uid}")),
+    paths(get_export_srgf),
+    components(schemas(
+        SRGFExport,
+        SRGFInfo,
+        SRGFListItem,
+    ))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_export_srgf);
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+struct SRGFExport {
+    info: SRGFInfo,
+    list: Vec<SRGFListItem>,
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+struct SRGFInfo {
+    uid: String,
+    lang: String,
+    region_time_zone: i32,
+    export_timestamp: u64,
+    export_app: String,
+    export_app_version: String,
+    srgf_version: String,
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+struct SRGFListItem {
+    /// Id of the banner whose window matches the warp, else `gacha_type`
+    gacha_id: String,
+    gacha_type: String,
+    item_id: String,
+    count: String,
+    time: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    item_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rank_type: Option<String>,
+    id: String,
+}
+
+fn warp_to_srgf_item(
+    warp: database::warps::DbWarp,
+    gacha_type: &str,
+    banners: &[database::banners::DbBanner],
+    language: Language,
+    offset: i32,
+) -> SRGFListItem {
+    let (item_id, item_type) = if let Some(char_id) = warp.character {
+        (char_id, "Character")
+    } else {
+        (warp.light_cone.unwrap_or_default(), "Light Cone")
+    };
+
+    let gacha_id = banner_helpers::hsr_banner_id(banners, gacha_type, item_id, warp.timestamp)
+        .map_or_else(|| gacha_type.to_string(), |id| id.to_string());
+
+    SRGFListItem {
+        gacha_id,
+        gacha_type: gacha_type.to_string(),
+        item_id: item_id.to_string(),
+        count: "1".to_string(),
+        time: format_uigf_time(warp.timestamp, offset),
+        name: warp.name,
+        item_type: localized_item_type(item_type, language).to_string(),
+        rank_type: warp.rarity.map(|r| r.to_string()),
+        id: warp.id.to_string(),
+    }
+}
+
+#[utoipa::path(
+    tag = "users/me/export-srgf/{uid}",
+    get,
+    path = "/api/users/me/export-srgf/{uid}",
+    params(LanguageParams),
+    responses(
+        (status = 200, description = "SRGF v1.0 Export", body = SRGFExport),
+        (status = 400, description = "Not logged in"),
+        (status = 403, description = "Uid not connected"),
+    )
+)]
+#[get("/api/users/me/export-srgf/{uid}")]
+async fn get_export_srgf(
+    auth: Auth,
+    uid: web::Path<i32>,
+    language_params: web::Query<LanguageParams>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(username) = auth.username(Scope::ReadPrivate) else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let uid = *uid;
+    let language = language_params.lang;
+
+    if database::connections::get_by_uid_and_username(uid, &username, &pool)
+        .await
+        .is_err()
+    {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    let offset = get_timezone_offset(uid, "hsr");
+    let banners = database::banners::get_all(&pool).await?;
+
+    let mut list = Vec::new();
+
+    for warp in database::warps::departure::get_by_uid(uid, language, &pool).await? {
+        list.push(warp_to_srgf_item(warp, "2", &banners, language, offset));
+    }
+    for warp in database::warps::standard::get_by_uid(uid, language, &pool).await? {
+        list.push(warp_to_srgf_item(warp, "1", &banners, language, offset));
+    }
+    for warp in database::warps::special::get_by_uid(uid, language, &pool).await? {
+        list.push(warp_to_srgf_item(warp, "11", &banners, language, offset));
+    }
+    for warp in database::warps::lc::get_by_uid(uid, language, &pool).await? {
+        list.push(warp_to_srgf_item(warp, "12", &banners, language, offset));
+    }
+    for warp in database::warps::collab::get_by_uid(uid, language, &pool).await? {
+        list.push(warp_to_srgf_item(warp, "21", &banners, language, offset));
+    }
+    for warp in database::warps::collab_lc::get_by_uid(uid, language, &pool).await? {
+        list.push(warp_to_srgf_item(warp, "22", &banners, language, offset));
+    }
+
+    list.sort_by(|a, b| a.time.cmp(&b.time).then(a.id.cmp(&b.id)));
+
+    let export = SRGFExport {
+        info: SRGFInfo {
+            uid: uid.to_string(),
+            lang: language.uigf(),
+            region_time_zone: offset,
+            export_timestamp: Utc::now().timestamp() as u64,
+            export_app: "stardb".to_string(),
+            export_app_version: "v0".to_string(),
+            srgf_version: "v1.0".to_string(),
+        },
+        list,
+    };
+
+    Ok(HttpResponse::Ok().json(export))
+}
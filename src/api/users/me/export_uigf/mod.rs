@@ -1,10 +1,15 @@
-use actix_session::Session;
 use actix_web::{get, web, HttpResponse, Responder};
 use chrono::{FixedOffset, Utc};
 use sqlx::PgPool;
 use utoipa::OpenApi;
 
-use crate::{api::ApiResult, database, Language};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        banner_helpers, ApiResult,
+    },
+    database, Language,
+};
 
 #[derive(utoipa::OpenApi)]
 #[openapi(
@@ -59,6 +64,7 @@ struct UIGFListItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     uigf_gacha_type: Option<String>,
     gacha_type: String,
+    /// For HSR, the id of the banner whose window matches the warp, else `gacha_type`
     #[serde(skip_serializing_if = "Option::is_none")]
     gacha_id: Option<String>,
     item_id: String,
@@ -73,7 +79,7 @@ struct UIGFListItem {
     id: String,
 }
 
-fn get_timezone_offset(uid: i32, game: &str) -> i32 {
+pub fn get_timezone_offset(uid: i32, game: &str) -> i32 {
     let uid_str = uid.to_string();
     let first_char = uid_str.chars().next().unwrap_or('0');
     let first_two: String = uid_str.chars().take(2).collect();
@@ -99,32 +105,72 @@ fn get_timezone_offset(uid: i32, game: &str) -> i32 {
     }
 }
 
-fn format_uigf_time(chrono_datetime: chrono::DateTime<Utc>, offset_hours: i32) -> String {
+pub fn format_uigf_time(chrono_datetime: chrono::DateTime<Utc>, offset_hours: i32) -> String {
     let offset = FixedOffset::east_opt(offset_hours * 3600).unwrap();
     let local_time = chrono_datetime.with_timezone(&offset);
     local_time.format("%Y-%m-%d %H:%M:%S").to_string()
 }
 
+// `item_type` as the in-game gacha log writes it in `language`. English when there is no translation.
+pub fn localized_item_type(item_type: &'static str, language: Language) -> &'static str {
+    match (item_type, language) {
+        ("Character", Language::ZhCn | Language::ZhTw) => "角色",
+        ("Character", Language::De) => "Figur",
+        ("Character", Language::EsEs) => "Personaje",
+        ("Character", Language::Fr) => "Personnage",
+        ("Character", Language::Id) => "Karakter",
+        ("Character", Language::Ja) => "キャラクター",
+        ("Character", Language::Ko) => "캐릭터",
+        ("Character", Language::PtPt) => "Personagem",
+        ("Character", Language::Ru) => "Персонаж",
+        ("Character", Language::Th) => "ตัวละคร",
+        ("Character", Language::Vi) => "Nhân Vật",
+        ("Light Cone", Language::ZhCn) => "光锥",
+        ("Light Cone", Language::ZhTw) => "光錐",
+        ("Light Cone", Language::De) => "Lichtkegel",
+        ("Light Cone", Language::EsEs) => "Cono de luz",
+        ("Light Cone", Language::Fr) => "Cône de lumière",
+        ("Light Cone", Language::Ja) => "光円錐",
+        ("Light Cone", Language::Ko) => "광추",
+        ("Light Cone", Language::PtPt) => "Cone de Luz",
+        ("Light Cone", Language::Ru) => "Световой конус",
+        ("Light Cone", Language::Vi) => "Nón Ánh Sáng",
+        ("Weapon", Language::ZhCn | Language::ZhTw | Language::Ja) => "武器",
+        ("Weapon", Language::De) => "Waffe",
+        ("Weapon", Language::EsEs | Language::PtPt) => "Arma",
+        ("Weapon", Language::Fr) => "Arme",
+        ("Weapon", Language::Id) => "Senjata",
+        ("Weapon", Language::Ko) => "무기",
+        ("Weapon", Language::Ru) => "Оружие",
+        ("Weapon", Language::Th) => "อาวุธ",
+        ("Weapon", Language::Vi) => "Vũ Khí",
+        _ => item_type,
+    }
+}
+
 
 fn warp_to_uigf_item(
     warp: database::warps::DbWarp,
     gacha_type: &str,
-    gacha_id: Option<&str>,
+    banners: &[database::banners::DbBanner],
     offset: i32,
 ) -> UIGFListItem {
     let (item_id, item_type) = if let Some(char_id) = warp.character {
-        (char_id.to_string(), Some("Character".to_string()))
+        (char_id, Some("Character".to_string()))
     } else if let Some(lc_id) = warp.light_cone {
-        (lc_id.to_string(), Some("Light Cone".to_string()))
+        (lc_id, Some("Light Cone".to_string()))
     } else {
-        ("0".to_string(), None)
+        (0, None)
     };
 
+    let gacha_id = banner_helpers::hsr_banner_id(banners, gacha_type, item_id, warp.timestamp)
+        .map_or_else(|| gacha_type.to_string(), |id| id.to_string());
+
     UIGFListItem {
         uigf_gacha_type: None,
         gacha_type: gacha_type.to_string(),
-        gacha_id: gacha_id.map(|s| s.to_string()),
-        item_id,
+        gacha_id: Some(gacha_id),
+        item_id: item_id.to_string(),
         count: "1".to_string(),
         time: format_uigf_time(warp.timestamp, offset),
         name: warp.name,
@@ -201,15 +247,14 @@ fn wish_to_uigf_item(
     )
 )]
 #[get("/api/users/me/export-uigf")]
-async fn get_export_uigf(
-    session: Session,
-    pool: web::Data<PgPool>,
-) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+async fn get_export_uigf(auth: Auth, pool: web::Data<PgPool>) -> ApiResult<impl Responder> {
+    let Some(username) = auth.username(Scope::ReadPrivate) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
     // HSR (hkrpg)
+    let banners = database::banners::get_all(&pool).await?;
+
     let mut hkrpg = Vec::new();
     for connection in database::connections::get_by_username(&username, &pool).await? {
         let uid = connection.uid;
@@ -218,22 +263,22 @@ async fn get_export_uigf(
         let mut list = Vec::new();
 
         for warp in database::warps::departure::get_by_uid(uid, Language::En, &pool).await? {
-            list.push(warp_to_uigf_item(warp, "2", Some("2"), offset));
+            list.push(warp_to_uigf_item(warp, "2", &banners, offset));
         }
         for warp in database::warps::standard::get_by_uid(uid, Language::En, &pool).await? {
-            list.push(warp_to_uigf_item(warp, "1", Some("1"), offset));
+            list.push(warp_to_uigf_item(warp, "1", &banners, offset));
         }
         for warp in database::warps::special::get_by_uid(uid, Language::En, &pool).await? {
-            list.push(warp_to_uigf_item(warp, "11", Some("11"), offset));
+            list.push(warp_to_uigf_item(warp, "11", &banners, offset));
         }
         for warp in database::warps::lc::get_by_uid(uid, Language::En, &pool).await? {
-            list.push(warp_to_uigf_item(warp, "12", Some("12"), offset));
+            list.push(warp_to_uigf_item(warp, "12", &banners, offset));
         }
         for warp in database::warps::collab::get_by_uid(uid, Language::En, &pool).await? {
-            list.push(warp_to_uigf_item(warp, "21", Some("21"), offset));
+            list.push(warp_to_uigf_item(warp, "21", &banners, offset));
         }
         for warp in database::warps::collab_lc::get_by_uid(uid, Language::En, &pool).await? {
-            list.push(warp_to_uigf_item(warp, "22", Some("22"), offset));
+            list.push(warp_to_uigf_item(warp, "22", &banners, offset));
         }
 
         list.sort_by(|a, b| a.time.cmp(&b.time).then(a.id.cmp(&b.id)));
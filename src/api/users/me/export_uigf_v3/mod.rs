@@ -0,0 +1,166 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use chrono::Utc;
+use sqlx::PgPool;
+use utoipa::OpenApi;
+
+use super::export_uigf::{format_uigf_time, get_timezone_offset, localized_item_type};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        ApiResult, LanguageParams,
+    },
+    database, Language,
+};
+
+#[derive(utoipa::OpenApi)]
+#[openapi(
+    tags((name = "users/me/export-uigf-v3/{uid}")),
+    paths(get_export_uigf_v3),
+    components(schemas(
+        UIGFv3Export,
+        UIGFv3Info,
+        UIGFv3ListItem,
+    ))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_export_uigf_v3);
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+struct UIGFv3Export {
+    info: UIGFv3Info,
+    list: Vec<UIGFv3ListItem>,
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+struct UIGFv3Info {
+    uid: String,
+    lang: String,
+    export_timestamp: u64,
+    export_time: String,
+    export_app: String,
+    export_app_version: String,
+    uigf_version: String,
+    region_time_zone: i32,
+}
+
+#[derive(serde::Serialize, utoipa::ToSchema)]
+struct UIGFv3ListItem {
+    uigf_gacha_type: String,
+    gacha_type: String,
+    item_id: String,
+    count: String,
+    time: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    item_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rank_type: Option<String>,
+    id: String,
+}
+
+// The second character event banner (gacha_type 400) isn't stored separately,
+// so both event banners are exported as 301
+fn wish_to_uigf_v3_item(
+    wish: database::gi::wishes::DbWish,
+    uigf_gacha_type: &str,
+    language: Language,
+    offset: i32,
+) -> UIGFv3ListItem {
+    let (item_id, item_type) = if let Some(char_id) = wish.character {
+        (char_id, "Character")
+    } else {
+        (wish.weapon.unwrap_or_default(), "Weapon")
+    };
+
+    UIGFv3ListItem {
+        uigf_gacha_type: uigf_gacha_type.to_string(),
+        gacha_type: uigf_gacha_type.to_string(),
+        item_id: item_id.to_string(),
+        count: "1".to_string(),
+        time: format_uigf_time(wish.timestamp, offset),
+        name: wish.name,
+        item_type: localized_item_type(item_type, language).to_string(),
+        rank_type: wish.rarity.map(|r| r.to_string()),
+        id: wish.id.to_string(),
+    }
+}
+
+#[utoipa::path(
+    tag = "users/me/export-uigf-v3/{uid}",
+    get,
+    path = "/api/users/me/export-uigf-v3/{uid}",
+    params(LanguageParams),
+    responses(
+        (status = 200, description = "UIGF v3.0 Export", body = UIGFv3Export),
+        (status = 400, description = "Not logged in"),
+        (status = 403, description = "Uid not connected"),
+    )
+)]
+#[get("/api/users/me/export-uigf-v3/{uid}")]
+async fn get_export_uigf_v3(
+    auth: Auth,
+    uid: web::Path<i32>,
+    language_params: web::Query<LanguageParams>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(username) = auth.username(Scope::ReadPrivate) else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let uid = *uid;
+    let language = language_params.lang;
+
+    if database::gi::connections::get_by_uid_and_username(uid, &username, &pool)
+        .await
+        .is_err()
+    {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    let offset = get_timezone_offset(uid, "gi");
+
+    let mut list = Vec::new();
+
+    for wish in database::gi::wishes::beginner::get_by_uid(uid, language, &pool).await? {
+        list.push(wish_to_uigf_v3_item(wish, "100", language, offset));
+    }
+    for wish in database::gi::wishes::standard::get_by_uid(uid, language, &pool).await? {
+        list.push(wish_to_uigf_v3_item(wish, "200", language, offset));
+    }
+    for wish in database::gi::wishes::character::get_by_uid(uid, language, &pool).await? {
+        list.push(wish_to_uigf_v3_item(wish, "301", language, offset));
+    }
+    for wish in database::gi::wishes::weapon::get_by_uid(uid, language, &pool).await? {
+        list.push(wish_to_uigf_v3_item(wish, "302", language, offset));
+    }
+    for wish in database::gi::wishes::chronicled::get_by_uid(uid, language, &pool).await? {
+        list.push(wish_to_uigf_v3_item(wish, "500", language, offset));
+    }
+
+    list.sort_by(|a, b| a.time.cmp(&b.time).then(a.id.cmp(&b.id)));
+
+    let now = Utc::now();
+
+    let export = UIGFv3Export {
+        info: UIGFv3Info {
+            uid: uid.to_string(),
+            lang: language.uigf(),
+            export_timestamp: now.timestamp() as u64,
+            export_time: format_uigf_time(now, offset),
+            export_app: "stardb".to_string(),
+            export_app_version: "v0".to_string(),
+            uigf_version: "v3.0".to_string(),
+            region_time_zone: offset,
+        },
+        list,
+    };
+
+    Ok(HttpResponse::Ok().json(export))
+}
@@ -1,9 +1,14 @@
-use actix_session::Session;
 use actix_web::{delete, put, web, HttpResponse, Responder};
 use sqlx::PgPool;
 use utoipa::OpenApi;
 
-use crate::{api::ApiResult, database};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        ApiResult,
+    },
+    database,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -32,18 +37,18 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
 )]
 #[put("/api/users/me/gi/achievements/completed/{id}")]
 async fn put_gi_user_achievement_completed(
-    session: Session,
+    auth: Auth,
     id: web::Path<i32>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::AchievementsWrite) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
     let id = *id;
     let db_complete =
         database::gi::users_achievements_completed::DbUserAchievementCompleted { username, id };
-    database::gi::users_achievements_completed::add(&db_complete, &pool).await?;
+    database::gi::users_achievements_completed::add(&db_complete, false, &**pool).await?;
 
     Ok(HttpResponse::Ok().finish())
 }
@@ -59,11 +64,11 @@ async fn put_gi_user_achievement_completed(
 )]
 #[delete("/api/users/me/gi/achievements/completed/{id}")]
 async fn delete_gi_user_achievement_completed(
-    session: Session,
+    auth: Auth,
     id: web::Path<i32>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::AchievementsWrite) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
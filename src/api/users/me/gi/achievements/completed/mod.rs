@@ -1,11 +1,16 @@
 mod id;
 
-use actix_session::Session;
 use actix_web::{delete, get, put, web, HttpResponse, Responder};
 use sqlx::PgPool;
 use utoipa::OpenApi;
 
-use crate::{api::ApiResult, database};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        ApiResult,
+    },
+    database,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -38,10 +43,10 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
 )]
 #[get("/api/users/me/gi/achievements/completed")]
 async fn get_gi_user_achievements_completed(
-    session: Session,
+    auth: Auth,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::ReadPrivate) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
@@ -67,11 +72,11 @@ async fn get_gi_user_achievements_completed(
 )]
 #[put("/api/users/me/gi/achievements/completed")]
 async fn put_gi_user_achievements_completed(
-    session: Session,
+    auth: Auth,
     ids: web::Json<Vec<i32>>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::AchievementsWrite) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
@@ -81,7 +86,7 @@ async fn put_gi_user_achievements_completed(
     for id in ids.0 {
         complete.id = id;
 
-        let _ = database::gi::users_achievements_completed::add(&complete, &pool).await;
+        let _ = database::gi::users_achievements_completed::add(&complete, true, &**pool).await;
     }
 
     Ok(HttpResponse::Ok().finish())
@@ -99,11 +104,11 @@ async fn put_gi_user_achievements_completed(
 )]
 #[delete("/api/users/me/gi/achievements/completed")]
 async fn delete_gi_user_achievements_completed(
-    session: Session,
+    auth: Auth,
     ids: web::Json<Vec<i32>>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::AchievementsWrite) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
@@ -1,9 +1,14 @@
-use actix_session::Session;
 use actix_web::{delete, put, web, HttpResponse, Responder};
 use sqlx::PgPool;
 use utoipa::OpenApi;
 
-use crate::{api::ApiResult, database};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        ApiResult,
+    },
+    database,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -32,18 +37,18 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
 )]
 #[put("/api/users/me/gi/achievements/favorites/{id}")]
 async fn put_gi_user_achievement_favorites(
-    session: Session,
+    auth: Auth,
     id: web::Path<i32>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::AchievementsWrite) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
     let id = *id;
     let favorite =
         database::gi::users_achievements_favorites::DbUserAchievementFavorite { username, id };
-    database::gi::users_achievements_favorites::add(&favorite, &pool).await?;
+    database::gi::users_achievements_favorites::add(&favorite, &**pool).await?;
 
     Ok(HttpResponse::Ok().finish())
 }
@@ -59,11 +64,11 @@ async fn put_gi_user_achievement_favorites(
 )]
 #[delete("/api/users/me/gi/achievements/favorites/{id}")]
 async fn delete_gi_user_achievement_favorites(
-    session: Session,
+    auth: Auth,
     id: web::Path<i32>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::AchievementsWrite) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
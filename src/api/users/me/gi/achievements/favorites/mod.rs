@@ -1,11 +1,16 @@
 mod id;
 
-use actix_session::Session;
 use actix_web::{get, put, web, HttpResponse, Responder};
 use sqlx::PgPool;
 use utoipa::OpenApi;
 
-use crate::{api::ApiResult, database};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        ApiResult,
+    },
+    database,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -37,10 +42,10 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
 )]
 #[get("/api/users/me/gi/achievements/favorites")]
 async fn get_gi_user_achievements_favorites(
-    session: Session,
+    auth: Auth,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::ReadPrivate) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
@@ -66,11 +71,11 @@ async fn get_gi_user_achievements_favorites(
 )]
 #[put("/api/users/me/gi/achievements/favorites")]
 async fn put_gi_user_achievements_favorites(
-    session: Session,
+    auth: Auth,
     ids: web::Json<Vec<i32>>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::AchievementsWrite) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
@@ -80,7 +85,7 @@ async fn put_gi_user_achievements_favorites(
     for id in ids.0 {
         favorite.id = id;
 
-        database::gi::users_achievements_favorites::add(&favorite, &pool).await?;
+        database::gi::users_achievements_favorites::add(&favorite, &**pool).await?;
     }
 
     Ok(HttpResponse::Ok().finish())
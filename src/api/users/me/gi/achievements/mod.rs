@@ -1,5 +1,7 @@
 mod completed;
 mod favorites;
+mod timeline;
+mod uiaf;
 
 use actix_web::web;
 use utoipa::OpenApi;
@@ -12,10 +14,14 @@ pub fn openapi() -> utoipa::openapi::OpenApi {
     let mut openapi = ApiDoc::openapi();
     openapi.merge(completed::openapi());
     openapi.merge(favorites::openapi());
+    openapi.merge(timeline::openapi());
+    openapi.merge(uiaf::openapi());
     openapi
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.configure(completed::configure)
-        .configure(favorites::configure);
+        .configure(favorites::configure)
+        .configure(timeline::configure)
+        .configure(uiaf::configure);
 }
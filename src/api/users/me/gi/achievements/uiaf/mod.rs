@@ -0,0 +1,237 @@
+use std::collections::HashMap;
+
+use actix_web::{get, put, web, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        ApiResult,
+    },
+    database::{self, gi::users_achievements_completed::SetAll},
+    Language,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/gi/achievements/uiaf")),
+    paths(get_gi_user_achievements_uiaf, put_gi_user_achievements_uiaf),
+    components(schemas(Uiaf, UiafInfo, UiafAchievement, UiafMode, UiafImport))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_gi_user_achievements_uiaf)
+        .service(put_gi_user_achievements_uiaf);
+}
+
+/// Unified Achievement Format, as spoken by Snap.Hutao, Inventory Kamera and Cocogoat
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct Uiaf {
+    #[serde(default)]
+    info: UiafInfo,
+    list: Vec<UiafAchievement>,
+}
+
+#[derive(Default, Serialize, Deserialize, ToSchema)]
+#[serde(default)]
+pub struct UiafInfo {
+    export_app: String,
+    export_app_version: String,
+    uiaf_version: String,
+    /// Unix seconds
+    export_timestamp: i64,
+}
+
+#[derive(Serialize, Deserialize, ToSchema)]
+pub struct UiafAchievement {
+    id: i32,
+    /// Unix seconds of the completion. 0 if unknown
+    #[serde(default)]
+    timestamp: i64,
+    /// Progress towards the goal
+    #[serde(default)]
+    current: i32,
+    /// 0 invalid, 1 unfinished, 2 finished, 3 reward taken. Missing in v1.0, which only lists finished
+    /// achievements
+    status: Option<u8>,
+}
+
+#[derive(Default, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UiafMode {
+    /// Keeps the completions that aren't in the file. Earlier completion times win
+    #[default]
+    Merge,
+    /// Removes the completions that aren't in the file
+    Replace,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct UiafParams {
+    #[serde(default)]
+    mode: UiafMode,
+}
+
+#[derive(Default, Serialize, ToSchema)]
+pub struct UiafImport {
+    /// Finished achievements that were imported
+    imported: usize,
+    /// Skipped, because they weren't finished
+    unfinished: usize,
+    /// Skipped, because they don't exist
+    unknown: Vec<i32>,
+    /// Skipped, because they can't be completed
+    impossible: Vec<i32>,
+}
+
+#[utoipa::path(
+    tag = "users/me/gi/achievements/uiaf",
+    get,
+    path = "/api/users/me/gi/achievements/uiaf",
+    responses(
+        (status = 200, description = "Completed achievements as UIAF v1.1", body = Uiaf),
+        (status = 400, description = "Not logged in"),
+    )
+)]
+#[get("/api/users/me/gi/achievements/uiaf")]
+async fn get_gi_user_achievements_uiaf(
+    auth: Auth,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(username) = auth.username(Scope::ReadPrivate) else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let list = database::gi::users_achievements_completed::get_uiaf_by_username(&username, &pool)
+        .await?
+        .into_iter()
+        .map(|a| UiafAchievement {
+            id: a.id,
+            timestamp: a.completed_at.map(|t| t.timestamp()).unwrap_or_default(),
+            current: a.current.unwrap_or_default(),
+            status: Some(3),
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(Uiaf {
+        info: UiafInfo {
+            export_app: "stardb".to_string(),
+            export_app_version: env!("CARGO_PKG_VERSION").to_string(),
+            uiaf_version: "v1.1".to_string(),
+            export_timestamp: Utc::now().timestamp(),
+        },
+        list,
+    }))
+}
+
+#[utoipa::path(
+    tag = "users/me/gi/achievements/uiaf",
+    put,
+    path = "/api/users/me/gi/achievements/uiaf",
+    params(UiafParams),
+    request_body = Uiaf,
+    responses(
+        (status = 200, description = "Imported", body = UiafImport),
+        (status = 400, description = "Not logged in"),
+    )
+)]
+#[put("/api/users/me/gi/achievements/uiaf")]
+async fn put_gi_user_achievements_uiaf(
+    auth: Auth,
+    params: web::Query<UiafParams>,
+    uiaf: web::Json<Uiaf>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(username) = auth.username(Scope::AchievementsWrite) else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let impossible: HashMap<_, _> = database::gi::achievements::get_all(Language::En, &pool)
+        .await?
+        .into_iter()
+        .map(|a| (a.id, a.impossible))
+        .collect();
+
+    let (set_all, import) = parse(&uiaf.list, &impossible);
+
+    let replace = matches!(params.mode, UiafMode::Replace);
+    database::gi::users_achievements_completed::set_all(&username, &set_all, replace, &pool)
+        .await?;
+
+    Ok(HttpResponse::Ok().json(import))
+}
+
+/// `impossible` maps every known achievement id to whether it is impossible
+fn parse(list: &[UiafAchievement], impossible: &HashMap<i32, bool>) -> (SetAll, UiafImport) {
+    let mut set_all = SetAll::default();
+    let mut import = UiafImport::default();
+
+    for achievement in list {
+        if matches!(achievement.status, Some(0 | 1)) {
+            import.unfinished += 1;
+            continue;
+        }
+
+        match impossible.get(&achievement.id) {
+            None => import.unknown.push(achievement.id),
+            Some(true) => import.impossible.push(achievement.id),
+            Some(false) => {
+                set_all.id.push(achievement.id);
+                set_all.completed_at.push(
+                    (achievement.timestamp > 0)
+                        .then(|| DateTime::from_timestamp(achievement.timestamp, 0))
+                        .flatten(),
+                );
+                set_all
+                    .current
+                    .push((achievement.current > 0).then_some(achievement.current));
+
+                import.imported += 1;
+            }
+        }
+    }
+
+    (set_all, import)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unfinished_unknown_and_impossible_are_skipped() {
+        let uiaf: Uiaf = serde_json::from_value(serde_json::json!({
+            "list": [
+                { "id": 1, "timestamp": 1700000000, "current": 5, "status": 3 },
+                { "id": 2, "timestamp": 0, "current": 0, "status": 2 },
+                { "id": 3 },
+                { "id": 4, "timestamp": 0, "current": 1, "status": 1 },
+                { "id": 5, "status": 3 },
+                { "id": 6, "status": 3 },
+            ]
+        }))
+        .unwrap();
+        let impossible = HashMap::from([(1, false), (2, false), (3, false), (4, false), (6, true)]);
+
+        let (set_all, import) = parse(&uiaf.list, &impossible);
+
+        assert_eq!(set_all.id, [1, 2, 3]);
+        assert_eq!(
+            set_all.completed_at,
+            [DateTime::from_timestamp(1700000000, 0), None, None]
+        );
+        assert_eq!(set_all.current, [Some(5), None, None]);
+        assert_eq!(import.imported, 3);
+        assert_eq!(import.unfinished, 1);
+        assert_eq!(import.unknown, [5]);
+        assert_eq!(import.impossible, [6]);
+    }
+}
@@ -0,0 +1,146 @@
+use actix_session::Session;
+use actix_web::{delete, put, web, HttpResponse, Responder};
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    api::{banner_helpers, ApiResult},
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/gi/uids/{uid}/charts/{banner}")),
+    paths(put_user_gi_uid_chart, delete_user_gi_uid_chart),
+    components(schemas(PutWeaponChart))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(put_user_gi_uid_chart)
+        .service(delete_user_gi_uid_chart);
+}
+
+#[derive(Deserialize, ToSchema)]
+struct PutWeaponChart {
+    weapon: i32,
+}
+
+#[utoipa::path(
+    tag = "users/me/gi/uids/{uid}/charts/{banner}",
+    put,
+    path = "/api/users/me/gi/uids/{uid}/charts/{banner}",
+    request_body = PutWeaponChart,
+    responses(
+        (status = 200, description = "Charted weapon"),
+        (status = 400, description = "Not logged in or weapon not featured on the banner"),
+        (status = 403, description = "Uid not verified"),
+        (status = 404, description = "No weapon banner with this id"),
+    )
+)]
+#[put("/api/users/me/gi/uids/{uid}/charts/{banner}")]
+async fn put_user_gi_uid_chart(
+    session: Session,
+    path: web::Path<(i32, i32)>,
+    chart: web::Json<PutWeaponChart>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let (uid, banner) = path.into_inner();
+
+    let allowed = database::gi::connections::get_by_username(&username, &pool)
+        .await?
+        .iter()
+        .find(|c| c.uid == uid)
+        .map(|c| c.verified)
+        .unwrap_or_default();
+
+    if !allowed {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    let banners = database::gi::banners::get_all(&pool).await?;
+
+    let Some(weapon_banner) = find_weapon_banner(banner, &banners) else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    if !weapon_banner.weapons.contains(&chart.weapon) {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    let db_chart = database::gi::weapon_charts::DbWeaponChart {
+        uid,
+        banner: weapon_banner.id,
+        weapon: chart.weapon,
+    };
+
+    database::gi::weapon_charts::set(&db_chart, &pool).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+#[utoipa::path(
+    tag = "users/me/gi/uids/{uid}/charts/{banner}",
+    delete,
+    path = "/api/users/me/gi/uids/{uid}/charts/{banner}",
+    responses(
+        (status = 200, description = "Removed chart"),
+        (status = 400, description = "Not logged in"),
+        (status = 403, description = "Uid not verified"),
+        (status = 404, description = "No weapon banner with this id"),
+    )
+)]
+#[delete("/api/users/me/gi/uids/{uid}/charts/{banner}")]
+async fn delete_user_gi_uid_chart(
+    session: Session,
+    path: web::Path<(i32, i32)>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let (uid, banner) = path.into_inner();
+
+    let allowed = database::gi::connections::get_by_username(&username, &pool)
+        .await?
+        .iter()
+        .find(|c| c.uid == uid)
+        .map(|c| c.verified)
+        .unwrap_or_default();
+
+    if !allowed {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    let banners = database::gi::banners::get_all(&pool).await?;
+
+    let Some(weapon_banner) = find_weapon_banner(banner, &banners) else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    database::gi::weapon_charts::delete(uid, weapon_banner.id, &pool).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
+
+// Any row of a weapon banner may be passed, charts are always stored under the banner's lowest id.
+fn find_weapon_banner(
+    id: i32,
+    banners: &[database::gi::banners::DbBanner],
+) -> Option<banner_helpers::GiWeaponBanner> {
+    let banner = banners.iter().find(|b| b.id == id && b.weapon.is_some())?;
+
+    banner_helpers::gi_weapon_banners(banners)
+        .into_iter()
+        .find(|b| b.start == banner.start && b.end == banner.end)
+}
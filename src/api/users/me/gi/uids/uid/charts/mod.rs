@@ -0,0 +1,88 @@
+mod banner;
+
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        ApiResult,
+    },
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/gi/uids/{uid}/charts")),
+    paths(get_user_gi_uid_charts),
+    components(schemas(WeaponChart))
+)]
+struct ApiDoc;
+
+#[derive(Serialize, ToSchema)]
+struct WeaponChart {
+    banner: i32,
+    weapon: i32,
+}
+
+impl From<database::gi::weapon_charts::DbWeaponChart> for WeaponChart {
+    fn from(chart: database::gi::weapon_charts::DbWeaponChart) -> Self {
+        Self {
+            banner: chart.banner,
+            weapon: chart.weapon,
+        }
+    }
+}
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(banner::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_user_gi_uid_charts)
+        .configure(banner::configure);
+}
+
+#[utoipa::path(
+    tag = "users/me/gi/uids/{uid}/charts",
+    get,
+    path = "/api/users/me/gi/uids/{uid}/charts",
+    responses(
+        (status = 200, description = "[WeaponChart]", body = Vec<WeaponChart>),
+        (status = 400, description = "Not logged in"),
+        (status = 403, description = "Uid not verified"),
+    )
+)]
+#[get("/api/users/me/gi/uids/{uid}/charts")]
+async fn get_user_gi_uid_charts(
+    auth: Auth,
+    uid: web::Path<i32>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(username) = auth.username(Scope::ReadPrivate) else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let allowed = database::gi::connections::get_by_username(&username, &pool)
+        .await?
+        .iter()
+        .find(|c| c.uid == *uid)
+        .map(|c| c.verified)
+        .unwrap_or_default();
+
+    if !allowed {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    let charts: Vec<_> = database::gi::weapon_charts::get_by_uid(*uid, &pool)
+        .await?
+        .into_iter()
+        .map(WeaponChart::from)
+        .collect();
+
+    Ok(HttpResponse::Ok().json(charts))
+}
@@ -1,3 +1,4 @@
+mod charts;
 mod private;
 
 use actix_session::Session;
@@ -16,12 +17,14 @@ struct ApiDoc;
 
 pub fn openapi() -> utoipa::openapi::OpenApi {
     let mut openapi = ApiDoc::openapi();
+    openapi.merge(charts::openapi());
     openapi.merge(private::openapi());
     openapi
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.configure(private::configure)
+    cfg.configure(charts::configure)
+        .configure(private::configure)
         .service(put_user_gi_uid)
         .service(delete_user_gi_uid);
 }
@@ -59,7 +62,7 @@ async fn put_user_gi_uid(
         private: false,
     };
 
-    database::gi::connections::set(&connection, &pool).await?;
+    database::gi::connections::set(&connection, &**pool).await?;
 
     Ok(HttpResponse::Ok().finish())
 }
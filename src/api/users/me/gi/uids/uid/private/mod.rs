@@ -51,7 +51,7 @@ async fn put_user_gi_uid_private(
         return Ok(HttpResponse::Forbidden().finish());
     }
 
-    database::gi::connections::update_private_by_uid_and_username(*uid, &username, true, &pool)
+    database::gi::connections::update_private_by_uid_and_username(*uid, &username, true, &**pool)
         .await?;
 
     Ok(HttpResponse::Ok().finish())
@@ -87,7 +87,7 @@ async fn delete_user_gi_uid_private(
         return Ok(HttpResponse::Forbidden().finish());
     }
 
-    database::gi::connections::update_private_by_uid_and_username(*uid, &username, false, &pool)
+    database::gi::connections::update_private_by_uid_and_username(*uid, &username, false, &**pool)
         .await?;
 
     Ok(HttpResponse::Ok().finish())
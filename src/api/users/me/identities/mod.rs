@@ -0,0 +1,63 @@
+mod provider;
+
+use actix_session::Session;
+use actix_web::{get, web, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{api::ApiResult, database};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/identities")),
+    paths(get_identities),
+    components(schemas(Identity))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(provider::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_identities).configure(provider::configure);
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct Identity {
+    provider: String,
+    email: Option<String>,
+    created: DateTime<Utc>,
+}
+
+#[utoipa::path(
+    tag = "users/me/identities",
+    get,
+    path = "/api/users/me/identities",
+    responses(
+        (status = 200, description = "Linked OIDC identities", body = Vec<Identity>),
+        (status = 400, description = "Not logged in"),
+    )
+)]
+#[get("/api/users/me/identities")]
+async fn get_identities(session: Session, pool: web::Data<PgPool>) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let identities: Vec<_> = database::users_identities::get_by_username(&username, &pool)
+        .await?
+        .into_iter()
+        .map(|i| Identity {
+            provider: i.provider,
+            email: i.email,
+            created: i.created,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(identities))
+}
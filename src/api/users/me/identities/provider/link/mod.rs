@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use actix_session::Session;
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::{IntoParams, OpenApi};
+
+use crate::{
+    api::{users::oidc, ApiResult},
+    app_config::AppConfig,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/identities/{provider}/link")),
+    paths(get_link_identity)
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_link_identity);
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct LinkParams {
+    /// Path on the frontend to return to. `oidc=linked` or `oidc_error=taken` is appended
+    redirect: Option<String>,
+}
+
+#[utoipa::path(
+    tag = "users/me/identities/{provider}/link",
+    get,
+    path = "/api/users/me/identities/{provider}/link",
+    params(
+        ("provider" = String, Path, description = "Provider name"),
+        LinkParams
+    ),
+    responses(
+        (status = 302, description = "Redirect to the provider. Navigate the browser here instead of fetching it"),
+        (status = 400, description = "Not logged in"),
+        (status = 404, description = "Unknown provider"),
+    )
+)]
+#[get("/api/users/me/identities/{provider}/link")]
+async fn get_link_identity(
+    session: Session,
+    provider: web::Path<String>,
+    link_params: web::Query<LinkParams>,
+    app_config: web::Data<Arc<AppConfig>>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let Some(provider) = oidc::provider(&provider, &app_config) else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    let redirect = oidc::sanitize_redirect(link_params.redirect.as_deref());
+
+    let (url, state) =
+        oidc::authorize(provider, Some(&username), &redirect, &app_config, &pool).await?;
+
+    Ok(oidc::to_provider(url, state))
+}
@@ -0,0 +1,64 @@
+mod link;
+
+use actix_session::Session;
+use actix_web::{delete, web, HttpResponse, Responder};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+
+use crate::{api::ApiResult, database};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/identities/{provider}")),
+    paths(delete_identity)
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(link::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(delete_identity).configure(link::configure);
+}
+
+#[utoipa::path(
+    tag = "users/me/identities/{provider}",
+    delete,
+    path = "/api/users/me/identities/{provider}",
+    params(("provider" = String, Path, description = "Provider name")),
+    responses(
+        (status = 200, description = "Unlinked the identity"),
+        (status = 400, description = "Not logged in"),
+        (status = 404, description = "Not linked"),
+        (status = 409, description = "The account has no password and no other identity to log in with"),
+    )
+)]
+#[delete("/api/users/me/identities/{provider}")]
+async fn delete_identity(
+    session: Session,
+    provider: web::Path<String>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let user = database::users::get_one_by_username(&username, &pool).await?;
+    let identities = database::users_identities::get_by_username(&username, &pool).await?;
+
+    if !identities.iter().any(|i| i.provider == *provider) {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    if user.password.is_empty() && identities.len() == 1 {
+        return Ok(HttpResponse::Conflict().finish());
+    }
+
+    database::users_identities::delete_by_provider_and_username(&provider, &username, &pool)
+        .await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -1,19 +1,21 @@
-use actix_session::Session;
 use actix_web::{put, web, HttpResponse, Responder};
+use chrono::{DateTime, Duration, Utc};
 use serde::Deserialize;
 use sqlx::PgPool;
-use utoipa::{OpenApi, ToSchema};
+use utoipa::{IntoParams, OpenApi};
 
-use crate::{api::ApiResult, database};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        export::{Export, Signal, SignalType, UserExport, Warp, WarpType, Wish, WishType},
+        users::tokens::{self, Purpose},
+        ApiResult,
+    },
+    database, mihomo, signing, Language,
+};
 
 #[derive(OpenApi)]
-#[openapi(
-    tags((name = "users/me/import")),
-    paths(import),
-    components(schemas(
-        ImportData,
-    ))
-)]
+#[openapi(tags((name = "users/me/import")), paths(import))]
 struct ApiDoc;
 
 pub fn openapi() -> utoipa::openapi::OpenApi {
@@ -24,59 +26,551 @@ pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(import);
 }
 
-#[derive(Deserialize, ToSchema)]
-struct ImportData {
-    hsr_achievements: Option<Vec<i32>>,
-    gi_achievements: Option<Vec<i32>>,
+fn warps_set_all(uid: i32, warps: &[Warp]) -> anyhow::Result<database::warps::SetAll> {
+    let mut set_all = database::warps::SetAll::default();
+
+    for warp in warps {
+        set_all.id.push(warp.id.parse()?);
+        set_all.uid.push(uid);
+        set_all
+            .character
+            .push(matches!(warp.r#type, WarpType::Character).then_some(warp.item_id));
+        set_all
+            .light_cone
+            .push(matches!(warp.r#type, WarpType::LightCone).then_some(warp.item_id));
+        set_all.timestamp.push(warp.timestamp);
+        set_all.official.push(warp.official);
+    }
+
+    Ok(set_all)
+}
+
+fn signals_set_all(uid: i32, signals: &[Signal]) -> anyhow::Result<database::zzz::signals::SetAll> {
+    let mut set_all = database::zzz::signals::SetAll::default();
+
+    for signal in signals {
+        set_all.id.push(signal.id.parse()?);
+        set_all.uid.push(uid);
+        set_all
+            .character
+            .push(matches!(signal.r#type, SignalType::Character).then_some(signal.item_id));
+        set_all
+            .w_engine
+            .push(matches!(signal.r#type, SignalType::WEngine).then_some(signal.item_id));
+        set_all
+            .bangboo
+            .push(matches!(signal.r#type, SignalType::Bangboo).then_some(signal.item_id));
+        set_all.timestamp.push(signal.timestamp);
+        set_all.official.push(signal.official);
+    }
+
+    Ok(set_all)
+}
+
+fn wishes_set_all(uid: i32, wishes: &[Wish]) -> anyhow::Result<database::gi::wishes::SetAll> {
+    let mut set_all = database::gi::wishes::SetAll::default();
+
+    for wish in wishes {
+        set_all.id.push(wish.id.parse()?);
+        set_all.uid.push(uid);
+        set_all
+            .character
+            .push(matches!(wish.r#type, WishType::Character).then_some(wish.item_id));
+        set_all
+            .weapon
+            .push(matches!(wish.r#type, WishType::Weapon).then_some(wish.item_id));
+        set_all.timestamp.push(wish.timestamp);
+        set_all.official.push(wish.official);
+    }
+
+    Ok(set_all)
+}
+
+/// Also the lifetime of the final export of a deleted account
+const MAX_EXPORT_AGE_DAYS: i64 = 30;
+
+#[derive(Deserialize, IntoParams)]
+struct ImportParams {
+    /// From `/api/users/me/merge/token`, created while logged into the exported account. Only
+    /// needed to keep verifications of another account or an old export
+    token: Option<String>,
+}
+
+/// Anyone with an export can restore it, but its verifications are only kept when the caller
+/// owns the exported account. That is either the same user with a fresh export, or someone with a
+/// merge token of that account. Exports from before the issue time was signed count as too old.
+fn owns_export(user: &UserExport, username: &str, now: DateTime<Utc>) -> bool {
+    user.username == username
+        && user
+            .issued_at
+            .is_some_and(|issued_at| now - issued_at <= Duration::days(MAX_EXPORT_AGE_DAYS))
 }
 
 #[utoipa::path(
     tag = "users/me/import",
     put,
     path = "/api/users/me/import",
-    request_body = ImportData,
+    params(ImportParams),
+    request_body = Export,
     responses(
-        (status = 200, description = "Successfully imported"),
-        (status = 400, description = "Not logged in"),
+        (status = 200, description = "Successfully imported. Verifications are only kept for the owner of the export"),
+        (status = 400, description = "Not logged in or invalid token"),
+        (status = 403, description = "Invalid signature"),
     )
 )]
 #[put("/api/users/me/import")]
 async fn import(
-    session: Session,
-    import_data: web::Json<ImportData>,
+    auth: Auth,
+    params: web::Query<ImportParams>,
+    export: web::Json<Export>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::Restore) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
-    if let Some(achievements) = &import_data.hsr_achievements {
-        database::users_achievements_completed::delete_by_username(&username, &pool).await?;
+    let Export {
+        user,
+        signature,
+        key_id,
+    } = export.into_inner();
+
+    let user_bytes = serde_json::to_vec(&user)?;
+    if signing::verify(&user_bytes, &signature, key_id.as_deref(), &pool)
+        .await?
+        .is_none()
+    {
+        return Ok(HttpResponse::Forbidden().finish());
+    }
+
+    let keep_verified = if let Some(token) = &params.token {
+        let Some(token) = tokens::consume(token, Purpose::Merge, &pool).await? else {
+            return Ok(HttpResponse::BadRequest().finish());
+        };
+
+        token.username == user.username
+    } else {
+        owns_export(&user, &username, Utc::now())
+    };
+
+    let mut tx = pool.begin().await?;
+
+    // Everything a connection references has to exist. Missing profiles are filled in with
+    // placeholders and looked up after the commit.
+    let mut new_hsr_uids = Vec::new();
+    for uid in user.hsr.uids.iter().map(|hsr_uid| hsr_uid.uid) {
+        if !database::mihomo::exists(uid, &mut *tx).await? {
+            let region = match uid.to_string().chars().next() {
+                Some('6') => "na",
+                Some('7') => "eu",
+                Some('8') | Some('9') => "asia",
+                _ => "cn",
+            }
+            .to_string();
+
+            let db_mihomo = database::mihomo::DbMihomo {
+                uid,
+                region,
+                ..Default::default()
+            };
+
+            database::mihomo::set(&db_mihomo, &mut *tx).await?;
+            new_hsr_uids.push(uid);
+        }
+    }
+
+    for uid in user.zzz.uids.iter().map(|zzz_uid| zzz_uid.uid) {
+        database::zzz::uids::set(&database::zzz::uids::DbUid { uid }, &mut *tx).await?;
+    }
+
+    for uid in user.gi.uids.iter().map(|gi_uid| gi_uid.uid) {
+        if database::gi::profiles::get_by_uid(uid, &mut *tx)
+            .await?
+            .is_none()
+        {
+            let profile = database::gi::profiles::DbProfile {
+                uid,
+                name: String::new(),
+            };
+
+            database::gi::profiles::set(&profile, &mut *tx).await?;
+        }
+    }
+
+    {
+        let hsr = user.hsr;
+
+        database::users_achievements_completed::delete_by_username(&username, &mut *tx).await?;
         let mut achievement_completed =
             database::users_achievements_completed::DbUserAchievementCompleted {
                 username: username.clone(),
                 id: 0,
             };
-        for &achievement in achievements {
+        for achievement in hsr.achievements {
+            achievement_completed.id = achievement;
+
+            database::users_achievements_completed::add(&achievement_completed, true, &mut *tx)
+                .await?;
+        }
+
+        for completion in hsr.completions.into_iter().flatten() {
+            let completed_at =
+                database::users_achievements_completed::DbUserAchievementCompletedAt {
+                    id: completion.id,
+                    completed_at: completion.completed_at,
+                    imported: completion.imported,
+                };
+
+            database::users_achievements_completed::set_completed_at(
+                &username,
+                &completed_at,
+                &mut *tx,
+            )
+            .await?;
+        }
+
+        if let Some(favorites) = hsr.favorites {
+            database::users_achievements_favorites::delete_by_username(&username, &mut *tx).await?;
+            let mut achievement_favorite =
+                database::users_achievements_favorites::DbUserAchievementFavorite {
+                    username: username.clone(),
+                    id: 0,
+                };
+            for achievement in favorites {
+                achievement_favorite.id = achievement;
+
+                database::users_achievements_favorites::add(&achievement_favorite, &mut *tx)
+                    .await?;
+            }
+        }
+
+        let connections = database::connections::get_by_username(&username, &pool).await?;
+
+        for hsr_uid in hsr.uids {
+            let uid = hsr_uid.uid;
+
+            let verified = keep_verified && hsr_uid.verified
+                || connections
+                    .iter()
+                    .find(|c| c.uid == uid)
+                    .map(|c| c.verified)
+                    .unwrap_or_default();
+
+            let connection = database::connections::DbConnection {
+                uid,
+                username: username.clone(),
+                verified,
+                private: hsr_uid.private,
+            };
+            database::connections::set(&connection, &mut *tx).await?;
+            database::connections::update_private_by_uid_and_username(
+                uid,
+                &username,
+                hsr_uid.private,
+                &mut *tx,
+            )
+            .await?;
+
+            let warps = hsr_uid.warps;
+
+            database::warps::departure::set_all(&warps_set_all(uid, &warps.departure)?, &mut *tx)
+                .await?;
+            database::warps::standard::set_all(&warps_set_all(uid, &warps.standard)?, &mut *tx)
+                .await?;
+            database::warps::special::set_all(&warps_set_all(uid, &warps.character)?, &mut *tx)
+                .await?;
+            database::warps::lc::set_all(&warps_set_all(uid, &warps.light_cone)?, &mut *tx).await?;
+
+            if let Some(collab) = warps.collab {
+                database::warps::collab::set_all(&warps_set_all(uid, &collab)?, &mut *tx).await?;
+            }
+
+            if let Some(collab_lc) = warps.collab_lc {
+                database::warps::collab_lc::set_all(&warps_set_all(uid, &collab_lc)?, &mut *tx)
+                    .await?;
+            }
+        }
+    }
+
+    {
+        let zzz = user.zzz;
+
+        database::zzz::users_achievements_completed::delete_by_username(&username, &mut *tx)
+            .await?;
+        let mut achievement_completed =
+            database::zzz::users_achievements_completed::DbUserAchievementCompleted {
+                username: username.clone(),
+                id: 0,
+            };
+        for achievement in zzz.achievements {
             achievement_completed.id = achievement;
 
-            database::users_achievements_completed::add(&achievement_completed, &pool).await?;
+            database::zzz::users_achievements_completed::add(
+                &achievement_completed,
+                true,
+                &mut *tx,
+            )
+            .await?;
+        }
+
+        for completion in zzz.completions.into_iter().flatten() {
+            let completed_at =
+                database::zzz::users_achievements_completed::DbUserAchievementCompletedAt {
+                    id: completion.id,
+                    completed_at: completion.completed_at,
+                    imported: completion.imported,
+                };
+
+            database::zzz::users_achievements_completed::set_completed_at(
+                &username,
+                &completed_at,
+                &mut *tx,
+            )
+            .await?;
+        }
+
+        if let Some(favorites) = zzz.favorites {
+            database::zzz::users_achievements_favorites::delete_by_username(&username, &mut *tx)
+                .await?;
+            let mut achievement_favorite =
+                database::zzz::users_achievements_favorites::DbUserAchievementFavorite {
+                    username: username.clone(),
+                    id: 0,
+                };
+            for achievement in favorites {
+                achievement_favorite.id = achievement;
+
+                database::zzz::users_achievements_favorites::add(&achievement_favorite, &mut *tx)
+                    .await?;
+            }
+        }
+
+        let connections = database::zzz::connections::get_by_username(&username, &pool).await?;
+
+        for zzz_uid in zzz.uids {
+            let uid = zzz_uid.uid;
+
+            let verified = keep_verified && zzz_uid.verified
+                || connections
+                    .iter()
+                    .find(|c| c.uid == uid)
+                    .map(|c| c.verified)
+                    .unwrap_or_default();
+
+            let connection = database::zzz::connections::DbConnection {
+                uid,
+                username: username.clone(),
+                verified,
+                private: zzz_uid.private,
+            };
+            database::zzz::connections::set(&connection, &mut *tx).await?;
+            database::zzz::connections::update_private_by_uid_and_username(
+                uid,
+                &username,
+                zzz_uid.private,
+                &mut *tx,
+            )
+            .await?;
+
+            let signals = zzz_uid.signals;
+
+            database::zzz::signals::standard::set_all(
+                &signals_set_all(uid, &signals.standard)?,
+                &mut *tx,
+            )
+            .await?;
+            database::zzz::signals::special::set_all(
+                &signals_set_all(uid, &signals.character)?,
+                &mut *tx,
+            )
+            .await?;
+            database::zzz::signals::w_engine::set_all(
+                &signals_set_all(uid, &signals.w_engine)?,
+                &mut *tx,
+            )
+            .await?;
+            database::zzz::signals::bangboo::set_all(
+                &signals_set_all(uid, &signals.bangboo)?,
+                &mut *tx,
+            )
+            .await?;
+
+            if let Some(exclusive_rescreening) = signals.exclusive_rescreening {
+                database::zzz::signals::exclusive_rescreening::set_all(
+                    &signals_set_all(uid, &exclusive_rescreening)?,
+                    &mut *tx,
+                )
+                .await?;
+            }
+
+            if let Some(w_engine_reverberation) = signals.w_engine_reverberation {
+                database::zzz::signals::w_engine_reverberation::set_all(
+                    &signals_set_all(uid, &w_engine_reverberation)?,
+                    &mut *tx,
+                )
+                .await?;
+            }
         }
     }
 
-    if let Some(achievements) = &import_data.gi_achievements {
-        database::gi::users_achievements_completed::delete_by_username(&username, &pool).await?;
+    {
+        let gi = user.gi;
+
+        database::gi::users_achievements_completed::delete_by_username(&username, &mut *tx).await?;
         let mut achievement_completed =
             database::gi::users_achievements_completed::DbUserAchievementCompleted {
                 username: username.clone(),
                 id: 0,
             };
-        for &achievement in achievements {
+        for achievement in gi.achievements {
             achievement_completed.id = achievement;
 
-            database::gi::users_achievements_completed::add(&achievement_completed, &pool).await?;
+            database::gi::users_achievements_completed::add(&achievement_completed, true, &mut *tx)
+                .await?;
+        }
+
+        for completion in gi.completions.into_iter().flatten() {
+            let completed_at =
+                database::gi::users_achievements_completed::DbUserAchievementCompletedAt {
+                    id: completion.id,
+                    completed_at: completion.completed_at,
+                    imported: completion.imported,
+                };
+
+            database::gi::users_achievements_completed::set_completed_at(
+                &username,
+                &completed_at,
+                &mut *tx,
+            )
+            .await?;
+        }
+
+        if let Some(favorites) = gi.favorites {
+            database::gi::users_achievements_favorites::delete_by_username(&username, &mut *tx)
+                .await?;
+            let mut achievement_favorite =
+                database::gi::users_achievements_favorites::DbUserAchievementFavorite {
+                    username: username.clone(),
+                    id: 0,
+                };
+            for achievement in favorites {
+                achievement_favorite.id = achievement;
+
+                database::gi::users_achievements_favorites::add(&achievement_favorite, &mut *tx)
+                    .await?;
+            }
+        }
+
+        let connections = database::gi::connections::get_by_username(&username, &pool).await?;
+
+        for gi_uid in gi.uids {
+            let uid = gi_uid.uid;
+
+            let verified = keep_verified && gi_uid.verified
+                || connections
+                    .iter()
+                    .find(|c| c.uid == uid)
+                    .map(|c| c.verified)
+                    .unwrap_or_default();
+
+            let connection = database::gi::connections::DbConnection {
+                uid,
+                username: username.clone(),
+                verified,
+                private: gi_uid.private,
+            };
+            database::gi::connections::set(&connection, &mut *tx).await?;
+            database::gi::connections::update_private_by_uid_and_username(
+                uid,
+                &username,
+                gi_uid.private,
+                &mut *tx,
+            )
+            .await?;
+
+            let wishes = gi_uid.wishes;
+
+            database::gi::wishes::beginner::set_all(
+                &wishes_set_all(uid, &wishes.beginner)?,
+                &mut *tx,
+            )
+            .await?;
+            database::gi::wishes::standard::set_all(
+                &wishes_set_all(uid, &wishes.standard)?,
+                &mut *tx,
+            )
+            .await?;
+            database::gi::wishes::character::set_all(
+                &wishes_set_all(uid, &wishes.character)?,
+                &mut *tx,
+            )
+            .await?;
+            database::gi::wishes::weapon::set_all(&wishes_set_all(uid, &wishes.weapon)?, &mut *tx)
+                .await?;
+            database::gi::wishes::chronicled::set_all(
+                &wishes_set_all(uid, &wishes.chronicled)?,
+                &mut *tx,
+            )
+            .await?;
+        }
+    }
+
+    tx.commit().await?;
+
+    for uid in new_hsr_uids {
+        if let Err(e) = mihomo::get(uid, Language::En, &pool).await {
+            warn!("Looking up restored uid {uid} failed with {e}");
         }
     }
 
     Ok(HttpResponse::Ok().finish())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::export::{Gi, Hsr, Zzz};
+
+    fn user_export(username: &str, issued_at: Option<DateTime<Utc>>) -> UserExport {
+        UserExport {
+            username: username.to_string(),
+            issued_at,
+            hsr: Hsr {
+                achievements: Vec::new(),
+                favorites: None,
+                uids: Vec::new(),
+                completions: None,
+            },
+            zzz: Zzz {
+                achievements: Vec::new(),
+                favorites: None,
+                uids: Vec::new(),
+                completions: None,
+            },
+            gi: Gi {
+                achievements: Vec::new(),
+                favorites: None,
+                uids: Vec::new(),
+                completions: None,
+            },
+        }
+    }
+
+    #[test]
+    fn only_the_owner_of_a_fresh_export_keeps_verifications() {
+        let now = Utc::now();
+
+        assert!(owns_export(&user_export("alice", Some(now)), "alice", now));
+        assert!(!owns_export(
+            &user_export("alice", Some(now)),
+            "mallory",
+            now
+        ));
+        assert!(!owns_export(&user_export("alice", None), "alice", now));
+        assert!(!owns_export(
+            &user_export("alice", Some(now - Duration::days(MAX_EXPORT_AGE_DAYS + 1))),
+            "alice",
+            now
+        ));
+    }
+}
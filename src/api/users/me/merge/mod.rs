@@ -0,0 +1,297 @@
+mod token;
+
+use actix_session::Session;
+use actix_web::{post, web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    api::{
+        users::tokens::{self, Purpose},
+        ApiResult,
+    },
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/merge")),
+    paths(post_merge),
+    components(schemas(Merge, MergeReport, MergeCounts))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(token::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_merge).configure(token::configure);
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct Merge {
+    /// From `/api/users/me/merge/token`, created while logged into the other account
+    token: String,
+}
+
+/// What happened to the rows of the merged account
+#[derive(Serialize, ToSchema)]
+pub struct MergeCounts {
+    /// New to this account
+    moved: i64,
+    /// This account already had them, combined without losing anything
+    merged: i64,
+    /// Conflicted with this account, which won
+    dropped: i64,
+}
+
+impl From<database::users_merges::DbMergeCounts> for MergeCounts {
+    fn from(counts: database::users_merges::DbMergeCounts) -> Self {
+        Self {
+            moved: counts.moved,
+            merged: counts.merged,
+            dropped: counts.dropped,
+        }
+    }
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MergeReport {
+    /// The account that was merged and deleted
+    merged_username: String,
+    achievements_completed: MergeCounts,
+    achievements_favorites: MergeCounts,
+    gi_achievements_completed: MergeCounts,
+    gi_achievements_favorites: MergeCounts,
+    zzz_achievements_completed: MergeCounts,
+    zzz_achievements_favorites: MergeCounts,
+    /// Uids both are connected to keep the strongest verification
+    uids: MergeCounts,
+    gi_uids: MergeCounts,
+    zzz_uids: MergeCounts,
+    ntehelper_completions: MergeCounts,
+    /// Namespaces both have keep this account's settings
+    ntehelper_settings: MergeCounts,
+    ntehelper_marker_comments: MergeCounts,
+    ntehelper_marker_comment_votes: MergeCounts,
+    ntehelper_tracker_claims: MergeCounts,
+    /// OIDC identities. Only one per provider is kept
+    identities: MergeCounts,
+    /// Whether this account had no email and took over the merged one's
+    email: bool,
+}
+
+#[utoipa::path(
+    tag = "users/me/merge",
+    post,
+    path = "/api/users/me/merge",
+    request_body = Merge,
+    responses(
+        (status = 200, description = "Merged the other account into this one and deleted it", body = MergeReport),
+        (status = 400, description = "Not logged in, invalid token or the token is for this account"),
+    )
+)]
+#[post("/api/users/me/merge")]
+async fn post_merge(
+    session: Session,
+    merge: web::Json<Merge>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let Some(token) = tokens::consume(&merge.token, Purpose::Merge, &pool).await? else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    if token.username == username {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    let report = database::users_merges::merge(&token.username, &username, &pool).await?;
+
+    info!("Merged {} into {username}", token.username);
+
+    Ok(HttpResponse::Ok().json(MergeReport {
+        merged_username: token.username,
+        achievements_completed: report.achievements_completed.into(),
+        achievements_favorites: report.achievements_favorites.into(),
+        gi_achievements_completed: report.gi_achievements_completed.into(),
+        gi_achievements_favorites: report.gi_achievements_favorites.into(),
+        zzz_achievements_completed: report.zzz_achievements_completed.into(),
+        zzz_achievements_favorites: report.zzz_achievements_favorites.into(),
+        uids: report.connections.into(),
+        gi_uids: report.gi_connections.into(),
+        zzz_uids: report.zzz_connections.into(),
+        ntehelper_completions: report.ntehelper_completions.into(),
+        ntehelper_settings: report.ntehelper_settings.into(),
+        ntehelper_marker_comments: report.ntehelper_marker_comments.into(),
+        ntehelper_marker_comment_votes: report.ntehelper_marker_comment_votes.into(),
+        ntehelper_tracker_claims: report.ntehelper_tracker_claims.into(),
+        identities: report.identities.into(),
+        email: report.email,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::*;
+    use crate::test_util::{self, test_pool};
+
+    async fn create_test_user(email: Option<&str>, pool: &PgPool) -> String {
+        match email {
+            Some(email) => {
+                test_util::create_test_user_with_email("merge_test", email, true, pool).await
+            }
+            None => test_util::create_test_user("merge_test", pool).await,
+        }
+    }
+
+    async fn create_test_uid(pool: &PgPool) -> i32 {
+        let uid = 900_000_000 + (Uuid::new_v4().as_u128() % 90_000_000) as i32;
+        database::mihomo::set(
+            &database::mihomo::DbMihomo {
+                uid,
+                region: "eu".to_string(),
+                ..Default::default()
+            },
+            pool,
+        )
+        .await
+        .unwrap();
+        uid
+    }
+
+    async fn connect(uid: i32, username: &str, verified: bool, pool: &PgPool) {
+        let connection = database::connections::DbConnection {
+            uid,
+            username: username.to_string(),
+            verified,
+            private: false,
+        };
+        database::connections::set(&connection, pool).await.unwrap();
+    }
+
+    fn completion(id: &str) -> database::ntehelper::DbCompletion {
+        database::ntehelper::DbCompletion {
+            kind: "achievement".to_string(),
+            id: id.to_string(),
+        }
+    }
+
+    #[actix_web::test]
+    async fn merging_unions_and_reports_conflicts() {
+        let pool = test_pool().await;
+
+        let into = create_test_user(None, &pool).await;
+        let from = create_test_user(Some("merge@example.com"), &pool).await;
+
+        let shared_uid = create_test_uid(&pool).await;
+        let other_uid = create_test_uid(&pool).await;
+        connect(shared_uid, &into, false, &pool).await;
+        connect(shared_uid, &from, true, &pool).await;
+        connect(other_uid, &from, false, &pool).await;
+
+        database::ntehelper::patch_completions(
+            &into,
+            &[completion("a"), completion("b")],
+            &[],
+            &pool,
+        )
+        .await
+        .unwrap();
+        database::ntehelper::patch_completions(
+            &from,
+            &[completion("b"), completion("c")],
+            &[],
+            &pool,
+        )
+        .await
+        .unwrap();
+        database::ntehelper::set_setting(&into, "map", &json!({"kept": true}), &pool)
+            .await
+            .unwrap();
+        database::ntehelper::set_setting(&from, "map", &json!({"kept": false}), &pool)
+            .await
+            .unwrap();
+
+        let subject = Uuid::new_v4().to_string();
+        database::users_identities::set("discord", &subject, &into, None, &pool)
+            .await
+            .unwrap();
+        let subject = Uuid::new_v4().to_string();
+        database::users_identities::set("discord", &subject, &from, None, &pool)
+            .await
+            .unwrap();
+        let subject = Uuid::new_v4().to_string();
+        database::users_identities::set("google", &subject, &from, None, &pool)
+            .await
+            .unwrap();
+
+        let report = database::users_merges::merge(&from, &into, &pool)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            (report.connections.moved, report.connections.merged),
+            (1, 1)
+        );
+        assert_eq!(
+            (
+                report.ntehelper_completions.moved,
+                report.ntehelper_completions.merged
+            ),
+            (1, 1)
+        );
+        assert_eq!(report.ntehelper_settings.dropped, 1);
+        assert_eq!((report.identities.moved, report.identities.dropped), (1, 1));
+        assert!(report.email);
+
+        let connections = database::connections::get_by_username(&into, &pool)
+            .await
+            .unwrap();
+        assert_eq!(connections.len(), 2);
+        assert!(
+            connections
+                .iter()
+                .find(|c| c.uid == shared_uid)
+                .unwrap()
+                .verified
+        );
+
+        let completions = database::ntehelper::get_completions(&into, &pool)
+            .await
+            .unwrap();
+        assert_eq!(completions.len(), 3);
+
+        let settings = database::ntehelper::get_settings(&into, &pool)
+            .await
+            .unwrap();
+        assert_eq!(settings[0].data, json!({"kept": true}));
+
+        let user = database::users::get_one_by_username(&into, &pool)
+            .await
+            .unwrap();
+        assert_eq!(user.email.as_deref(), Some("merge@example.com"));
+
+        assert!(database::users::get_one_by_username(&from, &pool)
+            .await
+            .is_err());
+
+        for uid in [shared_uid, other_uid] {
+            sqlx::query("DELETE FROM mihomo WHERE uid = $1")
+                .bind(uid)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+    }
+}
@@ -0,0 +1,55 @@
+use actix_session::Session;
+use actix_web::{post, web, HttpResponse, Responder};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::api::{
+    users::tokens::{self, Purpose},
+    ApiResult,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/merge/token")),
+    paths(post_merge_token),
+    components(schemas(MergeToken))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_merge_token);
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct MergeToken {
+    /// Valid for 10 minutes. Replaces any previous one
+    token: String,
+}
+
+#[utoipa::path(
+    tag = "users/me/merge/token",
+    post,
+    path = "/api/users/me/merge/token",
+    responses(
+        (status = 200, description = "Token for merging this account into the one that redeems it, or for importing its export there with verifications", body = MergeToken),
+        (status = 400, description = "Not logged in"),
+    )
+)]
+#[post("/api/users/me/merge/token")]
+async fn post_merge_token(session: Session, pool: web::Data<PgPool>) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    // Merge tokens aren't mailed, so there's always one
+    let Some(token) = tokens::create(&username, Purpose::Merge, None, &pool).await? else {
+        return Ok(HttpResponse::InternalServerError().finish());
+    };
+
+    Ok(HttpResponse::Ok().json(MergeToken { token }))
+}
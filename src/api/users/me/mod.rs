@@ -1,42 +1,69 @@
+mod access_tokens;
 mod achievements;
 mod email;
-mod export;
+pub(super) mod export;
+mod export_srgf;
 mod export_uigf;
+mod export_uigf_v3;
 mod gi;
+mod identities;
 mod import;
+mod merge;
 mod password;
+mod sessions;
+mod totp;
 mod uids;
 mod username;
 mod zzz;
 
+use std::sync::Arc;
+
 use actix_session::Session;
-use actix_web::{get, web, HttpResponse, Responder};
-use serde::Serialize;
+use actix_web::{delete, get, web, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use utoipa::{OpenApi, ToSchema};
 
-use crate::{api::ApiResult, database};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        users::deletions,
+        ApiResult,
+    },
+    app_config::AppConfig,
+    database, mail,
+};
 
 #[derive(OpenApi)]
 #[openapi(
     tags((name = "users/me")),
-    paths(get_me),
+    paths(get_me, delete_me),
     components(schemas(
         Uid,
         User,
+        UserDelete,
+        DeletionScheduled,
     ))
 )]
 struct ApiDoc;
 
 pub fn openapi() -> utoipa::openapi::OpenApi {
     let mut openapi = ApiDoc::openapi();
+    openapi.merge(access_tokens::openapi());
     openapi.merge(achievements::openapi());
     openapi.merge(email::openapi());
     openapi.merge(export::openapi());
+    openapi.merge(export_srgf::openapi());
     openapi.merge(export_uigf::openapi());
+    openapi.merge(export_uigf_v3::openapi());
     openapi.merge(gi::openapi());
+    openapi.merge(identities::openapi());
     openapi.merge(import::openapi());
+    openapi.merge(merge::openapi());
     openapi.merge(password::openapi());
+    openapi.merge(sessions::openapi());
+    openapi.merge(totp::openapi());
     openapi.merge(uids::openapi());
     openapi.merge(username::openapi());
     openapi.merge(zzz::openapi());
@@ -45,13 +72,21 @@ pub fn openapi() -> utoipa::openapi::OpenApi {
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.service(get_me)
+        .service(delete_me)
+        .configure(access_tokens::configure)
         .configure(achievements::configure)
         .configure(email::configure)
         .configure(export::configure)
+        .configure(export_srgf::configure)
         .configure(export_uigf::configure)
+        .configure(export_uigf_v3::configure)
         .configure(gi::configure)
+        .configure(identities::configure)
         .configure(import::configure)
+        .configure(merge::configure)
         .configure(password::configure)
+        .configure(sessions::configure)
+        .configure(totp::configure)
         .configure(uids::configure)
         .configure(username::configure)
         .configure(zzz::configure);
@@ -69,6 +104,12 @@ pub struct User {
     username: String,
     admin: bool,
     email: Option<String>,
+    email_verified: bool,
+    /// Accounts registered through OIDC start without one
+    password: bool,
+    totp: bool,
+    /// When the account will be deleted. Logging in cancels it
+    deletion_scheduled: Option<DateTime<Utc>>,
     uids: Vec<Uid>,
     zzz_uids: Vec<Uid>,
     gi_uids: Vec<Uid>,
@@ -86,18 +127,25 @@ pub struct User {
     )
 )]
 #[get("/api/users/me")]
-async fn get_me(session: Session, pool: web::Data<PgPool>) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+async fn get_me(auth: Auth, pool: web::Data<PgPool>) -> ApiResult<impl Responder> {
+    let Some(username) = auth.username(Scope::ReadPrivate) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
-    //session.renew();
-
     let admin = database::admins::exists(&username, &pool).await?;
 
     let user = database::users::get_one_by_username(&username, &pool).await?;
 
     let email = user.email;
+    let email_verified = user.email_verified;
+    let password = !user.password.is_empty();
+
+    let totp = database::users_totp::get_one_by_username(&username, &pool)
+        .await?
+        .is_some_and(|user_totp| user_totp.enabled);
+
+    let deletion_scheduled =
+        database::users_deletions::get_scheduled_by_username(&username, &pool).await?;
 
     let uids = database::connections::get_by_username(&username, &pool)
         .await?
@@ -146,6 +194,10 @@ async fn get_me(session: Session, pool: web::Data<PgPool>) -> ApiResult<impl Res
         username,
         admin,
         email,
+        email_verified,
+        password,
+        totp,
+        deletion_scheduled,
         uids,
         zzz_uids,
         gi_uids,
@@ -155,3 +207,81 @@ async fn get_me(session: Session, pool: web::Data<PgPool>) -> ApiResult<impl Res
 
     Ok(HttpResponse::Ok().json(user))
 }
+
+#[derive(Deserialize, ToSchema)]
+pub struct UserDelete {
+    /// Ignored if the account has no password
+    #[serde(default)]
+    password: String,
+    /// Also delete the pulls of uids no one else is verified on
+    #[serde(default)]
+    delete_pulls: bool,
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct DeletionScheduled {
+    scheduled: DateTime<Utc>,
+    /// Downloads the final export at `/api/users/deleted-export` once the account is deleted.
+    /// Only shown once
+    export_token: String,
+}
+
+#[utoipa::path(
+    tag = "users/me",
+    delete,
+    path = "/api/users/me",
+    request_body = UserDelete,
+    responses(
+        (status = 202, description = "Deletion scheduled. All sessions are logged out and logging in again cancels it", body = DeletionScheduled),
+        (status = 400, description = "Not logged in or wrong password"),
+    )
+)]
+#[delete("/api/users/me")]
+async fn delete_me(
+    session: Session,
+    user_delete: web::Json<UserDelete>,
+    app_config: web::Data<Arc<AppConfig>>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let user = database::users::get_one_by_username(&username, &pool).await?;
+
+    // Accounts registered through OIDC may not have a password
+    if !user.password.is_empty()
+        && !argon2::verify_encoded(&user.password, user_delete.password.as_bytes())
+            .unwrap_or_default()
+    {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    let (scheduled, export_token) =
+        deletions::schedule(&username, user_delete.delete_pulls, &pool).await?;
+
+    database::sessions::delete_by_username_except_id(&username, None, &pool).await?;
+    session.purge();
+
+    if let Some(email) = &user.email {
+        if let Err(e) = mail::send(
+            &format!("{username} <{email}>"),
+            "StarDB.GG Account Deletion",
+            format!(
+                "Your account will be deleted on {}. Log in before then to cancel.\nAfterwards, your data can be downloaded for 30 days at:\n{}/deleted-export?token={export_token}",
+                scheduled.format("%Y-%m-%d %H:%M UTC"),
+                app_config.public_url
+            ),
+            &app_config,
+        )
+        .await
+        {
+            error!("Sending the deletion mail to {username} failed with {e}");
+        }
+    }
+
+    Ok(HttpResponse::Accepted().json(DeletionScheduled {
+        scheduled,
+        export_token,
+    }))
+}
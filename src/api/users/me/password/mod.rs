@@ -6,7 +6,10 @@ use serde::Deserialize;
 use sqlx::PgPool;
 use utoipa::{OpenApi, ToSchema};
 
-use crate::{api::ApiResult, database};
+use crate::{
+    api::{users::sessions, ApiResult},
+    database,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -37,7 +40,7 @@ pub struct PasswordUpdate {
     path = "/api/users/me/password",
     request_body = PasswordUpdate,
     responses(
-        (status = 200, description = "Updated password"),
+        (status = 200, description = "Updated password. All other sessions are logged out"),
     )
 )]
 #[put("/api/users/me/password")]
@@ -60,5 +63,12 @@ async fn put_password(
 
     database::users::update_password_by_username(&username, &password, &pool).await?;
 
+    database::sessions::delete_by_username_except_id(
+        &username,
+        sessions::current_id(&session),
+        &pool,
+    )
+    .await?;
+
     Ok(HttpResponse::Ok().finish())
 }
@@ -0,0 +1,56 @@
+use actix_session::Session;
+use actix_web::{delete, web, HttpResponse, Responder};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+use crate::{
+    api::{users::sessions, ApiResult},
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(tags((name = "users/me/sessions/{id}")), paths(delete_session))]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(delete_session);
+}
+
+#[utoipa::path(
+    tag = "users/me/sessions/{id}",
+    delete,
+    path = "/api/users/me/sessions/{id}",
+    params(("id" = String, Path, description = "Session id")),
+    responses(
+        (status = 200, description = "Revoked the session. Revoking the current one logs out"),
+        (status = 400, description = "Not logged in"),
+        (status = 404, description = "No such session"),
+    )
+)]
+#[delete("/api/users/me/sessions/{id}")]
+async fn delete_session(
+    session: Session,
+    id: web::Path<Uuid>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let id = id.into_inner();
+
+    if !database::sessions::delete_by_id_and_username(id, &username, &pool).await? {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    if sessions::current_id(&session) == Some(id) {
+        session.purge();
+    }
+
+    Ok(HttpResponse::Ok().finish())
+}
@@ -0,0 +1,109 @@
+mod id;
+
+use actix_session::Session;
+use actix_web::{delete, get, web, HttpResponse, Responder};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    api::{users::sessions, ApiResult},
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/sessions")),
+    paths(get_sessions, delete_sessions),
+    components(schemas(
+        SessionInfo
+    ))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(id::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_sessions)
+        .service(delete_sessions)
+        .configure(id::configure);
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct SessionInfo {
+    #[schema(value_type = String)]
+    id: Uuid,
+    created: DateTime<Utc>,
+    last_seen: DateTime<Utc>,
+    expiry: DateTime<Utc>,
+    user_agent: Option<String>,
+    /// Hex encoded. Equal hashes mean the same ip
+    ip_hash: Option<String>,
+    current: bool,
+}
+
+#[utoipa::path(
+    tag = "users/me/sessions",
+    get,
+    path = "/api/users/me/sessions",
+    responses(
+        (status = 200, description = "Active sessions, most recently seen first", body = Vec<SessionInfo>),
+        (status = 400, description = "Not logged in"),
+    )
+)]
+#[get("/api/users/me/sessions")]
+async fn get_sessions(session: Session, pool: web::Data<PgPool>) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let current_id = sessions::current_id(&session);
+
+    let sessions: Vec<_> = database::sessions::get_by_username(&username, &pool)
+        .await?
+        .into_iter()
+        .map(|s| SessionInfo {
+            id: s.id,
+            created: s.created,
+            last_seen: s.last_seen,
+            expiry: s.expiry,
+            user_agent: s.user_agent,
+            ip_hash: s
+                .ip_hash
+                .map(|ip_hash| ip_hash.iter().map(|b| format!("{b:02x}")).collect()),
+            current: Some(s.id) == current_id,
+        })
+        .collect();
+
+    Ok(HttpResponse::Ok().json(sessions))
+}
+
+#[utoipa::path(
+    tag = "users/me/sessions",
+    delete,
+    path = "/api/users/me/sessions",
+    responses(
+        (status = 200, description = "Revoked all sessions except the current one"),
+        (status = 400, description = "Not logged in"),
+    )
+)]
+#[delete("/api/users/me/sessions")]
+async fn delete_sessions(session: Session, pool: web::Data<PgPool>) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    let Some(current_id) = sessions::current_id(&session) else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    database::sessions::delete_by_username_except_id(&username, Some(current_id), &pool).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
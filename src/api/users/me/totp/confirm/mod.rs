@@ -0,0 +1,53 @@
+use actix_session::Session;
+use actix_web::{post, web, HttpResponse, Responder};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+
+use super::TotpCode;
+use crate::{
+    api::{users::totp, ApiResult},
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(tags((name = "users/me/totp/confirm")), paths(post_totp_confirm))]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_totp_confirm);
+}
+
+#[utoipa::path(
+    tag = "users/me/totp/confirm",
+    post,
+    path = "/api/users/me/totp/confirm",
+    request_body = TotpCode,
+    responses(
+        (status = 200, description = "Enabled 2FA. The recovery codes are only shown once", body = Vec<String>),
+        (status = 400, description = "Not logged in, no pending enrollment or invalid code"),
+    )
+)]
+#[post("/api/users/me/totp/confirm")]
+async fn post_totp_confirm(
+    session: Session,
+    totp_code: web::Json<TotpCode>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    if !totp::verify_enrollment(&username, &totp_code.code, &pool).await? {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    database::users_totp::enable_by_username(&username, &pool).await?;
+
+    let recovery_codes = totp::regenerate_recovery_codes(&username, &pool).await?;
+
+    Ok(HttpResponse::Ok().json(recovery_codes))
+}
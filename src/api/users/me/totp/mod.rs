@@ -0,0 +1,111 @@
+mod confirm;
+mod recovery_codes;
+
+use actix_session::Session;
+use actix_web::{delete, post, web, HttpResponse, Responder};
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use utoipa::{OpenApi, ToSchema};
+
+use crate::{
+    api::{users::totp, ApiResult},
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/totp")),
+    paths(post_totp, delete_totp),
+    components(schemas(
+        TotpEnrollment,
+        TotpCode,
+    ))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    let mut openapi = ApiDoc::openapi();
+    openapi.merge(confirm::openapi());
+    openapi.merge(recovery_codes::openapi());
+    openapi
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_totp)
+        .service(delete_totp)
+        .configure(confirm::configure)
+        .configure(recovery_codes::configure);
+}
+
+#[derive(Serialize, ToSchema)]
+pub struct TotpEnrollment {
+    /// Base32, for entering it manually
+    secret: String,
+    uri: String,
+    /// The uri as an svg QR code
+    qr: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+pub struct TotpCode {
+    /// A code from the authenticator app or a recovery code
+    code: String,
+}
+
+#[utoipa::path(
+    tag = "users/me/totp",
+    post,
+    path = "/api/users/me/totp",
+    responses(
+        (status = 200, description = "Started enrollment. 2FA is enabled once a code is confirmed", body = TotpEnrollment),
+        (status = 400, description = "Not logged in"),
+        (status = 409, description = "2FA already enabled"),
+    )
+)]
+#[post("/api/users/me/totp")]
+async fn post_totp(session: Session, pool: web::Data<PgPool>) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    if totp::is_enabled(&username, &pool).await? {
+        return Ok(HttpResponse::Conflict().finish());
+    }
+
+    let secret = totp::generate_secret();
+    database::users_totp::set(&username, &secret, &pool).await?;
+
+    let (uri, secret) = totp::uri_and_secret(&username, &secret);
+    let qr = totp::qr_svg(&uri)?;
+
+    Ok(HttpResponse::Ok().json(TotpEnrollment { secret, uri, qr }))
+}
+
+#[utoipa::path(
+    tag = "users/me/totp",
+    delete,
+    path = "/api/users/me/totp",
+    request_body = TotpCode,
+    responses(
+        (status = 200, description = "Disabled 2FA"),
+        (status = 400, description = "Not logged in or invalid code"),
+    )
+)]
+#[delete("/api/users/me/totp")]
+async fn delete_totp(
+    session: Session,
+    totp_code: web::Json<TotpCode>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    if !totp::verify(&username, &totp_code.code, &pool).await? {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    totp::disable(&username, &pool).await?;
+
+    Ok(HttpResponse::Ok().finish())
+}
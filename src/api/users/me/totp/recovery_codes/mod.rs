@@ -0,0 +1,51 @@
+use actix_session::Session;
+use actix_web::{post, web, HttpResponse, Responder};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+
+use super::TotpCode;
+use crate::api::{users::totp, ApiResult};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "users/me/totp/recovery-codes")),
+    paths(post_totp_recovery_codes)
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(post_totp_recovery_codes);
+}
+
+#[utoipa::path(
+    tag = "users/me/totp/recovery-codes",
+    post,
+    path = "/api/users/me/totp/recovery-codes",
+    request_body = TotpCode,
+    responses(
+        (status = 200, description = "New recovery codes. The old ones are invalidated", body = Vec<String>),
+        (status = 400, description = "Not logged in or invalid code"),
+    )
+)]
+#[post("/api/users/me/totp/recovery-codes")]
+async fn post_totp_recovery_codes(
+    session: Session,
+    totp_code: web::Json<TotpCode>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Ok(Some(username)) = session.get::<String>("username") else {
+        return Ok(HttpResponse::BadRequest().finish());
+    };
+
+    if !totp::verify(&username, &totp_code.code, &pool).await? {
+        return Ok(HttpResponse::BadRequest().finish());
+    }
+
+    let recovery_codes = totp::regenerate_recovery_codes(&username, &pool).await?;
+
+    Ok(HttpResponse::Ok().json(recovery_codes))
+}
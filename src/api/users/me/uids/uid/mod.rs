@@ -66,7 +66,7 @@ async fn put_user_uid(
     };
 
     // Wacky way to update the database in case the uid isn't in there
-    if !database::mihomo::exists(uid, &pool).await?
+    if !database::mihomo::exists(uid, &**pool).await?
         && mihomo::get(uid, Language::En, &pool).await?.is_none()
     {
         let region = match uid.to_string().chars().next() {
@@ -83,10 +83,10 @@ async fn put_user_uid(
             ..Default::default()
         };
 
-        database::mihomo::set(&db_mihomo, &pool).await?;
+        database::mihomo::set(&db_mihomo, &**pool).await?;
     }
 
-    database::connections::set(&connection, &pool).await?;
+    database::connections::set(&connection, &**pool).await?;
 
     Ok(HttpResponse::Ok().finish())
 }
@@ -51,7 +51,8 @@ async fn put_user_uid_private(
         return Ok(HttpResponse::Forbidden().finish());
     }
 
-    database::connections::update_private_by_uid_and_username(*uid, &username, true, &pool).await?;
+    database::connections::update_private_by_uid_and_username(*uid, &username, true, &**pool)
+        .await?;
 
     Ok(HttpResponse::Ok().finish())
 }
@@ -86,7 +87,7 @@ async fn delete_user_uid_private(
         return Ok(HttpResponse::Forbidden().finish());
     }
 
-    database::connections::update_private_by_uid_and_username(*uid, &username, false, &pool)
+    database::connections::update_private_by_uid_and_username(*uid, &username, false, &**pool)
         .await?;
 
     Ok(HttpResponse::Ok().finish())
@@ -59,8 +59,8 @@ async fn put_user_zzz_uid(
         private: false,
     };
 
-    database::zzz::uids::set(&database::zzz::uids::DbUid { uid: *uid }, &pool).await?;
-    database::zzz::connections::set(&connection, &pool).await?;
+    database::zzz::uids::set(&database::zzz::uids::DbUid { uid: *uid }, &**pool).await?;
+    database::zzz::connections::set(&connection, &**pool).await?;
 
     Ok(HttpResponse::Ok().finish())
 }
@@ -51,7 +51,7 @@ async fn put_user_zzz_uid_private(
         return Ok(HttpResponse::Forbidden().finish());
     }
 
-    database::zzz::connections::update_private_by_uid_and_username(*uid, &username, true, &pool)
+    database::zzz::connections::update_private_by_uid_and_username(*uid, &username, true, &**pool)
         .await?;
 
     Ok(HttpResponse::Ok().finish())
@@ -87,7 +87,7 @@ async fn delete_user_zzz_uid_private(
         return Ok(HttpResponse::Forbidden().finish());
     }
 
-    database::zzz::connections::update_private_by_uid_and_username(*uid, &username, false, &pool)
+    database::zzz::connections::update_private_by_uid_and_username(*uid, &username, false, &**pool)
         .await?;
 
     Ok(HttpResponse::Ok().finish())
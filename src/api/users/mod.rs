@@ -1,9 +1,20 @@
 mod auth;
+mod deleted_export;
+mod deletions;
 mod me;
+mod oidc;
+pub(super) mod sessions;
+mod tokens;
+mod totp;
 
 use actix_web::web;
+use ed25519_dalek::SigningKey;
+use futures::lock::Mutex;
+use sqlx::PgPool;
 use utoipa::OpenApi;
 
+use crate::app_config::AppConfig;
+
 #[derive(OpenApi)]
 #[openapi()]
 struct ApiDoc;
@@ -11,10 +22,22 @@ struct ApiDoc;
 pub fn openapi() -> utoipa::openapi::OpenApi {
     let mut openapi = ApiDoc::openapi();
     openapi.merge(auth::openapi());
+    openapi.merge(deleted_export::openapi());
     openapi.merge(me::openapi());
     openapi
 }
 
-pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.configure(auth::configure).configure(me::configure);
+pub fn configure(
+    cfg: &mut web::ServiceConfig,
+    pool: PgPool,
+    app_config: &AppConfig,
+    signing_key: web::Data<Mutex<SigningKey>>,
+) {
+    if app_config.enable_account_deletions_worker {
+        deletions::configure(pool, signing_key);
+    }
+
+    cfg.configure(auth::configure)
+        .configure(deleted_export::configure)
+        .configure(me::configure);
 }
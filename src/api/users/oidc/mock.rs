@@ -0,0 +1,147 @@
+//! A local OpenID Connect issuer. The tests play the browser and the user, so authorizing just
+//! hands out a code for the given claims.
+
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+
+use actix_web::{rt, web, App, HttpResponse, HttpServer};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use url::Url;
+
+use crate::app_config::OidcProvider;
+
+const CLIENT_ID: &str = "stardb";
+const CLIENT_SECRET: &str = "secret";
+
+struct Code {
+    code_challenge: String,
+    redirect_uri: String,
+    claims: Value,
+}
+
+#[derive(Default)]
+struct State {
+    codes: HashMap<String, Code>,
+}
+
+pub struct MockIssuer {
+    addr: SocketAddr,
+    state: Arc<Mutex<State>>,
+}
+
+impl MockIssuer {
+    pub async fn start() -> Self {
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let data = web::Data::new(state.clone());
+        let server = HttpServer::new(move || {
+            App::new()
+                .app_data(data.clone())
+                .route(
+                    "/.well-known/openid-configuration",
+                    web::get().to(discovery),
+                )
+                .route("/token", web::post().to(token))
+        })
+        .workers(1)
+        .bind(("127.0.0.1", 0))
+        .expect("mock issuer should bind");
+
+        let addr = server.addrs()[0];
+        rt::spawn(server.run());
+
+        Self { addr, state }
+    }
+
+    pub fn provider(&self, name: &str) -> OidcProvider {
+        OidcProvider {
+            name: name.to_string(),
+            issuer: format!("http://{}", self.addr),
+            client_id: CLIENT_ID.to_string(),
+            client_secret: CLIENT_SECRET.to_string(),
+            scopes: vec!["openid".to_string()],
+        }
+    }
+
+    /// Logs in at the authorization url and returns the code the callback would get
+    pub fn authorize(&self, url: &Url, mut claims: Value) -> String {
+        let query: HashMap<_, _> = url.query_pairs().into_owned().collect();
+
+        assert_eq!(query["response_type"], "code");
+        assert_eq!(query["client_id"], CLIENT_ID);
+        assert_eq!(query["code_challenge_method"], "S256");
+
+        claims["iss"] = json!(format!("http://{}", self.addr));
+        claims["aud"] = json!(CLIENT_ID);
+        claims["exp"] = json!(Utc::now().timestamp() + 300);
+        claims["nonce"] = json!(query["nonce"]);
+
+        let code = uuid::Uuid::new_v4().to_string();
+
+        self.state.lock().unwrap().codes.insert(
+            code.clone(),
+            Code {
+                code_challenge: query["code_challenge"].clone(),
+                redirect_uri: query["redirect_uri"].clone(),
+                claims,
+            },
+        );
+
+        code
+    }
+}
+
+async fn discovery(request: actix_web::HttpRequest) -> HttpResponse {
+    let issuer = format!("http://{}", request.connection_info().host());
+
+    HttpResponse::Ok().json(json!({
+        "issuer": issuer,
+        "authorization_endpoint": format!("{issuer}/authorize"),
+        "token_endpoint": format!("{issuer}/token"),
+    }))
+}
+
+async fn token(
+    form: web::Form<HashMap<String, String>>,
+    state: web::Data<Arc<Mutex<State>>>,
+) -> HttpResponse {
+    let Some(code) = form
+        .get("code")
+        .and_then(|code| state.lock().unwrap().codes.remove(code))
+    else {
+        return HttpResponse::BadRequest().json(json!({"error": "invalid_grant"}));
+    };
+
+    let code_challenge = form
+        .get("code_verifier")
+        .map(|code_verifier| BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier)));
+
+    if form.get("grant_type").map(String::as_str) != Some("authorization_code")
+        || form.get("client_id").map(String::as_str) != Some(CLIENT_ID)
+        || form.get("client_secret").map(String::as_str) != Some(CLIENT_SECRET)
+        || form.get("redirect_uri") != Some(&code.redirect_uri)
+        || code_challenge.as_ref() != Some(&code.code_challenge)
+    {
+        return HttpResponse::BadRequest().json(json!({"error": "invalid_grant"}));
+    }
+
+    let encode = |value: &Value| BASE64_URL_SAFE_NO_PAD.encode(value.to_string());
+    let id_token = format!(
+        "{}.{}.{}",
+        encode(&json!({"alg": "RS256", "typ": "JWT"})),
+        encode(&code.claims),
+        BASE64_URL_SAFE_NO_PAD.encode("unchecked"),
+    );
+
+    HttpResponse::Ok().json(json!({
+        "access_token": "unused",
+        "token_type": "Bearer",
+        "id_token": id_token,
+    }))
+}
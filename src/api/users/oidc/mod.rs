@@ -0,0 +1,570 @@
+//! OpenID Connect logins with the authorization code flow and PKCE.
+//!
+//! The id token comes straight from the provider's token endpoint over TLS, so its claims are
+//! checked but not its signature (OpenID Connect Core 3.1.3.7).
+
+#[cfg(test)]
+pub mod mock;
+
+use std::{collections::HashMap, sync::Mutex};
+
+use actix_web::{
+    cookie::{time::Duration as CookieDuration, Cookie, SameSite},
+    http::header,
+    HttpResponse,
+};
+use anyhow::{anyhow, bail};
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use rand::Rng;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+use url::Url;
+
+use crate::{
+    api::users::tokens,
+    app_config::{AppConfig, OidcProvider},
+    database,
+};
+
+/// Binds the login to the browser that started it
+pub const STATE_COOKIE: &str = "oidc_state";
+const STATE_COOKIE_PATH: &str = "/api/users/auth/oidc";
+const STATE_LIFETIME_MINUTES: i64 = 10;
+
+lazy_static::lazy_static! {
+    static ref CLIENT: reqwest::Client = reqwest::Client::new();
+    static ref DISCOVERY: Mutex<HashMap<String, Discovery>> = Mutex::new(HashMap::new());
+}
+
+#[derive(Clone, Deserialize)]
+struct Discovery {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Audience {
+    One(String),
+    Many(Vec<String>),
+}
+
+#[derive(Deserialize)]
+struct Claims {
+    iss: String,
+    sub: String,
+    aud: Audience,
+    exp: i64,
+    nonce: Option<String>,
+    email: Option<String>,
+    email_verified: Option<bool>,
+    preferred_username: Option<String>,
+    name: Option<String>,
+}
+
+pub struct Identity {
+    pub subject: String,
+    /// Only set if the provider verified it
+    pub email: Option<String>,
+    username_hint: Option<String>,
+}
+
+pub struct Pending {
+    /// The user to link the identity to. A login if `None`
+    pub username: Option<String>,
+    /// Path on the frontend to return to
+    pub redirect: String,
+}
+
+pub fn provider<'a>(name: &str, app_config: &'a AppConfig) -> Option<&'a OidcProvider> {
+    app_config.oidc_providers.iter().find(|p| p.name == name)
+}
+
+fn redirect_uri(provider: &OidcProvider, app_config: &AppConfig) -> String {
+    format!(
+        "{}/api/users/auth/oidc/{}/callback",
+        app_config.public_url, provider.name
+    )
+}
+
+/// Only paths on the frontend, so the login can't be used as an open redirect
+pub fn sanitize_redirect(redirect: Option<&str>) -> String {
+    match redirect {
+        Some(redirect)
+            if redirect.starts_with('/')
+                && !redirect.starts_with("//")
+                && !redirect.contains('\\')
+                && redirect.len() <= 256 =>
+        {
+            redirect.to_string()
+        }
+        _ => "/".to_string(),
+    }
+}
+
+async fn discover(provider: &OidcProvider) -> anyhow::Result<Discovery> {
+    if let Some(discovery) = DISCOVERY.lock().unwrap().get(&provider.issuer) {
+        return Ok(discovery.clone());
+    }
+
+    let url = format!(
+        "{}/.well-known/openid-configuration",
+        provider.issuer.trim_end_matches('/')
+    );
+
+    let discovery: Discovery = CLIENT
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    if discovery.issuer.trim_end_matches('/') != provider.issuer.trim_end_matches('/') {
+        bail!("Discovered issuer {} doesn't match", discovery.issuer);
+    }
+
+    DISCOVERY
+        .lock()
+        .unwrap()
+        .insert(provider.issuer.clone(), discovery.clone());
+
+    Ok(discovery)
+}
+
+fn random_token() -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(rand::rng().random::<[u8; 32]>())
+}
+
+fn code_challenge(code_verifier: &str) -> String {
+    BASE64_URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+}
+
+/// Returns the provider's authorization url and the state, which has to be set as
+/// [`STATE_COOKIE`]
+pub async fn authorize(
+    provider: &OidcProvider,
+    username: Option<&str>,
+    redirect: &str,
+    app_config: &AppConfig,
+    pool: &PgPool,
+) -> anyhow::Result<(Url, String)> {
+    let discovery = discover(provider).await?;
+
+    let state = random_token();
+    let code_verifier = random_token();
+    let nonce = random_token();
+
+    database::users_oidc_states::delete_expired(pool).await?;
+
+    let db_state = database::users_oidc_states::DbUserOidcState {
+        hash: tokens::hash(&state),
+        provider: provider.name.clone(),
+        code_verifier: code_verifier.clone(),
+        nonce: nonce.clone(),
+        username: username.map(ToString::to_string),
+        redirect: redirect.to_string(),
+        expires: Utc::now() + Duration::minutes(STATE_LIFETIME_MINUTES),
+    };
+    database::users_oidc_states::set(&db_state, pool).await?;
+
+    let mut url = Url::parse(&discovery.authorization_endpoint)?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &provider.client_id)
+        .append_pair("redirect_uri", &redirect_uri(provider, app_config))
+        .append_pair("scope", &provider.scopes.join(" "))
+        .append_pair("state", &state)
+        .append_pair("nonce", &nonce)
+        .append_pair("code_challenge", &code_challenge(&code_verifier))
+        .append_pair("code_challenge_method", "S256");
+
+    Ok((url, state))
+}
+
+/// Exchanges the code for the identity. Returns `None` if the state is unknown, expired or used.
+pub async fn finish(
+    provider: &OidcProvider,
+    code: &str,
+    state: &str,
+    app_config: &AppConfig,
+    pool: &PgPool,
+) -> anyhow::Result<Option<(Pending, Identity)>> {
+    let Some(db_state) =
+        database::users_oidc_states::consume(&tokens::hash(state), &provider.name, pool).await?
+    else {
+        return Ok(None);
+    };
+
+    let discovery = discover(provider).await?;
+
+    let token_response: TokenResponse = CLIENT
+        .post(&discovery.token_endpoint)
+        .form(&[
+            ("grant_type", "authorization_code"),
+            ("code", code),
+            ("redirect_uri", &redirect_uri(provider, app_config)),
+            ("client_id", &provider.client_id),
+            ("client_secret", &provider.client_secret),
+            ("code_verifier", &db_state.code_verifier),
+        ])
+        .send()
+        .await?
+        .error_for_status()?
+        .json()
+        .await?;
+
+    let claims = claims(&token_response.id_token)?;
+
+    if claims.iss.trim_end_matches('/') != discovery.issuer.trim_end_matches('/') {
+        bail!("Wrong issuer {}", claims.iss);
+    }
+
+    let audience_ok = match &claims.aud {
+        Audience::One(aud) => *aud == provider.client_id,
+        Audience::Many(aud) => aud.contains(&provider.client_id),
+    };
+    if !audience_ok {
+        bail!("Wrong audience");
+    }
+
+    if claims.exp <= Utc::now().timestamp() {
+        bail!("Expired id token");
+    }
+
+    if claims.nonce.as_deref() != Some(db_state.nonce.as_str()) {
+        bail!("Wrong nonce");
+    }
+
+    let email = claims
+        .email
+        .filter(|email| claims.email_verified == Some(true) && email.len() <= 64);
+
+    let username_hint = claims
+        .preferred_username
+        .or(claims.name)
+        .or_else(|| {
+            email
+                .as_ref()
+                .and_then(|email| email.split('@').next().map(ToString::to_string))
+        });
+
+    let pending = Pending {
+        username: db_state.username,
+        redirect: db_state.redirect,
+    };
+
+    let identity = Identity {
+        subject: claims.sub,
+        email,
+        username_hint,
+    };
+
+    Ok(Some((pending, identity)))
+}
+
+/// The signature isn't checked. The id token comes straight from the token endpoint, over TLS and
+/// authenticated with the client secret, which is enough to trust it (OIDC Core 3.1.3.7). Don't
+/// use this for id tokens from anywhere else.
+fn claims(id_token: &str) -> anyhow::Result<Claims> {
+    let payload = id_token
+        .split('.')
+        .nth(1)
+        .ok_or_else(|| anyhow!("Malformed id token"))?;
+
+    Ok(serde_json::from_slice(
+        &BASE64_URL_SAFE_NO_PAD.decode(payload)?,
+    )?)
+}
+
+/// The user the identity belongs to. Registers one on the first login.
+pub async fn login(
+    provider: &OidcProvider,
+    identity: &Identity,
+    pool: &PgPool,
+) -> anyhow::Result<String> {
+    if let Some(username) = database::users_identities::get_username_by_provider_and_subject(
+        &provider.name,
+        &identity.subject,
+        pool,
+    )
+    .await?
+    {
+        return Ok(username);
+    }
+
+    let username = unique_username(identity.username_hint.as_deref(), pool).await?;
+
+    // No password, so only the identity can log in until one is set
+    let user = database::users::DbUser {
+        username: username.clone(),
+        password: String::new(),
+        email: identity.email.clone(),
+        email_verified: identity.email.is_some(),
+    };
+    database::users::set(&user, pool).await?;
+
+    if !database::users_identities::set(
+        &provider.name,
+        &identity.subject,
+        &username,
+        identity.email.as_deref(),
+        pool,
+    )
+    .await?
+    {
+        // A concurrent first login won
+        database::users::delete_by_username(&username, pool).await?;
+
+        return database::users_identities::get_username_by_provider_and_subject(
+            &provider.name,
+            &identity.subject,
+            pool,
+        )
+        .await?
+        .ok_or_else(|| anyhow!("Identity vanished"));
+    }
+
+    Ok(username)
+}
+
+/// Returns `false` if the identity belongs to someone else or the user already linked another
+/// identity of this provider
+pub async fn link(
+    provider: &OidcProvider,
+    identity: &Identity,
+    username: &str,
+    pool: &PgPool,
+) -> anyhow::Result<bool> {
+    if let Some(owner) = database::users_identities::get_username_by_provider_and_subject(
+        &provider.name,
+        &identity.subject,
+        pool,
+    )
+    .await?
+    {
+        return Ok(owner == username);
+    }
+
+    database::users_identities::set(
+        &provider.name,
+        &identity.subject,
+        username,
+        identity.email.as_deref(),
+        pool,
+    )
+    .await
+}
+
+fn username_base(hint: Option<&str>) -> String {
+    let base: String = hint
+        .unwrap_or_default()
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+        .take(24)
+        .collect();
+
+    if base.is_empty() {
+        "user".to_string()
+    } else {
+        base
+    }
+}
+
+async fn unique_username(hint: Option<&str>, pool: &PgPool) -> anyhow::Result<String> {
+    let base = username_base(hint);
+
+    if database::users::get_one_by_username(&base, pool)
+        .await
+        .is_err()
+    {
+        return Ok(base);
+    }
+
+    for _ in 0..10 {
+        let username = format!("{base}{}", rand::rng().random_range(1000..10000));
+
+        if database::users::get_one_by_username(&username, pool)
+            .await
+            .is_err()
+        {
+            return Ok(username);
+        }
+    }
+
+    Ok(format!("{base}{:08x}", rand::rng().random::<u32>()))
+}
+
+/// Sends the browser to the provider
+pub fn to_provider(url: Url, state: String) -> HttpResponse {
+    let cookie = Cookie::build(STATE_COOKIE, state)
+        .path(STATE_COOKIE_PATH)
+        .http_only(true)
+        .secure(!cfg!(debug_assertions))
+        .same_site(SameSite::Lax)
+        .max_age(CookieDuration::minutes(STATE_LIFETIME_MINUTES))
+        .finish();
+
+    HttpResponse::Found()
+        .insert_header((header::LOCATION, url.to_string()))
+        .cookie(cookie)
+        .finish()
+}
+
+/// Sends the browser back to the frontend
+pub fn to_frontend(path: &str, app_config: &AppConfig) -> HttpResponse {
+    let mut cookie = Cookie::build(STATE_COOKIE, "")
+        .path(STATE_COOKIE_PATH)
+        .finish();
+    cookie.make_removal();
+
+    HttpResponse::Found()
+        .insert_header((header::LOCATION, format!("{}{path}", app_config.public_url)))
+        .cookie(cookie)
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use uuid::Uuid;
+
+    use super::{mock::MockIssuer, *};
+    use crate::test_util::test_pool;
+
+    fn app_config() -> AppConfig {
+        envy::from_iter(std::iter::empty::<(String, String)>()).unwrap()
+    }
+
+    #[test]
+    fn redirects_stay_on_the_frontend() {
+        assert_eq!(sanitize_redirect(Some("/profile")), "/profile");
+        assert_eq!(sanitize_redirect(Some("//evil.com")), "/");
+        assert_eq!(sanitize_redirect(Some("/\\evil.com")), "/");
+        assert_eq!(sanitize_redirect(Some("https://evil.com")), "/");
+        assert_eq!(sanitize_redirect(None), "/");
+    }
+
+    #[test]
+    fn usernames_are_derived_from_the_hint() {
+        assert_eq!(username_base(Some(" Meow Cat ")), "meowcat");
+        assert_eq!(username_base(Some("ü")), "user");
+        assert_eq!(username_base(None), "user");
+        assert_eq!(username_base(Some(&"a".repeat(40))).len(), 24);
+    }
+
+    #[actix_web::test]
+    async fn first_logins_register_and_later_ones_find_the_user() {
+        let pool = test_pool().await;
+        let app_config = app_config();
+        let issuer = MockIssuer::start().await;
+        let provider = issuer.provider("mock");
+
+        let subject = Uuid::new_v4().to_string();
+        let claims = json!({
+            "sub": subject,
+            "preferred_username": "Oidc Tester",
+            "email": "oidc@example.com",
+            "email_verified": true,
+        });
+
+        let (url, state) = authorize(&provider, None, "/profile", &app_config, &pool)
+            .await
+            .unwrap();
+        let code = issuer.authorize(&url, claims.clone());
+
+        let (pending, identity) = finish(&provider, &code, &state, &app_config, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(pending.username.is_none());
+        assert_eq!(pending.redirect, "/profile");
+        assert_eq!(identity.email.as_deref(), Some("oidc@example.com"));
+
+        let username = login(&provider, &identity, &pool).await.unwrap();
+        assert!(username.starts_with("oidctester"));
+        let user = database::users::get_one_by_username(&username, &pool)
+            .await
+            .unwrap();
+        assert!(user.email_verified);
+        assert!(user.password.is_empty());
+
+        // States are single use
+        assert!(finish(&provider, &code, &state, &app_config, &pool)
+            .await
+            .unwrap()
+            .is_none());
+
+        let (url, state) = authorize(&provider, None, "/", &app_config, &pool)
+            .await
+            .unwrap();
+        let code = issuer.authorize(&url, claims);
+        let (_, identity) = finish(&provider, &code, &state, &app_config, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(login(&provider, &identity, &pool).await.unwrap(), username);
+
+        let (url, state) = authorize(&provider, None, "/", &app_config, &pool)
+            .await
+            .unwrap();
+        let code = issuer.authorize(
+            &url,
+            json!({"sub": Uuid::new_v4().to_string(), "preferred_username": "Oidc Tester"}),
+        );
+        let (_, identity) = finish(&provider, &code, &state, &app_config, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(identity.email.is_none());
+        let other = login(&provider, &identity, &pool).await.unwrap();
+        assert_ne!(other, username);
+        assert!(other.starts_with("oidctester"));
+
+        // The linked account is taken, but the other one can link its own identity once
+        let (url, state) = authorize(&provider, Some(&other), "/", &app_config, &pool)
+            .await
+            .unwrap();
+        let code = issuer.authorize(&url, json!({"sub": subject}));
+        let (pending, identity) = finish(&provider, &code, &state, &app_config, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(pending.username.as_deref(), Some(other.as_str()));
+        assert!(!link(&provider, &identity, &other, &pool).await.unwrap());
+        assert!(link(&provider, &identity, &username, &pool).await.unwrap());
+    }
+
+    #[actix_web::test]
+    async fn codes_only_work_with_their_own_state() {
+        let pool = test_pool().await;
+        let app_config = app_config();
+        let issuer = MockIssuer::start().await;
+        let provider = issuer.provider("mock");
+
+        let claims = json!({"sub": Uuid::new_v4().to_string()});
+
+        let (url, _) = authorize(&provider, None, "/", &app_config, &pool)
+            .await
+            .unwrap();
+        let code = issuer.authorize(&url, claims);
+
+        // A different state means a different code verifier and nonce
+        let (_, state) = authorize(&provider, None, "/", &app_config, &pool)
+            .await
+            .unwrap();
+        assert!(finish(&provider, &code, &state, &app_config, &pool)
+            .await
+            .is_err());
+    }
+}
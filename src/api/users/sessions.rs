@@ -0,0 +1,83 @@
+use std::net::{IpAddr, SocketAddr};
+
+use actix_session::{Session, SessionInsertError};
+use actix_web::{dev::ConnectionInfo, HttpRequest};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::app_config::AppConfig;
+
+/// Logs the session in. The user agent and a hash of the ip are stored with it, so the user can
+/// tell their sessions apart.
+pub fn start(
+    session: &Session,
+    request: &HttpRequest,
+    username: String,
+    app_config: &AppConfig,
+) -> Result<(), SessionInsertError> {
+    let user_agent = request
+        .headers()
+        .get("user-agent")
+        .and_then(|user_agent| user_agent.to_str().ok())
+        .map(|user_agent| user_agent.chars().take(256).collect::<String>());
+
+    let ip_hash =
+        client_ip(&request.connection_info(), app_config).map(|ip| ip_hash(ip, app_config));
+
+    session.insert("username", username)?;
+    if let Some(user_agent) = user_agent {
+        session.insert("user_agent", user_agent)?;
+    }
+    if let Some(ip_hash) = ip_hash {
+        session.insert("ip_hash", ip_hash)?;
+    }
+
+    Ok(())
+}
+
+/// The ip of the client. Clients can send forwarded headers themselves, so they're only believed
+/// if the peer is one of the trusted proxies.
+pub fn client_ip(connection_info: &ConnectionInfo, app_config: &AppConfig) -> Option<IpAddr> {
+    let peer = parse_ip(connection_info.peer_addr()?)?;
+
+    if app_config.trusted_proxies.contains(&peer) {
+        forwarded_ip(connection_info).or(Some(peer))
+    } else {
+        Some(peer)
+    }
+}
+
+/// The ip the forwarded headers name, if there are any. Only use it for peers that are trusted
+/// to set them.
+fn forwarded_ip(connection_info: &ConnectionInfo) -> Option<IpAddr> {
+    let forwarded = connection_info.realip_remote_addr()?;
+
+    // Without forwarded headers this falls back to the peer
+    if Some(forwarded) == connection_info.peer_addr() {
+        return None;
+    }
+
+    parse_ip(forwarded)
+}
+
+fn parse_ip(ip: &str) -> Option<IpAddr> {
+    ip.parse()
+        .ok()
+        .or_else(|| ip.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
+
+/// A salted hash of the client ip, so it can be stored and compared without keeping the ip
+pub fn ip_hash(ip: IpAddr, app_config: &AppConfig) -> String {
+    Sha256::new()
+        .chain_update(&app_config.ip_hash_salt)
+        .chain_update(ip.to_string())
+        .finalize()
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// The id of the logged in session. Not the session key, so it's safe to show.
+pub fn current_id(session: &Session) -> Option<Uuid> {
+    session.get("session_id").ok().flatten()
+}
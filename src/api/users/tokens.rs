@@ -0,0 +1,256 @@
+use std::sync::Arc;
+
+use actix_web::rt;
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use chrono::{Duration, Utc};
+use rand::Rng;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use crate::{app_config::AppConfig, database, mail};
+
+/// Tokens are mailed to the user. Only their hash is stored, so a database leak can't be used to
+/// log in.
+#[derive(Clone, Copy, strum::Display)]
+#[strum(serialize_all = "snake_case")]
+pub enum Purpose {
+    Login,
+    ResetPassword,
+    VerifyEmail,
+    /// The pending second step of a login with 2FA
+    Totp,
+    /// Proves ownership of the account that gets merged into another or whose export gets imported
+    /// with its verifications
+    Merge,
+}
+
+impl Purpose {
+    fn lifetime(self) -> Duration {
+        match self {
+            Purpose::Login => Duration::minutes(5),
+            Purpose::ResetPassword => Duration::hours(1),
+            Purpose::VerifyEmail => Duration::days(1),
+            Purpose::Totp => Duration::minutes(5),
+            Purpose::Merge => Duration::minutes(10),
+        }
+    }
+
+    fn mailed(self) -> bool {
+        !matches!(self, Purpose::Totp | Purpose::Merge)
+    }
+}
+
+/// How often a token can be tried before it's invalid. Only matters for tokens that are checked
+/// together with something else, like a TOTP code.
+const MAX_ATTEMPTS: i32 = 5;
+
+pub(super) fn hash(token: &str) -> Vec<u8> {
+    Sha256::digest(token.as_bytes()).to_vec()
+}
+
+/// Replaces any previous token of the same purpose. Returns `None` if the last mailed one was
+/// created less than a minute ago, so the mail endpoints can't be used to spam someone.
+pub async fn create(
+    username: &str,
+    purpose: Purpose,
+    email: Option<&str>,
+    pool: &PgPool,
+) -> anyhow::Result<Option<String>> {
+    let now = Utc::now();
+    let purpose_name = purpose.to_string();
+
+    if purpose.mailed()
+        && database::users_tokens::exists_recent(
+            username,
+            &purpose_name,
+            now - Duration::minutes(1),
+            pool,
+        )
+        .await?
+    {
+        return Ok(None);
+    }
+
+    database::users_tokens::delete_expired(pool).await?;
+    database::users_tokens::delete_by_username_and_purpose(username, &purpose_name, pool).await?;
+
+    let token = BASE64_URL_SAFE_NO_PAD.encode(rand::rng().random::<[u8; 32]>());
+
+    let db_token = database::users_tokens::DbUserToken {
+        hash: hash(&token),
+        username: username.to_string(),
+        purpose: purpose_name,
+        email: email.map(ToString::to_string),
+        expires: now + purpose.lifetime(),
+    };
+    database::users_tokens::set(&db_token, pool).await?;
+
+    Ok(Some(token))
+}
+
+pub async fn get(
+    token: &str,
+    purpose: Purpose,
+    pool: &PgPool,
+) -> anyhow::Result<Option<database::users_tokens::DbUserToken>> {
+    database::users_tokens::get_one_by_hash_and_purpose(
+        &hash(token),
+        &purpose.to_string(),
+        MAX_ATTEMPTS,
+        pool,
+    )
+    .await
+}
+
+/// Counts a failed attempt. After `MAX_ATTEMPTS` the token can't be used anymore.
+pub async fn fail(token: &str, pool: &PgPool) -> anyhow::Result<()> {
+    database::users_tokens::increment_attempts_by_hash(&hash(token), pool).await
+}
+
+/// Tokens are single use. A consumed token is gone, even if the caller fails afterwards.
+pub async fn consume(
+    token: &str,
+    purpose: Purpose,
+    pool: &PgPool,
+) -> anyhow::Result<Option<database::users_tokens::DbUserToken>> {
+    database::users_tokens::consume(&hash(token), &purpose.to_string(), pool).await
+}
+
+/// The mail is sent in the background, only creating the token can fail
+pub async fn send_verification(
+    username: &str,
+    email: &str,
+    app_config: &Arc<AppConfig>,
+    pool: &PgPool,
+) -> anyhow::Result<()> {
+    let Some(token) = create(username, Purpose::VerifyEmail, Some(email), pool).await? else {
+        return Ok(());
+    };
+
+    let username = username.to_string();
+    let to = format!("{username} <{email}>");
+    let body = format!(
+        "Use the following link to verify your email:\n{}/verify-email?token={token}",
+        app_config.public_url
+    );
+    let app_config = app_config.clone();
+
+    rt::spawn(async move {
+        if let Err(e) = mail::send(&to, "StarDB.GG Email Verification", body, &app_config).await {
+            error!("Sending the verification mail to {username} failed with {e}");
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{create_test_user_with_email, delete_test_user, test_pool};
+
+    #[actix_web::test]
+    async fn tokens_are_single_use_and_rate_limited() {
+        let pool = test_pool().await;
+        let username =
+            create_test_user_with_email("tokens_test", "meow@example.com", false, &pool).await;
+
+        let token = create(&username, Purpose::Login, None, &pool)
+            .await
+            .unwrap()
+            .expect("first token should be created");
+        assert!(create(&username, Purpose::Login, None, &pool)
+            .await
+            .unwrap()
+            .is_none());
+
+        assert!(consume(&token, Purpose::ResetPassword, &pool)
+            .await
+            .unwrap()
+            .is_none());
+        let consumed = consume(&token, Purpose::Login, &pool)
+            .await
+            .unwrap()
+            .expect("token should be valid once");
+        assert_eq!(consumed.username, username);
+        assert!(consume(&token, Purpose::Login, &pool)
+            .await
+            .unwrap()
+            .is_none());
+
+        delete_test_user(&username, &pool).await;
+    }
+
+    #[actix_web::test]
+    async fn tokens_are_invalid_after_too_many_attempts() {
+        let pool = test_pool().await;
+        let username =
+            create_test_user_with_email("tokens_test", "attempts@example.com", false, &pool).await;
+
+        let token = create(&username, Purpose::Totp, None, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+        // Not rate limited, because it isn't mailed
+        let token_again = create(&username, Purpose::Totp, None, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(get(&token, Purpose::Totp, &pool).await.unwrap().is_none());
+        let token = token_again;
+
+        for _ in 0..MAX_ATTEMPTS {
+            assert!(get(&token, Purpose::Totp, &pool).await.unwrap().is_some());
+            fail(&token, &pool).await.unwrap();
+        }
+        assert!(get(&token, Purpose::Totp, &pool).await.unwrap().is_none());
+
+        delete_test_user(&username, &pool).await;
+    }
+
+    #[actix_web::test]
+    async fn verification_is_bound_to_the_email_it_was_sent_to() {
+        let pool = test_pool().await;
+        let username =
+            create_test_user_with_email("tokens_test", "old@example.com", false, &pool).await;
+
+        let token = create(
+            &username,
+            Purpose::VerifyEmail,
+            Some("old@example.com"),
+            &pool,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        database::users::update_email_by_username(&username, "new@example.com", &pool)
+            .await
+            .unwrap();
+
+        let consumed = consume(&token, Purpose::VerifyEmail, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(!database::users::verify_email_by_username(
+            &username,
+            consumed.email.as_deref().unwrap(),
+            &pool
+        )
+        .await
+        .unwrap());
+        assert!(
+            database::users::verify_email_by_username(&username, "new@example.com", &pool)
+                .await
+                .unwrap()
+        );
+        assert!(
+            database::users::get_one_by_username(&username, &pool)
+                .await
+                .unwrap()
+                .email_verified
+        );
+
+        delete_test_user(&username, &pool).await;
+    }
+}
@@ -0,0 +1,259 @@
+use chrono::{Duration, Utc};
+use qrcode::{render::svg, QrCode};
+use rand::Rng;
+use sqlx::PgPool;
+use totp_rs::{Algorithm, TOTP};
+
+use super::tokens;
+use crate::database;
+
+const STEP: u64 = 30;
+const RECOVERY_CODES: usize = 10;
+/// Every login creates a new pending token with its own attempts, so wrong codes are also counted
+/// per user. After this many within `FAILURE_WINDOW`, no code is accepted until the window passed.
+const MAX_FAILURES: i32 = 10;
+const FAILURE_WINDOW: Duration = Duration::minutes(15);
+
+/// The usual authenticator app parameters. Skew is handled in `verify`, because the used step has
+/// to be known to prevent replays.
+fn totp(username: &str, secret: Vec<u8>) -> TOTP {
+    TOTP::new_unchecked(
+        Algorithm::SHA1,
+        6,
+        0,
+        STEP,
+        secret,
+        Some("StarDB.GG".to_string()),
+        username.to_string(),
+    )
+}
+
+pub fn generate_secret() -> Vec<u8> {
+    rand::rng().random::<[u8; 20]>().to_vec()
+}
+
+/// The otpauth uri and the base32 secret for manual entry
+pub fn uri_and_secret(username: &str, secret: &[u8]) -> (String, String) {
+    let totp = totp(username, secret.to_vec());
+
+    (totp.get_url(), totp.get_secret_base32())
+}
+
+pub fn qr_svg(uri: &str) -> anyhow::Result<String> {
+    Ok(QrCode::new(uri)?
+        .render::<svg::Color>()
+        .min_dimensions(200, 200)
+        .build())
+}
+
+/// Returns the step of the code, if it's valid at `time` with one step of skew in both
+/// directions
+fn code_step(totp: &TOTP, code: &str, time: u64) -> Option<u64> {
+    let current = time / STEP;
+
+    (current.saturating_sub(1)..=current + 1).find(|step| totp.check(code, step * STEP))
+}
+
+fn normalize_recovery_code(code: &str) -> String {
+    code.trim().replace('-', "").to_lowercase()
+}
+
+/// Checks a TOTP or recovery code for a user with enabled 2FA. Both can only be used once. Wrong
+/// codes count towards `MAX_FAILURES`.
+pub async fn verify(username: &str, code: &str, pool: &PgPool) -> anyhow::Result<bool> {
+    let Some(user_totp) = database::users_totp::get_one_by_username(username, pool).await? else {
+        return Ok(false);
+    };
+
+    if !user_totp.enabled {
+        return Ok(false);
+    }
+
+    let since = Utc::now() - FAILURE_WINDOW;
+
+    if user_totp.failures >= MAX_FAILURES && user_totp.failed_at.is_some_and(|t| t > since) {
+        return Ok(false);
+    }
+
+    let totp = totp(username, user_totp.secret);
+
+    let valid = if let Some(step) = code_step(&totp, code.trim(), Utc::now().timestamp() as u64) {
+        database::users_totp::update_last_step_by_username(username, step as i64, pool).await?
+    } else {
+        let hash = tokens::hash(&normalize_recovery_code(code));
+        database::users_recovery_codes::delete_by_username_and_hash(username, &hash, pool).await?
+    };
+
+    if valid {
+        database::users_totp::reset_failures_by_username(username, pool).await?;
+    } else {
+        database::users_totp::increment_failures_by_username(username, since, pool).await?;
+    }
+
+    Ok(valid)
+}
+
+/// Checks a code against a pending enrollment
+pub async fn verify_enrollment(username: &str, code: &str, pool: &PgPool) -> anyhow::Result<bool> {
+    let Some(user_totp) = database::users_totp::get_one_by_username(username, pool).await? else {
+        return Ok(false);
+    };
+
+    if user_totp.enabled {
+        return Ok(false);
+    }
+
+    let totp = totp(username, user_totp.secret);
+
+    let Some(step) = code_step(&totp, code.trim(), Utc::now().timestamp() as u64) else {
+        return Ok(false);
+    };
+
+    database::users_totp::update_last_step_by_username(username, step as i64, pool).await
+}
+
+pub async fn is_enabled(username: &str, pool: &PgPool) -> anyhow::Result<bool> {
+    Ok(database::users_totp::get_one_by_username(username, pool)
+        .await?
+        .is_some_and(|user_totp| user_totp.enabled))
+}
+
+/// Replaces all recovery codes of a user. Only the hashes are stored, so the codes are returned to
+/// be shown once.
+pub async fn regenerate_recovery_codes(
+    username: &str,
+    pool: &PgPool,
+) -> anyhow::Result<Vec<String>> {
+    let codes: Vec<_> = (0..RECOVERY_CODES)
+        .map(|_| {
+            let hex: String = rand::rng()
+                .random::<[u8; 10]>()
+                .iter()
+                .map(|b| format!("{b:02x}"))
+                .collect();
+
+            format!(
+                "{}-{}-{}-{}",
+                &hex[..5],
+                &hex[5..10],
+                &hex[10..15],
+                &hex[15..]
+            )
+        })
+        .collect();
+
+    let hashes: Vec<_> = codes
+        .iter()
+        .map(|code| tokens::hash(&normalize_recovery_code(code)))
+        .collect();
+
+    database::users_recovery_codes::delete_by_username(username, pool).await?;
+    database::users_recovery_codes::set_all(username, &hashes, pool).await?;
+
+    Ok(codes)
+}
+
+pub async fn disable(username: &str, pool: &PgPool) -> anyhow::Result<()> {
+    database::users_recovery_codes::delete_by_username(username, pool).await?;
+    database::users_totp::delete_by_username(username, pool).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{create_test_user, delete_test_user, test_pool};
+
+    #[test]
+    fn codes_are_valid_one_step_around_now() {
+        let totp = totp("meow", generate_secret());
+        let time = 1_700_000_000;
+
+        let code = totp.generate(time);
+        assert_eq!(code_step(&totp, &code, time), Some(time / STEP));
+        assert_eq!(code_step(&totp, &code, time + STEP), Some(time / STEP));
+        assert_eq!(code_step(&totp, &code, time + 2 * STEP), None);
+    }
+
+    #[actix_web::test]
+    async fn codes_and_recovery_codes_are_single_use() {
+        let pool = test_pool().await;
+
+        let username = create_test_user("totp_test", &pool).await;
+
+        let secret = generate_secret();
+        database::users_totp::set(&username, &secret, &pool)
+            .await
+            .unwrap();
+
+        let code = totp(&username, secret).generate_current().unwrap();
+
+        assert!(!verify(&username, &code, &pool).await.unwrap());
+        assert!(verify_enrollment(&username, &code, &pool).await.unwrap());
+        database::users_totp::enable_by_username(&username, &pool)
+            .await
+            .unwrap();
+        assert!(is_enabled(&username, &pool).await.unwrap());
+
+        // Already used during enrollment
+        assert!(!verify(&username, &code, &pool).await.unwrap());
+
+        let recovery_codes = regenerate_recovery_codes(&username, &pool).await.unwrap();
+        assert_eq!(recovery_codes.len(), RECOVERY_CODES);
+
+        let recovery_code = recovery_codes[0].to_uppercase().replace('-', "");
+        assert!(verify(&username, &recovery_code, &pool).await.unwrap());
+        assert!(!verify(&username, &recovery_code, &pool).await.unwrap());
+
+        disable(&username, &pool).await.unwrap();
+        assert!(!is_enabled(&username, &pool).await.unwrap());
+        assert!(!verify(&username, &recovery_codes[1], &pool).await.unwrap());
+
+        delete_test_user(&username, &pool).await;
+    }
+
+    #[actix_web::test]
+    async fn wrong_codes_lock_the_user_across_login_tokens() {
+        let pool = test_pool().await;
+
+        let username = create_test_user("totp_test", &pool).await;
+
+        let secret = generate_secret();
+        database::users_totp::set(&username, &secret, &pool)
+            .await
+            .unwrap();
+        database::users_totp::enable_by_username(&username, &pool)
+            .await
+            .unwrap();
+        let recovery_codes = regenerate_recovery_codes(&username, &pool).await.unwrap();
+
+        // A fresh pending login doesn't reset the count
+        for _ in 0..MAX_FAILURES {
+            tokens::create(&username, tokens::Purpose::Totp, None, &pool)
+                .await
+                .unwrap()
+                .unwrap();
+            assert!(!verify(&username, "not-a-code", &pool).await.unwrap());
+        }
+        assert!(!verify(&username, &recovery_codes[0], &pool).await.unwrap());
+
+        // Once the window passed, a valid code works again and resets the count
+        sqlx::query(
+            "UPDATE users_totp SET failed_at = now() - interval '1 hour' WHERE username = $1",
+        )
+        .bind(&username)
+        .execute(&pool)
+        .await
+        .unwrap();
+        assert!(verify(&username, &recovery_codes[0], &pool).await.unwrap());
+        assert_eq!(
+            database::users_totp::get_one_by_username(&username, &pool)
+                .await
+                .unwrap()
+                .unwrap()
+                .failures,
+            0
+        );
+
+        delete_test_user(&username, &pool).await;
+    }
+}
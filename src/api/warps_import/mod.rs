@@ -1,20 +1,19 @@
-mod uid;
+use std::collections::HashMap;
 
-use std::{collections::HashMap, sync::Arc, time::Duration};
-
-use actix_session::Session;
-use actix_web::{post, rt, web, HttpResponse, Responder};
+use actix_web::{post, web, HttpResponse, Responder};
 use chrono::{FixedOffset, NaiveDateTime};
-use futures::lock::Mutex;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use strum::IntoEnumIterator;
 use url::Url;
 use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
 
 use crate::{
     api::{
+        auth::{Auth, Scope},
         banner_helpers::{self, HSR_STANDARD},
+        import_jobs::{self, GachaLogFetcher, Game, Upstream},
         validate_import_url, ApiResult,
     },
     database, mihomo, GachaType, Language,
@@ -24,24 +23,16 @@ use crate::{
 #[openapi(
     tags((name = "warps-import")),
     paths(post_warps_import),
-    components(schemas(WarpsImportParams, WarpsImport, WarpsImportInfo, Status))
+    components(schemas(WarpsImportParams, WarpsImport))
 )]
 struct ApiDoc;
 
 pub fn openapi() -> utoipa::openapi::OpenApi {
-    let mut openapi = ApiDoc::openapi();
-    openapi.merge(uid::openapi());
-    openapi
-}
-
-lazy_static::lazy_static! {
-    static ref DATA: web::Data<WarpsImportInfos> = web::Data::new(WarpsImportInfos::default());
+    ApiDoc::openapi()
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.app_data(DATA.clone())
-        .service(post_warps_import)
-        .configure(uid::configure);
+    cfg.service(post_warps_import);
 }
 
 #[derive(Deserialize)]
@@ -64,29 +55,6 @@ struct Entry {
     time: String,
 }
 
-type WarpsImportInfos = Mutex<HashMap<i32, Arc<Mutex<WarpsImportInfo>>>>;
-
-#[derive(Serialize, ToSchema, Clone)]
-#[serde(rename_all = "snake_case")]
-enum Status {
-    Pending,
-    Calculating,
-    Finished,
-    Error(String),
-}
-
-#[derive(Serialize, ToSchema, Clone)]
-struct WarpsImportInfo {
-    gacha_type: GachaType,
-    standard: usize,
-    departure: usize,
-    special: usize,
-    lc: usize,
-    collab: usize,
-    collab_lc: usize,
-    status: Status,
-}
-
 #[derive(Deserialize, ToSchema)]
 struct WarpsImportParams {
     url: String,
@@ -96,6 +64,9 @@ struct WarpsImportParams {
 
 #[derive(Serialize, ToSchema)]
 struct WarpsImport {
+    /// Poll /api/import-jobs/{id} for progress
+    #[schema(value_type = String)]
+    id: Uuid,
     uid: i32,
 }
 
@@ -111,9 +82,8 @@ struct WarpsImport {
 )]
 #[post("/api/warps-import")]
 async fn post_warps_import(
-    session: Session,
+    auth: Auth,
     params: web::Json<WarpsImportParams>,
-    warps_import_infos: web::Data<WarpsImportInfos>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
     let original_url = match validate_import_url(&params.url) {
@@ -121,44 +91,14 @@ async fn post_warps_import(
         Err(response) => return Ok(response),
     };
 
-    let mut uid = None;
-
-    // try to find the users uid. check each until we find one
-    for gacha_type in GachaType::iter() {
-        let gacha_type_id = gacha_type.id();
-        let url = gacha_log_url(gacha_type, &original_url)?;
-
-        let gacha_log: GachaLog =
-            reqwest::get(format!("{url}&gacha_type={gacha_type_id}&end_id=0"))
-                .await?
-                .json()
-                .await?;
-
-        if let Some(entry) = gacha_log.data.list.first() {
-            uid = Some(entry.uid.parse()?);
-            break;
-        }
-    }
+    let Some(uid) = find_uid(&Upstream, &original_url).await? else {
+        let id = import_jobs::insert_failed(Game::Hsr, "No data", &pool).await?;
 
-    let Some(uid) = uid else {
-        let info = Arc::new(Mutex::new(WarpsImportInfo {
-            gacha_type: GachaType::Standard,
-            standard: 0,
-            departure: 0,
-            special: 0,
-            lc: 0,
-            collab: 0,
-            collab_lc: 0,
-            status: Status::Error("No data".to_string()),
-        }));
-
-        warps_import_infos.lock().await.insert(0, info.clone());
-
-        return Ok(HttpResponse::Ok().json(WarpsImport { uid: 0 }));
+        return Ok(HttpResponse::Ok().json(WarpsImport { id, uid: 0 }));
     };
 
     // Wacky way to update the database in case the uid isn't in there
-    if !database::mihomo::exists(uid, &pool).await?
+    if !database::mihomo::exists(uid, &**pool).await?
         && mihomo::get(uid, Language::En, &pool).await?.is_none()
     {
         let region = match uid.to_string().chars().next() {
@@ -175,10 +115,10 @@ async fn post_warps_import(
             ..Default::default()
         };
 
-        database::mihomo::set(&db_mihomo, &pool).await?;
+        database::mihomo::set(&db_mihomo, &**pool).await?;
     }
 
-    if let Ok(Some(username)) = session.get::<String>("username") {
+    if let Some(username) = auth.username(Scope::WarpsImport) {
         let connection = database::connections::DbConnection {
             uid,
             username,
@@ -186,72 +126,79 @@ async fn post_warps_import(
             private: false,
         };
 
-        database::connections::set(&connection, &pool).await?;
+        database::connections::set(&connection, &**pool).await?;
     }
 
-    if warps_import_infos.lock().await.contains_key(&uid) {
-        return Ok(HttpResponse::Ok().json(WarpsImport { uid }));
-    }
+    let id = import_jobs::enqueue(
+        Game::Hsr,
+        uid,
+        &original_url,
+        params.ignore_timestamps,
+        &pool,
+    )
+    .await?;
 
-    let info = Arc::new(Mutex::new(WarpsImportInfo {
-        gacha_type: GachaType::Standard,
-        standard: 0,
-        departure: 0,
-        special: 0,
-        lc: 0,
-        collab: 0,
-        collab_lc: 0,
-        status: Status::Pending,
-    }));
+    Ok(HttpResponse::Ok().json(WarpsImport { id, uid }))
+}
 
-    warps_import_infos.lock().await.insert(uid, info.clone());
+/// Checks the banners until one has a warp, which has the uid
+async fn find_uid(fetcher: &impl GachaLogFetcher, original_url: &Url) -> ApiResult<Option<i32>> {
+    for gacha_type in GachaType::iter() {
+        let gacha_type_id = gacha_type.id();
+        let url = gacha_log_url(gacha_type, original_url)?;
 
-    rt::spawn(async move {
-        let mut error = Ok(());
+        let gacha_log: GachaLog = import_jobs::get_gacha_log(
+            fetcher,
+            &format!("{url}&gacha_type={gacha_type_id}&end_id=0"),
+        )
+        .await?;
 
-        for gacha_type in GachaType::iter() {
-            info.lock().await.gacha_type = gacha_type;
+        if let Some(entry) = gacha_log.data.list.first() {
+            return Ok(Some(entry.uid.parse()?));
+        }
+    }
 
-            if let Err(e) = import_warps(
-                uid,
+    Ok(None)
+}
+
+pub async fn run(
+    job: &database::import_jobs::DbImportJob,
+    fetcher: &impl GachaLogFetcher,
+    pool: &PgPool,
+) -> ApiResult<()> {
+    // A job reclaimed while calculating has fetched everything and dropped its url already
+    if job.status != import_jobs::Status::Calculating.to_string() {
+        let Some(url) = &job.url else {
+            return Err(anyhow::anyhow!("Missing url").into());
+        };
+        let original_url = Url::parse(url)?;
+
+        for gacha_type in import_jobs::remaining_gacha_types(job) {
+            import_warps(
+                job.id,
+                fetcher,
+                job.uid,
                 &original_url,
-                params.ignore_timestamps,
+                job.ignore_timestamps,
                 gacha_type,
-                &info,
-                &pool,
+                pool,
             )
-            .await
-            {
-                error = Err(e);
-
-                break;
-            }
-        }
-
-        if let Err(e) = error {
-            info.lock().await.status = Status::Error(e.to_string());
-        } else if let Err(e) = calculate_stats(uid, &info, &pool).await {
-            info.lock().await.status = Status::Error(e.to_string());
-        } else {
-            info.lock().await.status = Status::Finished;
+            .await?;
         }
+    }
 
-        rt::spawn(async move {
-            rt::time::sleep(Duration::from_secs(60)).await;
-
-            warps_import_infos.lock().await.remove(&uid);
-        });
-    });
+    calculate_stats(job.id, job.uid, pool).await?;
 
-    Ok(HttpResponse::Ok().json(WarpsImport { uid }))
+    Ok(())
 }
 
 async fn import_warps(
+    job_id: Uuid,
+    fetcher: &impl GachaLogFetcher,
     uid: i32,
     original_url: &Url,
     ignore_timestamps: bool,
     gacha_type: GachaType,
-    info: &Arc<Mutex<WarpsImportInfo>>,
     pool: &PgPool,
 ) -> ApiResult<()> {
     let mut url = gacha_log_url(gacha_type, original_url)?;
@@ -282,22 +229,12 @@ async fn import_warps(
         }
     };
 
-    'outer: loop {
-        let mut i = 0;
-        let gacha_log = loop {
-            let response = reqwest::get(format!("{url}&end_id={end_id}")).await?;
-            if let Ok(gacha_log) = response.json::<GachaLog>().await {
-                break gacha_log;
-            }
+    let gacha_type_name = gacha_type.to_string();
+    database::import_jobs::set_progress(job_id, &gacha_type_name, 0, pool).await?;
 
-            if i > 2 {
-                return Err(anyhow::anyhow!("Unsure").into());
-            }
-
-            rt::time::sleep(Duration::from_secs(1)).await;
-
-            i += 1;
-        };
+    'outer: loop {
+        let gacha_log: GachaLog =
+            import_jobs::get_gacha_log(fetcher, &format!("{url}&end_id={end_id}")).await?;
 
         if gacha_log.data.list.is_empty() {
             break;
@@ -346,18 +283,20 @@ async fn import_warps(
             set_all.light_cone.push(light_cone);
             set_all.timestamp.push(timestamp);
             set_all.official.push(true);
-
-            match gacha_type {
-                GachaType::Standard => info.lock().await.standard += 1,
-                GachaType::Departure => info.lock().await.departure += 1,
-                GachaType::Special => info.lock().await.special += 1,
-                GachaType::Lc => info.lock().await.lc += 1,
-                GachaType::Collab => info.lock().await.collab += 1,
-                GachaType::CollabLc => info.lock().await.collab_lc += 1,
-            }
         }
+
+        database::import_jobs::set_progress(
+            job_id,
+            &gacha_type_name,
+            set_all.id.len() as i32,
+            pool,
+        )
+        .await?;
     }
 
+    database::import_jobs::set_progress(job_id, &gacha_type_name, set_all.id.len() as i32, pool)
+        .await?;
+
     match gacha_type {
         GachaType::Departure => database::warps::departure::set_all(&set_all, pool).await?,
         GachaType::Standard => database::warps::standard::set_all(&set_all, pool).await?,
@@ -370,23 +309,15 @@ async fn import_warps(
     Ok(())
 }
 
-async fn calculate_stats(
-    uid: i32,
-    info: &Arc<Mutex<WarpsImportInfo>>,
-    pool: &PgPool,
-) -> anyhow::Result<()> {
-    info.lock().await.status = Status::Calculating;
+async fn calculate_stats(job_id: Uuid, uid: i32, pool: &PgPool) -> anyhow::Result<()> {
+    database::import_jobs::set_calculating(job_id, pool).await?;
 
-    info.lock().await.gacha_type = GachaType::Standard;
     calculate_stats_standard(uid, pool).await?;
-    info.lock().await.gacha_type = GachaType::Special;
     calculate_stats_special(uid, pool).await?;
-    info.lock().await.gacha_type = GachaType::Lc;
     calculate_stats_lc(uid, pool).await?;
-    info.lock().await.gacha_type = GachaType::Collab;
     calculate_stats_collab(uid, pool).await?;
-    info.lock().await.gacha_type = GachaType::CollabLc;
     calculate_stats_collab_lc(uid, pool).await?;
+    calculate_stats_departure(uid, pool).await?;
 
     Ok(())
 }
@@ -473,6 +404,61 @@ async fn calculate_stats_standard(uid: i32, pool: &PgPool) -> anyhow::Result<()>
     Ok(())
 }
 
+async fn calculate_stats_departure(uid: i32, pool: &PgPool) -> anyhow::Result<()> {
+    let warps = database::warps::departure::get_infos_by_uid(uid, pool).await?;
+
+    let mut pull_4 = 0;
+    let mut sum_4 = 0;
+    let mut count_4 = 0;
+
+    let mut pull_5 = 0;
+    let mut sum_5 = 0;
+    let mut count_5 = 0;
+
+    for warp in &warps {
+        pull_4 += 1;
+        pull_5 += 1;
+
+        match warp.rarity.unwrap() {
+            4 => {
+                count_4 += 1;
+                sum_4 += pull_4;
+                pull_4 = 0;
+            }
+            5 => {
+                count_5 += 1;
+                sum_5 += pull_5;
+                pull_5 = 0;
+            }
+            _ => {}
+        }
+    }
+
+    let luck_4 = if count_4 != 0 {
+        sum_4 as f64 / count_4 as f64
+    } else {
+        0.0
+    };
+    let luck_5 = if count_5 != 0 {
+        sum_5 as f64 / count_5 as f64
+    } else {
+        0.0
+    };
+
+    let stat = database::warps_stats::DbWarpsStat {
+        uid,
+        luck_4,
+        luck_5,
+        // these don't apply to departure warps
+        win_rate: 0.0,
+        win_streak: 0,
+        loss_streak: 0,
+    };
+    database::warps_stats::departure::set(&stat, pool).await?;
+
+    Ok(())
+}
+
 async fn calculate_stats_special(uid: i32, pool: &PgPool) -> anyhow::Result<()> {
     let mut banners: HashMap<_, Vec<_>> = HashMap::new();
 
@@ -892,3 +878,232 @@ async fn calculate_stats_collab_lc(uid: i32, pool: &PgPool) -> anyhow::Result<()
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        api::import_jobs::mock::{self, MockUpstream},
+        test_util::test_pool,
+    };
+
+    const UID: i32 = 700_000_001;
+
+    fn entry(id: &str, gacha_type: &str, item_id: &str, item_type: &str, time: &str) -> Value {
+        json!({
+            "uid": UID.to_string(),
+            "gacha_id": "1001",
+            "gacha_type": gacha_type,
+            "item_id": item_id,
+            "count": "1",
+            "time": time,
+            "name": "",
+            "lang": "en-us",
+            "item_type": item_type,
+            "rank_type": "4",
+            "id": id,
+        })
+    }
+
+    async fn setup(uid: i32, pool: &PgPool) {
+        sqlx::query("DELETE FROM import_jobs WHERE game = 'hsr' AND uid = $1")
+            .bind(uid)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM mihomo WHERE uid = $1")
+            .bind(uid)
+            .execute(pool)
+            .await
+            .unwrap();
+
+        database::characters::set_all(&[1001, 1003], &[4, 5], pool)
+            .await
+            .unwrap();
+        database::light_cones::set_all(&[20000, 21000], &[3, 4], pool)
+            .await
+            .unwrap();
+        database::mihomo::set(
+            &database::mihomo::DbMihomo {
+                uid,
+                region: "eu".to_string(),
+                ..Default::default()
+            },
+            pool,
+        )
+        .await
+        .unwrap();
+    }
+
+    async fn import(upstream: &MockUpstream, pool: &PgPool) -> database::import_jobs::DbImportJob {
+        let url = Url::parse(
+            "https://gs.hoyoverse.com/common/gacha_record/api/getGachaLog?authkey=key&authkey_ver=1&sign_type=2&game_biz=hkrpg_global",
+        )
+        .unwrap();
+
+        let id = import_jobs::enqueue(Game::Hsr, UID, &url, false, pool)
+            .await
+            .unwrap();
+        let job = database::import_jobs::get_by_id(id, pool)
+            .await
+            .unwrap()
+            .unwrap();
+
+        run(&job, upstream, pool).await.unwrap();
+        assert!(database::import_jobs::get_by_id(id, pool)
+            .await
+            .unwrap()
+            .unwrap()
+            .url
+            .is_none());
+        database::import_jobs::finish(id, pool).await.unwrap();
+
+        database::import_jobs::get_by_id(id, pool)
+            .await
+            .unwrap()
+            .unwrap()
+    }
+
+    #[actix_web::test]
+    async fn imports_all_pages_and_only_new_warps() {
+        let pool = test_pool().await;
+        setup(UID, &pool).await;
+
+        let upstream = MockUpstream::start().await;
+        upstream.page(
+            1,
+            "0",
+            mock::gacha_log(vec![
+                entry(
+                    "1700000000000000003",
+                    "1",
+                    "1003",
+                    "Character",
+                    "2024-01-03 12:00:00",
+                ),
+                entry(
+                    "1700000000000000002",
+                    "1",
+                    "21000",
+                    "光錐",
+                    "2024-01-02 12:00:00",
+                ),
+            ]),
+        );
+        upstream.page(
+            1,
+            "1700000000000000002",
+            mock::gacha_log(vec![entry(
+                "1700000000000000001",
+                "1",
+                "1001",
+                "角色",
+                "2024-01-01 12:00:00",
+            )]),
+        );
+        upstream.page(
+            21,
+            "0",
+            mock::gacha_log(vec![entry(
+                "1700000000000000004",
+                "21",
+                "20000",
+                "Light Cone",
+                "2024-01-04 12:00:00",
+            )]),
+        );
+        upstream.throttle(1, "0", 1);
+
+        let job = import(&upstream, &pool).await;
+
+        assert_eq!(job.counts["standard"], 3);
+        assert_eq!(job.counts["departure"], 0);
+        assert_eq!(job.counts["collab"], 1);
+        assert!(job.url.is_none());
+
+        let warps = database::warps::standard::get_infos_by_uid(UID, &pool)
+            .await
+            .unwrap();
+        let light_cones: Vec<_> = warps.iter().filter_map(|w| w.light_cone).collect();
+        assert_eq!(warps.len(), 3);
+        assert_eq!(light_cones, vec![21000]);
+
+        let requests = upstream.requests();
+        assert!(requests
+            .iter()
+            .all(|url| url.query_pairs().any(|(k, v)| k == "authkey" && v == "key")));
+        assert!(requests
+            .iter()
+            .any(|url| url.path() == "/common/gacha_record/api/getLdGachaLog"));
+
+        // Everything is older than what's stored now, so a second import adds nothing
+        let job = import(&upstream, &pool).await;
+
+        assert_eq!(job.counts["standard"], 0);
+        assert_eq!(
+            database::warps::standard::get_count_by_uid(UID, &pool)
+                .await
+                .unwrap(),
+            3
+        );
+    }
+
+    #[actix_web::test]
+    async fn expired_authkey_is_not_retried() {
+        let pool = test_pool().await;
+        setup(UID + 1, &pool).await;
+
+        let upstream = MockUpstream::start().await;
+        upstream.page(1, "0", mock::error(-101, "authkey timeout"));
+
+        let url =
+            Url::parse("https://gs.hoyoverse.com/common/gacha_record/api/getGachaLog").unwrap();
+        let id = import_jobs::enqueue(Game::Hsr, UID + 1, &url, false, &pool)
+            .await
+            .unwrap();
+        let job = database::import_jobs::get_by_id(id, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+
+        let error = run(&job, &upstream, &pool).await.unwrap_err();
+        database::import_jobs::finish(id, &pool).await.unwrap();
+
+        assert!(!error.is::<import_jobs::TransientError>());
+        assert_eq!(error.to_string(), "authkey timeout");
+        assert_eq!(upstream.requests().len(), 1);
+    }
+
+    #[actix_web::test]
+    async fn stale_jobs_fail_once_out_of_attempts() {
+        let pool = test_pool().await;
+        setup(UID + 2, &pool).await;
+
+        let url =
+            Url::parse("https://gs.hoyoverse.com/common/gacha_record/api/getGachaLog?authkey=key")
+                .unwrap();
+        let id = import_jobs::enqueue(Game::Hsr, UID + 2, &url, false, &pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE import_jobs SET status = 'running', attempts = $2, updated = now() - interval '1 hour' WHERE id = $1")
+            .bind(id)
+            .bind(import_jobs::MAX_JOB_ATTEMPTS)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let claimed = database::import_jobs::claim(import_jobs::MAX_JOB_ATTEMPTS, &pool)
+            .await
+            .unwrap();
+        assert!(claimed.is_none_or(|job| job.id != id));
+
+        let job = database::import_jobs::get_by_id(id, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(job.status, "error");
+        assert!(job.url.is_none());
+    }
+}
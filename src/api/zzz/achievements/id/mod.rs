@@ -57,7 +57,7 @@ async fn get_zzz_achievement(
 
     if let Some(set) = achievement.set {
         achievement.related = Some(
-            database::zzz::achievements::get_all_related_ids(achievement.id, set, &pool).await?,
+            database::zzz::achievements::get_all_related_ids(achievement.id, set, &**pool).await?,
         );
     }
 
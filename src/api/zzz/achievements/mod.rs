@@ -132,7 +132,7 @@ async fn get_zzz_achievements(
     for achievement in &mut achievements {
         if let Some(set) = achievement.set {
             achievement.related = Some(
-                database::zzz::achievements::get_all_related_ids(achievement.id, set, &pool)
+                database::zzz::achievements::get_all_related_ids(achievement.id, set, &**pool)
                     .await?,
             );
         }
@@ -0,0 +1,48 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+
+use crate::{
+    api::{
+        banner_stats::{BannerLoss, BannerStats},
+        ApiResult,
+    },
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "zzz/banners/{id}/stats")),
+    paths(get_zzz_banner_stats),
+    components(schemas(BannerStats, BannerLoss))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_zzz_banner_stats);
+}
+
+#[utoipa::path(
+    tag = "zzz/banners/{id}/stats",
+    get,
+    path = "/api/zzz/banners/{id}/stats",
+    responses(
+        (status = 200, description = "BannerStats", body = BannerStats),
+        (status = 404, description = "No stats yet"),
+    )
+)]
+#[get("/api/zzz/banners/{id}/stats")]
+async fn get_zzz_banner_stats(
+    id: web::Path<i32>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let Some(stats) = database::zzz::banners_stats::get_by_id(*id, &pool).await? else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    Ok(HttpResponse::Ok().json(BannerStats::from(stats)))
+}
@@ -1,7 +1,10 @@
 mod achievements;
+mod banners;
 mod rng_import;
+mod simulate;
+mod stats;
 mod signals;
-mod signals_import;
+pub(super) mod signals_import;
 
 use actix_web::web;
 use utoipa::OpenApi;
@@ -13,7 +16,10 @@ struct ApiDoc;
 pub fn openapi() -> utoipa::openapi::OpenApi {
     let mut openapi = ApiDoc::openapi();
     openapi.merge(achievements::openapi());
+    openapi.merge(banners::openapi());
     openapi.merge(rng_import::openapi());
+    openapi.merge(simulate::openapi());
+    openapi.merge(stats::openapi());
     openapi.merge(signals::openapi());
     openapi.merge(signals_import::openapi());
     openapi
@@ -21,7 +27,10 @@ pub fn openapi() -> utoipa::openapi::OpenApi {
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
     cfg.configure(achievements::configure)
+        .configure(banners::configure)
         .configure(rng_import::configure)
+        .configure(simulate::configure)
+        .configure(stats::configure)
         .configure(signals::configure)
         .configure(signals_import::configure);
 }
@@ -1,9 +1,14 @@
-use actix_session::Session;
 use actix_web::{post, web, HttpResponse, Responder};
 use sqlx::PgPool;
 use utoipa::OpenApi;
 
-use crate::{api::ApiResult, database, ZzzGachaType};
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        ApiResult,
+    },
+    database, ZzzGachaType,
+};
 
 #[derive(OpenApi)]
 #[openapi(
@@ -46,11 +51,11 @@ struct Signal {
 )]
 #[post("/api/zzz/rng-signals-import")]
 async fn post_rng_signals_import(
-    session: Session,
+    auth: Auth,
     params: web::Json<RngSignalsImportParams>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
-    let Ok(Some(username)) = session.get::<String>("username") else {
+    let Some(username) = auth.username(Scope::WarpsImport) else {
         return Ok(HttpResponse::BadRequest().finish());
     };
 
@@ -63,7 +68,7 @@ async fn post_rng_signals_import(
     };
     let uid = uid as i32;
 
-    let admin = database::admins::exists(&username, &pool).await?;
+    let admin = auth.is_admin(&pool).await?;
 
     if !admin && database::zzz::uids::get_by_uid(uid, &pool).await.is_err() {
         return Ok(HttpResponse::BadRequest().finish());
@@ -160,22 +165,22 @@ async fn post_rng_signals_import(
 
         match gacha_type {
             ZzzGachaType::Standard => {
-                database::zzz::signals::standard::set_all(&set_all, &pool).await?
+                database::zzz::signals::standard::set_all(&set_all, &**pool).await?
             }
             ZzzGachaType::Special => {
-                database::zzz::signals::special::set_all(&set_all, &pool).await?
+                database::zzz::signals::special::set_all(&set_all, &**pool).await?
             }
             ZzzGachaType::WEngine => {
-                database::zzz::signals::w_engine::set_all(&set_all, &pool).await?
+                database::zzz::signals::w_engine::set_all(&set_all, &**pool).await?
             }
             ZzzGachaType::Bangboo => {
-                database::zzz::signals::bangboo::set_all(&set_all, &pool).await?
+                database::zzz::signals::bangboo::set_all(&set_all, &**pool).await?
             }
             ZzzGachaType::ExclusiveRescreening => {
-                database::zzz::signals::exclusive_rescreening::set_all(&set_all, &pool).await?
+                database::zzz::signals::exclusive_rescreening::set_all(&set_all, &**pool).await?
             }
             ZzzGachaType::WEngineReverberation => {
-                database::zzz::signals::w_engine_reverberation::set_all(&set_all, &pool).await?
+                database::zzz::signals::w_engine_reverberation::set_all(&set_all, &**pool).await?
             }
         }
     }
@@ -1,19 +1,19 @@
-mod uid;
-
-use std::{collections::HashMap, sync::Arc, time::Duration};
-
-use actix_session::Session;
-use actix_web::{post, rt, web, HttpResponse, Responder};
+use actix_web::{post, web, HttpResponse, Responder};
 use chrono::{FixedOffset, NaiveDateTime};
-use futures::lock::Mutex;
 use serde::{Deserialize, Serialize};
 use sqlx::PgPool;
 use strum::IntoEnumIterator;
 use url::Url;
 use utoipa::{OpenApi, ToSchema};
+use uuid::Uuid;
 
 use crate::{
-    api::{validate_import_url, ApiResult},
+    api::{
+        auth::{Auth, Scope},
+        banner_helpers::{self, ZZZ_STANDARD},
+        import_jobs::{self, GachaLogFetcher, Game, Upstream},
+        validate_import_url, ApiResult,
+    },
     database, ZzzGachaType,
 };
 
@@ -21,24 +21,16 @@ use crate::{
 #[openapi(
     tags((name = "zzz/signals-import")),
     paths(post_zzz_signals_import),
-    components(schemas(SignalsImportParams, SignalsImport, SignalsImportInfo, Status))
+    components(schemas(SignalsImportParams, SignalsImport))
 )]
 struct ApiDoc;
 
 pub fn openapi() -> utoipa::openapi::OpenApi {
-    let mut openapi = ApiDoc::openapi();
-    openapi.merge(uid::openapi());
-    openapi
-}
-
-lazy_static::lazy_static! {
-    static ref DATA: web::Data<SignalsImportInfos> = web::Data::new(SignalsImportInfos::default());
+    ApiDoc::openapi()
 }
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.app_data(DATA.clone())
-        .service(post_zzz_signals_import)
-        .configure(uid::configure);
+    cfg.service(post_zzz_signals_import);
 }
 
 #[derive(Deserialize)]
@@ -61,29 +53,6 @@ struct Entry {
     time: String,
 }
 
-type SignalsImportInfos = Mutex<HashMap<i32, Arc<Mutex<SignalsImportInfo>>>>;
-
-#[derive(Serialize, ToSchema, Clone)]
-#[serde(rename_all = "snake_case")]
-enum Status {
-    Pending,
-    Calculating,
-    Finished,
-    Error(String),
-}
-
-#[derive(Serialize, ToSchema, Clone)]
-struct SignalsImportInfo {
-    gacha_type: ZzzGachaType,
-    standard: usize,
-    special: usize,
-    w_engine: usize,
-    bangboo: usize,
-    exclusive_rescreening: usize,
-    w_engine_reverberation: usize,
-    status: Status,
-}
-
 #[derive(Deserialize, ToSchema)]
 struct SignalsImportParams {
     url: String,
@@ -91,6 +60,9 @@ struct SignalsImportParams {
 
 #[derive(Serialize, ToSchema)]
 struct SignalsImport {
+    /// Poll /api/import-jobs/{id} for progress
+    #[schema(value_type = String)]
+    id: Uuid,
     uid: i32,
 }
 
@@ -106,9 +78,8 @@ struct SignalsImport {
 )]
 #[post("/api/zzz/signals-import")]
 async fn post_zzz_signals_import(
-    session: Session,
+    auth: Auth,
     params: web::Json<SignalsImportParams>,
-    signals_import_infos: web::Data<SignalsImportInfos>,
     pool: web::Data<PgPool>,
 ) -> ApiResult<impl Responder> {
     let url = match validate_import_url(&params.url) {
@@ -132,40 +103,14 @@ async fn post_zzz_signals_import(
         .extend_pairs(&[("lang", "en"), ("game_biz", "nap_global"), ("size", "20")])
         .finish();
 
-    let mut uid = 0;
-
-    for gacha_type in ZzzGachaType::iter().map(|gt| gt.id()) {
-        let gacha_log: GachaLog =
-            reqwest::get(format!("{url}&real_gacha_type={gacha_type}&end_id=0"))
-                .await?
-                .json()
-                .await?;
-
-        if let Some(entry) = gacha_log.data.list.first() {
-            uid = entry.uid.parse()?;
-            break;
-        }
-    }
+    let Some(uid) = find_uid(&Upstream, &url).await? else {
+        let id = import_jobs::insert_failed(Game::Zzz, "No data", &pool).await?;
 
-    if uid == 0 {
-        let info = Arc::new(Mutex::new(SignalsImportInfo {
-            gacha_type: ZzzGachaType::Standard,
-            standard: 0,
-            bangboo: 0,
-            special: 0,
-            w_engine: 0,
-            exclusive_rescreening: 0,
-            w_engine_reverberation: 0,
-            status: Status::Error("No data".to_string()),
-        }));
-
-        signals_import_infos.lock().await.insert(uid, info.clone());
-
-        return Ok(HttpResponse::Ok().json(SignalsImport { uid }));
-    }
+        return Ok(HttpResponse::Ok().json(SignalsImport { id, uid: 0 }));
+    };
 
-    database::zzz::uids::set(&database::zzz::uids::DbUid { uid }, &pool).await?;
-    if let Ok(Some(username)) = session.get::<String>("username") {
+    database::zzz::uids::set(&database::zzz::uids::DbUid { uid }, &**pool).await?;
+    if let Some(username) = auth.username(Scope::WarpsImport) {
         let connection = database::zzz::connections::DbConnection {
             uid,
             username,
@@ -173,61 +118,58 @@ async fn post_zzz_signals_import(
             private: false,
         };
 
-        database::zzz::connections::set(&connection, &pool).await?;
-    }
-
-    if signals_import_infos.lock().await.contains_key(&uid) {
-        return Ok(HttpResponse::Ok().json(SignalsImport { uid }));
+        database::zzz::connections::set(&connection, &**pool).await?;
     }
 
-    let info = Arc::new(Mutex::new(SignalsImportInfo {
-        gacha_type: ZzzGachaType::Standard,
-        standard: 0,
-        bangboo: 0,
-        special: 0,
-        w_engine: 0,
-        exclusive_rescreening: 0,
-        w_engine_reverberation: 0,
-        status: Status::Pending,
-    }));
+    let id = import_jobs::enqueue(Game::Zzz, uid, &url, false, &pool).await?;
 
-    signals_import_infos.lock().await.insert(uid, info.clone());
+    Ok(HttpResponse::Ok().json(SignalsImport { id, uid }))
+}
 
-    rt::spawn(async move {
-        let mut error = Ok(());
+/// Checks the banners until one has a signal, which has the uid
+async fn find_uid(fetcher: &impl GachaLogFetcher, url: &Url) -> ApiResult<Option<i32>> {
+    for gacha_type in ZzzGachaType::iter().map(|gt| gt.id()) {
+        let gacha_log: GachaLog = import_jobs::get_gacha_log(
+            fetcher,
+            &format!("{url}&real_gacha_type={gacha_type}&end_id=0"),
+        )
+        .await?;
 
-        for gacha_type in ZzzGachaType::iter() {
-            info.lock().await.gacha_type = gacha_type;
+        if let Some(entry) = gacha_log.data.list.first() {
+            return Ok(Some(entry.uid.parse()?));
+        }
+    }
 
-            if let Err(e) = import_signals(&url, gacha_type, &info, &pool).await {
-                error = Err(e);
+    Ok(None)
+}
 
-                break;
-            }
-        }
+pub async fn run(
+    job: &database::import_jobs::DbImportJob,
+    fetcher: &impl GachaLogFetcher,
+    pool: &PgPool,
+) -> ApiResult<()> {
+    // A job reclaimed while calculating has fetched everything and dropped its url already
+    if job.status != import_jobs::Status::Calculating.to_string() {
+        let Some(url) = &job.url else {
+            return Err(anyhow::anyhow!("Missing url").into());
+        };
+        let url = Url::parse(url)?;
 
-        if let Err(e) = error {
-            info.lock().await.status = Status::Error(e.to_string());
-        } else if let Err(e) = calculate_stats(uid, &info, &pool).await {
-            info.lock().await.status = Status::Error(e.to_string());
-        } else {
-            info.lock().await.status = Status::Finished;
+        for gacha_type in import_jobs::remaining_gacha_types(job) {
+            import_signals(job.id, fetcher, &url, gacha_type, pool).await?;
         }
+    }
 
-        rt::spawn(async move {
-            rt::time::sleep(Duration::from_secs(60)).await;
-
-            signals_import_infos.lock().await.remove(&uid);
-        });
-    });
+    calculate_stats(job.id, job.uid, pool).await?;
 
-    Ok(HttpResponse::Ok().json(SignalsImport { uid }))
+    Ok(())
 }
 
 async fn import_signals(
+    job_id: Uuid,
+    fetcher: &impl GachaLogFetcher,
     url: &Url,
     gacha_type: ZzzGachaType,
-    info: &Arc<Mutex<SignalsImportInfo>>,
     pool: &PgPool,
 ) -> ApiResult<()> {
     let mut url = url.clone();
@@ -239,22 +181,12 @@ async fn import_signals(
 
     let mut set_all = database::zzz::signals::SetAll::default();
 
-    loop {
-        let mut i = 0;
-        let gacha_log = loop {
-            let response = reqwest::get(format!("{url}&end_id={end_id}")).await?;
-            if let Ok(gacha_log) = response.json::<GachaLog>().await {
-                break gacha_log;
-            }
-
-            if i > 2 {
-                return Err(anyhow::anyhow!("Unsure").into());
-            }
-
-            rt::time::sleep(Duration::from_secs(1)).await;
+    let gacha_type_name = gacha_type.to_string();
+    database::import_jobs::set_progress(job_id, &gacha_type_name, 0, pool).await?;
 
-            i += 1;
-        };
+    loop {
+        let gacha_log: GachaLog =
+            import_jobs::get_gacha_log(fetcher, &format!("{url}&end_id={end_id}")).await?;
 
         if gacha_log.data.list.is_empty() {
             break;
@@ -299,16 +231,15 @@ async fn import_signals(
             set_all.bangboo.push(bangboo);
             set_all.timestamp.push(timestamp);
             set_all.official.push(true);
-
-            match gacha_type {
-                ZzzGachaType::Standard => info.lock().await.standard += 1,
-                ZzzGachaType::Special => info.lock().await.special += 1,
-                ZzzGachaType::WEngine => info.lock().await.w_engine += 1,
-                ZzzGachaType::Bangboo => info.lock().await.bangboo += 1,
-                ZzzGachaType::ExclusiveRescreening => info.lock().await.exclusive_rescreening += 1,
-                ZzzGachaType::WEngineReverberation => info.lock().await.w_engine_reverberation += 1,
-            }
         }
+
+        database::import_jobs::set_progress(
+            job_id,
+            &gacha_type_name,
+            set_all.id.len() as i32,
+            pool,
+        )
+        .await?;
     }
 
     match gacha_type {
@@ -327,21 +258,15 @@ async fn import_signals(
     Ok(())
 }
 
-async fn calculate_stats(
-    uid: i32,
-    info: &Arc<Mutex<SignalsImportInfo>>,
-    pool: &PgPool,
-) -> anyhow::Result<()> {
-    info.lock().await.status = Status::Calculating;
+async fn calculate_stats(job_id: Uuid, uid: i32, pool: &PgPool) -> anyhow::Result<()> {
+    database::import_jobs::set_calculating(job_id, pool).await?;
 
-    info.lock().await.gacha_type = ZzzGachaType::Standard;
     calculate_stats_standard(uid, pool).await?;
-    info.lock().await.gacha_type = ZzzGachaType::Special;
     calculate_stats_special(uid, pool).await?;
-    info.lock().await.gacha_type = ZzzGachaType::WEngine;
     calculate_stats_w_engine(uid, pool).await?;
-    info.lock().await.gacha_type = ZzzGachaType::Bangboo;
     calculate_stats_bangboo(uid, pool).await?;
+    calculate_stats_exclusive_rescreening(uid, pool).await?;
+    calculate_stats_w_engine_reverberation(uid, pool).await?;
 
     Ok(())
 }
@@ -406,6 +331,10 @@ async fn calculate_stats_standard(uid: i32, pool: &PgPool) -> anyhow::Result<()>
 }
 
 async fn calculate_stats_special(uid: i32, pool: &PgPool) -> anyhow::Result<()> {
+    let banners = banner_helpers::zzz_banner_ranges(pool).await?;
+
+    let is_win = banner_helpers::is_win_fn(&banners, ZZZ_STANDARD);
+
     let signals = database::zzz::signals::special::get_infos_by_uid(uid, pool).await?;
 
     let mut pull_a = 0;
@@ -447,20 +376,20 @@ async fn calculate_stats_special(uid: i32, pool: &PgPool) -> anyhow::Result<()>
                 } else {
                     count_win += 1;
 
-                    if [1021, 1041, 1101, 1141, 1181, 1211].contains(&signal.character.unwrap()) {
-                        win_streak = 0;
-
-                        loss_streak += 1;
-                        max_loss_streak = max_loss_streak.max(loss_streak);
-
-                        guarantee = true;
-                    } else {
+                    if is_win(signal.character.unwrap(), signal.timestamp) {
                         sum_win += 1;
 
                         loss_streak = 0;
 
                         win_streak += 1;
                         max_win_streak = max_win_streak.max(win_streak);
+                    } else {
+                        win_streak = 0;
+
+                        loss_streak += 1;
+                        max_loss_streak = max_loss_streak.max(loss_streak);
+
+                        guarantee = true;
                     }
                 }
             }
@@ -501,6 +430,10 @@ async fn calculate_stats_special(uid: i32, pool: &PgPool) -> anyhow::Result<()>
 }
 
 async fn calculate_stats_w_engine(uid: i32, pool: &PgPool) -> anyhow::Result<()> {
+    let banners = banner_helpers::zzz_banner_ranges(pool).await?;
+
+    let is_win = banner_helpers::is_win_fn(&banners, ZZZ_STANDARD);
+
     let signals = database::zzz::signals::w_engine::get_infos_by_uid(uid, pool).await?;
 
     let mut pull_a = 0;
@@ -542,22 +475,20 @@ async fn calculate_stats_w_engine(uid: i32, pool: &PgPool) -> anyhow::Result<()>
                 } else {
                     count_win += 1;
 
-                    if [14102, 14104, 14110, 14114, 14118, 14121]
-                        .contains(&signal.w_engine.unwrap())
-                    {
-                        win_streak = 0;
-
-                        loss_streak += 1;
-                        max_loss_streak = max_loss_streak.max(loss_streak);
-
-                        guarantee = true;
-                    } else {
+                    if is_win(signal.w_engine.unwrap(), signal.timestamp) {
                         sum_win += 1;
 
                         loss_streak = 0;
 
                         win_streak += 1;
                         max_win_streak = max_win_streak.max(win_streak);
+                    } else {
+                        win_streak = 0;
+
+                        loss_streak += 1;
+                        max_loss_streak = max_loss_streak.max(loss_streak);
+
+                        guarantee = true;
                     }
                 }
             }
@@ -647,3 +578,333 @@ async fn calculate_stats_bangboo(uid: i32, pool: &PgPool) -> anyhow::Result<()>
 
     Ok(())
 }
+
+async fn calculate_stats_exclusive_rescreening(uid: i32, pool: &PgPool) -> anyhow::Result<()> {
+    let banners = banner_helpers::zzz_banner_ranges(pool).await?;
+
+    let is_win = banner_helpers::is_win_fn(&banners, ZZZ_STANDARD);
+
+    let signals =
+        database::zzz::signals::exclusive_rescreening::get_infos_by_uid(uid, pool).await?;
+
+    let mut pull_a = 0;
+    let mut sum_a = 0;
+    let mut count_a = 0;
+
+    let mut pull_s = 0;
+    let mut sum_s = 0;
+    let mut count_s = 0;
+
+    let mut guarantee = false;
+
+    let mut sum_win = 0;
+    let mut count_win = 0;
+
+    let mut win_streak = 0;
+    let mut max_win_streak = 0;
+
+    let mut loss_streak = 0;
+    let mut max_loss_streak = 0;
+
+    for signal in &signals {
+        pull_a += 1;
+        pull_s += 1;
+
+        match signal.rarity.unwrap() {
+            3 => {
+                count_a += 1;
+                sum_a += pull_a;
+                pull_a = 0;
+            }
+            4 => {
+                count_s += 1;
+                sum_s += pull_s;
+                pull_s = 0;
+
+                if guarantee {
+                    guarantee = false;
+                } else {
+                    count_win += 1;
+
+                    if is_win(signal.character.unwrap(), signal.timestamp) {
+                        sum_win += 1;
+
+                        loss_streak = 0;
+
+                        win_streak += 1;
+                        max_win_streak = max_win_streak.max(win_streak);
+                    } else {
+                        win_streak = 0;
+
+                        loss_streak += 1;
+                        max_loss_streak = max_loss_streak.max(loss_streak);
+
+                        guarantee = true;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let win_streak = max_win_streak;
+    let loss_streak = max_loss_streak;
+
+    let luck_a = if count_a != 0 {
+        sum_a as f64 / count_a as f64
+    } else {
+        0.0
+    };
+    let luck_s = if count_s != 0 {
+        sum_s as f64 / count_s as f64
+    } else {
+        0.0
+    };
+    let win_rate = if count_win != 0 {
+        sum_win as f64 / count_win as f64
+    } else {
+        0.0
+    };
+
+    let stat =
+        database::zzz::signals_stats::exclusive_rescreening::DbSignalsStatExclusiveRescreening {
+            uid,
+            luck_a,
+            luck_s,
+            win_rate,
+            win_streak,
+            loss_streak,
+        };
+    database::zzz::signals_stats::exclusive_rescreening::set(&stat, pool).await?;
+
+    Ok(())
+}
+
+async fn calculate_stats_w_engine_reverberation(uid: i32, pool: &PgPool) -> anyhow::Result<()> {
+    let banners = banner_helpers::zzz_banner_ranges(pool).await?;
+
+    let is_win = banner_helpers::is_win_fn(&banners, ZZZ_STANDARD);
+
+    let signals =
+        database::zzz::signals::w_engine_reverberation::get_infos_by_uid(uid, pool).await?;
+
+    let mut pull_a = 0;
+    let mut sum_a = 0;
+    let mut count_a = 0;
+
+    let mut pull_s = 0;
+    let mut sum_s = 0;
+    let mut count_s = 0;
+
+    let mut guarantee = false;
+
+    let mut sum_win = 0;
+    let mut count_win = 0;
+
+    let mut win_streak = 0;
+    let mut max_win_streak = 0;
+
+    let mut loss_streak = 0;
+    let mut max_loss_streak = 0;
+
+    for signal in &signals {
+        pull_a += 1;
+        pull_s += 1;
+
+        match signal.rarity.unwrap() {
+            3 => {
+                count_a += 1;
+                sum_a += pull_a;
+                pull_a = 0;
+            }
+            4 => {
+                count_s += 1;
+                sum_s += pull_s;
+                pull_s = 0;
+
+                if guarantee {
+                    guarantee = false;
+                } else {
+                    count_win += 1;
+
+                    if is_win(signal.w_engine.unwrap(), signal.timestamp) {
+                        sum_win += 1;
+
+                        loss_streak = 0;
+
+                        win_streak += 1;
+                        max_win_streak = max_win_streak.max(win_streak);
+                    } else {
+                        win_streak = 0;
+
+                        loss_streak += 1;
+                        max_loss_streak = max_loss_streak.max(loss_streak);
+
+                        guarantee = true;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let win_streak = max_win_streak;
+    let loss_streak = max_loss_streak;
+
+    let luck_a = if count_a != 0 {
+        sum_a as f64 / count_a as f64
+    } else {
+        0.0
+    };
+    let luck_s = if count_s != 0 {
+        sum_s as f64 / count_s as f64
+    } else {
+        0.0
+    };
+    let win_rate = if count_win != 0 {
+        sum_win as f64 / count_win as f64
+    } else {
+        0.0
+    };
+
+    let stat =
+        database::zzz::signals_stats::w_engine_reverberation::DbSignalsStatWEngineReverberation {
+            uid,
+            luck_a,
+            luck_s,
+            win_rate,
+            win_streak,
+            loss_streak,
+        };
+    database::zzz::signals_stats::w_engine_reverberation::set(&stat, pool).await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::{
+        api::import_jobs::mock::{self, MockUpstream},
+        test_util::test_pool,
+    };
+
+    const UID: i32 = 1_500_000_003;
+
+    fn entry(id: &str, gacha_type: &str, item_id: &str, item_type: &str, time: &str) -> Value {
+        json!({
+            "uid": UID.to_string(),
+            "gacha_id": "",
+            "gacha_type": gacha_type,
+            "item_id": item_id,
+            "count": "1",
+            "time": time,
+            "name": "",
+            "lang": "zh-cn",
+            "item_type": item_type,
+            "rank_type": "3",
+            "id": id,
+        })
+    }
+
+    #[actix_web::test]
+    async fn imports_signals_with_localized_item_types() {
+        let pool = test_pool().await;
+
+        sqlx::query("DELETE FROM import_jobs WHERE game = 'zzz' AND uid = $1")
+            .bind(UID)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("DELETE FROM zzz_uids WHERE uid = $1")
+            .bind(UID)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        database::zzz::characters::set_all(&[1011], &[3], &pool)
+            .await
+            .unwrap();
+        database::zzz::w_engines::set_all(&[13001], &[3], &pool)
+            .await
+            .unwrap();
+        database::zzz::bangboos::set_all(&[54001], &[3], &pool)
+            .await
+            .unwrap();
+        database::zzz::uids::set(&database::zzz::uids::DbUid { uid: UID }, &pool)
+            .await
+            .unwrap();
+
+        let upstream = MockUpstream::start().await;
+        upstream.page(
+            1,
+            "0",
+            mock::gacha_log(vec![
+                entry(
+                    "1700000000000000002",
+                    "1001",
+                    "1011",
+                    "代理人",
+                    "2024-01-02 12:00:00",
+                ),
+                entry(
+                    "1700000000000000001",
+                    "1001",
+                    "13001",
+                    "音擎",
+                    "2024-01-01 12:00:00",
+                ),
+            ]),
+        );
+        // Without a known item_type the item id decides
+        upstream.page(
+            5,
+            "0",
+            mock::gacha_log(vec![entry(
+                "1700000000000000003",
+                "5001",
+                "54001",
+                "",
+                "2024-01-03 12:00:00",
+            )]),
+        );
+
+        let url = Url::parse(
+            "https://public-operation-nap-sg.hoyoverse.com/common/gacha_record/api/getGachaLog?authkey=key&lang=en&game_biz=nap_global&size=20",
+        )
+        .unwrap();
+        let id = import_jobs::enqueue(Game::Zzz, UID, &url, false, &pool)
+            .await
+            .unwrap();
+        let job = database::import_jobs::get_by_id(id, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+
+        run(&job, &upstream, &pool).await.unwrap();
+        database::import_jobs::finish(id, &pool).await.unwrap();
+
+        let job = database::import_jobs::get_by_id(id, &pool)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(job.counts["standard"], 2);
+        assert_eq!(job.counts["bangboo"], 1);
+
+        let signals = database::zzz::signals::standard::get_infos_by_uid(UID, &pool)
+            .await
+            .unwrap();
+        assert_eq!(signals.len(), 2);
+        assert!(signals.iter().any(|s| s.character == Some(1011)));
+        assert!(signals.iter().any(|s| s.w_engine == Some(13001)));
+
+        assert_eq!(
+            database::zzz::signals::bangboo::get_count_by_uid(UID, &pool)
+                .await
+                .unwrap(),
+            1
+        );
+    }
+}
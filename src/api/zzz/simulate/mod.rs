@@ -0,0 +1,158 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use serde::Deserialize;
+use sqlx::PgPool;
+use utoipa::{IntoParams, OpenApi, ToSchema};
+
+use crate::{
+    api::{
+        auth::{Auth, Scope},
+        banner_helpers::{self, ZZZ_STANDARD},
+        private,
+        pull_simulator::{self, Pity, Rules, SimulatedCopies, SimulatedSpend, Simulation, State},
+        ApiResult,
+    },
+    database, Language,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "zzz/simulate")),
+    paths(get_zzz_simulate),
+    components(schemas(SimulateBanner, Simulation, SimulatedCopies, SimulatedSpend, State))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_zzz_simulate);
+}
+
+#[derive(Clone, Copy, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SimulateBanner {
+    Agent,
+    WEngine,
+}
+
+#[derive(Deserialize, IntoParams)]
+pub struct SimulateParams {
+    banner: SimulateBanner,
+    /// Start from the pity and guarantee of this uid's signals
+    uid: Option<i32>,
+    /// Overrides the pity of the uid
+    pull: Option<usize>,
+    /// Overrides the guarantee of the uid
+    guarantee: Option<bool>,
+    /// Polychromes
+    #[serde(default)]
+    currency: i32,
+    /// Encrypted Master Tapes
+    #[serde(default)]
+    passes: i32,
+    /// Defaults to M6 or R5
+    copies: Option<usize>,
+}
+
+#[utoipa::path(
+    tag = "zzz/simulate",
+    get,
+    path = "/api/zzz/simulate",
+    params(SimulateParams),
+    responses(
+        (status = 200, description = "Simulated copies of the featured agent or W-Engine", body = Simulation),
+        (status = 403, description = "The uid is private"),
+    )
+)]
+#[get("/api/zzz/simulate", guard = "private")]
+async fn get_zzz_simulate(
+    auth: Auth,
+    params: web::Query<SimulateParams>,
+    pool: web::Data<PgPool>,
+) -> ApiResult<impl Responder> {
+    let mut state = State::default();
+
+    if let Some(uid) = params.uid {
+        let mut forbidden = database::zzz::connections::get_by_uid(uid, &pool)
+            .await?
+            .iter()
+            .any(|c| c.private);
+
+        if forbidden {
+            if let Some(username) = auth.username(Scope::ReadPrivate) {
+                if let Ok(connection) =
+                    database::zzz::connections::get_by_uid_and_username(uid, &username, &pool).await
+                {
+                    forbidden = !connection.verified;
+                }
+            }
+        }
+
+        if forbidden {
+            return Ok(HttpResponse::Forbidden().finish());
+        }
+
+        let banners = banner_helpers::zzz_banner_ranges(&pool).await?;
+
+        let is_win = banner_helpers::is_win_fn(&banners, ZZZ_STANDARD);
+
+        let signals = match params.banner {
+            SimulateBanner::Agent => {
+                database::zzz::signals::special::get_by_uid(uid, Language::En, &pool).await?
+            }
+            SimulateBanner::WEngine => {
+                database::zzz::signals::w_engine::get_by_uid(uid, Language::En, &pool).await?
+            }
+        };
+
+        // S-Rank is rarity 4
+        for signal in signals {
+            state.pull += 1;
+
+            if signal.rarity != Some(4) {
+                continue;
+            }
+
+            state.pull = 0;
+
+            let item_id = signal.character.or(signal.w_engine).unwrap();
+            state.guarantee = !state.guarantee && !is_win(item_id, signal.timestamp);
+        }
+    }
+
+    state.pull = params.pull.unwrap_or(state.pull);
+    state.guarantee = params.guarantee.unwrap_or(state.guarantee);
+
+    let (rules, max_copies) = match params.banner {
+        SimulateBanner::Agent => (
+            Rules {
+                pity: Pity::ZZZ_AGENT,
+                win_rate: 0.5,
+                radiance: false,
+                epitomized: false,
+            },
+            7,
+        ),
+        SimulateBanner::WEngine => (
+            Rules {
+                pity: Pity::ZZZ_W_ENGINE,
+                win_rate: 0.75,
+                radiance: false,
+                epitomized: false,
+            },
+            5,
+        ),
+    };
+
+    let copies = params.copies.unwrap_or(max_copies).clamp(1, max_copies);
+    let (currency, passes) = (params.currency.max(0), params.passes.max(0));
+
+    let simulation = web::block(move || {
+        pull_simulator::simulate(&rules, state, currency, passes, copies, &mut rand::rng())
+    })
+    .await?;
+
+    Ok(HttpResponse::Ok().json(simulation))
+}
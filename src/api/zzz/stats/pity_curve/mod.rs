@@ -0,0 +1,42 @@
+use actix_web::{get, web, HttpResponse, Responder};
+use sqlx::PgPool;
+use utoipa::OpenApi;
+
+use crate::{
+    api::{
+        pity_curves::{self, PityCurve, PityPoint},
+        ApiResult,
+    },
+    database,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    tags((name = "zzz/stats/pity-curve")),
+    paths(get_zzz_pity_curve),
+    components(schemas(PityCurve, PityPoint))
+)]
+struct ApiDoc;
+
+pub fn openapi() -> utoipa::openapi::OpenApi {
+    ApiDoc::openapi()
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.service(get_zzz_pity_curve);
+}
+
+#[utoipa::path(
+    tag = "zzz/stats/pity-curve",
+    get,
+    path = "/api/zzz/stats/pity-curve",
+    responses(
+        (status = 200, description = "Observed hit rates by pity of every banner type, from all official signals", body = Vec<PityCurve>),
+    )
+)]
+#[get("/api/zzz/stats/pity-curve")]
+async fn get_zzz_pity_curve(pool: web::Data<PgPool>) -> ApiResult<impl Responder> {
+    let curves = database::zzz::pity_curves::get_all(&pool).await?;
+
+    Ok(HttpResponse::Ok().json(pity_curves::pity_curves(curves)))
+}
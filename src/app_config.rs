@@ -1,4 +1,6 @@
-use serde::Deserialize;
+use std::{collections::HashMap, fmt, net::IpAddr};
+
+use serde::{Deserialize, Deserializer};
 
 #[derive(Debug, Deserialize)]
 pub struct AppConfig {
@@ -38,10 +40,155 @@ pub struct AppConfig {
     #[serde(default = "default_true")]
     pub enable_update_wishes_stats: bool,
 
+    #[serde(default = "default_true")]
+    pub enable_update_pity_curves: bool,
+
+    #[serde(default = "default_true")]
+    pub enable_update_banners_stats: bool,
+
     #[serde(default = "default_true")]
     pub enable_update_sitemaps: bool,
+
+    #[serde(default = "default_true")]
+    pub enable_import_jobs_worker: bool,
+
+    #[serde(default = "default_true")]
+    pub enable_account_deletions_worker: bool,
+
+    #[serde(default)]
+    pub mail_transport: MailTransport,
+
+    #[serde(default = "default_smtp_relay")]
+    pub smtp_relay: String,
+
+    /// Where the file transport writes mails to
+    #[serde(default = "default_mail_dir")]
+    pub mail_dir: String,
+
+    /// Salts the ip hashes stored with sessions, so they can't be reversed by hashing every ip
+    #[serde(default)]
+    pub ip_hash_salt: String,
+
+    /// Comma separated ips of the reverse proxies in front of the api. Only their forwarded headers
+    /// are believed for the client ip, so they have to overwrite `X-Forwarded-For` instead of
+    /// appending to it.
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+
+    /// Whether the private routes need a service key. Off for debug builds by default.
+    #[serde(default = "default_require_service_keys")]
+    pub require_service_keys: bool,
+
+    /// Where the frontend is. OIDC logins redirect back to it
+    #[serde(default = "default_public_url")]
+    pub public_url: String,
+
+    /// A json array, e.g.
+    /// `[{"name": "discord", "issuer": "https://discord.com", "client_id": "...", "client_secret": "..."}]`
+    #[serde(default, deserialize_with = "deserialize_json")]
+    pub oidc_providers: Vec<OidcProvider>,
+
+    #[serde(default = "default_true")]
+    pub enable_rate_limits: bool,
+
+    /// `postgres` shares the buckets between instances
+    #[serde(default)]
+    pub rate_limit_store: RateLimitStore,
+
+    /// A json object replacing the default limits of route groups, e.g.
+    /// `{"auth": {"ip": {"capacity": 20, "per_minute": 10}}}`. A missing `ip` or `user` means no
+    /// limit of that kind for the group.
+    #[serde(default, deserialize_with = "deserialize_json")]
+    pub rate_limits: HashMap<String, RateLimits>,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct RateLimits {
+    #[serde(default)]
+    pub ip: Option<RateLimit>,
+    #[serde(default)]
+    pub user: Option<RateLimit>,
+}
+
+/// A token bucket holding up to `capacity` requests, refilled by `per_minute`
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct RateLimit {
+    pub capacity: u32,
+    pub per_minute: u32,
+}
+
+#[derive(Clone, Deserialize)]
+pub struct OidcProvider {
+    /// Identifies the provider in urls, e.g. `discord`
+    pub name: String,
+    /// The endpoints are discovered at `{issuer}/.well-known/openid-configuration`
+    pub issuer: String,
+    pub client_id: String,
+    pub client_secret: String,
+    #[serde(default = "default_oidc_scopes")]
+    pub scopes: Vec<String>,
+}
+
+/// The config gets logged, so the secret is left out
+impl fmt::Debug for OidcProvider {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("OidcProvider")
+            .field("name", &self.name)
+            .field("issuer", &self.issuer)
+            .field("client_id", &self.client_id)
+            .field("scopes", &self.scopes)
+            .finish_non_exhaustive()
+    }
+}
+
+/// `file` and `log` are for local testing, so no mails actually go out
+#[derive(Debug, Default, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MailTransport {
+    #[default]
+    Smtp,
+    File,
+    Log,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitStore {
+    Memory,
+    #[default]
+    Postgres,
 }
 
 fn default_true() -> bool {
     true
 }
+
+fn default_require_service_keys() -> bool {
+    !cfg!(debug_assertions)
+}
+
+fn default_public_url() -> String {
+    "https://stardb.gg".to_string()
+}
+
+fn default_oidc_scopes() -> Vec<String> {
+    vec!["openid".to_string(), "email".to_string()]
+}
+
+fn deserialize_json<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: serde::de::DeserializeOwned,
+{
+    let json = String::deserialize(deserializer)?;
+
+    serde_json::from_str(&json).map_err(serde::de::Error::custom)
+}
+
+fn default_smtp_relay() -> String {
+    "smtppro.zoho.eu".to_string()
+}
+
+fn default_mail_dir() -> String {
+    "mails".to_string()
+}
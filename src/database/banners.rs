@@ -9,6 +9,8 @@ pub struct DbBanner {
     pub end: DateTime<Utc>,
     pub character: Option<i32>,
     pub light_cone: Option<i32>,
+    /// Pulled on the collab warps instead of the regular event warps
+    pub collab: bool,
 }
 
 pub async fn set(banner: &DbBanner, pool: &PgPool) -> Result<()> {
@@ -20,6 +22,7 @@ pub async fn set(banner: &DbBanner, pool: &PgPool) -> Result<()> {
         banner.end,
         banner.character,
         banner.light_cone,
+        banner.collab,
     )
     .execute(pool)
     .await?;
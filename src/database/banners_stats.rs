@@ -0,0 +1,92 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// A pull of the top rarity. `kind` is the kind of banner it was pulled on, `source` the history
+/// it is part of
+pub struct DbBannerHit {
+    pub source: String,
+    pub kind: String,
+    pub uid: i32,
+    pub timestamp: DateTime<Utc>,
+    pub official: bool,
+    pub item: i32,
+    pub pity: Option<i32>,
+}
+
+pub struct DbBannerCount {
+    pub id: i32,
+    pub pulls: i64,
+    pub uids: i64,
+}
+
+pub struct DbBannerStats {
+    pub id: i32,
+    pub pulls: i64,
+    pub uids: i64,
+    /// Uids by copies of the featured item, starting with 1
+    pub copies: Vec<i64>,
+    pub wins: i64,
+    pub losses: i64,
+    pub guarantees: i64,
+    /// Average pulls between featured hits
+    pub featured_pity: Option<f64>,
+    /// Most common first
+    pub loss_items: Vec<i32>,
+    pub loss_counts: Vec<i64>,
+}
+
+pub async fn get_hits(pool: &PgPool) -> Result<Vec<DbBannerHit>> {
+    Ok(
+        sqlx::query_file_as!(DbBannerHit, "sql/banners_stats/get_hits.sql")
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+pub async fn get_counts(pool: &PgPool) -> Result<Vec<DbBannerCount>> {
+    Ok(
+        sqlx::query_file_as!(DbBannerCount, "sql/banners_stats/get_counts.sql")
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+/// Replaces all stats
+pub async fn set_all(stats: &[DbBannerStats], pool: &PgPool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query_file!("sql/banners_stats/delete_all.sql")
+        .execute(&mut *tx)
+        .await?;
+
+    for stats in stats {
+        sqlx::query_file!(
+            "sql/banners_stats/set.sql",
+            stats.id,
+            stats.pulls,
+            stats.uids,
+            &stats.copies,
+            stats.wins,
+            stats.losses,
+            stats.guarantees,
+            stats.featured_pity,
+            &stats.loss_items,
+            &stats.loss_counts,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn get_by_id(id: i32, pool: &PgPool) -> Result<Option<DbBannerStats>> {
+    Ok(
+        sqlx::query_file_as!(DbBannerStats, "sql/banners_stats/get_by_id.sql", id)
+            .fetch_optional(pool)
+            .await?,
+    )
+}
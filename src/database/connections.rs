@@ -1,5 +1,5 @@
 use anyhow::Result;
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 
 pub struct DbConnection {
     pub uid: i32,
@@ -8,7 +8,7 @@ pub struct DbConnection {
     pub private: bool,
 }
 
-pub async fn set(connection: &DbConnection, pool: &PgPool) -> Result<()> {
+pub async fn set(connection: &DbConnection, executor: impl PgExecutor<'_>) -> Result<()> {
     sqlx::query!(
         "INSERT INTO connections
             (uid, username, verified, private) 
@@ -24,7 +24,7 @@ pub async fn set(connection: &DbConnection, pool: &PgPool) -> Result<()> {
         connection.verified,
         connection.private,
     )
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
@@ -81,7 +81,7 @@ pub async fn update_private_by_uid_and_username(
     uid: i32,
     username: &str,
     private: bool,
-    pool: &PgPool,
+    executor: impl PgExecutor<'_>,
 ) -> Result<()> {
     sqlx::query!(
         "UPDATE connections SET private = $3 WHERE uid = $1 AND username = $2",
@@ -89,7 +89,7 @@ pub async fn update_private_by_uid_and_username(
         username,
         private,
     )
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
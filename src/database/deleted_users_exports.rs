@@ -0,0 +1,33 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool};
+
+/// Keeps an existing export, so a retried deletion can't replace it with a less complete one
+pub async fn set(
+    hash: &[u8],
+    export: &str,
+    expires: DateTime<Utc>,
+    executor: impl PgExecutor<'_>,
+) -> Result<()> {
+    sqlx::query_file!("sql/deleted_users_exports/set.sql", hash, export, expires)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_one_by_hash(hash: &[u8], pool: &PgPool) -> Result<Option<String>> {
+    Ok(
+        sqlx::query_file_scalar!("sql/deleted_users_exports/get_one_by_hash.sql", hash)
+            .fetch_optional(pool)
+            .await?,
+    )
+}
+
+pub async fn delete_expired(pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/deleted_users_exports/delete_expired.sql")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+pub struct DbExportKey {
+    pub id: String,
+    pub public_key: Vec<u8>,
+    pub created: DateTime<Utc>,
+    pub retired: Option<DateTime<Utc>>,
+    pub revoked: Option<DateTime<Utc>>,
+}
+
+pub struct DbActiveExportKey {
+    pub id: String,
+    pub private_key: Vec<u8>,
+}
+
+/// Makes the key the one to sign with and retires all others
+pub async fn activate(
+    id: &str,
+    public_key: &[u8],
+    private_key: &[u8],
+    pool: &PgPool,
+) -> Result<()> {
+    sqlx::query_file!("sql/export_keys/activate.sql", id, public_key, private_key)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_active(pool: &PgPool) -> Result<Option<DbActiveExportKey>> {
+    Ok(
+        sqlx::query_file_as!(DbActiveExportKey, "sql/export_keys/get_active.sql")
+            .fetch_optional(pool)
+            .await?,
+    )
+}
+
+/// Returns whether the key exists
+pub async fn revoke(id: &str, pool: &PgPool) -> Result<bool> {
+    Ok(sqlx::query_file!("sql/export_keys/revoke.sql", id)
+        .execute(pool)
+        .await?
+        .rows_affected()
+        > 0)
+}
+
+pub async fn get_all(pool: &PgPool) -> Result<Vec<DbExportKey>> {
+    Ok(
+        sqlx::query_file_as!(DbExportKey, "sql/export_keys/get_all.sql")
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+pub async fn get_by_id(id: &str, pool: &PgPool) -> Result<Option<DbExportKey>> {
+    Ok(
+        sqlx::query_file_as!(DbExportKey, "sql/export_keys/get_by_id.sql", id)
+            .fetch_optional(pool)
+            .await?,
+    )
+}
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 
 use crate::Language;
 
@@ -74,10 +74,14 @@ pub async fn get_one_by_id(
     .await?)
 }
 
-pub async fn get_all_related_ids(id: i32, set: i32, pool: &PgPool) -> Result<Vec<i32>> {
+pub async fn get_all_related_ids(
+    id: i32,
+    set: i32,
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<i32>> {
     Ok(
         sqlx::query_file!("sql/gi/achievements/get_all_related_ids.sql", id, set)
-            .fetch_all(pool)
+            .fetch_all(executor)
             .await?
             .iter_mut()
             .map(|id| id.id)
@@ -1,5 +1,5 @@
 use anyhow::Result;
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 
 pub struct DbConnection {
     pub uid: i32,
@@ -8,7 +8,7 @@ pub struct DbConnection {
     pub private: bool,
 }
 
-pub async fn set(connection: &DbConnection, pool: &PgPool) -> Result<()> {
+pub async fn set(connection: &DbConnection, executor: impl PgExecutor<'_>) -> Result<()> {
     sqlx::query_file!(
         "sql/gi/connections/set.sql",
         connection.uid,
@@ -16,7 +16,7 @@ pub async fn set(connection: &DbConnection, pool: &PgPool) -> Result<()> {
         connection.verified,
         false,
     )
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
@@ -71,7 +71,7 @@ pub async fn update_private_by_uid_and_username(
     uid: i32,
     username: &str,
     private: bool,
-    pool: &PgPool,
+    executor: impl PgExecutor<'_>,
 ) -> Result<()> {
     sqlx::query_file!(
         "sql/gi/connections/update_private_by_uid_and_username.sql",
@@ -79,7 +79,7 @@ pub async fn update_private_by_uid_and_username(
         username,
         private,
     )
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
@@ -4,12 +4,15 @@ pub mod achievements;
 pub mod achievements_percent;
 pub mod achievements_text;
 pub mod banners;
+pub mod banners_stats;
 pub mod characters;
 pub mod characters_text;
 pub mod connections;
+pub mod pity_curves;
 pub mod profiles;
 pub mod users_achievements_completed;
 pub mod users_achievements_favorites;
+pub mod weapon_charts;
 pub mod weapons;
 pub mod weapons_text;
 pub mod wishes;
@@ -1,22 +1,25 @@
-use sqlx::PgPool;
+use sqlx::PgExecutor;
 
 pub struct DbProfile {
     pub uid: i32,
     pub name: String,
 }
 
-pub async fn set(profile: &DbProfile, pool: &PgPool) -> anyhow::Result<()> {
+pub async fn set(profile: &DbProfile, executor: impl PgExecutor<'_>) -> anyhow::Result<()> {
     sqlx::query_file!("sql/gi/profiles/set.sql", profile.uid, profile.name)
-        .execute(pool)
+        .execute(executor)
         .await?;
 
     Ok(())
 }
 
-pub async fn get_by_uid(uid: i32, pool: &PgPool) -> anyhow::Result<Option<DbProfile>> {
+pub async fn get_by_uid(
+    uid: i32,
+    executor: impl PgExecutor<'_>,
+) -> anyhow::Result<Option<DbProfile>> {
     Ok(
         sqlx::query_file_as!(DbProfile, "sql/gi/profiles/get_by_uid.sql", uid)
-            .fetch_optional(pool)
+            .fetch_optional(executor)
             .await?,
     )
 }
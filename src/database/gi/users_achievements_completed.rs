@@ -1,5 +1,6 @@
 use anyhow::Result;
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{Acquire, PgExecutor, PgPool, Postgres};
 
 use crate::database::gi::achievements::DbAchievement;
 
@@ -8,13 +9,49 @@ pub struct DbUserAchievementCompleted {
     pub id: i32,
 }
 
-pub async fn add(user_achievement: &DbUserAchievementCompleted, pool: &PgPool) -> Result<()> {
+/// When an achievement was completed, as kept in exports
+pub struct DbUserAchievementCompletedAt {
+    pub id: i32,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub imported: bool,
+}
+
+pub struct DbUserAchievementCompletion {
+    /// `None` for completions from before times were recorded
+    pub completed_at: Option<DateTime<Utc>>,
+    pub imported: bool,
+    pub version: Option<String>,
+    pub rewards: i32,
+}
+
+/// Completions from a UIAF import
+#[derive(Default)]
+pub struct SetAll {
+    pub id: Vec<i32>,
+    pub completed_at: Vec<Option<DateTime<Utc>>>,
+    pub current: Vec<Option<i32>>,
+}
+
+pub struct DbUiafAchievement {
+    pub id: i32,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub current: Option<i32>,
+}
+
+/// `imported` marks completions from bulk imports, whose time is the import time
+pub async fn add(
+    user_achievement: &DbUserAchievementCompleted,
+    imported: bool,
+    conn: impl Acquire<'_, Database = Postgres>,
+) -> Result<()> {
+    let mut conn = conn.acquire().await?;
+
     if sqlx::query_file!(
         "sql/gi/achievements/get_one_by_id.sql",
         user_achievement.id,
         "en"
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?
     .impossible
     {
@@ -25,8 +62,9 @@ pub async fn add(user_achievement: &DbUserAchievementCompleted, pool: &PgPool) -
         "sql/gi/users/achievements/completed/set.sql",
         user_achievement.username,
         user_achievement.id,
+        imported,
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
 
     if let Some(set) = sqlx::query_file_as!(
@@ -35,19 +73,19 @@ pub async fn add(user_achievement: &DbUserAchievementCompleted, pool: &PgPool) -
         user_achievement.id,
         "en"
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?
     .set
     {
         for related in
-            super::achievements::get_all_related_ids(user_achievement.id, set, pool).await?
+            super::achievements::get_all_related_ids(user_achievement.id, set, &mut *conn).await?
         {
             sqlx::query_file!(
                 "sql/gi/users/achievements/completed/delete.sql",
                 user_achievement.username,
                 related,
             )
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         }
     }
@@ -67,12 +105,12 @@ pub async fn delete(user_achievement: &DbUserAchievementCompleted, pool: &PgPool
     Ok(())
 }
 
-pub async fn delete_by_username(username: &str, pool: &PgPool) -> Result<()> {
+pub async fn delete_by_username(username: &str, executor: impl PgExecutor<'_>) -> Result<()> {
     sqlx::query_file!(
         "sql/gi/users/achievements/completed/delete_by_username.sql",
         username,
     )
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
@@ -90,3 +128,105 @@ pub async fn get_by_username(
     .fetch_all(pool)
     .await?)
 }
+
+pub async fn get_completed_at_by_username(
+    username: &str,
+    pool: &PgPool,
+) -> Result<Vec<DbUserAchievementCompletedAt>> {
+    Ok(sqlx::query_file_as!(
+        DbUserAchievementCompletedAt,
+        "sql/gi/users/achievements/completed/get_completed_at_by_username.sql",
+        username
+    )
+    .fetch_all(pool)
+    .await?)
+}
+
+/// Restores when an existing completion happened
+pub async fn set_completed_at(
+    username: &str,
+    completed_at: &DbUserAchievementCompletedAt,
+    executor: impl PgExecutor<'_>,
+) -> Result<()> {
+    sqlx::query_file!(
+        "sql/gi/users/achievements/completed/set_completed_at.sql",
+        username,
+        completed_at.id,
+        completed_at.completed_at,
+        completed_at.imported,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_timeline_by_username(
+    username: &str,
+    pool: &PgPool,
+) -> Result<Vec<DbUserAchievementCompletion>> {
+    Ok(sqlx::query_file_as!(
+        DbUserAchievementCompletion,
+        "sql/gi/users/achievements/completed/get_timeline_by_username.sql",
+        username
+    )
+    .fetch_all(pool)
+    .await?)
+}
+
+/// Adds all completions at once, marked as imported. With `replace`, the previous completions are
+/// removed first.
+pub async fn set_all(username: &str, set_all: &SetAll, replace: bool, pool: &PgPool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    if replace {
+        sqlx::query_file!(
+            "sql/gi/users/achievements/completed/delete_by_username.sql",
+            username,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    sqlx::query_file!(
+        "sql/gi/users/achievements/completed/update_all.sql",
+        username,
+        &set_all.id,
+        &set_all.completed_at as &[Option<DateTime<Utc>>],
+        &set_all.current as &[Option<i32>],
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query_file!(
+        "sql/gi/users/achievements/completed/set_all.sql",
+        username,
+        &set_all.id,
+        &set_all.completed_at as &[Option<DateTime<Utc>>],
+        &set_all.current as &[Option<i32>],
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    sqlx::query_file!(
+        "sql/gi/users/achievements/completed/delete_related_all.sql",
+        username,
+        &set_all.id,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn get_uiaf_by_username(username: &str, pool: &PgPool) -> Result<Vec<DbUiafAchievement>> {
+    Ok(sqlx::query_file_as!(
+        DbUiafAchievement,
+        "sql/gi/users/achievements/completed/get_uiaf_by_username.sql",
+        username
+    )
+    .fetch_all(pool)
+    .await?)
+}
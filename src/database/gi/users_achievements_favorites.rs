@@ -1,5 +1,5 @@
 use anyhow::Result;
-use sqlx::PgPool;
+use sqlx::{Acquire, PgExecutor, PgPool, Postgres};
 
 use crate::database::gi::achievements::DbAchievement;
 
@@ -8,13 +8,18 @@ pub struct DbUserAchievementFavorite {
     pub id: i32,
 }
 
-pub async fn add(user_achievement: &DbUserAchievementFavorite, pool: &PgPool) -> Result<()> {
+pub async fn add(
+    user_achievement: &DbUserAchievementFavorite,
+    conn: impl Acquire<'_, Database = Postgres>,
+) -> Result<()> {
+    let mut conn = conn.acquire().await?;
+
     sqlx::query_file!(
         "sql/gi/users/achievements/favorites/set.sql",
         user_achievement.username,
         user_achievement.id,
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
 
     if let Some(set) = sqlx::query_file_as!(
@@ -23,19 +28,19 @@ pub async fn add(user_achievement: &DbUserAchievementFavorite, pool: &PgPool) ->
         user_achievement.id,
         "en",
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?
     .set
     {
         for related in
-            super::achievements::get_all_related_ids(user_achievement.id, set, pool).await?
+            super::achievements::get_all_related_ids(user_achievement.id, set, &mut *conn).await?
         {
             sqlx::query_file!(
                 "sql/gi/users/achievements/favorites/delete.sql",
                 user_achievement.username,
                 related,
             )
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         }
     }
@@ -55,6 +60,17 @@ pub async fn delete(user_achievement: &DbUserAchievementFavorite, pool: &PgPool)
     Ok(())
 }
 
+pub async fn delete_by_username(username: &str, executor: impl PgExecutor<'_>) -> Result<()> {
+    sqlx::query_file!(
+        "sql/gi/users/achievements/favorites/delete_by_username.sql",
+        username,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
 pub async fn get_by_username(
     username: &str,
     pool: &PgPool,
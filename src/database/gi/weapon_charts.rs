@@ -0,0 +1,37 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+pub struct DbWeaponChart {
+    pub uid: i32,
+    pub banner: i32,
+    pub weapon: i32,
+}
+
+pub async fn set(chart: &DbWeaponChart, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!(
+        "sql/gi/weapon_charts/set.sql",
+        chart.uid,
+        chart.banner,
+        chart.weapon,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete(uid: i32, banner: i32, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/gi/weapon_charts/delete.sql", uid, banner)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Vec<DbWeaponChart>> {
+    Ok(
+        sqlx::query_file_as!(DbWeaponChart, "sql/gi/weapon_charts/get_by_uid.sql", uid)
+            .fetch_all(pool)
+            .await?,
+    )
+}
@@ -0,0 +1,40 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+pub struct DbWishesStatBeginner {
+    pub uid: i32,
+    pub luck_4: f64,
+    pub luck_5: f64,
+}
+
+pub async fn set(stat: &DbWishesStatBeginner, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!(
+        "sql/gi/wishes_stats/beginner/set.sql",
+        stat.uid,
+        stat.luck_4,
+        stat.luck_5,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbWishesStatBeginner>> {
+    Ok(sqlx::query_file_as!(
+        DbWishesStatBeginner,
+        "sql/gi/wishes_stats/beginner/get_by_uid.sql",
+        uid,
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+pub async fn get_updated_at(pool: &PgPool) -> Result<Option<DateTime<Utc>>> {
+    Ok(
+        sqlx::query_file_scalar!("sql/gi/wishes_stats/beginner/get_updated_at.sql")
+            .fetch_one(pool)
+            .await?,
+    )
+}
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 
 pub struct DbWishesStatCharacter {
@@ -36,11 +37,10 @@ pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbWishesStatCh
     .await?)
 }
 
-pub async fn get_all(pool: &PgPool) -> Result<Vec<DbWishesStatCharacter>> {
-    Ok(sqlx::query_file_as!(
-        DbWishesStatCharacter,
-        "sql/gi/wishes_stats/character/get_all.sql",
+pub async fn get_updated_at(pool: &PgPool) -> Result<Option<DateTime<Utc>>> {
+    Ok(
+        sqlx::query_file_scalar!("sql/gi/wishes_stats/character/get_updated_at.sql")
+            .fetch_one(pool)
+            .await?,
     )
-    .fetch_all(pool)
-    .await?)
 }
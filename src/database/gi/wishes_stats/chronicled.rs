@@ -1,4 +1,5 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 
 pub struct DbWishesStatChronicled {
@@ -30,11 +31,10 @@ pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbWishesStatCh
     .await?)
 }
 
-pub async fn get_all(pool: &PgPool) -> Result<Vec<DbWishesStatChronicled>> {
-    Ok(sqlx::query_file_as!(
-        DbWishesStatChronicled,
-        "sql/gi/wishes_stats/chronicled/get_all.sql",
+pub async fn get_updated_at(pool: &PgPool) -> Result<Option<DateTime<Utc>>> {
+    Ok(
+        sqlx::query_file_scalar!("sql/gi/wishes_stats/chronicled/get_updated_at.sql")
+            .fetch_one(pool)
+            .await?,
     )
-    .fetch_all(pool)
-    .await?)
 }
@@ -1,4 +1,5 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 
 pub struct DbWishesStatWeapon {
@@ -36,10 +37,10 @@ pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbWishesStatWe
     .await?)
 }
 
-pub async fn get_all(pool: &PgPool) -> Result<Vec<DbWishesStatWeapon>> {
+pub async fn get_updated_at(pool: &PgPool) -> Result<Option<DateTime<Utc>>> {
     Ok(
-        sqlx::query_file_as!(DbWishesStatWeapon, "sql/gi/wishes_stats/weapon/get_all.sql")
-            .fetch_all(pool)
+        sqlx::query_file_scalar!("sql/gi/wishes_stats/weapon/get_updated_at.sql")
+            .fetch_one(pool)
             .await?,
     )
 }
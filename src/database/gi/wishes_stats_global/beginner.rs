@@ -0,0 +1,29 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+pub struct DbWishesStatGlobalBeginner {
+    pub count_percentile: f64,
+    pub luck_4_percentile: f64,
+    pub luck_5_percentile: f64,
+}
+
+/// Ranks every eligible uid, ties share a percentile. Only writes the rows that changed and
+/// returns how many
+pub async fn update(pool: &PgPool) -> Result<u64> {
+    Ok(
+        sqlx::query_file!("sql/gi/wishes_stats_global/beginner/update.sql")
+            .execute(pool)
+            .await?
+            .rows_affected(),
+    )
+}
+
+pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbWishesStatGlobalBeginner>> {
+    Ok(sqlx::query_file_as!(
+        DbWishesStatGlobalBeginner,
+        "sql/gi/wishes_stats_global/beginner/get_by_uid.sql",
+        uid
+    )
+    .fetch_optional(pool)
+    .await?)
+}
@@ -1,3 +1,4 @@
+pub mod beginner;
 pub mod character;
 pub mod chronicled;
 pub mod standard;
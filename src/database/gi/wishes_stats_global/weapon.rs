@@ -2,24 +2,20 @@ use anyhow::Result;
 use sqlx::PgPool;
 
 pub struct DbWishesStatGlobalWeapon {
-    pub uid: i32,
     pub count_percentile: f64,
     pub luck_4_percentile: f64,
     pub luck_5_percentile: f64,
 }
 
-pub async fn set(stat: &DbWishesStatGlobalWeapon, pool: &PgPool) -> Result<()> {
-    sqlx::query_file!(
-        "sql/gi/wishes_stats_global/weapon/set.sql",
-        stat.uid,
-        stat.count_percentile,
-        stat.luck_4_percentile,
-        stat.luck_5_percentile,
+/// Ranks every eligible uid, ties share a percentile. Only writes the rows that changed and
+/// returns how many
+pub async fn update(pool: &PgPool) -> Result<u64> {
+    Ok(
+        sqlx::query_file!("sql/gi/wishes_stats_global/weapon/update.sql")
+            .execute(pool)
+            .await?
+            .rows_affected(),
     )
-    .execute(pool)
-    .await?;
-
-    Ok(())
 }
 
 pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbWishesStatGlobalWeapon>> {
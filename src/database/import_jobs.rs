@@ -0,0 +1,161 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub struct DbImportJob {
+    pub id: Uuid,
+    pub game: String,
+    pub uid: i32,
+    pub url: Option<String>,
+    pub ignore_timestamps: bool,
+    pub status: String,
+    pub gacha_type: Option<String>,
+    pub counts: serde_json::Value,
+    pub error: Option<String>,
+    pub attempts: i32,
+    pub run_after: DateTime<Utc>,
+    pub created: DateTime<Utc>,
+    pub updated: DateTime<Utc>,
+    pub username: Option<String>,
+    pub data: Option<String>,
+    pub admin: bool,
+    pub dry_run: bool,
+    pub report: Option<serde_json::Value>,
+}
+
+/// Returns `None` if there already is an active job for this game and uid
+pub async fn insert(
+    game: &str,
+    uid: i32,
+    url: Option<&str>,
+    ignore_timestamps: bool,
+    status: &str,
+    error: Option<&str>,
+    pool: &PgPool,
+) -> Result<Option<Uuid>> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/import_jobs/insert.sql",
+        game,
+        uid,
+        url,
+        ignore_timestamps,
+        status,
+        error,
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+/// UIGF jobs aren't unique per uid, so this always inserts
+pub async fn insert_uigf(
+    username: &str,
+    data: &str,
+    admin: bool,
+    dry_run: bool,
+    pool: &PgPool,
+) -> Result<Uuid> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/import_jobs/insert_uigf.sql",
+        username,
+        data,
+        admin,
+        dry_run,
+    )
+    .fetch_one(pool)
+    .await?)
+}
+
+pub async fn get_active_id_by_game_and_uid(
+    game: &str,
+    uid: i32,
+    pool: &PgPool,
+) -> Result<Option<Uuid>> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/import_jobs/get_active_id_by_game_and_uid.sql",
+        game,
+        uid,
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+pub async fn get_by_id(id: Uuid, pool: &PgPool) -> Result<Option<DbImportJob>> {
+    Ok(
+        sqlx::query_file_as!(DbImportJob, "sql/import_jobs/get_by_id.sql", id)
+            .fetch_optional(pool)
+            .await?,
+    )
+}
+
+/// Takes the oldest runnable job, including ones whose worker stopped sending updates. Those are
+/// failed instead once they were claimed `max_attempts` times, so a job crashing its worker can't
+/// come back forever.
+pub async fn claim(max_attempts: i32, pool: &PgPool) -> Result<Option<DbImportJob>> {
+    sqlx::query_file!("sql/import_jobs/fail_stale.sql", max_attempts)
+        .execute(pool)
+        .await?;
+
+    Ok(
+        sqlx::query_file_as!(DbImportJob, "sql/import_jobs/claim.sql", max_attempts)
+            .fetch_optional(pool)
+            .await?,
+    )
+}
+
+pub async fn set_progress(id: Uuid, gacha_type: &str, count: i32, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/import_jobs/set_progress.sql", id, gacha_type, count)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn set_report(id: Uuid, report: &serde_json::Value, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/import_jobs/set_report.sql", id, report)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Drops the url, fetching is done
+pub async fn set_calculating(id: Uuid, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/import_jobs/set_calculating.sql", id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn finish(id: Uuid, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/import_jobs/finish.sql", id)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn fail(id: Uuid, error: &str, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/import_jobs/fail.sql", id, error)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn retry(id: Uuid, error: &str, delay_secs: f64, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/import_jobs/retry.sql", id, error, delay_secs)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_done_before(before: DateTime<Utc>, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/import_jobs/delete_done_before.sql", before)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use anyhow::{anyhow, Result};
 use chrono::{DateTime, Utc};
-use sqlx::PgPool;
+use sqlx::{Acquire, PgExecutor, PgPool, Postgres};
 
 #[derive(Default)]
 pub struct DbMihomo {
@@ -14,7 +14,12 @@ pub struct DbMihomo {
     pub updated_at: DateTime<Utc>,
 }
 
-pub async fn set(mihomo: &DbMihomo, pool: &PgPool) -> Result<DbMihomo> {
+pub async fn set(
+    mihomo: &DbMihomo,
+    conn: impl Acquire<'_, Database = Postgres>,
+) -> Result<DbMihomo> {
+    let mut conn = conn.acquire().await?;
+
     sqlx::query_file!(
         "sql/mihomo/set.sql",
         mihomo.uid,
@@ -26,25 +31,25 @@ pub async fn set(mihomo: &DbMihomo, pool: &PgPool) -> Result<DbMihomo> {
         mihomo.achievement_count,
         mihomo.updated_at,
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
 
-    get_one_by_uid(mihomo.uid, pool)
+    get_one_by_uid(mihomo.uid, &mut *conn)
         .await?
         .ok_or_else(|| anyhow!("mihomo unavailable after set"))
 }
 
-pub async fn get_one_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbMihomo>> {
+pub async fn get_one_by_uid(uid: i32, executor: impl PgExecutor<'_>) -> Result<Option<DbMihomo>> {
     Ok(
         sqlx::query_file_as!(DbMihomo, "sql/mihomo/get_one_by_uid.sql", uid)
-            .fetch_optional(pool)
+            .fetch_optional(executor)
             .await?,
     )
 }
 
-pub async fn exists(uid: i32, pool: &PgPool) -> Result<bool> {
+pub async fn exists(uid: i32, executor: impl PgExecutor<'_>) -> Result<bool> {
     Ok(sqlx::query_file!("sql/mihomo/exists.sql", uid)
-        .fetch_optional(pool)
+        .fetch_optional(executor)
         .await?
         .is_some())
 }
@@ -6,19 +6,35 @@ pub mod achievements_percent;
 pub mod achievements_text;
 pub mod admins;
 pub mod banners;
+pub mod banners_stats;
 pub mod characters;
 pub mod characters_text;
 pub mod connections;
+pub mod deleted_users_exports;
+pub mod export_keys;
 pub mod gi;
+pub mod import_jobs;
 pub mod light_cones;
 pub mod light_cones_text;
 pub mod mihomo;
+pub mod rate_limits;
 pub mod ntehelper;
 pub mod ntehelper_tracker;
+pub mod pity_curves;
+pub mod service_keys;
 pub mod sessions;
+pub mod stats_global_updates;
 pub mod users;
+pub mod users_access_tokens;
 pub mod users_achievements_completed;
 pub mod users_achievements_favorites;
+pub mod users_deletions;
+pub mod users_identities;
+pub mod users_merges;
+pub mod users_oidc_states;
+pub mod users_recovery_codes;
+pub mod users_tokens;
+pub mod users_totp;
 pub mod warps;
 pub mod warps_stats;
 pub mod warps_stats_global;
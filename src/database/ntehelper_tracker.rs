@@ -460,8 +460,7 @@ pub async fn clear_tracker_pulls(uid: i64, pool: &PgPool) -> Result<i64> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::database::users::{self, DbUser};
-    use sqlx::postgres::PgPoolOptions;
+    use crate::test_util::{self, delete_test_user, test_pool};
     use std::sync::atomic::{AtomicI64, Ordering};
     use uuid::Uuid;
 
@@ -473,46 +472,16 @@ mod tests {
         210_000_000_000 + (suffix % 10_000_000_000)
     }
 
-    async fn test_pool() -> PgPool {
-        let _ = dotenv::dotenv();
-        let database_url =
-            std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for DB-backed tests");
-        let pool = PgPoolOptions::new()
-            .max_connections(5)
-            .connect(&database_url)
-            .await
-            .expect("test database should connect");
-        sqlx::migrate!()
-            .run(&pool)
-            .await
-            .expect("test database migrations should run");
-        pool
-    }
-
     async fn create_test_user(pool: &PgPool) -> (String, i64) {
-        let username = format!("tracker_test_{}", Uuid::new_v4().simple());
-        let user = DbUser {
-            username: username.clone(),
-            password: "test-password-hash".to_string(),
-            email: Some(format!("{username}@example.com")),
-        };
-        users::set(&user, pool)
-            .await
-            .expect("test user should insert successfully");
+        let email = format!("{}@example.com", Uuid::new_v4().simple());
+        let username =
+            test_util::create_test_user_with_email("tracker_test", &email, false, pool).await;
         let user_id = get_tracker_user_id(&username, pool)
             .await
             .expect("test user id should load");
         (username, user_id)
     }
 
-    async fn delete_test_user(username: &str, pool: &PgPool) {
-        sqlx::query("DELETE FROM users WHERE username = $1")
-            .bind(username)
-            .execute(pool)
-            .await
-            .expect("test user should delete successfully");
-    }
-
     async fn delete_test_claim(uid: i64, pool: &PgPool) {
         sqlx::query("DELETE FROM ntehelper_tracker_uid_claim WHERE uid = $1")
             .bind(uid)
@@ -814,12 +783,10 @@ mod tests {
             .expect("delete should be allowed");
 
         assert_eq!(deleted_pulls, 2);
-        assert!(
-            get_tracker_claim(uid, &pool)
-                .await
-                .expect("claim lookup should succeed")
-                .is_none()
-        );
+        assert!(get_tracker_claim(uid, &pool)
+            .await
+            .expect("claim lookup should succeed")
+            .is_none());
         assert_eq!(
             tracker_pull_count(uid, &pool)
                 .await
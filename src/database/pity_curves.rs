@@ -0,0 +1,77 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+/// Official pulls made at a pity and how many of them hit. Rarity 5 and 4 in every game
+pub struct DbPityCount {
+    pub banner: String,
+    pub rarity: i32,
+    pub pity: i32,
+    pub pulls: i64,
+    pub hits: i64,
+}
+
+pub struct DbPityCurve {
+    pub banner: String,
+    pub rarity: i32,
+    pub pity: i32,
+    pub pulls: i64,
+    pub hits: i64,
+    pub rate: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+#[derive(Default)]
+pub struct SetAll {
+    pub banner: Vec<String>,
+    pub rarity: Vec<i32>,
+    pub pity: Vec<i32>,
+    pub pulls: Vec<i64>,
+    pub hits: Vec<i64>,
+    pub rate: Vec<f64>,
+    pub lower: Vec<f64>,
+    pub upper: Vec<f64>,
+}
+
+pub async fn get_counts(pool: &PgPool) -> Result<Vec<DbPityCount>> {
+    Ok(
+        sqlx::query_file_as!(DbPityCount, "sql/pity_curves/get_counts.sql")
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+/// Replaces all curves
+pub async fn set_all(set_all: &SetAll, pool: &PgPool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query_file!("sql/pity_curves/delete_all.sql")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query_file!(
+        "sql/pity_curves/set_all.sql",
+        &set_all.banner,
+        &set_all.rarity,
+        &set_all.pity,
+        &set_all.pulls,
+        &set_all.hits,
+        &set_all.rate,
+        &set_all.lower,
+        &set_all.upper,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn get_all(pool: &PgPool) -> Result<Vec<DbPityCurve>> {
+    Ok(
+        sqlx::query_file_as!(DbPityCurve, "sql/pity_curves/get_all.sql")
+            .fetch_all(pool)
+            .await?,
+    )
+}
@@ -0,0 +1,39 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// Returns whether a token was taken from the bucket
+pub async fn take(key: &str, capacity: f64, per_second: f64, pool: &PgPool) -> Result<bool> {
+    Ok(
+        sqlx::query_file_scalar!("sql/rate_limits/take.sql", key, capacity, per_second)
+            .fetch_optional(pool)
+            .await?
+            .is_some(),
+    )
+}
+
+/// Seconds until the bucket has a token again
+pub async fn get_retry_after_by_key(
+    key: &str,
+    capacity: f64,
+    per_second: f64,
+    pool: &PgPool,
+) -> Result<Option<f64>> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/rate_limits/get_retry_after_by_key.sql",
+        key,
+        capacity,
+        per_second,
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+/// Buckets that weren't touched since are full again anyway
+pub async fn delete_stale(before: DateTime<Utc>, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/rate_limits/delete_stale.sql", before)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
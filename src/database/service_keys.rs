@@ -0,0 +1,84 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub struct DbServiceKey {
+    pub id: Uuid,
+    pub name: String,
+    pub owner: String,
+    pub hash: Vec<u8>,
+    pub route_groups: Vec<String>,
+    /// Unlimited if `None`
+    pub quota_per_minute: Option<i32>,
+    pub requests: i64,
+    pub last_used: Option<DateTime<Utc>>,
+    pub created: DateTime<Utc>,
+}
+
+/// Returns `None` if the name is taken
+pub async fn set(
+    name: &str,
+    owner: &str,
+    hash: &[u8],
+    route_groups: &[String],
+    quota_per_minute: Option<i32>,
+    pool: &PgPool,
+) -> Result<Option<Uuid>> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/service_keys/set.sql",
+        name,
+        owner,
+        hash,
+        route_groups,
+        quota_per_minute,
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+pub async fn get_all(pool: &PgPool) -> Result<Vec<DbServiceKey>> {
+    Ok(
+        sqlx::query_file_as!(DbServiceKey, "sql/service_keys/get_all.sql")
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+/// Returns whether the key existed
+pub async fn update_hash_by_id(id: Uuid, hash: &[u8], pool: &PgPool) -> Result<bool> {
+    Ok(
+        sqlx::query_file!("sql/service_keys/update_hash_by_id.sql", id, hash)
+            .execute(pool)
+            .await?
+            .rows_affected()
+            > 0,
+    )
+}
+
+pub async fn add_requests_by_id(
+    id: Uuid,
+    requests: i64,
+    last_used: DateTime<Utc>,
+    pool: &PgPool,
+) -> Result<()> {
+    sqlx::query_file!(
+        "sql/service_keys/add_requests_by_id.sql",
+        id,
+        requests,
+        last_used,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Returns whether the key existed
+pub async fn delete_by_id(id: Uuid, pool: &PgPool) -> Result<bool> {
+    Ok(sqlx::query_file!("sql/service_keys/delete_by_id.sql", id)
+        .execute(pool)
+        .await?
+        .rows_affected()
+        > 0)
+}
@@ -4,9 +4,16 @@ use sqlx::PgPool;
 use uuid::Uuid;
 
 pub struct DbSession {
+    /// The session key. This is a secret, so it must never leave the api
     pub uuid: Uuid,
     pub username: String,
     pub expiry: DateTime<Utc>,
+    /// Identifies the session towards the user
+    pub id: Uuid,
+    pub created: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub user_agent: Option<String>,
+    pub ip_hash: Option<Vec<u8>>,
 }
 
 pub async fn set(session: &DbSession, pool: &PgPool) -> Result<()> {
@@ -15,6 +22,35 @@ pub async fn set(session: &DbSession, pool: &PgPool) -> Result<()> {
         session.uuid,
         session.username,
         session.expiry,
+        session.id,
+        session.created,
+        session.last_seen,
+        session.user_agent,
+        session.ip_hash,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// Doesn't insert the session again if it was revoked in the meantime. `user_agent` and `ip_hash`
+/// are kept if they are `None`.
+pub async fn update_by_uuid(
+    uuid: Uuid,
+    username: &str,
+    expiry: DateTime<Utc>,
+    user_agent: Option<&str>,
+    ip_hash: Option<&[u8]>,
+    pool: &PgPool,
+) -> Result<()> {
+    sqlx::query_file!(
+        "sql/sessions/update_by_uuid.sql",
+        uuid,
+        username,
+        expiry,
+        user_agent,
+        ip_hash,
     )
     .execute(pool)
     .await?;
@@ -38,6 +74,14 @@ pub async fn get_one_by_uuid(uuid: Uuid, pool: &PgPool) -> Result<DbSession> {
     )
 }
 
+pub async fn get_by_username(username: &str, pool: &PgPool) -> Result<Vec<DbSession>> {
+    Ok(
+        sqlx::query_file_as!(DbSession, "sql/sessions/get_by_username.sql", username)
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
 pub async fn update_expiry_by_uuid(uuid: Uuid, expiry: DateTime<Utc>, pool: &PgPool) -> Result<()> {
     sqlx::query_file!("sql/sessions/update_expiry_by_uuid.sql", uuid, expiry)
         .execute(pool)
@@ -46,6 +90,14 @@ pub async fn update_expiry_by_uuid(uuid: Uuid, expiry: DateTime<Utc>, pool: &PgP
     Ok(())
 }
 
+pub async fn update_last_seen_by_uuid(uuid: Uuid, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/sessions/update_last_seen_by_uuid.sql", uuid)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
 pub async fn delete_by_uuid(uuid: Uuid, pool: &PgPool) -> Result<()> {
     sqlx::query_file!("sql/sessions/delete_by_uuid.sql", uuid)
         .execute(pool)
@@ -53,3 +105,31 @@ pub async fn delete_by_uuid(uuid: Uuid, pool: &PgPool) -> Result<()> {
 
     Ok(())
 }
+
+/// Returns whether the session existed
+pub async fn delete_by_id_and_username(id: Uuid, username: &str, pool: &PgPool) -> Result<bool> {
+    Ok(
+        sqlx::query_file!("sql/sessions/delete_by_id_and_username.sql", id, username)
+            .execute(pool)
+            .await?
+            .rows_affected()
+            > 0,
+    )
+}
+
+/// Deletes all sessions of the user if `id` is `None`
+pub async fn delete_by_username_except_id(
+    username: &str,
+    id: Option<Uuid>,
+    pool: &PgPool,
+) -> Result<()> {
+    sqlx::query_file!(
+        "sql/sessions/delete_by_username_except_id.sql",
+        username,
+        id,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+/// The newest stat that went into the global percentiles of a banner
+pub async fn get_by_name(name: &str, pool: &PgPool) -> Result<Option<DateTime<Utc>>> {
+    Ok(
+        sqlx::query_file_scalar!("sql/stats_global_updates/get_by_name.sql", name)
+            .fetch_optional(pool)
+            .await?,
+    )
+}
+
+pub async fn set(name: &str, updated_at: DateTime<Utc>, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/stats_global_updates/set.sql", name, updated_at)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
@@ -1,10 +1,11 @@
 use anyhow::Result;
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 
 pub struct DbUser {
     pub username: String,
     pub password: String,
     pub email: Option<String>,
+    pub email_verified: bool,
 }
 
 pub async fn set(user: &DbUser, pool: &PgPool) -> Result<()> {
@@ -13,6 +14,7 @@ pub async fn set(user: &DbUser, pool: &PgPool) -> Result<()> {
         user.username,
         user.password,
         user.email,
+        user.email_verified,
     )
     .execute(pool)
     .await?;
@@ -44,6 +46,17 @@ pub async fn update_email_by_username(username: &str, email: &str, pool: &PgPool
     Ok(())
 }
 
+/// Only verifies if the email is still the one the verification was sent to
+pub async fn verify_email_by_username(username: &str, email: &str, pool: &PgPool) -> Result<bool> {
+    Ok(
+        sqlx::query_file!("sql/users/verify_email_by_username.sql", username, email)
+            .execute(pool)
+            .await?
+            .rows_affected()
+            > 0,
+    )
+}
+
 pub async fn update_password_by_username(
     username: &str,
     password: &str,
@@ -67,3 +80,12 @@ pub async fn delete_email_by_username(username: &str, pool: &PgPool) -> Result<(
 
     Ok(())
 }
+
+/// Everything tied to the user cascades
+pub async fn delete_by_username(username: &str, executor: impl PgExecutor<'_>) -> Result<()> {
+    sqlx::query_file!("sql/users/delete_by_username.sql", username)
+        .execute(executor)
+        .await?;
+
+    Ok(())
+}
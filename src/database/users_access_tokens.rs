@@ -0,0 +1,78 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub struct DbUserAccessToken {
+    pub id: Uuid,
+    pub username: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires: Option<DateTime<Utc>>,
+    pub last_used: Option<DateTime<Utc>>,
+    pub created: DateTime<Utc>,
+}
+
+pub async fn set(
+    username: &str,
+    name: &str,
+    hash: &[u8],
+    scopes: &[String],
+    expires: Option<DateTime<Utc>>,
+    pool: &PgPool,
+) -> Result<Uuid> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/users_access_tokens/set.sql",
+        username,
+        name,
+        hash,
+        scopes,
+        expires,
+    )
+    .fetch_one(pool)
+    .await?)
+}
+
+pub async fn get_by_username(username: &str, pool: &PgPool) -> Result<Vec<DbUserAccessToken>> {
+    Ok(sqlx::query_file_as!(
+        DbUserAccessToken,
+        "sql/users_access_tokens/get_by_username.sql",
+        username
+    )
+    .fetch_all(pool)
+    .await?)
+}
+
+pub async fn get_count_by_username(username: &str, pool: &PgPool) -> Result<i64> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/users_access_tokens/get_count_by_username.sql",
+        username
+    )
+    .fetch_one(pool)
+    .await?
+    .unwrap_or_default())
+}
+
+/// Returns the token if it exists and hasn't expired, and marks it as used
+pub async fn use_by_hash(hash: &[u8], pool: &PgPool) -> Result<Option<DbUserAccessToken>> {
+    Ok(sqlx::query_file_as!(
+        DbUserAccessToken,
+        "sql/users_access_tokens/use_by_hash.sql",
+        hash
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+/// Returns whether the token existed
+pub async fn delete_by_id_and_username(id: Uuid, username: &str, pool: &PgPool) -> Result<bool> {
+    Ok(sqlx::query_file!(
+        "sql/users_access_tokens/delete_by_id_and_username.sql",
+        id,
+        username
+    )
+    .execute(pool)
+    .await?
+    .rows_affected()
+        > 0)
+}
@@ -1,17 +1,40 @@
 use anyhow::Result;
-use sqlx::PgPool;
+use chrono::{DateTime, Utc};
+use sqlx::{Acquire, PgExecutor, PgPool, Postgres};
 
 pub struct DbUserAchievementCompleted {
     pub username: String,
     pub id: i32,
 }
 
-pub async fn add(user_achievement: &DbUserAchievementCompleted, pool: &PgPool) -> Result<()> {
+/// When an achievement was completed, as kept in exports
+pub struct DbUserAchievementCompletedAt {
+    pub id: i32,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub imported: bool,
+}
+
+pub struct DbUserAchievementCompletion {
+    /// `None` for completions from before times were recorded
+    pub completed_at: Option<DateTime<Utc>>,
+    pub imported: bool,
+    pub version: Option<String>,
+    pub rewards: i32,
+}
+
+/// `imported` marks completions from bulk imports, whose time is the import time
+pub async fn add(
+    user_achievement: &DbUserAchievementCompleted,
+    imported: bool,
+    conn: impl Acquire<'_, Database = Postgres>,
+) -> Result<()> {
+    let mut conn = conn.acquire().await?;
+
     if sqlx::query!(
         "SELECT impossible FROM achievements WHERE id = $1",
         user_achievement.id
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?
     .impossible
     {
@@ -19,30 +42,31 @@ pub async fn add(user_achievement: &DbUserAchievementCompleted, pool: &PgPool) -
     }
 
     sqlx::query!(
-        "INSERT INTO users_achievements_completed(username, id) VALUES($1, $2) ON CONFLICT(username, id) DO NOTHING",
+        "INSERT INTO users_achievements_completed(username, id, imported) VALUES($1, $2, $3) ON CONFLICT(username, id) DO NOTHING",
         user_achievement.username,
         user_achievement.id,
+        imported,
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
 
     if let Some(set) = sqlx::query!(
         "SELECT set FROM achievements WHERE id = $1",
         user_achievement.id,
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?
     .set
     {
         for related in
-            super::achievements::get_all_related_ids(user_achievement.id, set, pool).await?
+            super::achievements::get_all_related_ids(user_achievement.id, set, &mut *conn).await?
         {
             sqlx::query!(
                 "DELETE FROM users_achievements_completed WHERE username = $1 AND id = $2",
                 user_achievement.username,
                 related,
             )
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         }
     }
@@ -62,12 +86,12 @@ pub async fn delete(user_achievement: &DbUserAchievementCompleted, pool: &PgPool
     Ok(())
 }
 
-pub async fn delete_by_username(username: &str, pool: &PgPool) -> Result<()> {
+pub async fn delete_by_username(username: &str, executor: impl PgExecutor<'_>) -> Result<()> {
     sqlx::query!(
         "DELETE FROM users_achievements_completed WHERE username = $1",
         username,
     )
-    .execute(pool)
+    .execute(executor)
     .await?;
 
     Ok(())
@@ -79,7 +103,52 @@ pub async fn get_by_username(
 ) -> Result<Vec<DbUserAchievementCompleted>> {
     Ok(sqlx::query_as!(
         DbUserAchievementCompleted,
-        "SELECT * FROM users_achievements_completed WHERE username = $1",
+        "SELECT username, id FROM users_achievements_completed WHERE username = $1",
+        username
+    )
+    .fetch_all(pool)
+    .await?)
+}
+
+pub async fn get_completed_at_by_username(
+    username: &str,
+    pool: &PgPool,
+) -> Result<Vec<DbUserAchievementCompletedAt>> {
+    Ok(sqlx::query_as!(
+        DbUserAchievementCompletedAt,
+        "SELECT id, completed_at, imported FROM users_achievements_completed WHERE username = $1",
+        username
+    )
+    .fetch_all(pool)
+    .await?)
+}
+
+/// Restores when an existing completion happened
+pub async fn set_completed_at(
+    username: &str,
+    completed_at: &DbUserAchievementCompletedAt,
+    executor: impl PgExecutor<'_>,
+) -> Result<()> {
+    sqlx::query!(
+        "UPDATE users_achievements_completed SET completed_at = $3, imported = $4 WHERE username = $1 AND id = $2",
+        username,
+        completed_at.id,
+        completed_at.completed_at,
+        completed_at.imported,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_timeline_by_username(
+    username: &str,
+    pool: &PgPool,
+) -> Result<Vec<DbUserAchievementCompletion>> {
+    Ok(sqlx::query_as!(
+        DbUserAchievementCompletion,
+        "SELECT users_achievements_completed.completed_at, users_achievements_completed.imported, achievements.version, achievements.jades AS rewards FROM users_achievements_completed JOIN achievements ON achievements.id = users_achievements_completed.id WHERE users_achievements_completed.username = $1 ORDER BY users_achievements_completed.completed_at",
         username
     )
     .fetch_all(pool)
@@ -1,37 +1,42 @@
 use anyhow::Result;
-use sqlx::PgPool;
+use sqlx::{Acquire, PgExecutor, PgPool, Postgres};
 
 pub struct DbUserAchievementFavorite {
     pub username: String,
     pub id: i32,
 }
 
-pub async fn add(user_achievement: &DbUserAchievementFavorite, pool: &PgPool) -> Result<()> {
+pub async fn add(
+    user_achievement: &DbUserAchievementFavorite,
+    conn: impl Acquire<'_, Database = Postgres>,
+) -> Result<()> {
+    let mut conn = conn.acquire().await?;
+
     sqlx::query!(
         "INSERT INTO users_achievements_favorites(username, id) VALUES($1, $2) ON CONFLICT(username, id) DO NOTHING",
         user_achievement.username,
         user_achievement.id,
     )
-    .execute(pool)
+    .execute(&mut *conn)
     .await?;
 
     if let Some(set) = sqlx::query!(
         "SELECT set FROM achievements WHERE id = $1",
         user_achievement.id,
     )
-    .fetch_one(pool)
+    .fetch_one(&mut *conn)
     .await?
     .set
     {
         for related in
-            super::achievements::get_all_related_ids(user_achievement.id, set, pool).await?
+            super::achievements::get_all_related_ids(user_achievement.id, set, &mut *conn).await?
         {
             sqlx::query!(
                 "DELETE FROM users_achievements_favorites WHERE username = $1 AND id = $2",
                 user_achievement.username,
                 related,
             )
-            .execute(pool)
+            .execute(&mut *conn)
             .await?;
         }
     }
@@ -51,6 +56,17 @@ pub async fn delete(user_achievement: &DbUserAchievementFavorite, pool: &PgPool)
     Ok(())
 }
 
+pub async fn delete_by_username(username: &str, executor: impl PgExecutor<'_>) -> Result<()> {
+    sqlx::query!(
+        "DELETE FROM users_achievements_favorites WHERE username = $1",
+        username,
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
+
 pub async fn get_by_username(
     username: &str,
     pool: &PgPool,
@@ -0,0 +1,113 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::{PgExecutor, PgPool};
+
+pub struct DbUserDeletion {
+    pub username: String,
+    pub delete_pulls: bool,
+    pub export_hash: Vec<u8>,
+}
+
+pub async fn set(
+    deletion: &DbUserDeletion,
+    scheduled: DateTime<Utc>,
+    pool: &PgPool,
+) -> Result<()> {
+    sqlx::query_file!(
+        "sql/users_deletions/set.sql",
+        deletion.username,
+        deletion.delete_pulls,
+        deletion.export_hash,
+        scheduled,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_due(pool: &PgPool) -> Result<Vec<DbUserDeletion>> {
+    Ok(
+        sqlx::query_file_as!(DbUserDeletion, "sql/users_deletions/get_due.sql")
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+pub async fn get_scheduled_by_username(
+    username: &str,
+    pool: &PgPool,
+) -> Result<Option<DateTime<Utc>>> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/users_deletions/get_scheduled_by_username.sql",
+        username
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+/// Returns whether a deletion was scheduled
+pub async fn delete_by_username(username: &str, pool: &PgPool) -> Result<bool> {
+    Ok(
+        sqlx::query_file!("sql/users_deletions/delete_by_username.sql", username)
+            .execute(pool)
+            .await?
+            .rows_affected()
+            > 0,
+    )
+}
+
+/// Deletes the warps of the uids only this user is verified on. Returns the uids.
+pub async fn delete_warps_by_username(
+    username: &str,
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<i32>> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/users_deletions/delete_warps_by_username.sql",
+        username
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+/// Deletes the wishes of the uids only this user is verified on. Returns the uids.
+pub async fn delete_wishes_by_username(
+    username: &str,
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<i32>> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/users_deletions/delete_wishes_by_username.sql",
+        username
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+/// Deletes the signals of the uids only this user is verified on. Returns the uids.
+pub async fn delete_signals_by_username(
+    username: &str,
+    executor: impl PgExecutor<'_>,
+) -> Result<Vec<i32>> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/users_deletions/delete_signals_by_username.sql",
+        username
+    )
+    .fetch_all(executor)
+    .await?)
+}
+
+/// Deletes the claims the user made along with their tracker pulls. Claims assigned by admins are
+/// detached when the user is deleted.
+pub async fn delete_tracker_claims_by_username(
+    username: &str,
+    executor: impl PgExecutor<'_>,
+) -> Result<()> {
+    sqlx::query_file!(
+        "sql/users_deletions/delete_tracker_claims_by_username.sql",
+        username
+    )
+    .execute(executor)
+    .await?;
+
+    Ok(())
+}
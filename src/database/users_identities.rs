@@ -0,0 +1,71 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+pub struct DbUserIdentity {
+    pub provider: String,
+    pub email: Option<String>,
+    pub created: DateTime<Utc>,
+}
+
+/// Returns `false` if the identity or the provider for this user is already linked
+pub async fn set(
+    provider: &str,
+    subject: &str,
+    username: &str,
+    email: Option<&str>,
+    pool: &PgPool,
+) -> Result<bool> {
+    Ok(sqlx::query_file!(
+        "sql/users_identities/set.sql",
+        provider,
+        subject,
+        username,
+        email,
+    )
+    .execute(pool)
+    .await?
+    .rows_affected()
+        > 0)
+}
+
+pub async fn get_username_by_provider_and_subject(
+    provider: &str,
+    subject: &str,
+    pool: &PgPool,
+) -> Result<Option<String>> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/users_identities/get_username_by_provider_and_subject.sql",
+        provider,
+        subject,
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+pub async fn get_by_username(username: &str, pool: &PgPool) -> Result<Vec<DbUserIdentity>> {
+    Ok(sqlx::query_file_as!(
+        DbUserIdentity,
+        "sql/users_identities/get_by_username.sql",
+        username
+    )
+    .fetch_all(pool)
+    .await?)
+}
+
+/// Returns whether the identity existed
+pub async fn delete_by_provider_and_username(
+    provider: &str,
+    username: &str,
+    pool: &PgPool,
+) -> Result<bool> {
+    Ok(sqlx::query_file!(
+        "sql/users_identities/delete_by_provider_and_username.sql",
+        provider,
+        username,
+    )
+    .execute(pool)
+    .await?
+    .rows_affected()
+        > 0)
+}
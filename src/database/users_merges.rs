@@ -0,0 +1,180 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+/// What happened to the rows of the merged account
+pub struct DbMergeCounts {
+    /// New to the surviving account
+    pub moved: i64,
+    /// The surviving account already had them. Nothing is lost.
+    pub merged: i64,
+    /// Conflicted with the surviving account, which won
+    pub dropped: i64,
+}
+
+pub struct DbMergeReport {
+    pub achievements_completed: DbMergeCounts,
+    pub achievements_favorites: DbMergeCounts,
+    pub gi_achievements_completed: DbMergeCounts,
+    pub gi_achievements_favorites: DbMergeCounts,
+    pub zzz_achievements_completed: DbMergeCounts,
+    pub zzz_achievements_favorites: DbMergeCounts,
+    pub connections: DbMergeCounts,
+    pub gi_connections: DbMergeCounts,
+    pub zzz_connections: DbMergeCounts,
+    pub ntehelper_completions: DbMergeCounts,
+    pub ntehelper_settings: DbMergeCounts,
+    pub ntehelper_marker_comments: DbMergeCounts,
+    pub ntehelper_marker_comment_votes: DbMergeCounts,
+    pub ntehelper_tracker_claims: DbMergeCounts,
+    pub identities: DbMergeCounts,
+    /// Whether the email of the merged account was taken over
+    pub email: bool,
+}
+
+/// Moves everything of `from` into `into` and deletes `from`, all in one transaction
+pub async fn merge(from: &str, into: &str, pool: &PgPool) -> Result<DbMergeReport> {
+    let mut tx = pool.begin().await?;
+
+    let from_id = sqlx::query_file_scalar!("sql/users_merges/get_id_by_username.sql", from)
+        .fetch_one(&mut *tx)
+        .await?;
+    let into_id = sqlx::query_file_scalar!("sql/users_merges/get_id_by_username.sql", into)
+        .fetch_one(&mut *tx)
+        .await?;
+
+    let report = DbMergeReport {
+        achievements_completed: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_achievements_completed.sql",
+            from,
+            into,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        achievements_favorites: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_achievements_favorites.sql",
+            from,
+            into,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        gi_achievements_completed: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_gi_achievements_completed.sql",
+            from,
+            into,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        gi_achievements_favorites: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_gi_achievements_favorites.sql",
+            from,
+            into,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        zzz_achievements_completed: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_zzz_achievements_completed.sql",
+            from,
+            into,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        zzz_achievements_favorites: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_zzz_achievements_favorites.sql",
+            from,
+            into,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        connections: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_connections.sql",
+            from,
+            into,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        gi_connections: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_gi_connections.sql",
+            from,
+            into,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        zzz_connections: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_zzz_connections.sql",
+            from,
+            into,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        ntehelper_completions: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_ntehelper_completions.sql",
+            from_id,
+            into_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        ntehelper_settings: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_ntehelper_settings.sql",
+            from_id,
+            into_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        ntehelper_marker_comments: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_ntehelper_marker_comments.sql",
+            from_id,
+            into_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        ntehelper_marker_comment_votes: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_ntehelper_marker_comment_votes.sql",
+            from_id,
+            into_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        ntehelper_tracker_claims: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_ntehelper_tracker_claims.sql",
+            from_id,
+            into_id,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        identities: sqlx::query_file_as!(
+            DbMergeCounts,
+            "sql/users_merges/merge_identities.sql",
+            from,
+            into,
+        )
+        .fetch_one(&mut *tx)
+        .await?,
+        email: sqlx::query_file_scalar!("sql/users_merges/merge_email.sql", from, into)
+            .fetch_optional(&mut *tx)
+            .await?
+            .is_some(),
+    };
+
+    // Sessions, tokens, 2FA and whatever else is left cascade
+    sqlx::query_file!("sql/users/delete_by_username.sql", from)
+        .execute(&mut *tx)
+        .await?;
+
+    tx.commit().await?;
+
+    Ok(report)
+}
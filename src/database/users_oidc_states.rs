@@ -0,0 +1,62 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+pub struct DbUserOidcState {
+    pub hash: Vec<u8>,
+    pub provider: String,
+    pub code_verifier: String,
+    pub nonce: String,
+    /// The user to link the identity to. A login if `None`
+    pub username: Option<String>,
+    pub redirect: String,
+    pub expires: DateTime<Utc>,
+}
+
+pub struct DbConsumedOidcState {
+    pub code_verifier: String,
+    pub nonce: String,
+    pub username: Option<String>,
+    pub redirect: String,
+}
+
+pub async fn set(state: &DbUserOidcState, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!(
+        "sql/users_oidc_states/set.sql",
+        state.hash,
+        state.provider,
+        state.code_verifier,
+        state.nonce,
+        state.username,
+        state.redirect,
+        state.expires,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+/// States are single use
+pub async fn consume(
+    hash: &[u8],
+    provider: &str,
+    pool: &PgPool,
+) -> Result<Option<DbConsumedOidcState>> {
+    Ok(sqlx::query_file_as!(
+        DbConsumedOidcState,
+        "sql/users_oidc_states/consume.sql",
+        hash,
+        provider,
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+pub async fn delete_expired(pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/users_oidc_states/delete_expired.sql")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
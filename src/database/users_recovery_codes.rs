@@ -0,0 +1,35 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+pub async fn set_all(username: &str, hashes: &[Vec<u8>], pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/users_recovery_codes/set_all.sql", username, hashes)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_by_username(username: &str, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/users_recovery_codes/delete_by_username.sql", username)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Returns whether the code existed. Either way it can't be used again
+pub async fn delete_by_username_and_hash(
+    username: &str,
+    hash: &[u8],
+    pool: &PgPool,
+) -> Result<bool> {
+    Ok(sqlx::query_file!(
+        "sql/users_recovery_codes/delete_by_username_and_hash.sql",
+        username,
+        hash,
+    )
+    .execute(pool)
+    .await?
+    .rows_affected()
+        > 0)
+}
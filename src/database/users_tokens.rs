@@ -0,0 +1,102 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+pub struct DbUserToken {
+    pub hash: Vec<u8>,
+    pub username: String,
+    pub purpose: String,
+    pub email: Option<String>,
+    pub expires: DateTime<Utc>,
+}
+
+pub async fn set(token: &DbUserToken, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!(
+        "sql/users_tokens/set.sql",
+        token.hash,
+        token.username,
+        token.purpose,
+        token.email,
+        token.expires,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn exists_recent(
+    username: &str,
+    purpose: &str,
+    since: DateTime<Utc>,
+    pool: &PgPool,
+) -> Result<bool> {
+    Ok(sqlx::query_file_scalar!(
+        "sql/users_tokens/exists_recent.sql",
+        username,
+        purpose,
+        since,
+    )
+    .fetch_one(pool)
+    .await?
+    .unwrap_or_default())
+}
+
+/// Returns the token, if it exists, hasn't expired and was tried less than `max_attempts` times
+pub async fn get_one_by_hash_and_purpose(
+    hash: &[u8],
+    purpose: &str,
+    max_attempts: i32,
+    pool: &PgPool,
+) -> Result<Option<DbUserToken>> {
+    Ok(sqlx::query_file_as!(
+        DbUserToken,
+        "sql/users_tokens/get_one_by_hash_and_purpose.sql",
+        hash,
+        purpose,
+        max_attempts,
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+pub async fn increment_attempts_by_hash(hash: &[u8], pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/users_tokens/increment_attempts_by_hash.sql", hash)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Deletes the token and returns it, if it exists and hasn't expired
+pub async fn consume(hash: &[u8], purpose: &str, pool: &PgPool) -> Result<Option<DbUserToken>> {
+    Ok(
+        sqlx::query_file_as!(DbUserToken, "sql/users_tokens/consume.sql", hash, purpose)
+            .fetch_optional(pool)
+            .await?,
+    )
+}
+
+pub async fn delete_by_username_and_purpose(
+    username: &str,
+    purpose: &str,
+    pool: &PgPool,
+) -> Result<()> {
+    sqlx::query_file!(
+        "sql/users_tokens/delete_by_username_and_purpose.sql",
+        username,
+        purpose,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn delete_expired(pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/users_tokens/delete_expired.sql")
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
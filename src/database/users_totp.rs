@@ -0,0 +1,87 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+pub struct DbUserTotp {
+    pub secret: Vec<u8>,
+    pub enabled: bool,
+    pub failures: i32,
+    pub failed_at: Option<DateTime<Utc>>,
+}
+
+/// Starts a new enrollment. Does nothing if 2FA is already enabled
+pub async fn set(username: &str, secret: &[u8], pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/users_totp/set.sql", username, secret)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn get_one_by_username(username: &str, pool: &PgPool) -> Result<Option<DbUserTotp>> {
+    Ok(sqlx::query_file_as!(
+        DbUserTotp,
+        "sql/users_totp/get_one_by_username.sql",
+        username
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+pub async fn enable_by_username(username: &str, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/users_totp/enable_by_username.sql", username)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+/// Returns false if the step (or a later one) was already used, so a code can't be replayed
+pub async fn update_last_step_by_username(
+    username: &str,
+    last_step: i64,
+    pool: &PgPool,
+) -> Result<bool> {
+    Ok(sqlx::query_file!(
+        "sql/users_totp/update_last_step_by_username.sql",
+        username,
+        last_step,
+    )
+    .execute(pool)
+    .await?
+    .rows_affected()
+        > 0)
+}
+
+/// Starts counting from one again if the last failure was before `since`
+pub async fn increment_failures_by_username(
+    username: &str,
+    since: DateTime<Utc>,
+    pool: &PgPool,
+) -> Result<()> {
+    sqlx::query_file!(
+        "sql/users_totp/increment_failures_by_username.sql",
+        username,
+        since,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn reset_failures_by_username(username: &str, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/users_totp/reset_failures_by_username.sql", username)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
+
+pub async fn delete_by_username(username: &str, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!("sql/users_totp/delete_by_username.sql", username)
+        .execute(pool)
+        .await?;
+
+    Ok(())
+}
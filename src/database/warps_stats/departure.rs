@@ -0,0 +1,57 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::database::warps_stats::DbWarpsStat;
+
+pub struct DbWarpsStatDeparture {
+    pub uid: i32,
+    pub luck_4: f64,
+    pub luck_5: f64,
+}
+
+impl From<DbWarpsStatDeparture> for DbWarpsStat {
+    fn from(s: DbWarpsStatDeparture) -> Self {
+        DbWarpsStat {
+            uid: s.uid,
+            luck_4: s.luck_4,
+            luck_5: s.luck_5,
+            win_rate: 0.0,
+            win_streak: 0,
+            loss_streak: 0,
+        }
+    }
+}
+
+pub async fn set(stat: &DbWarpsStat, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!(
+        "sql/warps_stats/departure/set.sql",
+        stat.uid,
+        stat.luck_4,
+        stat.luck_5,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_updated_at(pool: &PgPool) -> Result<Option<DateTime<Utc>>> {
+    Ok(
+        sqlx::query_file_scalar!("sql/warps_stats/departure/get_updated_at.sql")
+            .fetch_one(pool)
+            .await?,
+    )
+}
+
+pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbWarpsStat>> {
+    let results = sqlx::query_file_as!(
+        DbWarpsStatDeparture,
+        "sql/warps_stats/departure/get_by_uid.sql",
+        uid,
+    )
+    .fetch_optional(pool)
+    .await?;
+
+    Ok(results.map(DbWarpsStat::from))
+}
@@ -1,7 +1,8 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 
-use crate::database::warps_stats::{DbWarpsStat, DbWarpsStatCount};
+use crate::database::warps_stats::DbWarpsStat;
 
 pub async fn set(stat: &DbWarpsStat, pool: &PgPool) -> Result<()> {
     sqlx::query_file!(
@@ -19,10 +20,10 @@ pub async fn set(stat: &DbWarpsStat, pool: &PgPool) -> Result<()> {
     Ok(())
 }
 
-pub async fn get_all(pool: &PgPool) -> Result<Vec<DbWarpsStatCount>> {
+pub async fn get_updated_at(pool: &PgPool) -> Result<Option<DateTime<Utc>>> {
     Ok(
-        sqlx::query_file_as!(DbWarpsStatCount, "sql/warps_stats/lc/get_all_count.sql")
-            .fetch_all(pool)
+        sqlx::query_file_scalar!("sql/warps_stats/lc/get_updated_at.sql")
+            .fetch_one(pool)
             .await?,
     )
 }
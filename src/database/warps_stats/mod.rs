@@ -1,16 +1,10 @@
 pub mod collab;
 pub mod collab_lc;
+pub mod departure;
 pub mod lc;
 pub mod special;
 pub mod standard;
 
-pub struct DbWarpsStatCount {
-    pub uid: i32,
-    pub luck_4: f64,
-    pub luck_5: f64,
-    pub warp_count: Option<i64>,
-}
-
 pub struct DbWarpsStat {
     pub uid: i32,
     pub luck_4: f64,
@@ -1,7 +1,8 @@
 use anyhow::Result;
+use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 
-use crate::database::warps_stats::{DbWarpsStat, DbWarpsStatCount};
+use crate::database::warps_stats::DbWarpsStat;
 
 pub struct DbWarpsStatStandard {
     pub uid: i32,
@@ -35,13 +36,12 @@ pub async fn set(stat: &DbWarpsStat, pool: &PgPool) -> Result<()> {
     Ok(())
 }
 
-pub async fn get_all(pool: &PgPool) -> Result<Vec<DbWarpsStatCount>> {
-    Ok(sqlx::query_file_as!(
-        DbWarpsStatCount,
-        "sql/warps_stats/standard/get_all_count.sql"
+pub async fn get_updated_at(pool: &PgPool) -> Result<Option<DateTime<Utc>>> {
+    Ok(
+        sqlx::query_file_scalar!("sql/warps_stats/standard/get_updated_at.sql")
+            .fetch_one(pool)
+            .await?,
     )
-    .fetch_all(pool)
-    .await?)
 }
 
 pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbWarpsStat>> {
@@ -0,0 +1,25 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+use crate::database::warps_stats_global::DbWarpsStatGlobal;
+
+/// Ranks every eligible uid, ties share a percentile. Only writes the rows that changed and
+/// returns how many
+pub async fn update(pool: &PgPool) -> Result<u64> {
+    Ok(
+        sqlx::query_file!("sql/warps_stats_global/departure/update.sql")
+            .execute(pool)
+            .await?
+            .rows_affected(),
+    )
+}
+
+pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbWarpsStatGlobal>> {
+    Ok(sqlx::query_file_as!(
+        DbWarpsStatGlobal,
+        "sql/warps_stats_global/departure/get_by_uid.sql",
+        uid
+    )
+    .fetch_optional(pool)
+    .await?)
+}
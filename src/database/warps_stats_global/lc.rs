@@ -3,27 +3,13 @@ use sqlx::PgPool;
 
 use crate::database::warps_stats_global::DbWarpsStatGlobal;
 
-pub async fn set_bulk(stats: &[DbWarpsStatGlobal], pool: &PgPool) -> Result<()> {
-    if stats.is_empty() {
-        return Ok(());
-    }
-
-    let uids: Vec<i32> = stats.iter().map(|s| s.uid).collect();
-    let count_percentiles: Vec<f64> = stats.iter().map(|s| s.count_percentile).collect();
-    let luck_4_percentiles: Vec<f64> = stats.iter().map(|s| s.luck_4_percentile).collect();
-    let luck_5_percentiles: Vec<f64> = stats.iter().map(|s| s.luck_5_percentile).collect();
-
-    sqlx::query_file!(
-        "sql/warps_stats_global/lc/set_bulk.sql",
-        &uids,
-        &count_percentiles,
-        &luck_4_percentiles,
-        &luck_5_percentiles,
-    )
-    .execute(pool)
-    .await?;
-
-    Ok(())
+/// Ranks every eligible uid, ties share a percentile. Only writes the rows that changed and
+/// returns how many
+pub async fn update(pool: &PgPool) -> Result<u64> {
+    Ok(sqlx::query_file!("sql/warps_stats_global/lc/update.sql")
+        .execute(pool)
+        .await?
+        .rows_affected())
 }
 
 pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbWarpsStatGlobal>> {
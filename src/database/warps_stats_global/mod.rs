@@ -1,11 +1,11 @@
 pub mod collab;
 pub mod collab_lc;
+pub mod departure;
 pub mod lc;
 pub mod special;
 pub mod standard;
 
 pub struct DbWarpsStatGlobal {
-    pub uid: i32,
     pub count_percentile: f64,
     pub luck_4_percentile: f64,
     pub luck_5_percentile: f64,
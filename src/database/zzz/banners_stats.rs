@@ -0,0 +1,59 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+use crate::database::banners_stats::{DbBannerCount, DbBannerHit, DbBannerStats};
+
+pub async fn get_hits(pool: &PgPool) -> Result<Vec<DbBannerHit>> {
+    Ok(
+        sqlx::query_file_as!(DbBannerHit, "sql/zzz/banners_stats/get_hits.sql")
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+pub async fn get_counts(pool: &PgPool) -> Result<Vec<DbBannerCount>> {
+    Ok(
+        sqlx::query_file_as!(DbBannerCount, "sql/zzz/banners_stats/get_counts.sql")
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+/// Replaces all stats
+pub async fn set_all(stats: &[DbBannerStats], pool: &PgPool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query_file!("sql/zzz/banners_stats/delete_all.sql")
+        .execute(&mut *tx)
+        .await?;
+
+    for stats in stats {
+        sqlx::query_file!(
+            "sql/zzz/banners_stats/set.sql",
+            stats.id,
+            stats.pulls,
+            stats.uids,
+            &stats.copies,
+            stats.wins,
+            stats.losses,
+            stats.guarantees,
+            stats.featured_pity,
+            &stats.loss_items,
+            &stats.loss_counts,
+        )
+        .execute(&mut *tx)
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn get_by_id(id: i32, pool: &PgPool) -> Result<Option<DbBannerStats>> {
+    Ok(
+        sqlx::query_file_as!(DbBannerStats, "sql/zzz/banners_stats/get_by_id.sql", id)
+            .fetch_optional(pool)
+            .await?,
+    )
+}
@@ -5,9 +5,12 @@ pub mod achievements_percent;
 pub mod achievements_text;
 pub mod bangboos;
 pub mod bangboos_text;
+pub mod banners;
+pub mod banners_stats;
 pub mod characters;
 pub mod characters_text;
 pub mod connections;
+pub mod pity_curves;
 pub mod signals;
 pub mod signals_stats;
 pub mod signals_stats_global;
@@ -0,0 +1,47 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+use crate::database::pity_curves::{DbPityCount, DbPityCurve, SetAll};
+
+pub async fn get_counts(pool: &PgPool) -> Result<Vec<DbPityCount>> {
+    Ok(
+        sqlx::query_file_as!(DbPityCount, "sql/zzz/pity_curves/get_counts.sql")
+            .fetch_all(pool)
+            .await?,
+    )
+}
+
+/// Replaces all curves
+pub async fn set_all(set_all: &SetAll, pool: &PgPool) -> Result<()> {
+    let mut tx = pool.begin().await?;
+
+    sqlx::query_file!("sql/zzz/pity_curves/delete_all.sql")
+        .execute(&mut *tx)
+        .await?;
+
+    sqlx::query_file!(
+        "sql/zzz/pity_curves/set_all.sql",
+        &set_all.banner,
+        &set_all.rarity,
+        &set_all.pity,
+        &set_all.pulls,
+        &set_all.hits,
+        &set_all.rate,
+        &set_all.lower,
+        &set_all.upper,
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    tx.commit().await?;
+
+    Ok(())
+}
+
+pub async fn get_all(pool: &PgPool) -> Result<Vec<DbPityCurve>> {
+    Ok(
+        sqlx::query_file_as!(DbPityCurve, "sql/zzz/pity_curves/get_all.sql")
+            .fetch_all(pool)
+            .await?,
+    )
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+pub struct DbSignalsStatExclusiveRescreening {
+    pub uid: i32,
+    pub luck_a: f64,
+    pub luck_s: f64,
+    pub win_rate: f64,
+    pub win_streak: i32,
+    pub loss_streak: i32,
+}
+
+pub async fn set(stat: &DbSignalsStatExclusiveRescreening, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!(
+        "sql/zzz/signals_stats/exclusive_rescreening/set.sql",
+        stat.uid,
+        stat.luck_a,
+        stat.luck_s,
+        stat.win_rate,
+        stat.win_streak,
+        stat.loss_streak,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_by_uid(
+    uid: i32,
+    pool: &PgPool,
+) -> Result<Option<DbSignalsStatExclusiveRescreening>> {
+    Ok(sqlx::query_file_as!(
+        DbSignalsStatExclusiveRescreening,
+        "sql/zzz/signals_stats/exclusive_rescreening/get_by_uid.sql",
+        uid,
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+pub async fn get_updated_at(pool: &PgPool) -> Result<Option<DateTime<Utc>>> {
+    Ok(
+        sqlx::query_file_scalar!("sql/zzz/signals_stats/exclusive_rescreening/get_updated_at.sql")
+            .fetch_one(pool)
+            .await?,
+    )
+}
@@ -0,0 +1,49 @@
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+pub struct DbSignalsStatWEngineReverberation {
+    pub uid: i32,
+    pub luck_a: f64,
+    pub luck_s: f64,
+    pub win_rate: f64,
+    pub win_streak: i32,
+    pub loss_streak: i32,
+}
+
+pub async fn set(stat: &DbSignalsStatWEngineReverberation, pool: &PgPool) -> Result<()> {
+    sqlx::query_file!(
+        "sql/zzz/signals_stats/w_engine_reverberation/set.sql",
+        stat.uid,
+        stat.luck_a,
+        stat.luck_s,
+        stat.win_rate,
+        stat.win_streak,
+        stat.loss_streak,
+    )
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+pub async fn get_by_uid(
+    uid: i32,
+    pool: &PgPool,
+) -> Result<Option<DbSignalsStatWEngineReverberation>> {
+    Ok(sqlx::query_file_as!(
+        DbSignalsStatWEngineReverberation,
+        "sql/zzz/signals_stats/w_engine_reverberation/get_by_uid.sql",
+        uid,
+    )
+    .fetch_optional(pool)
+    .await?)
+}
+
+pub async fn get_updated_at(pool: &PgPool) -> Result<Option<DateTime<Utc>>> {
+    Ok(
+        sqlx::query_file_scalar!("sql/zzz/signals_stats/w_engine_reverberation/get_updated_at.sql")
+            .fetch_one(pool)
+            .await?,
+    )
+}
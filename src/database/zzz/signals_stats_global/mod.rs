@@ -1,4 +1,6 @@
 pub mod bangboo;
+pub mod exclusive_rescreening;
 pub mod special;
 pub mod standard;
 pub mod w_engine;
+pub mod w_engine_reverberation;
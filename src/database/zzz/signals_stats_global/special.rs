@@ -2,24 +2,20 @@ use anyhow::Result;
 use sqlx::PgPool;
 
 pub struct DbSignalsStatGlobalSpecial {
-    pub uid: i32,
     pub count_percentile: f64,
     pub luck_a_percentile: f64,
     pub luck_s_percentile: f64,
 }
 
-pub async fn set(stat: &DbSignalsStatGlobalSpecial, pool: &PgPool) -> Result<()> {
-    sqlx::query_file!(
-        "sql/zzz/signals_stats_global/special/set.sql",
-        stat.uid,
-        stat.count_percentile,
-        stat.luck_a_percentile,
-        stat.luck_s_percentile,
+/// Ranks every eligible uid, ties share a percentile. Only writes the rows that changed and
+/// returns how many
+pub async fn update(pool: &PgPool) -> Result<u64> {
+    Ok(
+        sqlx::query_file!("sql/zzz/signals_stats_global/special/update.sql")
+            .execute(pool)
+            .await?
+            .rows_affected(),
     )
-    .execute(pool)
-    .await?;
-
-    Ok(())
 }
 
 pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbSignalsStatGlobalSpecial>> {
@@ -31,14 +27,3 @@ pub async fn get_by_uid(uid: i32, pool: &PgPool) -> Result<Option<DbSignalsStatG
     .fetch_optional(pool)
     .await?)
 }
-
-pub async fn delete_by_uid(uid: i32, pool: &PgPool) -> Result<()> {
-    sqlx::query_file!(
-        "sql/zzz/signals_stats_global/special/delete_by_uid.sql",
-        uid
-    )
-    .execute(pool)
-    .await?;
-
-    Ok(())
-}
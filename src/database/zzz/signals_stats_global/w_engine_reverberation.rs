@@ -0,0 +1,32 @@
+use anyhow::Result;
+use sqlx::PgPool;
+
+pub struct DbSignalsStatGlobalWEngineReverberation {
+    pub count_percentile: f64,
+    pub luck_a_percentile: f64,
+    pub luck_s_percentile: f64,
+}
+
+/// Ranks every eligible uid, ties share a percentile. Only writes the rows that changed and
+/// returns how many
+pub async fn update(pool: &PgPool) -> Result<u64> {
+    Ok(
+        sqlx::query_file!("sql/zzz/signals_stats_global/w_engine_reverberation/update.sql")
+            .execute(pool)
+            .await?
+            .rows_affected(),
+    )
+}
+
+pub async fn get_by_uid(
+    uid: i32,
+    pool: &PgPool,
+) -> Result<Option<DbSignalsStatGlobalWEngineReverberation>> {
+    Ok(sqlx::query_file_as!(
+        DbSignalsStatGlobalWEngineReverberation,
+        "sql/zzz/signals_stats_global/w_engine_reverberation/get_by_uid.sql",
+        uid
+    )
+    .fetch_optional(pool)
+    .await?)
+}
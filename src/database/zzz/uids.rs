@@ -1,13 +1,13 @@
 use anyhow::Result;
-use sqlx::PgPool;
+use sqlx::{PgExecutor, PgPool};
 
 pub struct DbUid {
     pub uid: i32,
 }
 
-pub async fn set(uid: &DbUid, pool: &PgPool) -> Result<()> {
+pub async fn set(uid: &DbUid, executor: impl PgExecutor<'_>) -> Result<()> {
     sqlx::query_file!("sql/zzz/uids/set.sql", uid.uid)
-        .execute(pool)
+        .execute(executor)
         .await?;
 
     Ok(())
@@ -0,0 +1,64 @@
+use std::{env, fs};
+
+use actix_web::web;
+use lettre::{
+    transport::smtp::authentication::Credentials, FileTransport, Message, SmtpTransport, Transport,
+};
+
+use crate::app_config::{AppConfig, MailTransport};
+
+/// `to` can include a name, like `meow <meow@gmail.com>`. The transports block, so sending runs on
+/// the blocking thread pool.
+pub async fn send(
+    to: &str,
+    subject: &str,
+    body: String,
+    app_config: &AppConfig,
+) -> anyhow::Result<()> {
+    let message = Message::builder()
+        .from("stardb <noreply@stardb.gg>".parse()?)
+        .to(to.parse()?)
+        .subject(subject)
+        .body(body)?;
+
+    let to = to.to_string();
+    let mail_transport = app_config.mail_transport;
+    let smtp_relay = app_config.smtp_relay.clone();
+    let mail_dir = app_config.mail_dir.clone();
+
+    web::block(move || send_blocking(&to, &message, mail_transport, &smtp_relay, &mail_dir)).await?
+}
+
+fn send_blocking(
+    to: &str,
+    message: &Message,
+    mail_transport: MailTransport,
+    smtp_relay: &str,
+    mail_dir: &str,
+) -> anyhow::Result<()> {
+    match mail_transport {
+        MailTransport::Smtp => {
+            let credentials =
+                Credentials::new(env::var("SMTP_USERNAME")?, env::var("SMTP_PASSWORD")?);
+
+            let mailer = SmtpTransport::relay(smtp_relay)?
+                .credentials(credentials)
+                .build();
+
+            mailer.send(message)?;
+        }
+        MailTransport::File => {
+            fs::create_dir_all(mail_dir)?;
+
+            FileTransport::new(mail_dir).send(message)?;
+        }
+        MailTransport::Log => {
+            info!(
+                "Mail to {to}:\n{}",
+                String::from_utf8_lossy(&message.formatted())
+            );
+        }
+    }
+
+    Ok(())
+}
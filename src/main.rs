@@ -4,25 +4,27 @@ extern crate tracing;
 mod api;
 mod app_config;
 mod database;
+mod mail;
 mod mihomo;
 mod pg_session_store;
+mod signing;
 mod update;
+#[cfg(test)]
+mod test_util;
 
-use std::{env, fs, path::Path};
+use std::{env, fs};
 
 use actix_cors::Cors;
 use actix_files::Files;
 use actix_session::{config::PersistentSession, SessionMiddleware};
 use actix_web::{
     cookie::time::Duration,
-    middleware::Compress,
+    middleware::{from_fn, Compress, Condition},
     web::{self, Data},
     App, HttpServer,
 };
-use ed25519_dalek::SecretKey;
 use futures::lock::Mutex;
 use pg_session_store::PgSessionStore;
-use rand::RngCore;
 use sentry_tracing::EventFilter;
 use sqlx::postgres::PgPoolOptions;
 use std::sync::Arc;
@@ -100,6 +102,25 @@ impl Language {
         }
         .to_string()
     }
+
+    pub fn uigf(&self) -> String {
+        match self {
+            Language::ZhCn => "zh-cn",
+            Language::ZhTw => "zh-tw",
+            Language::De => "de-de",
+            Language::En => "en-us",
+            Language::EsEs => "es-es",
+            Language::Fr => "fr-fr",
+            Language::Id => "id-id",
+            Language::Ja => "ja-jp",
+            Language::Ko => "ko-kr",
+            Language::PtPt => "pt-pt",
+            Language::Ru => "ru-ru",
+            Language::Th => "th-th",
+            Language::Vi => "vi-vn",
+        }
+        .to_string()
+    }
 }
 
 #[derive(
@@ -328,13 +349,22 @@ async fn async_main() -> anyhow::Result<()> {
     if app_config.enable_update_wishes_stats {
         update::wishes_stats::spawn(pool.clone()).await;
     }
+    if app_config.enable_update_pity_curves {
+        update::pity_curves::spawn(pool.clone()).await;
+    }
+    if app_config.enable_update_banners_stats {
+        update::banners_stats::spawn(pool.clone()).await;
+    }
+
+    api::service_keys::load(&pool).await?;
 
     let session_key = session_key()?;
-    let signing_key = signing_key()?;
+    let signing_key = signing::load(&pool).await?;
 
     let signing_key_data = web::Data::new(Mutex::new(signing_key));
     let pool_data = Data::new(pool.clone());
     let app_config_data = Data::new(app_config.clone());
+    let rate_limiter_data = Data::new(api::rate_limit::RateLimiter::new(pool.clone(), &app_config));
 
     let openapi = api::openapi();
 
@@ -344,6 +374,12 @@ async fn async_main() -> anyhow::Result<()> {
             .app_data(pool_data.clone())
             .app_data(signing_key_data.clone())
             .app_data(app_config_data.clone())
+            .app_data(rate_limiter_data.clone())
+            // Inside the session middleware, so it knows who is logged in
+            .wrap(Condition::new(
+                app_config.enable_rate_limits,
+                from_fn(api::rate_limit::middleware),
+            ))
             // Captures request context + errors per-request. No-op if no SENTRY_DSN.
             .wrap(sentry_actix::Sentry::new())
             .wrap(Cors::permissive())
@@ -363,7 +399,14 @@ async fn async_main() -> anyhow::Result<()> {
                 SwaggerUi::new("/api/swagger-ui/{_:.*}")
                     .url("/api-doc/openapi.json", openapi.clone()),
             )
-            .configure(|sc| api::configure(sc, pool.clone(), app_config_data.clone()))
+            .configure(|sc| {
+                api::configure(
+                    sc,
+                    pool.clone(),
+                    app_config_data.clone(),
+                    signing_key_data.clone(),
+                )
+            })
     })
     .bind(("localhost", 8000))?
     .run()
@@ -394,25 +437,6 @@ fn session_key() -> anyhow::Result<actix_web::cookie::Key> {
     Ok(key)
 }
 
-fn signing_key() -> anyhow::Result<ed25519_dalek::SigningKey> {
-    use ed25519_dalek::{
-        pkcs8::{spki::der::pem::LineEnding, DecodePrivateKey, EncodePrivateKey},
-        SigningKey,
-    };
-
-    let path = Path::new("id_ed25519_sign");
-
-    Ok(if path.exists() {
-        SigningKey::read_pkcs8_pem_file(path)?
-    } else {
-        let mut secret_key = SecretKey::default();
-        rand::rng().fill_bytes(&mut secret_key);
-        let signing_key = SigningKey::from_bytes(&secret_key);
-        signing_key.write_pkcs8_pem_file(path, LineEnding::LF)?;
-        signing_key
-    })
-}
-
 fn load_app_config() -> anyhow::Result<Arc<app_config::AppConfig>> {
     let config = envy::from_env::<app_config::AppConfig>()?;
     tracing::debug!("AppConfig loaded: {:#?}", config);
@@ -20,6 +20,29 @@ impl PgSessionStore {
 
 type SessionState = HashMap<String, String>;
 
+/// `last_seen` is only written this often, so not every request is a write
+const LAST_SEEN_INTERVAL: chrono::Duration = chrono::Duration::minutes(5);
+
+/// Values in the session state are json
+fn get_state(session_state: &SessionState, key: &str) -> Option<String> {
+    session_state
+        .get(key)
+        .and_then(|value| serde_json::from_str(value).ok())
+}
+
+fn get_username(session_state: &SessionState) -> anyhow::Result<String> {
+    get_state(session_state, "username").ok_or_else(|| anyhow::anyhow!("Session has no username"))
+}
+
+fn get_ip_hash(session_state: &SessionState) -> Option<Vec<u8>> {
+    let ip_hash = get_state(session_state, "ip_hash")?;
+
+    (0..ip_hash.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(ip_hash.get(i..i + 2)?, 16).ok())
+        .collect()
+}
+
 impl SessionStore for PgSessionStore {
     async fn load(&self, session_key: &SessionKey) -> Result<Option<SessionState>, LoadError> {
         let uuid = Uuid::from_str(session_key.as_ref())
@@ -30,10 +53,21 @@ impl SessionStore for PgSessionStore {
             .await
             .map_err(LoadError::Deserialization)?;
 
+        if Utc::now() - db_session.last_seen > LAST_SEEN_INTERVAL {
+            database::sessions::update_last_seen_by_uuid(uuid, &self.pool)
+                .await
+                .map_err(LoadError::Other)?;
+        }
+
         let mut session_state = HashMap::new();
         session_state.insert(
             "username".to_string(),
-            format!("\"{}\"", db_session.username),
+            serde_json::to_string(&db_session.username).map_err(|e| LoadError::Other(e.into()))?,
+        );
+        // Lets handlers tell which of the user's sessions is the current one
+        session_state.insert(
+            "session_id".to_string(),
+            serde_json::to_string(&db_session.id).map_err(|e| LoadError::Other(e.into()))?,
         );
 
         Ok(Some(session_state))
@@ -46,19 +80,24 @@ impl SessionStore for PgSessionStore {
     ) -> Result<SessionKey, SaveError> {
         let uuid = Uuid::new_v4();
 
-        let username = &session_state["username"];
-        let username = username[1..username.len() - 1].to_string();
+        let username = get_username(&session_state).map_err(SaveError::Other)?;
 
         database::sessions::delete_oldest_by_username(&username, &self.pool)
             .await
             .map_err(SaveError::Other)?;
 
-        let expiry = Utc::now() + chrono::Duration::try_seconds(ttl.whole_seconds()).unwrap();
+        let now = Utc::now();
+        let expiry = now + chrono::Duration::try_seconds(ttl.whole_seconds()).unwrap();
 
         let db_session = database::sessions::DbSession {
             uuid,
             username,
             expiry,
+            id: Uuid::new_v4(),
+            created: now,
+            last_seen: now,
+            user_agent: get_state(&session_state, "user_agent"),
+            ip_hash: get_ip_hash(&session_state),
         };
 
         database::sessions::set(&db_session, &self.pool)
@@ -81,20 +120,20 @@ impl SessionStore for PgSessionStore {
             .map_err(anyhow::Error::new)
             .map_err(UpdateError::Other)?;
 
-        let username = &session_state["username"];
-        let username = username[1..username.len() - 1].to_string();
+        let username = get_username(&session_state).map_err(UpdateError::Other)?;
 
         let expiry = Utc::now() + chrono::Duration::try_seconds(ttl.whole_seconds()).unwrap();
 
-        let db_session = database::sessions::DbSession {
+        database::sessions::update_by_uuid(
             uuid,
-            username,
+            &username,
             expiry,
-        };
-
-        database::sessions::set(&db_session, &self.pool)
-            .await
-            .map_err(UpdateError::Other)?;
+            get_state(&session_state, "user_agent").as_deref(),
+            get_ip_hash(&session_state).as_deref(),
+            &self.pool,
+        )
+        .await
+        .map_err(UpdateError::Other)?;
 
         Ok(session_key)
     }
@@ -115,3 +154,58 @@ impl SessionStore for PgSessionStore {
         database::sessions::delete_by_uuid(uuid, &self.pool).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::{create_test_user, delete_test_user, test_pool};
+
+    #[actix_web::test]
+    async fn sessions_keep_metadata_and_stay_revoked() {
+        let pool = test_pool().await;
+        let store = PgSessionStore::new(pool.clone());
+
+        let username = create_test_user("sessions_test", &pool).await;
+
+        let mut session_state = SessionState::new();
+        session_state.insert("username".to_string(), format!("\"{username}\""));
+        session_state.insert("user_agent".to_string(), "\"meow/1.0\"".to_string());
+        session_state.insert("ip_hash".to_string(), "\"00ff\"".to_string());
+
+        let ttl = Duration::weeks(1);
+        let session_key = store.save(session_state, &ttl).await.unwrap();
+
+        let loaded = store.load(&session_key).await.unwrap().unwrap();
+        assert_eq!(get_username(&loaded).unwrap(), username);
+
+        let db_session = database::sessions::get_by_username(&username, &pool)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            get_state(&loaded, "session_id"),
+            Some(db_session.id.to_string())
+        );
+        assert_eq!(db_session.user_agent.as_deref(), Some("meow/1.0"));
+        assert_eq!(db_session.ip_hash, Some(vec![0x00, 0xff]));
+
+        // The loaded state has no user agent, which mustn't clear it
+        let session_key = store.update(session_key, loaded, &ttl).await.unwrap();
+        let db_session = database::sessions::get_by_username(&username, &pool)
+            .await
+            .unwrap()
+            .remove(0);
+        assert_eq!(db_session.user_agent.as_deref(), Some("meow/1.0"));
+
+        let loaded = store.load(&session_key).await.unwrap().unwrap();
+        assert!(
+            database::sessions::delete_by_id_and_username(db_session.id, &username, &pool)
+                .await
+                .unwrap()
+        );
+        let session_key = store.update(session_key, loaded, &ttl).await.unwrap();
+        assert!(store.load(&session_key).await.is_err());
+
+        delete_test_user(&username, &pool).await;
+    }
+}
@@ -0,0 +1,156 @@
+use std::path::Path;
+
+use base64::{prelude::BASE64_URL_SAFE_NO_PAD, Engine};
+use ed25519_dalek::{
+    pkcs8::DecodePrivateKey, SecretKey, Signature, SigningKey, Verifier, VerifyingKey,
+};
+use futures::lock::{Mutex, MutexGuard};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use sqlx::PgPool;
+
+use crate::database;
+
+const PATH: &str = "id_ed25519_sign";
+
+/// The active key. Keys used to live in a file per instance, so if there is none yet the first
+/// instance to start carries its file over.
+pub async fn load(pool: &PgPool) -> anyhow::Result<SigningKey> {
+    if let Some(signing_key) = get_active(pool).await? {
+        return Ok(signing_key);
+    }
+
+    let path = Path::new(PATH);
+
+    let signing_key = if path.exists() {
+        SigningKey::read_pkcs8_pem_file(path)?
+    } else {
+        generate()
+    };
+
+    activate(&signing_key, pool).await?;
+
+    Ok(signing_key)
+}
+
+/// Another instance may have rotated the key since this one last signed, so the active key is
+/// looked up on every use
+pub async fn active<'a>(
+    signing_key: &'a Mutex<SigningKey>,
+    pool: &PgPool,
+) -> anyhow::Result<MutexGuard<'a, SigningKey>> {
+    let mut signing_key = signing_key.lock().await;
+
+    let Some(active) = get_active(pool).await? else {
+        return Err(anyhow::anyhow!("No active export key"));
+    };
+
+    *signing_key = active;
+
+    Ok(signing_key)
+}
+
+async fn get_active(pool: &PgPool) -> anyhow::Result<Option<SigningKey>> {
+    let Some(key) = database::export_keys::get_active(pool).await? else {
+        return Ok(None);
+    };
+
+    Ok(Some(SigningKey::from_bytes(
+        key.private_key.as_slice().try_into()?,
+    )))
+}
+
+fn generate() -> SigningKey {
+    let mut secret_key = SecretKey::default();
+    rand::rng().fill_bytes(&mut secret_key);
+
+    SigningKey::from_bytes(&secret_key)
+}
+
+async fn activate(signing_key: &SigningKey, pool: &PgPool) -> anyhow::Result<String> {
+    let verifying_key = signing_key.verifying_key();
+    let id = key_id(&verifying_key);
+
+    database::export_keys::activate(&id, verifying_key.as_bytes(), signing_key.as_bytes(), pool)
+        .await?;
+
+    Ok(id)
+}
+
+/// RFC 7638 thumbprint of the key's JWK
+pub fn key_id(verifying_key: &VerifyingKey) -> String {
+    let x = BASE64_URL_SAFE_NO_PAD.encode(verifying_key.as_bytes());
+    let thumbprint = Sha256::digest(format!(r#"{{"crv":"Ed25519","kty":"OKP","x":"{x}"}}"#));
+
+    BASE64_URL_SAFE_NO_PAD.encode(thumbprint)
+}
+
+/// Replaces the signing key on every instance and retires all previous ones
+pub async fn rotate(signing_key: &Mutex<SigningKey>, pool: &PgPool) -> anyhow::Result<String> {
+    let mut signing_key = signing_key.lock().await;
+
+    let new_signing_key = generate();
+    let id = activate(&new_signing_key, pool).await?;
+
+    *signing_key = new_signing_key;
+
+    Ok(id)
+}
+
+/// Signatures of a revoked key aren't trusted anymore. The active key is rotated first, so there
+/// always is one to sign with. Returns whether the key exists.
+pub async fn revoke(
+    id: &str,
+    signing_key: &Mutex<SigningKey>,
+    pool: &PgPool,
+) -> anyhow::Result<bool> {
+    let active = database::export_keys::get_active(pool).await?;
+
+    if active.is_some_and(|active| active.id == id) {
+        rotate(signing_key, pool).await?;
+    }
+
+    database::export_keys::revoke(id, pool).await
+}
+
+/// Returns the id of the key that produced the signature. Exports without a
+/// key id are checked against every known key. Revoked keys are skipped.
+pub async fn verify(
+    message: &[u8],
+    signature: &str,
+    key_id: Option<&str>,
+    pool: &PgPool,
+) -> anyhow::Result<Option<String>> {
+    let Ok(signature) = signature.parse::<Signature>() else {
+        return Ok(None);
+    };
+
+    let keys = if let Some(key_id) = key_id {
+        database::export_keys::get_by_id(key_id, pool)
+            .await?
+            .into_iter()
+            .collect()
+    } else {
+        database::export_keys::get_all(pool).await?
+    };
+
+    for key in keys {
+        if key.revoked.is_some() {
+            continue;
+        }
+
+        let Ok(public_key) = key.public_key.as_slice().try_into() else {
+            continue;
+        };
+
+        let Ok(verifying_key) = VerifyingKey::from_bytes(public_key) else {
+            continue;
+        };
+
+        if verifying_key.verify(message, &signature).is_ok() {
+            return Ok(Some(key.id));
+        }
+    }
+
+    Ok(None)
+}
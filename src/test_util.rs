@@ -0,0 +1,64 @@
+//! Helpers for the DB-backed tests. They all run against `DATABASE_URL` at the same time, so
+//! everything they create gets a unique name.
+
+use sqlx::{postgres::PgPoolOptions, PgPool};
+use uuid::Uuid;
+
+use crate::database;
+
+pub async fn test_pool() -> PgPool {
+    let _ = dotenv::dotenv();
+    let database_url =
+        std::env::var("DATABASE_URL").expect("DATABASE_URL must be set for DB-backed tests");
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect(&database_url)
+        .await
+        .expect("test database should connect");
+    sqlx::migrate!()
+        .run(&pool)
+        .await
+        .expect("test database migrations should run");
+    pool
+}
+
+/// Named `<prefix>_<random>`, without an email
+pub async fn create_test_user(prefix: &str, pool: &PgPool) -> String {
+    insert_test_user(prefix, None, false, pool).await
+}
+
+pub async fn create_test_user_with_email(
+    prefix: &str,
+    email: &str,
+    email_verified: bool,
+    pool: &PgPool,
+) -> String {
+    insert_test_user(prefix, Some(email), email_verified, pool).await
+}
+
+async fn insert_test_user(
+    prefix: &str,
+    email: Option<&str>,
+    email_verified: bool,
+    pool: &PgPool,
+) -> String {
+    let username = format!("{prefix}_{}", Uuid::new_v4().simple());
+    let user = database::users::DbUser {
+        username: username.clone(),
+        password: "test-password-hash".to_string(),
+        email: email.map(ToString::to_string),
+        email_verified,
+    };
+    database::users::set(&user, pool)
+        .await
+        .expect("test user should insert successfully");
+    username
+}
+
+pub async fn delete_test_user(username: &str, pool: &PgPool) {
+    sqlx::query("DELETE FROM users WHERE username = $1")
+        .bind(username)
+        .execute(pool)
+        .await
+        .expect("test user should delete successfully");
+}
@@ -0,0 +1,367 @@
+use std::{
+    collections::HashMap,
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use actix_web::rt;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use crate::{
+    api::banner_helpers::{
+        self, RadianceState, WinOutcome, GI_STANDARD, HSR_STANDARD, ZZZ_STANDARD,
+    },
+    database::{
+        self,
+        banners_stats::{DbBannerCount, DbBannerHit, DbBannerStats},
+    },
+};
+
+const TOP_LOSSES: usize = 10;
+
+pub async fn spawn(pool: PgPool) {
+    actix::Arbiter::new().spawn(async move {
+        let mut interval = rt::time::interval(Duration::from_secs(60 * 60));
+
+        loop {
+            interval.tick().await;
+
+            let start = Instant::now();
+
+            if let Err(e) = update(pool.clone()).await {
+                error!(
+                    "Banners stats update failed with {e} in {}s",
+                    start.elapsed().as_secs_f64()
+                );
+            } else {
+                info!(
+                    "Banners stats update succeeded in {}s",
+                    start.elapsed().as_secs_f64()
+                );
+            }
+        }
+    });
+}
+
+async fn update(pool: PgPool) -> Result<()> {
+    hsr(&pool).await?;
+    gi(&pool).await?;
+    zzz(&pool).await?;
+
+    Ok(())
+}
+
+async fn hsr(pool: &PgPool) -> Result<()> {
+    let windows: Vec<_> = database::banners::get_all(pool)
+        .await?
+        .into_iter()
+        .filter_map(|b| {
+            let (kind, item) = match (b.collab, b.character, b.light_cone) {
+                (false, Some(character), _) => ("character", character),
+                (false, None, Some(light_cone)) => ("light_cone", light_cone),
+                (true, Some(character), _) => ("collab_character", character),
+                (true, None, Some(light_cone)) => ("collab_light_cone", light_cone),
+                _ => return None,
+            };
+
+            Some(Window {
+                id: b.id,
+                kind,
+                item,
+                range: b.start..b.end,
+            })
+        })
+        .collect();
+
+    let counts = database::banners_stats::get_counts(pool).await?;
+    let hits = database::banners_stats::get_hits(pool).await?;
+
+    let stats = stats(&windows, counts, hits, HSR_STANDARD);
+    database::banners_stats::set_all(&stats, pool).await?;
+
+    Ok(())
+}
+
+async fn gi(pool: &PgPool) -> Result<()> {
+    let windows: Vec<_> = database::gi::banners::get_all(pool)
+        .await?
+        .into_iter()
+        .filter_map(|b| {
+            let (kind, item) = match (b.character, b.weapon) {
+                (Some(character), _) => ("character", character),
+                (None, Some(weapon)) => ("weapon", weapon),
+                _ => return None,
+            };
+
+            Some(Window {
+                id: b.id,
+                kind,
+                item,
+                range: b.start..b.end,
+            })
+        })
+        .collect();
+
+    let counts = database::gi::banners_stats::get_counts(pool).await?;
+    let hits = database::gi::banners_stats::get_hits(pool).await?;
+
+    let stats = stats(&windows, counts, hits, GI_STANDARD);
+    database::gi::banners_stats::set_all(&stats, pool).await?;
+
+    Ok(())
+}
+
+async fn zzz(pool: &PgPool) -> Result<()> {
+    let windows: Vec<_> = database::zzz::banners::get_all(pool)
+        .await?
+        .into_iter()
+        .filter_map(|b| {
+            let (kind, item) = match (b.character, b.w_engine) {
+                (Some(character), _) => ("character", character),
+                (None, Some(w_engine)) => ("w_engine", w_engine),
+                _ => return None,
+            };
+
+            Some(Window {
+                id: b.id,
+                kind,
+                item,
+                range: b.start..b.end,
+            })
+        })
+        .collect();
+
+    let counts = database::zzz::banners_stats::get_counts(pool).await?;
+    let hits = database::zzz::banners_stats::get_hits(pool).await?;
+
+    let stats = stats(&windows, counts, hits, ZZZ_STANDARD);
+    database::zzz::banners_stats::set_all(&stats, pool).await?;
+
+    Ok(())
+}
+
+struct Window {
+    id: i32,
+    kind: &'static str,
+    item: i32,
+    range: Range<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+struct Accumulator {
+    copies: HashMap<i32, i64>,
+    wins: i64,
+    losses: i64,
+    guarantees: i64,
+    featured_pity: (i64, i64),
+    loss_items: HashMap<i32, i64>,
+}
+
+/// `hits` have to be ordered by source and uid. Every hit counts for all banners of its kind that
+/// were running at the time, so concurrent banners share their outcomes.
+fn stats(
+    windows: &[Window],
+    counts: Vec<DbBannerCount>,
+    hits: Vec<DbBannerHit>,
+    standard: &[i32],
+) -> Vec<DbBannerStats> {
+    let mut banners: HashMap<_, Vec<_>> = HashMap::new();
+    for window in windows {
+        banners
+            .entry(window.item)
+            .or_default()
+            .push(window.range.clone());
+    }
+
+    let is_win = banner_helpers::is_win_fn(&banners, standard);
+
+    let mut accumulators: HashMap<i32, Accumulator> = HashMap::new();
+
+    let mut history = None;
+    // Capturing Radiance only changes how a win is called, so this works for every game
+    let mut state = RadianceState::default();
+    let mut since_featured = None;
+
+    for hit in hits {
+        if !matches!(&history, Some((source, uid)) if *source == hit.source && *uid == hit.uid) {
+            history = Some((hit.source.clone(), hit.uid));
+            state = RadianceState::default();
+            since_featured = None;
+        }
+
+        let outcome = state.pull(is_win(hit.item, hit.timestamp), hit.timestamp);
+
+        let featured_pity = since_featured.zip(hit.pity).map(|(s, p)| s + p as i64);
+        since_featured = if outcome == WinOutcome::Loss {
+            featured_pity
+        } else {
+            Some(0)
+        };
+
+        if !hit.official {
+            continue;
+        }
+
+        for window in windows
+            .iter()
+            .filter(|w| w.kind == hit.kind && w.range.contains(&hit.timestamp))
+        {
+            let accumulator = accumulators.entry(window.id).or_default();
+
+            match outcome {
+                WinOutcome::Win | WinOutcome::Radiance => accumulator.wins += 1,
+                WinOutcome::Guarantee => accumulator.guarantees += 1,
+                WinOutcome::Loss => {
+                    accumulator.losses += 1;
+                    *accumulator.loss_items.entry(hit.item).or_default() += 1;
+                }
+            }
+
+            if hit.item == window.item {
+                *accumulator.copies.entry(hit.uid).or_default() += 1;
+
+                if let Some(featured_pity) = featured_pity {
+                    accumulator.featured_pity.0 += featured_pity;
+                    accumulator.featured_pity.1 += 1;
+                }
+            }
+        }
+    }
+
+    counts
+        .into_iter()
+        .map(|count| {
+            let accumulator = accumulators.remove(&count.id).unwrap_or_default();
+
+            let mut copies =
+                vec![0; accumulator.copies.values().max().copied().unwrap_or(0) as usize];
+            for &c in accumulator.copies.values() {
+                copies[c as usize - 1] += 1;
+            }
+
+            let mut loss_items: Vec<_> = accumulator.loss_items.into_iter().collect();
+            loss_items.sort_by_key(|&(item, count)| (-count, item));
+            loss_items.truncate(TOP_LOSSES);
+
+            let (sum, n) = accumulator.featured_pity;
+
+            DbBannerStats {
+                id: count.id,
+                pulls: count.pulls,
+                uids: count.uids,
+                copies,
+                wins: accumulator.wins,
+                losses: accumulator.losses,
+                guarantees: accumulator.guarantees,
+                featured_pity: (n > 0).then(|| sum as f64 / n as f64),
+                loss_items: loss_items.iter().map(|&(item, _)| item).collect(),
+                loss_counts: loss_items.iter().map(|&(_, count)| count).collect(),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    fn day(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 1, day, 0, 0, 0).unwrap()
+    }
+
+    fn hit(uid: i32, item: i32, day_: u32, pity: Option<i32>) -> DbBannerHit {
+        DbBannerHit {
+            source: "special".to_string(),
+            kind: "character".to_string(),
+            uid,
+            timestamp: day(day_),
+            official: true,
+            item,
+            pity,
+        }
+    }
+
+    #[test]
+    fn outcomes_copies_and_losses_are_attributed_to_the_window() {
+        let windows = [Window {
+            id: 1,
+            kind: "character",
+            item: 100,
+            range: day(10)..day(20),
+        }];
+        let counts = vec![DbBannerCount {
+            id: 1,
+            pulls: 500,
+            uids: 3,
+        }];
+        let hits = vec![
+            // Before the banner, starts the history
+            hit(1, 100, 1, None),
+            hit(1, 1, 11, Some(70)),
+            hit(1, 100, 12, Some(20)),
+            hit(1, 100, 13, Some(80)),
+            hit(2, 2, 11, None),
+            hit(2, 100, 12, Some(60)),
+            hit(3, 1, 14, Some(10)),
+        ];
+
+        let stats = stats(&windows, counts, hits, &[1, 2]);
+        let stats = &stats[0];
+
+        assert_eq!((stats.pulls, stats.uids), (500, 3));
+        assert_eq!(stats.copies, [1, 1]);
+        assert_eq!((stats.wins, stats.losses, stats.guarantees), (1, 3, 2));
+        // 70 + 20 and 80. The history of uid 2 started with a loss
+        assert_eq!(stats.featured_pity, Some(85.0));
+        assert_eq!(stats.loss_items, [1, 2]);
+        assert_eq!(stats.loss_counts, [2, 1]);
+    }
+    #[test]
+    fn collab_pulls_only_count_for_collab_banners() {
+        let windows = [
+            Window {
+                id: 1,
+                kind: "character",
+                item: 100,
+                range: day(10)..day(20),
+            },
+            Window {
+                id: 2,
+                kind: "collab_character",
+                item: 200,
+                range: day(10)..day(20),
+            },
+        ];
+        let counts = vec![
+            DbBannerCount {
+                id: 1,
+                pulls: 100,
+                uids: 1,
+            },
+            DbBannerCount {
+                id: 2,
+                pulls: 100,
+                uids: 1,
+            },
+        ];
+        let hits = vec![
+            hit(1, 100, 11, None),
+            DbBannerHit {
+                source: "collab".to_string(),
+                kind: "collab_character".to_string(),
+                ..hit(1, 200, 12, None)
+            },
+        ];
+
+        let stats = stats(&windows, counts, hits, &[1]);
+
+        assert_eq!(stats[0].copies, [1]);
+        assert_eq!((stats[0].wins, stats[0].losses), (1, 0));
+        assert_eq!(stats[1].copies, [1]);
+        assert_eq!((stats[1].wins, stats[1].losses), (1, 0));
+    }
+}
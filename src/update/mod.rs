@@ -1,6 +1,8 @@
 pub mod achievements_percent;
+pub mod banners_stats;
 pub mod dimbreath;
 pub mod gi_achievements_percent;
+pub mod pity_curves;
 pub mod scores;
 pub mod signals_stats;
 pub mod star_rail_res;
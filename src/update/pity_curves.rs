@@ -0,0 +1,97 @@
+use std::time::{Duration, Instant};
+
+use actix_web::rt;
+use anyhow::Result;
+use sqlx::PgPool;
+
+use crate::database::{
+    self,
+    pity_curves::{DbPityCount, SetAll},
+};
+
+/// 95% confidence
+const Z: f64 = 1.959964;
+
+pub async fn spawn(pool: PgPool) {
+    actix::Arbiter::new().spawn(async move {
+        let mut interval = rt::time::interval(Duration::from_secs(60 * 60));
+
+        loop {
+            interval.tick().await;
+
+            let start = Instant::now();
+
+            if let Err(e) = update(pool.clone()).await {
+                error!(
+                    "Pity curves update failed with {e} in {}s",
+                    start.elapsed().as_secs_f64()
+                );
+            } else {
+                info!(
+                    "Pity curves update succeeded in {}s",
+                    start.elapsed().as_secs_f64()
+                );
+            }
+        }
+    });
+}
+
+async fn update(pool: PgPool) -> Result<()> {
+    let counts = database::pity_curves::get_counts(&pool).await?;
+    database::pity_curves::set_all(&curves(counts), &pool).await?;
+
+    let counts = database::gi::pity_curves::get_counts(&pool).await?;
+    database::gi::pity_curves::set_all(&curves(counts), &pool).await?;
+
+    let counts = database::zzz::pity_curves::get_counts(&pool).await?;
+    database::zzz::pity_curves::set_all(&curves(counts), &pool).await?;
+
+    Ok(())
+}
+
+fn curves(counts: Vec<DbPityCount>) -> SetAll {
+    let mut set_all = SetAll::default();
+
+    for count in counts {
+        let (lower, upper) = wilson(count.hits, count.pulls);
+
+        set_all.rate.push(count.hits as f64 / count.pulls as f64);
+        set_all.lower.push(lower);
+        set_all.upper.push(upper);
+        set_all.banner.push(count.banner);
+        set_all.rarity.push(count.rarity);
+        set_all.pity.push(count.pity);
+        set_all.pulls.push(count.pulls);
+        set_all.hits.push(count.hits);
+    }
+
+    set_all
+}
+
+/// Wilson score interval, which stays inside 0..=1 for rates near 0 and 1 and small samples
+fn wilson(hits: i64, pulls: i64) -> (f64, f64) {
+    let n = pulls as f64;
+    let p = hits as f64 / n;
+
+    let denominator = 1.0 + Z * Z / n;
+    let center = (p + Z * Z / (2.0 * n)) / denominator;
+    let margin = Z * (p * (1.0 - p) / n + Z * Z / (4.0 * n * n)).sqrt() / denominator;
+
+    ((center - margin).max(0.0), (center + margin).min(1.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wilson_interval_contains_the_rate() {
+        let (lower, upper) = wilson(6, 1000);
+        assert!(lower < 0.006 && 0.006 < upper);
+        assert!((lower - 0.00275).abs() < 1e-4, "{lower}");
+        assert!((upper - 0.01303).abs() < 1e-4, "{upper}");
+
+        assert_eq!(wilson(0, 10).0, 0.0);
+        assert!((wilson(10, 10).1 - 1.0).abs() < 1e-12);
+    }
+}
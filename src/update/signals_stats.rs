@@ -1,7 +1,4 @@
-use std::{
-    collections::HashMap,
-    time::{Duration, Instant},
-};
+use std::time::{Duration, Instant};
 
 use actix_web::rt;
 use anyhow::Result;
@@ -42,301 +39,170 @@ pub async fn spawn(pool: PgPool) {
 }
 
 async fn update(pool: PgPool) -> Result<()> {
-    info!("Starting standard");
     standard(&pool).await?;
-
-    info!("Starting special");
     special(&pool).await?;
-
-    info!("Starting w_engine");
     w_engine(&pool).await?;
-
-    info!("Starting bangboo");
     bangboo(&pool).await?;
+    exclusive_rescreening(&pool).await?;
+    w_engine_reverberation(&pool).await?;
 
     Ok(())
 }
 
 async fn standard(pool: &PgPool) -> Result<()> {
-    let mut count_map = HashMap::new();
-    let mut luck_a_map = HashMap::new();
-    let mut luck_s_map = HashMap::new();
-
-    let mut stat_uids = Vec::new();
-
-    for signal_stat in database::zzz::signals_stats::standard::get_all(pool).await? {
-        let uid = signal_stat.uid;
+    const NAME: &str = "zzz_signals_stats_global_standard";
 
-        let count = database::zzz::signals::standard::get_count_by_uid(uid, pool).await? as i32;
+    let Some(updated_at) = database::zzz::signals_stats::standard::get_updated_at(pool).await?
+    else {
+        return Ok(());
+    };
 
-        if count < 50 || signal_stat.luck_s == 0.0 {
-            database::zzz::signals_stats_global::standard::delete_by_uid(uid, pool).await?;
+    if database::stats_global_updates::get_by_name(NAME, pool).await? >= Some(updated_at) {
+        info!("Standard signals stats unchanged");
+        return Ok(());
+    }
 
-            continue;
-        }
+    let start = Instant::now();
 
-        stat_uids.push(uid);
+    let updated = database::zzz::signals_stats_global::standard::update(pool).await?;
+    database::stats_global_updates::set(NAME, updated_at, pool).await?;
 
-        count_map.insert(uid, count);
-        luck_a_map.insert(uid, signal_stat.luck_a);
-        luck_s_map.insert(uid, signal_stat.luck_s);
-    }
-
-    let mut sorted_count: Vec<(i32, i32)> = count_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_count.sort_unstable_by(|(_, v1), (_, v2)| v2.cmp(v1));
-
-    let mut sorted_luck_a: Vec<(i32, f64)> = luck_a_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_a.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let mut sorted_luck_s: Vec<(i32, f64)> = luck_s_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_s.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let count_percentiles: HashMap<_, _> = sorted_count
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_a_percentiles: HashMap<_, _> = sorted_luck_a
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_s_percentiles: HashMap<_, _> = sorted_luck_s
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let len = stat_uids.len() as f64;
-    for uid in &stat_uids {
-        let count_percentile = count_percentiles[uid] as f64 / len;
-        let luck_a_percentile = luck_a_percentiles[uid] as f64 / len;
-        let luck_s_percentile = luck_s_percentiles[uid] as f64 / len;
-
-        let stat = database::zzz::signals_stats_global::standard::DbSignalsStatGlobalStandard {
-            uid: *uid,
-            count_percentile,
-            luck_a_percentile,
-            luck_s_percentile,
-        };
-
-        database::zzz::signals_stats_global::standard::set(&stat, pool).await?;
-    }
+    info!(
+        "Standard signals stats updated: {updated} in {}s",
+        start.elapsed().as_secs_f64()
+    );
 
     Ok(())
 }
 
 async fn special(pool: &PgPool) -> Result<()> {
-    let mut count_map = HashMap::new();
-    let mut luck_a_map = HashMap::new();
-    let mut luck_s_map = HashMap::new();
+    const NAME: &str = "zzz_signals_stats_global_special";
 
-    let mut stat_uids = Vec::new();
+    let Some(updated_at) = database::zzz::signals_stats::special::get_updated_at(pool).await?
+    else {
+        return Ok(());
+    };
 
-    for signal_stat in database::zzz::signals_stats::special::get_all(pool).await? {
-        let uid = signal_stat.uid;
-
-        let count = database::zzz::signals::special::get_count_by_uid(uid, pool).await? as i32;
+    if database::stats_global_updates::get_by_name(NAME, pool).await? >= Some(updated_at) {
+        info!("Special signals stats unchanged");
+        return Ok(());
+    }
 
-        if count < 50 || signal_stat.luck_s == 0.0 {
-            database::zzz::signals_stats_global::special::delete_by_uid(uid, pool).await?;
+    let start = Instant::now();
 
-            continue;
-        }
+    let updated = database::zzz::signals_stats_global::special::update(pool).await?;
+    database::stats_global_updates::set(NAME, updated_at, pool).await?;
 
-        stat_uids.push(uid);
-
-        count_map.insert(uid, count);
-        luck_a_map.insert(uid, signal_stat.luck_a);
-        luck_s_map.insert(uid, signal_stat.luck_s);
-    }
-
-    let mut sorted_count: Vec<(i32, i32)> = count_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_count.sort_unstable_by(|(_, v1), (_, v2)| v2.cmp(v1));
-
-    let mut sorted_luck_a: Vec<(i32, f64)> = luck_a_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_a.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let mut sorted_luck_s: Vec<(i32, f64)> = luck_s_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_s.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let count_percentiles: HashMap<_, _> = sorted_count
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_a_percentiles: HashMap<_, _> = sorted_luck_a
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_s_percentiles: HashMap<_, _> = sorted_luck_s
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let len = stat_uids.len() as f64;
-    for uid in &stat_uids {
-        let count_percentile = count_percentiles[uid] as f64 / len;
-        let luck_a_percentile = luck_a_percentiles[uid] as f64 / len;
-        let luck_s_percentile = luck_s_percentiles[uid] as f64 / len;
-
-        let stat = database::zzz::signals_stats_global::special::DbSignalsStatGlobalSpecial {
-            uid: *uid,
-            count_percentile,
-            luck_a_percentile,
-            luck_s_percentile,
-        };
-
-        database::zzz::signals_stats_global::special::set(&stat, pool).await?;
-    }
+    info!(
+        "Special signals stats updated: {updated} in {}s",
+        start.elapsed().as_secs_f64()
+    );
 
     Ok(())
 }
 
 async fn w_engine(pool: &PgPool) -> Result<()> {
-    let mut count_map = HashMap::new();
-    let mut luck_a_map = HashMap::new();
-    let mut luck_s_map = HashMap::new();
+    const NAME: &str = "zzz_signals_stats_global_w_engine";
 
-    let mut stat_uids = Vec::new();
+    let Some(updated_at) = database::zzz::signals_stats::w_engine::get_updated_at(pool).await?
+    else {
+        return Ok(());
+    };
 
-    for signal_stat in database::zzz::signals_stats::w_engine::get_all(pool).await? {
-        let uid = signal_stat.uid;
+    if database::stats_global_updates::get_by_name(NAME, pool).await? >= Some(updated_at) {
+        info!("W-Engine signals stats unchanged");
+        return Ok(());
+    }
 
-        let count = database::zzz::signals::w_engine::get_count_by_uid(uid, pool).await? as i32;
+    let start = Instant::now();
 
-        if count < 50 || signal_stat.luck_s == 0.0 {
-            database::zzz::signals_stats_global::w_engine::delete_by_uid(uid, pool).await?;
+    let updated = database::zzz::signals_stats_global::w_engine::update(pool).await?;
+    database::stats_global_updates::set(NAME, updated_at, pool).await?;
 
-            continue;
-        }
+    info!(
+        "W-Engine signals stats updated: {updated} in {}s",
+        start.elapsed().as_secs_f64()
+    );
 
-        stat_uids.push(uid);
+    Ok(())
+}
 
-        count_map.insert(uid, count);
-        luck_a_map.insert(uid, signal_stat.luck_a);
-        luck_s_map.insert(uid, signal_stat.luck_s);
-    }
+async fn bangboo(pool: &PgPool) -> Result<()> {
+    const NAME: &str = "zzz_signals_stats_global_bangboo";
+
+    let Some(updated_at) = database::zzz::signals_stats::bangboo::get_updated_at(pool).await?
+    else {
+        return Ok(());
+    };
 
-    let mut sorted_count: Vec<(i32, i32)> = count_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_count.sort_unstable_by(|(_, v1), (_, v2)| v2.cmp(v1));
-
-    let mut sorted_luck_a: Vec<(i32, f64)> = luck_a_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_a.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let mut sorted_luck_s: Vec<(i32, f64)> = luck_s_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_s.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let count_percentiles: HashMap<_, _> = sorted_count
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_a_percentiles: HashMap<_, _> = sorted_luck_a
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_s_percentiles: HashMap<_, _> = sorted_luck_s
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let len = stat_uids.len() as f64;
-    for uid in &stat_uids {
-        let count_percentile = count_percentiles[uid] as f64 / len;
-        let luck_a_percentile = luck_a_percentiles[uid] as f64 / len;
-        let luck_s_percentile = luck_s_percentiles[uid] as f64 / len;
-
-        let stat = database::zzz::signals_stats_global::w_engine::DbSignalsStatGlobalWEngine {
-            uid: *uid,
-            count_percentile,
-            luck_a_percentile,
-            luck_s_percentile,
-        };
-
-        database::zzz::signals_stats_global::w_engine::set(&stat, pool).await?;
+    if database::stats_global_updates::get_by_name(NAME, pool).await? >= Some(updated_at) {
+        info!("Bangboo signals stats unchanged");
+        return Ok(());
     }
 
+    let start = Instant::now();
+
+    let updated = database::zzz::signals_stats_global::bangboo::update(pool).await?;
+    database::stats_global_updates::set(NAME, updated_at, pool).await?;
+
+    info!(
+        "Bangboo signals stats updated: {updated} in {}s",
+        start.elapsed().as_secs_f64()
+    );
+
     Ok(())
 }
 
-async fn bangboo(pool: &PgPool) -> Result<()> {
-    let mut count_map = HashMap::new();
-    let mut luck_a_map = HashMap::new();
-    let mut luck_s_map = HashMap::new();
+async fn exclusive_rescreening(pool: &PgPool) -> Result<()> {
+    const NAME: &str = "zzz_signals_stats_global_exclusive_rescreening";
 
-    let mut stat_uids = Vec::new();
+    let Some(updated_at) =
+        database::zzz::signals_stats::exclusive_rescreening::get_updated_at(pool).await?
+    else {
+        return Ok(());
+    };
 
-    for signal_stat in database::zzz::signals_stats::bangboo::get_all(pool).await? {
-        let uid = signal_stat.uid;
+    if database::stats_global_updates::get_by_name(NAME, pool).await? >= Some(updated_at) {
+        info!("Exclusive Rescreening signals stats unchanged");
+        return Ok(());
+    }
 
-        let count = database::zzz::signals::bangboo::get_count_by_uid(uid, pool).await? as i32;
+    let start = Instant::now();
 
-        if count < 50 || signal_stat.luck_s == 0.0 {
-            database::zzz::signals_stats_global::bangboo::delete_by_uid(uid, pool).await?;
+    let updated = database::zzz::signals_stats_global::exclusive_rescreening::update(pool).await?;
+    database::stats_global_updates::set(NAME, updated_at, pool).await?;
 
-            continue;
-        }
+    info!(
+        "Exclusive Rescreening signals stats updated: {updated} in {}s",
+        start.elapsed().as_secs_f64()
+    );
 
-        stat_uids.push(uid);
+    Ok(())
+}
 
-        count_map.insert(uid, count);
-        luck_a_map.insert(uid, signal_stat.luck_a);
-        luck_s_map.insert(uid, signal_stat.luck_s);
-    }
+async fn w_engine_reverberation(pool: &PgPool) -> Result<()> {
+    const NAME: &str = "zzz_signals_stats_global_w_engine_reverberation";
 
-    let mut sorted_count: Vec<(i32, i32)> = count_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_count.sort_unstable_by(|(_, v1), (_, v2)| v2.cmp(v1));
-
-    let mut sorted_luck_a: Vec<(i32, f64)> = luck_a_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_a.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let mut sorted_luck_s: Vec<(i32, f64)> = luck_s_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_s.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let count_percentiles: HashMap<_, _> = sorted_count
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_a_percentiles: HashMap<_, _> = sorted_luck_a
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_s_percentiles: HashMap<_, _> = sorted_luck_s
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let len = stat_uids.len() as f64;
-    for uid in &stat_uids {
-        let count_percentile = count_percentiles[uid] as f64 / len;
-        let luck_a_percentile = luck_a_percentiles[uid] as f64 / len;
-        let luck_s_percentile = luck_s_percentiles[uid] as f64 / len;
-
-        let stat = database::zzz::signals_stats_global::bangboo::DbSignalsStatGlobalBangboo {
-            uid: *uid,
-            count_percentile,
-            luck_a_percentile,
-            luck_s_percentile,
-        };
-
-        database::zzz::signals_stats_global::bangboo::set(&stat, pool).await?;
+    let Some(updated_at) =
+        database::zzz::signals_stats::w_engine_reverberation::get_updated_at(pool).await?
+    else {
+        return Ok(());
+    };
+
+    if database::stats_global_updates::get_by_name(NAME, pool).await? >= Some(updated_at) {
+        info!("W-Engine Reverberation signals stats unchanged");
+        return Ok(());
     }
 
+    let start = Instant::now();
+
+    let updated = database::zzz::signals_stats_global::w_engine_reverberation::update(pool).await?;
+    database::stats_global_updates::set(NAME, updated_at, pool).await?;
+
+    info!(
+        "W-Engine Reverberation signals stats updated: {updated} in {}s",
+        start.elapsed().as_secs_f64()
+    );
+
     Ok(())
 }
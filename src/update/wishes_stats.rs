@@ -1,7 +1,4 @@
-use std::{
-    collections::HashMap,
-    time::{Duration, Instant},
-};
+use std::time::{Duration, Instant};
 
 use actix_web::rt;
 use anyhow::Result;
@@ -42,293 +39,138 @@ pub async fn spawn(pool: PgPool) {
 }
 
 async fn update(pool: PgPool) -> Result<()> {
-    info!("Starting standard");
     standard(&pool).await?;
-
-    info!("Starting character");
     character(&pool).await?;
-
-    info!("Starting weapon");
     weapon(&pool).await?;
-
-    info!("Starting chronicled");
     chronicled(&pool).await?;
+    beginner(&pool).await?;
 
     Ok(())
 }
 
 async fn standard(pool: &PgPool) -> Result<()> {
-    let mut count_map = HashMap::new();
-    let mut luck_4_map = HashMap::new();
-    let mut luck_5_map = HashMap::new();
-
-    let mut stat_uids = Vec::new();
+    const NAME: &str = "gi_wishes_stats_global_standard";
 
-    for wish_stat in database::gi::wishes_stats::standard::get_all(pool).await? {
-        let uid = wish_stat.uid;
+    let Some(updated_at) = database::gi::wishes_stats::standard::get_updated_at(pool).await? else {
+        return Ok(());
+    };
 
-        let count = database::gi::wishes::standard::get_count_by_uid(uid, pool).await? as i32;
-
-        if count < 100 {
-            continue;
-        }
+    if database::stats_global_updates::get_by_name(NAME, pool).await? >= Some(updated_at) {
+        info!("Standard wishes stats unchanged");
+        return Ok(());
+    }
 
-        stat_uids.push(uid);
+    let start = Instant::now();
 
-        count_map.insert(uid, count);
-        luck_4_map.insert(uid, wish_stat.luck_4);
-        luck_5_map.insert(uid, wish_stat.luck_5);
-    }
+    let updated = database::gi::wishes_stats_global::standard::update(pool).await?;
+    database::stats_global_updates::set(NAME, updated_at, pool).await?;
 
-    let mut sorted_count: Vec<(i32, i32)> = count_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_count.sort_unstable_by(|(_, v1), (_, v2)| v2.cmp(v1));
-
-    let mut sorted_luck_4: Vec<(i32, f64)> = luck_4_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_4.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let mut sorted_luck_5: Vec<(i32, f64)> = luck_5_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_5.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let count_percentiles: HashMap<_, _> = sorted_count
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_4_percentiles: HashMap<_, _> = sorted_luck_4
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_5_percentiles: HashMap<_, _> = sorted_luck_5
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let len = stat_uids.len() as f64;
-    for uid in &stat_uids {
-        let count_percentile = count_percentiles[uid] as f64 / len;
-        let luck_4_percentile = luck_4_percentiles[uid] as f64 / len;
-        let luck_5_percentile = luck_5_percentiles[uid] as f64 / len;
-
-        let stat = database::gi::wishes_stats_global::standard::DbWishesStatGlobalStandard {
-            uid: *uid,
-            count_percentile,
-            luck_4_percentile,
-            luck_5_percentile,
-        };
-
-        database::gi::wishes_stats_global::standard::set(&stat, pool).await?;
-    }
+    info!(
+        "Standard wishes stats updated: {updated} in {}s",
+        start.elapsed().as_secs_f64()
+    );
 
     Ok(())
 }
 
 async fn character(pool: &PgPool) -> Result<()> {
-    let mut count_map = HashMap::new();
-    let mut luck_4_map = HashMap::new();
-    let mut luck_5_map = HashMap::new();
-
-    let mut stat_uids = Vec::new();
+    const NAME: &str = "gi_wishes_stats_global_character";
 
-    for wish_stat in database::gi::wishes_stats::character::get_all(pool).await? {
-        let uid = wish_stat.uid;
+    let Some(updated_at) = database::gi::wishes_stats::character::get_updated_at(pool).await?
+    else {
+        return Ok(());
+    };
 
-        let count = database::gi::wishes::character::get_count_by_uid(uid, pool).await? as i32;
-
-        if count < 100 {
-            continue;
-        }
+    if database::stats_global_updates::get_by_name(NAME, pool).await? >= Some(updated_at) {
+        info!("Character wishes stats unchanged");
+        return Ok(());
+    }
 
-        stat_uids.push(uid);
+    let start = Instant::now();
 
-        count_map.insert(uid, count);
-        luck_4_map.insert(uid, wish_stat.luck_4);
-        luck_5_map.insert(uid, wish_stat.luck_5);
-    }
+    let updated = database::gi::wishes_stats_global::character::update(pool).await?;
+    database::stats_global_updates::set(NAME, updated_at, pool).await?;
 
-    let mut sorted_count: Vec<(i32, i32)> = count_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_count.sort_unstable_by(|(_, v1), (_, v2)| v2.cmp(v1));
-
-    let mut sorted_luck_4: Vec<(i32, f64)> = luck_4_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_4.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let mut sorted_luck_5: Vec<(i32, f64)> = luck_5_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_5.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let count_percentiles: HashMap<_, _> = sorted_count
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_4_percentiles: HashMap<_, _> = sorted_luck_4
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_5_percentiles: HashMap<_, _> = sorted_luck_5
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let len = stat_uids.len() as f64;
-    for uid in &stat_uids {
-        let count_percentile = count_percentiles[uid] as f64 / len;
-        let luck_4_percentile = luck_4_percentiles[uid] as f64 / len;
-        let luck_5_percentile = luck_5_percentiles[uid] as f64 / len;
-
-        let stat = database::gi::wishes_stats_global::character::DbWishesStatGlobalCharacter {
-            uid: *uid,
-            count_percentile,
-            luck_4_percentile,
-            luck_5_percentile,
-        };
-
-        database::gi::wishes_stats_global::character::set(&stat, pool).await?;
-    }
+    info!(
+        "Character wishes stats updated: {updated} in {}s",
+        start.elapsed().as_secs_f64()
+    );
 
     Ok(())
 }
 
 async fn weapon(pool: &PgPool) -> Result<()> {
-    let mut count_map = HashMap::new();
-    let mut luck_4_map = HashMap::new();
-    let mut luck_5_map = HashMap::new();
-
-    let mut stat_uids = Vec::new();
+    const NAME: &str = "gi_wishes_stats_global_weapon";
 
-    for wish_stat in database::gi::wishes_stats::weapon::get_all(pool).await? {
-        let uid = wish_stat.uid;
+    let Some(updated_at) = database::gi::wishes_stats::weapon::get_updated_at(pool).await? else {
+        return Ok(());
+    };
 
-        let count = database::gi::wishes::weapon::get_count_by_uid(uid, pool).await? as i32;
-
-        if count < 100 {
-            continue;
-        }
+    if database::stats_global_updates::get_by_name(NAME, pool).await? >= Some(updated_at) {
+        info!("Weapon wishes stats unchanged");
+        return Ok(());
+    }
 
-        stat_uids.push(uid);
+    let start = Instant::now();
 
-        count_map.insert(uid, count);
-        luck_4_map.insert(uid, wish_stat.luck_4);
-        luck_5_map.insert(uid, wish_stat.luck_5);
-    }
+    let updated = database::gi::wishes_stats_global::weapon::update(pool).await?;
+    database::stats_global_updates::set(NAME, updated_at, pool).await?;
 
-    let mut sorted_count: Vec<(i32, i32)> = count_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_count.sort_unstable_by(|(_, v1), (_, v2)| v2.cmp(v1));
-
-    let mut sorted_luck_4: Vec<(i32, f64)> = luck_4_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_4.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let mut sorted_luck_5: Vec<(i32, f64)> = luck_5_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_5.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let count_percentiles: HashMap<_, _> = sorted_count
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_4_percentiles: HashMap<_, _> = sorted_luck_4
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_5_percentiles: HashMap<_, _> = sorted_luck_5
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let len = stat_uids.len() as f64;
-    for uid in &stat_uids {
-        let count_percentile = count_percentiles[uid] as f64 / len;
-        let luck_4_percentile = luck_4_percentiles[uid] as f64 / len;
-        let luck_5_percentile = luck_5_percentiles[uid] as f64 / len;
-
-        let stat = database::gi::wishes_stats_global::weapon::DbWishesStatGlobalWeapon {
-            uid: *uid,
-            count_percentile,
-            luck_4_percentile,
-            luck_5_percentile,
-        };
-
-        database::gi::wishes_stats_global::weapon::set(&stat, pool).await?;
-    }
+    info!(
+        "Weapon wishes stats updated: {updated} in {}s",
+        start.elapsed().as_secs_f64()
+    );
 
     Ok(())
 }
 
 async fn chronicled(pool: &PgPool) -> Result<()> {
-    let mut count_map = HashMap::new();
-    let mut luck_4_map = HashMap::new();
-    let mut luck_5_map = HashMap::new();
+    const NAME: &str = "gi_wishes_stats_global_chronicled";
 
-    let mut stat_uids = Vec::new();
+    let Some(updated_at) = database::gi::wishes_stats::chronicled::get_updated_at(pool).await?
+    else {
+        return Ok(());
+    };
 
-    for wish_stat in database::gi::wishes_stats::chronicled::get_all(pool).await? {
-        let uid = wish_stat.uid;
+    if database::stats_global_updates::get_by_name(NAME, pool).await? >= Some(updated_at) {
+        info!("Chronicled wishes stats unchanged");
+        return Ok(());
+    }
 
-        let count = database::gi::wishes::chronicled::get_count_by_uid(uid, pool).await? as i32;
+    let start = Instant::now();
 
-        if count < 100 {
-            continue;
-        }
+    let updated = database::gi::wishes_stats_global::chronicled::update(pool).await?;
+    database::stats_global_updates::set(NAME, updated_at, pool).await?;
 
-        stat_uids.push(uid);
+    info!(
+        "Chronicled wishes stats updated: {updated} in {}s",
+        start.elapsed().as_secs_f64()
+    );
 
-        count_map.insert(uid, count);
-        luck_4_map.insert(uid, wish_stat.luck_4);
-        luck_5_map.insert(uid, wish_stat.luck_5);
-    }
+    Ok(())
+}
+
+async fn beginner(pool: &PgPool) -> Result<()> {
+    const NAME: &str = "gi_wishes_stats_global_beginner";
+
+    let Some(updated_at) = database::gi::wishes_stats::beginner::get_updated_at(pool).await? else {
+        return Ok(());
+    };
 
-    let mut sorted_count: Vec<(i32, i32)> = count_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_count.sort_unstable_by(|(_, v1), (_, v2)| v2.cmp(v1));
-
-    let mut sorted_luck_4: Vec<(i32, f64)> = luck_4_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_4.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let mut sorted_luck_5: Vec<(i32, f64)> = luck_5_map.iter().map(|(&k, &v)| (k, v)).collect();
-    sorted_luck_5.sort_unstable_by(|(_, v1), (_, v2)| v1.total_cmp(v2));
-
-    let count_percentiles: HashMap<_, _> = sorted_count
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_4_percentiles: HashMap<_, _> = sorted_luck_4
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let luck_5_percentiles: HashMap<_, _> = sorted_luck_5
-        .into_iter()
-        .enumerate()
-        .map(|(i, (uid, _))| (uid, i))
-        .collect();
-
-    let len = stat_uids.len() as f64;
-    for uid in &stat_uids {
-        let count_percentile = count_percentiles[uid] as f64 / len;
-        let luck_4_percentile = luck_4_percentiles[uid] as f64 / len;
-        let luck_5_percentile = luck_5_percentiles[uid] as f64 / len;
-
-        let stat = database::gi::wishes_stats_global::chronicled::DbWishesStatGlobalChronicled {
-            uid: *uid,
-            count_percentile,
-            luck_4_percentile,
-            luck_5_percentile,
-        };
-
-        database::gi::wishes_stats_global::chronicled::set(&stat, pool).await?;
+    if database::stats_global_updates::get_by_name(NAME, pool).await? >= Some(updated_at) {
+        info!("Beginner wishes stats unchanged");
+        return Ok(());
     }
 
+    let start = Instant::now();
+
+    let updated = database::gi::wishes_stats_global::beginner::update(pool).await?;
+    database::stats_global_updates::set(NAME, updated_at, pool).await?;
+
+    info!(
+        "Beginner wishes stats updated: {updated} in {}s",
+        start.elapsed().as_secs_f64()
+    );
+
     Ok(())
 }